@@ -0,0 +1,113 @@
+//! Costs of several standalone accounts side by side
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+
+use crate::periods;
+use crate::source::{AccountCosts, CostSource};
+
+/// One account's total, or why it is missing
+#[derive(Debug, Clone)]
+pub struct AccountTotal {
+    pub account: String,
+    /// `None` when the account failed to load
+    pub cost: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// A service's cost in each account, in the order of
+/// [`AccountsReport::accounts`]
+#[derive(Debug, Clone)]
+pub struct AccountServiceRow {
+    pub service: String,
+    pub costs: Vec<f64>,
+    pub total: f64,
+}
+
+/// Per-account totals and a breakdown by service with a column per account
+#[derive(Debug, Clone)]
+pub struct AccountsReport {
+    pub period: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub currency: String,
+    /// Accounts in the order they were given
+    pub accounts: Vec<AccountTotal>,
+    /// Services by total cost across accounts, descending
+    pub services: Vec<AccountServiceRow>,
+}
+
+impl AccountsReport {
+    /// Line up each account's services over `[start, end)`
+    pub fn new(costs: &[AccountCosts], period: &str, start: NaiveDate, end: NaiveDate) -> Self {
+        let mut rows: HashMap<&str, Vec<f64>> = HashMap::new();
+        let mut accounts = Vec::new();
+        let mut currency = None;
+        for (i, account) in costs.iter().enumerate() {
+            match &account.costs {
+                Ok(data) => {
+                    for service in &data.breakdown {
+                        rows.entry(&service.service)
+                            .or_insert_with(|| vec![0.0; costs.len()])[i] += service.cost;
+                    }
+                    currency.get_or_insert_with(|| data.currency.clone());
+                    accounts.push(AccountTotal {
+                        account: account.account.clone(),
+                        cost: Some(data.total_cost),
+                        error: None,
+                    });
+                }
+                Err(e) => accounts.push(AccountTotal {
+                    account: account.account.clone(),
+                    cost: None,
+                    error: Some(e.clone()),
+                }),
+            }
+        }
+
+        let mut services: Vec<AccountServiceRow> = rows
+            .into_iter()
+            .map(|(service, costs)| AccountServiceRow {
+                service: service.to_string(),
+                total: costs.iter().sum(),
+                costs,
+            })
+            .collect();
+        services.sort_by(|a, b| {
+            b.total
+                .total_cmp(&a.total)
+                .then_with(|| a.service.cmp(&b.service))
+        });
+
+        Self {
+            period: period.to_string(),
+            start,
+            end,
+            currency: currency.unwrap_or_else(|| "USD".to_string()),
+            accounts,
+            services,
+        }
+    }
+
+    /// Query every account of `source` over `[start, end)`
+    pub async fn load(source: &dyn CostSource, start: NaiveDate, end: NaiveDate) -> Result<Self> {
+        let costs = source.account_breakdown(start, end).await?;
+        Ok(Self::new(
+            &costs,
+            &periods::range_name(start, end),
+            start,
+            end,
+        ))
+    }
+
+    /// Spend across the accounts that loaded
+    pub fn total_cost(&self) -> f64 {
+        self.accounts.iter().filter_map(|a| a.cost).sum()
+    }
+
+    /// Accounts that failed to load
+    pub fn failed(&self) -> impl Iterator<Item = &AccountTotal> {
+        self.accounts.iter().filter(|a| a.error.is_some())
+    }
+}
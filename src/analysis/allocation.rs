@@ -0,0 +1,473 @@
+//! Showback and chargeback: split spend across teams with allocation rules
+//!
+//! Rules come from a TOML file:
+//!
+//! ```toml
+//! # Tag key naming the team that owns a resource
+//! tag = "team"
+//!
+//! # Team names for tag values; other values are teams of their own
+//! [tags]
+//! platform = "Platform"
+//!
+//! # Owners of spend without the tag, by linked account
+//! [accounts]
+//! "222222222222" = "Data"
+//!
+//! # Shared spend, split in proportion to each team's direct spend...
+//! [[shared]]
+//! name = "Support"
+//! services = ["AWS Support (Business)"]
+//!
+//! # ...or by fixed percentages
+//! [[shared]]
+//! name = "Untagged"
+//! untagged = true
+//! split = { fixed = { Platform = 60, Data = 40 } }
+//! ```
+//!
+//! Each cost goes to the first of: a shared bucket listing its service or
+//! tag value, the team named by its tag, the owner of its account, the
+//! `untagged` bucket. Anything left is reported as unallocated.
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use super::compare::CHANGE_EPSILON;
+use super::tags::tag_value;
+use crate::aws::{CostMatrix, Dimension, GroupBy};
+use crate::source::CostSource;
+
+/// How a shared bucket is divided between teams
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Split {
+    /// In proportion to each team's direct spend
+    #[default]
+    Proportional,
+    /// Fixed percentage per team, adding up to 100
+    Fixed(BTreeMap<String, f64>),
+}
+
+/// Spend pooled and then split between teams, e.g. support or data transfer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SharedBucket {
+    pub name: String,
+    /// Services whose spend goes into the bucket
+    #[serde(default)]
+    pub services: Vec<String>,
+    /// Team tag values whose spend goes into the bucket, e.g. `shared`
+    #[serde(default)]
+    pub tag_values: Vec<String>,
+    /// Collect spend that no tag or account rule assigns
+    #[serde(default)]
+    pub untagged: bool,
+    #[serde(default)]
+    pub split: Split,
+}
+
+/// Rules assigning spend to teams
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AllocationRules {
+    /// Tag key naming the owning team
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Team of each tag value; unlisted values are teams of their own
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Team owning each linked account's spend without the tag
+    #[serde(default)]
+    pub accounts: BTreeMap<String, String>,
+    #[serde(default)]
+    pub shared: Vec<SharedBucket>,
+}
+
+impl AllocationRules {
+    /// Read rules from a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read allocation rules {:?}", path))?;
+        Self::parse(&content).with_context(|| format!("Invalid allocation rules {:?}", path))
+    }
+
+    /// Parse and check rules written in TOML
+    pub fn parse(content: &str) -> Result<Self> {
+        let rules: Self = toml::from_str(content)?;
+        rules.validate()?;
+        Ok(rules)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.tag.is_none() && !self.tags.is_empty() {
+            bail!("[tags] maps team tag values, but no `tag` key is set");
+        }
+        let mut names = Vec::new();
+        for bucket in &self.shared {
+            if bucket.name.is_empty() {
+                bail!("Every shared bucket needs a name");
+            }
+            if names.contains(&&bucket.name) {
+                bail!("Shared bucket {:?} is defined twice", bucket.name);
+            }
+            names.push(&bucket.name);
+            if bucket.services.is_empty() && bucket.tag_values.is_empty() && !bucket.untagged {
+                bail!(
+                    "Shared bucket {:?} matches nothing; list services or tag_values, or set untagged = true",
+                    bucket.name
+                );
+            }
+            if let Split::Fixed(shares) = &bucket.split {
+                if shares.values().any(|p| *p < 0.0) {
+                    bail!("Fixed split of {:?} has a negative share", bucket.name);
+                }
+                let sum: f64 = shares.values().sum();
+                if (sum - 100.0).abs() > 0.01 {
+                    bail!(
+                        "Fixed split of {:?} adds up to {}%, not 100%",
+                        bucket.name,
+                        sum
+                    );
+                }
+            }
+        }
+        if self.shared.iter().filter(|b| b.untagged).count() > 1 {
+            bail!("Only one shared bucket can collect untagged spend");
+        }
+        Ok(())
+    }
+
+    /// Shared bucket claiming `line` because of its service or tag value
+    fn bucket_of(&self, line: &CostLine) -> Option<usize> {
+        self.shared.iter().position(|bucket| {
+            line.service
+                .as_ref()
+                .is_some_and(|s| bucket.services.contains(s))
+                || line
+                    .tag
+                    .as_ref()
+                    .is_some_and(|v| bucket.tag_values.contains(v))
+        })
+    }
+
+    /// Team owning `line` because of its tag or account
+    fn team_of(&self, line: &CostLine) -> Option<String> {
+        if let Some(value) = &line.tag {
+            return Some(self.tags.get(value).unwrap_or(value).clone());
+        }
+        line.account
+            .as_ref()
+            .and_then(|account| self.accounts.get(account))
+            .cloned()
+    }
+}
+
+/// Cost with everything the rules can match on
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostLine {
+    pub service: Option<String>,
+    pub account: Option<String>,
+    /// Value of the team tag, `None` for untagged spend
+    pub tag: Option<String>,
+    pub cost: f64,
+}
+
+/// One team's share of the bill
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamCost {
+    pub name: String,
+    /// Spend assigned by tag or account
+    pub direct: f64,
+    /// Share of each shared bucket, in [`AllocationRules::shared`] order
+    pub shared: Vec<f64>,
+}
+
+impl TeamCost {
+    pub fn total(&self) -> f64 {
+        self.direct + self.shared.iter().sum::<f64>()
+    }
+}
+
+/// Allocated spend checked against the bill
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reconciliation {
+    pub bill: f64,
+    pub allocated: f64,
+    pub unallocated: f64,
+}
+
+impl Reconciliation {
+    /// Bill not accounted for by allocated or unallocated spend
+    pub fn difference(&self) -> f64 {
+        self.bill - self.allocated - self.unallocated
+    }
+
+    /// Whether teams carry the whole bill, give or take rounding (a cent,
+    /// or 0.01% of a large bill)
+    pub fn is_balanced(&self) -> bool {
+        let tolerance = (self.bill.abs() * 0.0001).max(0.01);
+        self.unallocated.abs() < tolerance && self.difference().abs() < tolerance
+    }
+}
+
+/// Spend per team over one period
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    pub period: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Spend the rules were applied to
+    pub total_cost: f64,
+    pub currency: String,
+    /// Names of the shared buckets
+    pub buckets: Vec<String>,
+    /// Spend pooled in each shared bucket
+    pub bucket_costs: Vec<f64>,
+    /// Teams by total cost descending
+    pub teams: Vec<TeamCost>,
+    /// Spend no rule assigns to a team
+    pub unallocated: f64,
+}
+
+impl Allocation {
+    /// Apply `rules` to `lines`
+    pub fn new(
+        rules: &AllocationRules,
+        lines: &[CostLine],
+        period: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Self {
+        let bucket_count = rules.shared.len();
+        let mut bucket_costs = vec![0.0; bucket_count];
+        let mut direct: BTreeMap<String, f64> = BTreeMap::new();
+        let mut unallocated = 0.0;
+        let untagged_bucket = rules.shared.iter().position(|b| b.untagged);
+
+        for line in lines {
+            if let Some(bucket) = rules.bucket_of(line) {
+                bucket_costs[bucket] += line.cost;
+            } else if let Some(team) = rules.team_of(line) {
+                *direct.entry(team).or_default() += line.cost;
+            } else if let Some(bucket) = untagged_bucket {
+                bucket_costs[bucket] += line.cost;
+            } else {
+                unallocated += line.cost;
+            }
+        }
+
+        let mut teams: HashMap<String, TeamCost> = direct
+            .iter()
+            .map(|(name, &cost)| {
+                let team = TeamCost {
+                    name: name.clone(),
+                    direct: cost,
+                    shared: vec![0.0; bucket_count],
+                };
+                (name.clone(), team)
+            })
+            .collect();
+        let direct_total: f64 = direct.values().filter(|c| **c > 0.0).sum();
+
+        for (index, (bucket, &cost)) in rules.shared.iter().zip(&bucket_costs).enumerate() {
+            let shares: Vec<(&String, f64)> = match &bucket.split {
+                Split::Proportional if direct_total > 0.0 => direct
+                    .iter()
+                    .filter(|(_, c)| **c > 0.0)
+                    .map(|(name, c)| (name, c / direct_total))
+                    .collect(),
+                Split::Proportional => Vec::new(),
+                Split::Fixed(percentages) => percentages
+                    .iter()
+                    .map(|(name, pct)| (name, pct / 100.0))
+                    .collect(),
+            };
+            if shares.is_empty() {
+                unallocated += cost;
+            }
+            for (name, share) in shares {
+                let team = teams.entry(name.clone()).or_insert_with(|| TeamCost {
+                    name: name.clone(),
+                    direct: 0.0,
+                    shared: vec![0.0; bucket_count],
+                });
+                team.shared[index] += cost * share;
+            }
+        }
+
+        let mut teams: Vec<TeamCost> = teams.into_values().collect();
+        teams.sort_by(|a, b| {
+            b.total()
+                .total_cmp(&a.total())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Self {
+            period: period.to_string(),
+            start,
+            end,
+            total_cost: lines.iter().map(|l| l.cost).sum(),
+            currency: "USD".to_string(),
+            buckets: rules.shared.iter().map(|b| b.name.clone()).collect(),
+            bucket_costs,
+            teams,
+            unallocated,
+        }
+    }
+
+    /// Query `source` for spend over `[start, end)` and apply `rules`.
+    ///
+    /// Cost Explorer groups by at most two keys, so with a team tag the
+    /// untagged spend of each service is spread over accounts in proportion
+    /// to each account's untagged spend.
+    pub async fn load(
+        source: &dyn CostSource,
+        rules: &AllocationRules,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Self> {
+        let services = GroupBy::Dimension(Dimension::Service);
+        let accounts = GroupBy::Dimension(Dimension::LinkedAccount);
+
+        let (matrix, lines) = match &rules.tag {
+            Some(key) => {
+                let tag = GroupBy::Tag(key.clone());
+                let (by_service, by_account) =
+                    futures::try_join!(source.matrix(start, end, &services, &tag), async {
+                        if rules.accounts.is_empty() {
+                            return Ok(None);
+                        }
+                        source.matrix(start, end, &accounts, &tag).await.map(Some)
+                    })?;
+                let lines = tag_lines(key, &by_service, by_account.as_ref());
+                (by_service, lines)
+            }
+            None => {
+                let matrix = source.matrix(start, end, &services, &accounts).await?;
+                let lines = matrix
+                    .cells
+                    .iter()
+                    .map(|cell| CostLine {
+                        service: Some(cell.row.clone()),
+                        account: Some(cell.column.clone()),
+                        tag: None,
+                        cost: cell.cost,
+                    })
+                    .collect();
+                (matrix, lines)
+            }
+        };
+
+        let mut allocation = Self::new(rules, &lines, &matrix.period, start, end);
+        allocation.currency = matrix.currency;
+        Ok(allocation)
+    }
+
+    /// Spend carried by teams
+    pub fn allocated(&self) -> f64 {
+        self.teams.iter().map(TeamCost::total).sum()
+    }
+
+    /// Check the allocation against the `bill` for the same period
+    pub fn reconcile(&self, bill: f64) -> Reconciliation {
+        Reconciliation {
+            bill,
+            allocated: self.allocated(),
+            unallocated: self.unallocated,
+        }
+    }
+
+    /// Write per-team totals as CSV for invoicing: a row per team, an
+    /// `(unallocated)` row if any spend is left over, and a `total` row
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+
+        let mut header = vec!["team".to_string(), "direct_cost".to_string()];
+        header.extend(self.buckets.iter().map(|b| format!("{}_cost", b)));
+        header.extend(["total_cost".to_string(), "share_pct".to_string()]);
+        csv.write_record(&header)?;
+
+        let share = |cost: f64| {
+            if self.total_cost > 0.0 {
+                cost / self.total_cost * 100.0
+            } else {
+                0.0
+            }
+        };
+        for team in &self.teams {
+            let mut record = vec![team.name.clone(), format!("{:.2}", team.direct)];
+            record.extend(team.shared.iter().map(|c| format!("{:.2}", c)));
+            record.push(format!("{:.2}", team.total()));
+            record.push(format!("{:.2}", share(team.total())));
+            csv.write_record(&record)?;
+        }
+        if self.unallocated.abs() >= CHANGE_EPSILON {
+            let mut record = vec!["(unallocated)".to_string(), String::new()];
+            record.extend(self.buckets.iter().map(|_| String::new()));
+            record.push(format!("{:.2}", self.unallocated));
+            record.push(format!("{:.2}", share(self.unallocated)));
+            csv.write_record(&record)?;
+        }
+
+        let mut record = vec![
+            "total".to_string(),
+            format!("{:.2}", self.teams.iter().map(|t| t.direct).sum::<f64>()),
+        ];
+        record.extend((0..self.buckets.len()).map(|i| {
+            let allocated: f64 = self.teams.iter().map(|t| t.shared[i]).sum();
+            format!("{:.2}", allocated)
+        }));
+        record.push(format!("{:.2}", self.allocated() + self.unallocated));
+        record.push(format!("{:.2}", share(self.allocated() + self.unallocated)));
+        csv.write_record(&record)?;
+
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+/// Lines from a `SERVICE by TAG:key` matrix, with untagged spend spread over
+/// the accounts of an optional `LINKED_ACCOUNT by TAG:key` matrix
+fn tag_lines(key: &str, by_service: &CostMatrix, by_account: Option<&CostMatrix>) -> Vec<CostLine> {
+    let mut untagged: BTreeMap<&str, f64> = BTreeMap::new();
+    for cell in by_account.map_or(&[][..], |m| &m.cells) {
+        if tag_value(key, &cell.column).is_none() {
+            *untagged.entry(&cell.row).or_default() += cell.cost;
+        }
+    }
+    let untagged_total: f64 = untagged.values().sum();
+
+    let mut lines = Vec::new();
+    for cell in &by_service.cells {
+        let service = Some(cell.row.clone());
+        match tag_value(key, &cell.column) {
+            Some(value) => lines.push(CostLine {
+                service,
+                account: None,
+                tag: Some(value.to_string()),
+                cost: cell.cost,
+            }),
+            None if untagged_total > 0.0 => {
+                lines.extend(untagged.iter().map(|(account, cost)| CostLine {
+                    service: service.clone(),
+                    account: Some(account.to_string()),
+                    tag: None,
+                    cost: cell.cost * cost / untagged_total,
+                }))
+            }
+            None => lines.push(CostLine {
+                service,
+                account: None,
+                tag: None,
+                cost: cell.cost,
+            }),
+        }
+    }
+    lines
+}
@@ -0,0 +1,268 @@
+//! Local anomaly detection on daily per-service costs
+//!
+//! Works without AWS Cost Anomaly Detection monitors. Each day's cost is
+//! compared with a robust baseline built from the days before it: the median
+//! of a trailing window ([`Baseline::Rolling`]) or of the same weekday in
+//! previous weeks ([`Baseline::Weekday`]), which keeps weekday/weekend swings
+//! from looking like spikes. A day is flagged when it sits more than
+//! `sensitivity` scaled median absolute deviations (MADs) above the baseline.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+
+use crate::aws::DailyCosts;
+use crate::source::CostSource;
+
+/// Scales a MAD to a standard deviation for normally distributed costs
+const MAD_SCALE: f64 = 1.4826;
+
+/// Fewest past days a rolling baseline needs
+const MIN_ROLLING_DAYS: usize = 7;
+
+/// Fewest past weeks a weekday baseline needs
+const MIN_WEEKS: usize = 3;
+
+/// The spread is never taken to be below this share of the baseline, so
+/// near-constant costs don't flag every small wobble
+const MIN_RELATIVE_SPREAD: f64 = 0.1;
+
+/// Which past days a day is compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Baseline {
+    /// Every day of the trailing window
+    Rolling,
+    /// The same weekday in each week of the trailing window
+    #[default]
+    Weekday,
+}
+
+impl Baseline {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Baseline::Rolling => "rolling",
+            Baseline::Weekday => "weekday",
+        }
+    }
+}
+
+/// Settings for flagging spikes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detector {
+    pub baseline: Baseline,
+    /// Days of history each baseline is built from
+    pub window: usize,
+    /// How many scaled MADs above the baseline a day must be; lower flags
+    /// more days
+    pub sensitivity: f64,
+    /// Smallest excess over the baseline worth flagging, in the bill's
+    /// currency
+    pub min_excess: f64,
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self {
+            baseline: Baseline::default(),
+            window: 28,
+            sensitivity: 3.5,
+            min_excess: 1.0,
+        }
+    }
+}
+
+impl Detector {
+    /// Spikes on days from `from` to the end of `daily`, biggest excess
+    /// first. Consecutive flagged days of a service make one spike; days
+    /// without enough history before them are skipped.
+    pub fn detect(&self, daily: &DailyCosts, from: NaiveDate) -> Vec<Anomaly> {
+        let first = usize::try_from((from - daily.start).num_days()).unwrap_or(0);
+        let mut anomalies: Vec<Anomaly> = Vec::new();
+
+        for series in &daily.series {
+            let mut previous = None;
+            for day in first..series.costs.len() {
+                let Some((expected, spread)) = self.baseline_at(&series.costs, day) else {
+                    continue;
+                };
+                let actual = series.costs[day];
+                let score = (actual - expected) / spread;
+                if score <= self.sensitivity || actual - expected < self.min_excess {
+                    continue;
+                }
+                match anomalies.last_mut() {
+                    Some(last) if previous == Some(day - 1) => {
+                        last.days += 1;
+                        last.expected += expected;
+                        last.actual += actual;
+                        last.score = last.score.max(score);
+                    }
+                    _ => anomalies.push(Anomaly {
+                        service: series.service.clone(),
+                        provider: series.provider.clone(),
+                        date: daily.date(day),
+                        days: 1,
+                        expected,
+                        actual,
+                        score,
+                    }),
+                }
+                previous = Some(day);
+            }
+        }
+
+        anomalies.sort_by(|a, b| {
+            b.excess()
+                .total_cmp(&a.excess())
+                .then_with(|| b.date.cmp(&a.date))
+        });
+        anomalies
+    }
+
+    /// Median and spread of the history before `day`, `None` without enough
+    /// of it
+    fn baseline_at(&self, costs: &[f64], day: usize) -> Option<(f64, f64)> {
+        let (expected, deviations): (f64, Vec<f64>) = match self.baseline {
+            Baseline::Rolling => {
+                let history = &costs[day.saturating_sub(self.window)..day];
+                if history.len() < MIN_ROLLING_DAYS {
+                    return None;
+                }
+                let expected = median(history.to_vec());
+                (expected, history.iter().map(|c| c - expected).collect())
+            }
+            Baseline::Weekday => {
+                let window = self.window.max(MIN_WEEKS * 7);
+                let start = day.saturating_sub(window);
+                // Each weekday against its own median, so the spread
+                // doesn't include the weekly swing
+                let weekdays: Vec<Vec<f64>> = (0..7)
+                    .map(|weekday| {
+                        (start..day)
+                            .filter(|past| (day - past) % 7 == weekday)
+                            .map(|past| costs[past])
+                            .collect()
+                    })
+                    .collect();
+                if weekdays[0].len() < MIN_WEEKS {
+                    return None;
+                }
+                let deviations = weekdays
+                    .iter()
+                    .flat_map(|values| {
+                        let middle = median(values.clone());
+                        values.iter().map(move |c| c - middle)
+                    })
+                    .collect();
+                (median(weekdays[0].clone()), deviations)
+            }
+        };
+
+        let mad = median(deviations.into_iter().map(f64::abs).collect());
+        let spread = (mad * MAD_SCALE)
+            .max(expected.abs() * MIN_RELATIVE_SPREAD)
+            .max(0.01);
+        Some((expected, spread))
+    }
+}
+
+/// One or more days a service cost well above its baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub service: String,
+    /// Cloud provider, for merged multi-cloud data
+    pub provider: Option<String>,
+    /// First day of the spike
+    pub date: NaiveDate,
+    /// Consecutive days it lasted
+    pub days: usize,
+    /// Baseline cost over those days
+    pub expected: f64,
+    pub actual: f64,
+    /// Most scaled MADs above the baseline on any of the days
+    pub score: f64,
+}
+
+impl Anomaly {
+    /// Day after the last day of the spike
+    pub fn end(&self) -> NaiveDate {
+        self.date + Duration::days(self.days as i64)
+    }
+
+    /// Cost over the baseline
+    pub fn excess(&self) -> f64 {
+        self.actual - self.expected
+    }
+
+    /// Change from the baseline in percent, `None` for a zero baseline
+    pub fn change_percentage(&self) -> Option<f64> {
+        (self.expected > 0.0).then(|| self.excess() / self.expected * 100.0)
+    }
+}
+
+/// Spikes found over a range of days
+#[derive(Debug, Clone)]
+pub struct Anomalies {
+    /// First day checked (inclusive)
+    pub start: NaiveDate,
+    /// Day after the last day checked (exclusive)
+    pub end: NaiveDate,
+    pub currency: String,
+    pub detector: Detector,
+    /// Biggest excess first
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl Anomalies {
+    /// Run `detector` over the days of `daily` from `start` on
+    pub fn new(detector: Detector, daily: &DailyCosts, start: NaiveDate) -> Self {
+        Self {
+            start: start.max(daily.start),
+            end: daily.end,
+            currency: daily.currency.clone(),
+            detector,
+            anomalies: detector.detect(daily, start),
+        }
+    }
+
+    /// Check `[start, end)`, fetching the detector's window of history
+    /// before it too
+    pub async fn load(
+        source: &dyn CostSource,
+        detector: Detector,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Self> {
+        let history = Duration::days(detector.window.max(MIN_WEEKS * 7) as i64);
+        let daily = source.daily(start - history, end).await?;
+        Ok(Self::new(detector, &daily, start))
+    }
+
+    /// Spikes of one service, biggest first
+    pub fn of_service<'a>(
+        &'a self,
+        service: &'a str,
+        provider: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a Anomaly> + 'a {
+        self.anomalies
+            .iter()
+            .filter(move |a| a.service == service && a.provider.as_deref() == provider)
+    }
+
+    /// Cost over the baseline across every spike
+    pub fn total_excess(&self) -> f64 {
+        self.anomalies.iter().map(Anomaly::excess).sum()
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
@@ -0,0 +1,314 @@
+//! Budgets defined in a TOML file, for accounts without AWS Budgets
+//!
+//! ```toml
+//! # Whole bill, flagged at 50% and 80% of the limit and when it is passed
+//! [[budget]]
+//! name = "Monthly total"
+//! period = "monthly"
+//! amount = 5000
+//! thresholds = [50, 80, 100]
+//!
+//! # One scope per budget: service, account, tag or filter
+//! [[budget]]
+//! name = "EC2"
+//! period = "quarterly"
+//! amount = 6000
+//! service = "Amazon Elastic Compute Cloud - Compute"
+//!
+//! [[budget]]
+//! name = "Web team"
+//! period = "annual"
+//! amount = 20000
+//! tag = { key = "team", value = "web" }
+//!
+//! # Any grouping Cost Explorer knows, e.g. REGION or COST_CATEGORY:<name>
+//! [[budget]]
+//! name = "Frankfurt"
+//! period = "monthly"
+//! amount = 800
+//! filter = { key = "REGION", value = "eu-central-1" }
+//! ```
+//!
+//! Spend is evaluated against the period's [`CostData`] grouped by the
+//! budget's scope, and forecast at the period's run rate.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Duration, NaiveDate};
+use futures::future::join_all;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use super::tags::tag_value;
+use crate::aws::budgets::budget_period;
+use crate::aws::{Budget, CostData, Dimension, GroupBy};
+use crate::source::{group_by_label, parse_group_by_label, CostSource};
+
+/// How often a budget's amount resets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Monthly,
+    Quarterly,
+    Annual,
+}
+
+impl Period {
+    /// AWS Budgets name of the period
+    pub fn time_unit(&self) -> &'static str {
+        match self {
+            Period::Monthly => "MONTHLY",
+            Period::Quarterly => "QUARTERLY",
+            Period::Annual => "ANNUALLY",
+        }
+    }
+}
+
+/// A tag or grouping value a budget is limited to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScopeValue {
+    /// Tag key, or a grouping such as `REGION` or `TAG:team` for `filter`
+    pub key: String,
+    pub value: String,
+}
+
+/// Spend a budget covers
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scope {
+    /// The whole bill
+    All,
+    /// Spend whose `group_by` value is `value`
+    Value { group_by: GroupBy, value: String },
+}
+
+impl Scope {
+    /// Grouping the budget's spend is looked up in
+    pub fn group_by(&self) -> GroupBy {
+        match self {
+            Scope::All => GroupBy::Dimension(Dimension::Service),
+            Scope::Value { group_by, .. } => group_by.clone(),
+        }
+    }
+
+    /// Whether spend under a breakdown `label` counts towards the budget
+    pub fn matches(&self, label: &str) -> bool {
+        match self {
+            Scope::All => true,
+            Scope::Value {
+                group_by: GroupBy::Tag(key) | GroupBy::CostCategory(key),
+                value,
+            } => tag_value(key, label) == Some(value.as_str()),
+            Scope::Value { value, .. } => label == value,
+        }
+    }
+
+    /// Short description, e.g. "service Amazon EC2" or "tag team=web"
+    pub fn describe(&self) -> String {
+        match self {
+            Scope::All => "all spend".to_string(),
+            Scope::Value { group_by, value } => match group_by {
+                GroupBy::Dimension(Dimension::Service) => format!("service {}", value),
+                GroupBy::Dimension(Dimension::LinkedAccount) => format!("account {}", value),
+                GroupBy::Tag(key) => format!("tag {}={}", key, value),
+                GroupBy::CostCategory(name) => format!("cost category {}={}", name, value),
+                GroupBy::Dimension(d) => format!("{} {}", d.as_str().to_lowercase(), value),
+            },
+        }
+    }
+}
+
+/// A budget from the config
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalBudget {
+    pub name: String,
+    pub period: Period,
+    /// Limit per period, in the bill's currency
+    pub amount: f64,
+    /// Alert thresholds in percent of the amount; empty for 80% and 100%
+    #[serde(default)]
+    pub thresholds: Vec<f64>,
+    #[serde(default)]
+    pub service: Option<String>,
+    /// Linked account ID
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub tag: Option<ScopeValue>,
+    #[serde(default)]
+    pub filter: Option<ScopeValue>,
+}
+
+impl LocalBudget {
+    /// Spend the budget covers
+    pub fn scope(&self) -> Result<Scope> {
+        let value = |group_by: GroupBy, value: &str| Scope::Value {
+            group_by,
+            value: value.to_string(),
+        };
+        let scope = match (&self.service, &self.account, &self.tag, &self.filter) {
+            (None, None, None, None) => Scope::All,
+            (Some(service), None, None, None) => {
+                value(GroupBy::Dimension(Dimension::Service), service)
+            }
+            (None, Some(account), None, None) => {
+                value(GroupBy::Dimension(Dimension::LinkedAccount), account)
+            }
+            (None, None, Some(tag), None) => {
+                if tag.key.is_empty() {
+                    bail!("Tag scope needs a key");
+                }
+                value(GroupBy::Tag(tag.key.clone()), &tag.value)
+            }
+            (None, None, None, Some(filter)) => {
+                value(parse_group_by_label(&filter.key)?, &filter.value)
+            }
+            _ => bail!("Set at most one of service, account, tag and filter"),
+        };
+        Ok(scope)
+    }
+
+    /// First and day after the last day of the period containing `today`
+    pub fn period_dates(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+        budget_period(self.period.time_unit(), today)
+    }
+
+    /// The budget's spend in `data`, the period's costs up to `today`
+    /// grouped by [`Scope::group_by`]
+    pub fn evaluate(&self, data: &CostData, today: NaiveDate) -> Result<Budget> {
+        let (start, end) = self.period_dates(today)?;
+        let scope = self.scope()?;
+        let actual = match scope {
+            Scope::All => data.total_cost,
+            _ => data
+                .breakdown
+                .iter()
+                .filter(|s| scope.matches(&s.service))
+                .fold(0.0, |sum, s| sum + s.cost),
+        };
+
+        // Run rate over the days covered so far
+        let elapsed = (data.end.min(end) - start).num_days();
+        let forecasted =
+            (elapsed > 0).then(|| actual / elapsed as f64 * (end - start).num_days() as f64);
+
+        Ok(Budget {
+            name: self.name.clone(),
+            budget_type: "COST".to_string(),
+            time_unit: self.period.time_unit().to_string(),
+            start,
+            end,
+            limit: self.amount,
+            unit: data.currency.clone(),
+            actual,
+            forecasted,
+            history: Vec::new(),
+            thresholds: self.thresholds.clone(),
+            scope: Some(scope.describe()),
+        })
+    }
+}
+
+/// Budgets from the config
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LocalBudgets {
+    #[serde(default, rename = "budget")]
+    pub budgets: Vec<LocalBudget>,
+}
+
+impl LocalBudgets {
+    /// Read budgets from a TOML file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read budgets {:?}", path))?;
+        Self::parse(&content).with_context(|| format!("Invalid budgets {:?}", path))
+    }
+
+    /// Parse and check budgets written in TOML
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut budgets: Self = toml::from_str(content)?;
+        budgets.validate()?;
+        for budget in &mut budgets.budgets {
+            budget.thresholds.sort_by(f64::total_cmp);
+        }
+        Ok(budgets)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let mut names = Vec::new();
+        for budget in &self.budgets {
+            if budget.name.is_empty() {
+                bail!("Every budget needs a name");
+            }
+            if names.contains(&&budget.name) {
+                bail!("Budget {:?} is defined twice", budget.name);
+            }
+            names.push(&budget.name);
+            if !budget.amount.is_finite() || budget.amount <= 0.0 {
+                bail!("Budget {:?} needs a positive amount", budget.name);
+            }
+            if budget
+                .thresholds
+                .iter()
+                .any(|t| !t.is_finite() || *t <= 0.0)
+            {
+                bail!(
+                    "Budget {:?} has a threshold that is not a positive percentage",
+                    budget.name
+                );
+            }
+            budget
+                .scope()
+                .with_context(|| format!("Budget {:?}", budget.name))?;
+        }
+        Ok(())
+    }
+
+    /// Evaluate every budget for the period containing `today`, querying
+    /// `source` once per period and grouping
+    pub async fn evaluate(&self, source: &dyn CostSource, today: NaiveDate) -> Result<Vec<Budget>> {
+        let mut queries: Vec<(NaiveDate, NaiveDate, GroupBy)> = Vec::new();
+        let mut query_of = Vec::new();
+        for budget in &self.budgets {
+            let (start, end) = budget.period_dates(today)?;
+            let query = (
+                start,
+                (today + Duration::days(1)).min(end),
+                budget.scope()?.group_by(),
+            );
+            let index = match queries.iter().position(|q| *q == query) {
+                Some(index) => index,
+                None => {
+                    queries.push(query);
+                    queries.len() - 1
+                }
+            };
+            query_of.push(index);
+        }
+
+        let results = join_all(
+            queries
+                .iter()
+                .map(|(start, end, group_by)| source.breakdown(*start, *end, group_by)),
+        )
+        .await;
+
+        self.budgets
+            .iter()
+            .zip(query_of)
+            .map(|(budget, index)| {
+                let data = results[index].as_ref().map_err(|e| {
+                    anyhow!(
+                        "Budget {:?}: no spend by {}: {:#}",
+                        budget.name,
+                        group_by_label(&queries[index].2),
+                        e
+                    )
+                })?;
+                budget.evaluate(data, today)
+            })
+            .collect()
+    }
+}
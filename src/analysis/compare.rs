@@ -0,0 +1,170 @@
+//! Period-over-period comparison of service costs
+
+use std::collections::HashMap;
+
+use crate::aws::{CostData, ServiceCost};
+
+/// How a service's cost moved between two periods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceChange {
+    /// Present in both periods, cost went up
+    Increased,
+    /// Present in both periods, cost went down
+    Decreased,
+    /// Present in both periods with no meaningful change
+    Unchanged,
+    /// Only present in the current period
+    New,
+    /// Only present in the baseline period
+    Removed,
+}
+
+/// Cost delta for a single service
+#[derive(Debug, Clone)]
+pub struct ServiceDelta {
+    pub service: String,
+    /// Cloud provider, for merged multi-cloud data
+    pub provider: Option<String>,
+    /// Baseline cost (already prorated if requested)
+    pub baseline: f64,
+    pub current: f64,
+    /// Absolute change (`current - baseline`)
+    pub delta: f64,
+    /// Percentage change, `None` when the baseline is zero
+    pub delta_pct: Option<f64>,
+    pub change: ServiceChange,
+}
+
+/// Side-by-side comparison of two cost periods
+#[derive(Debug, Clone)]
+pub struct CostComparison {
+    pub baseline_period: String,
+    pub current_period: String,
+    pub baseline_total: f64,
+    pub current_total: f64,
+    /// Factor applied to baseline costs when prorating, `None` if not prorated
+    pub prorate_factor: Option<f64>,
+    /// Per-service deltas, largest increase first
+    pub rows: Vec<ServiceDelta>,
+}
+
+/// Provider and service name a row is matched on
+type ServiceKey = (Option<String>, String);
+
+/// Amounts smaller than this (in currency units) round to zero cents: changes
+/// below it are treated as unchanged, and an unallocated remainder as none
+pub const CHANGE_EPSILON: f64 = 0.005;
+
+impl CostComparison {
+    /// Compare `current` against `baseline`, lining services up by provider
+    /// and name.
+    ///
+    /// With `prorate` set, baseline costs are scaled by the ratio of days
+    /// covered so a partial month can be compared against a full one at the
+    /// same daily rate.
+    pub fn new(baseline: &CostData, current: &CostData, prorate: bool) -> Self {
+        let factor = if prorate {
+            current.days() as f64 / baseline.days() as f64
+        } else {
+            1.0
+        };
+
+        let key = |s: &ServiceCost| (s.provider.clone(), s.service.clone());
+        let baseline_costs: HashMap<ServiceKey, f64> = baseline
+            .breakdown
+            .iter()
+            .map(|s| (key(s), s.cost * factor))
+            .collect();
+        let current_costs: HashMap<ServiceKey, f64> =
+            current.breakdown.iter().map(|s| (key(s), s.cost)).collect();
+
+        let mut rows: Vec<ServiceDelta> = current
+            .breakdown
+            .iter()
+            .map(key)
+            .chain(
+                baseline
+                    .breakdown
+                    .iter()
+                    .map(key)
+                    .filter(|k| !current_costs.contains_key(k)),
+            )
+            .map(|k| {
+                let old = baseline_costs.get(&k).copied();
+                let new = current_costs.get(&k).copied();
+                service_delta(k, old, new)
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            b.delta
+                .partial_cmp(&a.delta)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.service.cmp(&b.service))
+        });
+
+        Self {
+            baseline_period: baseline.period.clone(),
+            current_period: current.period.clone(),
+            baseline_total: baseline.total_cost * factor,
+            current_total: current.total_cost,
+            prorate_factor: prorate.then_some(factor),
+            rows,
+        }
+    }
+
+    /// Absolute change in total cost
+    pub fn total_delta(&self) -> f64 {
+        self.current_total - self.baseline_total
+    }
+
+    /// Percentage change in total cost, `None` when the baseline is zero
+    pub fn total_delta_pct(&self) -> Option<f64> {
+        percent_change(self.baseline_total, self.current_total)
+    }
+
+    /// Services that only appear in the current period
+    pub fn new_services(&self) -> impl Iterator<Item = &ServiceDelta> {
+        self.rows.iter().filter(|r| r.change == ServiceChange::New)
+    }
+
+    /// Services that disappeared in the current period
+    pub fn removed_services(&self) -> impl Iterator<Item = &ServiceDelta> {
+        self.rows
+            .iter()
+            .filter(|r| r.change == ServiceChange::Removed)
+    }
+}
+
+fn service_delta(key: ServiceKey, baseline: Option<f64>, current: Option<f64>) -> ServiceDelta {
+    let (provider, service) = key;
+    let old = baseline.unwrap_or(0.0);
+    let new = current.unwrap_or(0.0);
+    let delta = new - old;
+
+    let change = match (baseline, current) {
+        (None, Some(_)) => ServiceChange::New,
+        (Some(_), None) => ServiceChange::Removed,
+        _ if delta > CHANGE_EPSILON => ServiceChange::Increased,
+        _ if delta < -CHANGE_EPSILON => ServiceChange::Decreased,
+        _ => ServiceChange::Unchanged,
+    };
+
+    ServiceDelta {
+        service,
+        provider,
+        baseline: old,
+        current: new,
+        delta,
+        delta_pct: percent_change(old, new),
+        change,
+    }
+}
+
+fn percent_change(old: f64, new: f64) -> Option<f64> {
+    if old > 0.0 {
+        Some(((new - old) / old) * 100.0)
+    } else {
+        None
+    }
+}
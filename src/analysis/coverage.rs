@@ -0,0 +1,284 @@
+//! Tag coverage: how much spend carries each required cost allocation tag
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use futures::future::{join_all, try_join_all};
+use std::collections::HashMap;
+use std::io::Write;
+use tracing::warn;
+
+use super::tags::{tag_value, TagSpend};
+use crate::aws::{CostData, CostMatrix, Dimension, GroupBy};
+use crate::periods;
+use crate::source::CostSource;
+
+/// Tagged spend of one service or account
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageRow {
+    pub name: String,
+    /// Cloud provider, for merged multi-cloud data
+    pub provider: Option<String>,
+    pub cost: f64,
+    /// Spend carrying each required key, in [`TagCoverage::keys`] order
+    pub tagged: Vec<f64>,
+}
+
+impl CoverageRow {
+    /// Share of spend carrying the `index`th key, 0-100
+    pub fn coverage(&self, index: usize) -> f64 {
+        percentage(self.tagged.get(index).copied().unwrap_or(0.0), self.cost)
+    }
+
+    /// Lowest coverage across all keys
+    pub fn worst_coverage(&self) -> f64 {
+        (0..self.tagged.len())
+            .map(|i| self.coverage(i))
+            .fold(100.0, f64::min)
+    }
+
+    /// Spend missing the worst-covered key
+    pub fn untagged_cost(&self) -> f64 {
+        self.tagged
+            .iter()
+            .map(|tagged| (self.cost - tagged).max(0.0))
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Overall coverage of one month
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageMonth {
+    pub period: String,
+    pub start: NaiveDate,
+    pub total_cost: f64,
+    /// Coverage of each required key, 0-100
+    pub coverage: Vec<f64>,
+}
+
+/// Coverage of required tag keys by service and account
+#[derive(Debug, Clone)]
+pub struct TagCoverage {
+    /// Required tag keys
+    pub keys: Vec<String>,
+    pub period: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub total_cost: f64,
+    pub currency: String,
+    /// Spend carrying each key across all services
+    pub tagged: Vec<f64>,
+    /// Services, worst offenders first
+    pub services: Vec<CoverageRow>,
+    /// Linked accounts, worst offenders first
+    pub accounts: Vec<CoverageRow>,
+    /// Overall coverage by month, oldest first
+    pub trend: Vec<CoverageMonth>,
+}
+
+impl TagCoverage {
+    /// Build the report from one `X by TAG:key` matrix per key (in `keys`
+    /// order) for services and for accounts
+    pub fn new(
+        keys: &[String],
+        services: &[CostMatrix],
+        accounts: &[CostMatrix],
+        trend: Vec<CoverageMonth>,
+    ) -> Self {
+        let first = services.first();
+        let service_rows = coverage_rows(keys, services);
+        let tagged = (0..keys.len())
+            .map(|i| service_rows.iter().map(|row| row.tagged[i]).sum())
+            .collect();
+
+        Self {
+            keys: keys.to_vec(),
+            period: first.map(|m| m.period.clone()).unwrap_or_default(),
+            start: first.map_or(NaiveDate::MIN, |m| m.start),
+            end: first.map_or(NaiveDate::MIN, |m| m.end),
+            total_cost: first.map_or(0.0, |m| m.total_cost),
+            currency: first.map_or_else(|| "USD".to_string(), |m| m.currency.clone()),
+            tagged,
+            services: service_rows,
+            accounts: coverage_rows(keys, accounts),
+            trend,
+        }
+    }
+
+    /// Query `source` for coverage of `keys` over `[start, end)`, with the
+    /// overall coverage of each of the `months` ranges as the trend.
+    ///
+    /// Trend months that fail to load or have no spend are left out.
+    pub async fn load(
+        source: &dyn CostSource,
+        keys: &[String],
+        start: NaiveDate,
+        end: NaiveDate,
+        months: &[(NaiveDate, NaiveDate)],
+    ) -> Result<Self> {
+        let matrices = |dimension: Dimension| {
+            try_join_all(keys.iter().map(move |key| async move {
+                let (rows, columns) = (GroupBy::Dimension(dimension), GroupBy::Tag(key.clone()));
+                source.matrix(start, end, &rows, &columns).await
+            }))
+        };
+        let (services, accounts) = futures::try_join!(
+            matrices(Dimension::Service),
+            matrices(Dimension::LinkedAccount)
+        )?;
+
+        let trend = join_all(months.iter().map(|&(start, end)| async move {
+            let spend = try_join_all(keys.iter().map(|key| async move {
+                let group_by = GroupBy::Tag(key.clone());
+                source.breakdown(start, end, &group_by).await
+            }))
+            .await;
+            match spend {
+                Ok(spend) => CoverageMonth::new(keys, start, &spend),
+                Err(e) => {
+                    warn!("No tag coverage for {}: {}", periods::month_name(start), e);
+                    None
+                }
+            }
+        }))
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Ok(Self::new(keys, &services, &accounts, trend))
+    }
+
+    /// Share of all spend carrying the `index`th key, 0-100
+    pub fn coverage(&self, index: usize) -> f64 {
+        percentage(
+            self.tagged.get(index).copied().unwrap_or(0.0),
+            self.total_cost,
+        )
+    }
+
+    /// Change in the `index`th key's coverage since the month before the
+    /// latest trend month, in percentage points
+    pub fn change(&self, index: usize) -> Option<f64> {
+        let [.., previous, latest] = self.trend.as_slice() else {
+            return None;
+        };
+        Some(latest.coverage.get(index)? - previous.coverage.get(index)?)
+    }
+
+    /// Write the report as CSV: a `total` row, then `service`, `account` and
+    /// `month` rows, each with a coverage and untagged cost column per key
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut csv = csv::Writer::from_writer(writer);
+
+        let mut header = vec![
+            "scope".to_string(),
+            "name".to_string(),
+            "provider".to_string(),
+            "cost".to_string(),
+        ];
+        for key in &self.keys {
+            header.push(format!("{}_coverage_pct", key));
+            header.push(format!("{}_untagged_cost", key));
+        }
+        csv.write_record(&header)?;
+
+        let mut write =
+            |scope: &str, name: &str, provider: Option<&str>, cost: f64, tagged: &[f64]| {
+                let mut record = vec![
+                    scope.to_string(),
+                    name.to_string(),
+                    provider.unwrap_or_default().to_string(),
+                    format!("{:.2}", cost),
+                ];
+                for &tagged in tagged {
+                    record.push(format!("{:.2}", percentage(tagged, cost)));
+                    record.push(format!("{:.2}", (cost - tagged).max(0.0)));
+                }
+                csv.write_record(&record)
+            };
+
+        write("total", &self.period, None, self.total_cost, &self.tagged)?;
+        for (scope, rows) in [("service", &self.services), ("account", &self.accounts)] {
+            for row in rows {
+                write(
+                    scope,
+                    &row.name,
+                    row.provider.as_deref(),
+                    row.cost,
+                    &row.tagged,
+                )?;
+            }
+        }
+        for month in &self.trend {
+            let tagged: Vec<f64> = month
+                .coverage
+                .iter()
+                .map(|coverage| month.total_cost * coverage / 100.0)
+                .collect();
+            write("month", &month.period, None, month.total_cost, &tagged)?;
+        }
+
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+impl CoverageMonth {
+    /// Coverage from one tag breakdown per key, `None` for a month without
+    /// spend
+    pub fn new(keys: &[String], start: NaiveDate, spend: &[CostData]) -> Option<Self> {
+        let first = spend.first().filter(|data| data.total_cost > 0.0)?;
+        Some(Self {
+            period: periods::month_name(start),
+            start,
+            total_cost: first.total_cost,
+            coverage: keys
+                .iter()
+                .zip(spend)
+                .map(|(key, data)| 100.0 - TagSpend::new(key, data).untagged_percentage())
+                .collect(),
+        })
+    }
+}
+
+/// Sum each matrix's tagged cells into rows, ranked by untagged spend and
+/// then by coverage
+fn coverage_rows(keys: &[String], matrices: &[CostMatrix]) -> Vec<CoverageRow> {
+    let mut rows: HashMap<(Option<String>, String), CoverageRow> = HashMap::new();
+    for (index, (key, matrix)) in keys.iter().zip(matrices).enumerate() {
+        for cell in &matrix.cells {
+            let row = rows
+                .entry((cell.provider.clone(), cell.row.clone()))
+                .or_insert_with(|| CoverageRow {
+                    name: cell.row.clone(),
+                    provider: cell.provider.clone(),
+                    cost: 0.0,
+                    tagged: vec![0.0; keys.len()],
+                });
+            // Every matrix splits the same spend, so count it once
+            if index == 0 {
+                row.cost += cell.cost;
+            }
+            if tag_value(key, &cell.column).is_some() {
+                row.tagged[index] += cell.cost;
+            }
+        }
+    }
+
+    let mut rows: Vec<CoverageRow> = rows.into_values().collect();
+    rows.sort_by(|a, b| {
+        b.untagged_cost()
+            .total_cmp(&a.untagged_cost())
+            .then(a.worst_coverage().total_cmp(&b.worst_coverage()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    rows
+}
+
+fn percentage(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        (part / total * 100.0).min(100.0)
+    } else {
+        100.0
+    }
+}
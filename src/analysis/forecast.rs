@@ -0,0 +1,335 @@
+//! Local cost forecasts from daily per-service costs
+//!
+//! Works on accounts too new for Cost Explorer's own forecasts, and with CUR
+//! and offline sources. Each service's recent days are fitted with one
+//! [`Method`] and projected to the end of the month and of the quarter, with
+//! a band from how far the fit strays from the days it was fitted to.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::aws::{DailyCosts, DailySeries};
+use crate::periods;
+use crate::source::CostSource;
+
+/// Half-width of a two-sided 90% band, in standard deviations
+const BAND_Z: f64 = 1.645;
+
+/// Most recent days held back to pick a method automatically
+const BACKTEST_DAYS: usize = 7;
+
+/// Weight of each new day in exponential smoothing
+const SMOOTHING_ALPHA: f64 = 0.3;
+
+/// How the days ahead are projected from the days behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Least-squares line through the window, continued
+    RunRate,
+    /// Average of each weekday over the window
+    Weekday,
+    /// Exponentially weighted level, recent days counting most
+    Smoothing,
+}
+
+impl Method {
+    /// Every method, in the order ties are broken when picking one
+    pub const ALL: [Method; 3] = [Method::RunRate, Method::Weekday, Method::Smoothing];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::RunRate => "linear run-rate",
+            Method::Weekday => "weekday-seasonal",
+            Method::Smoothing => "exponential smoothing",
+        }
+    }
+
+    /// Costs of the `days` days after `history`, whose first day is `first`,
+    /// and the standard deviation of one day's error
+    fn project(&self, history: &[f64], first: NaiveDate, days: usize) -> (Vec<f64>, f64) {
+        if history.is_empty() {
+            return (vec![0.0; days], 0.0);
+        }
+        let n = history.len();
+        let (predict, residuals): (Box<dyn Fn(usize) -> f64>, Vec<f64>) = match self {
+            Method::RunRate => {
+                let mean_x = (n - 1) as f64 / 2.0;
+                let mean_y = mean(history);
+                let (mut sxy, mut sxx) = (0.0, 0.0);
+                for (x, y) in history.iter().enumerate() {
+                    sxy += (x as f64 - mean_x) * (y - mean_y);
+                    sxx += (x as f64 - mean_x).powi(2);
+                }
+                let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+                let line = move |x: usize| mean_y + slope * (x as f64 - mean_x);
+                let residuals = history
+                    .iter()
+                    .enumerate()
+                    .map(|(x, y)| y - line(x))
+                    .collect();
+                (Box::new(line), residuals)
+            }
+            Method::Weekday => {
+                let weekday = move |x: usize| {
+                    (first + Duration::days(x as i64))
+                        .weekday()
+                        .num_days_from_monday() as usize
+                };
+                let overall = mean(history);
+                let means: Vec<f64> = (0..7)
+                    .map(|day| {
+                        let values: Vec<f64> = (0..n)
+                            .filter(|&x| weekday(x) == day)
+                            .map(|x| history[x])
+                            .collect();
+                        if values.is_empty() {
+                            overall
+                        } else {
+                            mean(&values)
+                        }
+                    })
+                    .collect();
+                let residuals = (0..n).map(|x| history[x] - means[weekday(x)]).collect();
+                (Box::new(move |x| means[weekday(x)]), residuals)
+            }
+            Method::Smoothing => {
+                let mut level = history[0];
+                let mut errors = Vec::with_capacity(n - 1);
+                for &cost in &history[1..] {
+                    errors.push(cost - level);
+                    level += SMOOTHING_ALPHA * (cost - level);
+                }
+                (Box::new(move |_| level), errors)
+            }
+        };
+
+        let spread = if residuals.is_empty() {
+            0.0
+        } else {
+            (residuals.iter().map(|r| r * r).sum::<f64>() / residuals.len() as f64).sqrt()
+        };
+        let costs = (n..n + days).map(|x| predict(x).max(0.0)).collect();
+        (costs, spread)
+    }
+}
+
+/// Settings for projecting costs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Forecaster {
+    /// Method to use, `None` to pick the one that best predicts the last
+    /// week
+    pub method: Option<Method>,
+    /// Days of history each projection is fitted to
+    pub window: usize,
+}
+
+impl Default for Forecaster {
+    fn default() -> Self {
+        Self {
+            method: None,
+            window: 28,
+        }
+    }
+}
+
+impl Forecaster {
+    /// Project `daily` from `today` on: days before it are actual costs
+    pub fn forecast(&self, daily: &DailyCosts, today: NaiveDate) -> Result<Forecast> {
+        let month_start = periods::month_start(today)?;
+        let month_end = periods::next_month_start(today)?;
+        let quarter_start = periods::quarter_start(today)?;
+        let quarter_end = periods::next_quarter_start(today)?;
+
+        // Costs are projected from the last day with data, which is today
+        // unless the data stops short of it
+        let history_end = today.clamp(daily.start, daily.end.max(daily.start));
+        let history_start = (history_end - Duration::days(self.window as i64)).max(daily.start);
+        let method = self
+            .method
+            .unwrap_or_else(|| self.pick_method(daily, history_start, history_end));
+
+        let mut services: Vec<ServiceForecast> = daily
+            .series
+            .iter()
+            .map(|series| {
+                let history = days(daily, series, history_start, history_end);
+                let ahead = (quarter_end - history_end).num_days().max(0) as usize;
+                let (projected, spread) = method.project(history, history_start, ahead);
+                let projection = |start: NaiveDate, end: NaiveDate| {
+                    let days_ahead = (end - history_end).num_days().max(0) as usize;
+                    Projection {
+                        actual: days(daily, series, start, history_end).iter().sum(),
+                        projected: projected[..days_ahead].iter().sum(),
+                        margin: BAND_Z * spread * (days_ahead as f64).sqrt(),
+                    }
+                };
+                ServiceForecast {
+                    service: series.service.clone(),
+                    provider: series.provider.clone(),
+                    month: projection(month_start, month_end),
+                    quarter: projection(quarter_start, quarter_end),
+                }
+            })
+            .filter(|s| s.quarter.total() > 0.001)
+            .collect();
+        services.sort_by(|a, b| b.month.total().total_cmp(&a.month.total()));
+
+        Ok(Forecast {
+            method,
+            window: self.window,
+            today: history_end,
+            month_end,
+            quarter_end,
+            currency: daily.currency.clone(),
+            month: Projection::sum(services.iter().map(|s| &s.month)),
+            quarter: Projection::sum(services.iter().map(|s| &s.quarter)),
+            services,
+        })
+    }
+
+    /// The method whose projection of the last [`BACKTEST_DAYS`] of the
+    /// overall daily cost, fitted to the days before them, was closest
+    fn pick_method(&self, daily: &DailyCosts, start: NaiveDate, end: NaiveDate) -> Method {
+        let total: Vec<f64> = (0..(end - start).num_days().max(0) as usize)
+            .map(|day| {
+                let date = start + Duration::days(day as i64);
+                daily
+                    .series
+                    .iter()
+                    .flat_map(|series| days(daily, series, date, date + Duration::days(1)))
+                    .sum()
+            })
+            .collect();
+        if total.len() < BACKTEST_DAYS * 2 {
+            return Method::RunRate;
+        }
+
+        let (fit, held_back) = total.split_at(total.len() - BACKTEST_DAYS);
+        let error = |method: &Method| {
+            let (projected, _) = method.project(fit, start, BACKTEST_DAYS);
+            projected
+                .iter()
+                .zip(held_back)
+                .map(|(p, a)| (p - a).abs())
+                .sum::<f64>()
+        };
+        Method::ALL
+            .into_iter()
+            .min_by(|a, b| error(a).total_cmp(&error(b)))
+            .unwrap_or(Method::RunRate)
+    }
+}
+
+/// Spend over a period: actual so far plus the projection of the rest
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Projection {
+    /// Cost of the days already past
+    pub actual: f64,
+    /// Projected cost of the days still ahead
+    pub projected: f64,
+    /// Half-width of the 90% band around the projected cost
+    pub margin: f64,
+}
+
+impl Projection {
+    /// Projected cost of the whole period
+    pub fn total(&self) -> f64 {
+        self.actual + self.projected
+    }
+
+    /// Bottom of the band; never below what was already spent
+    pub fn low(&self) -> f64 {
+        (self.total() - self.margin).max(self.actual)
+    }
+
+    /// Top of the band
+    pub fn high(&self) -> f64 {
+        self.total() + self.margin
+    }
+
+    /// Sum of projections, taking their errors to be independent
+    fn sum<'a>(projections: impl Iterator<Item = &'a Projection>) -> Self {
+        let mut sum = Projection::default();
+        for p in projections {
+            sum.actual += p.actual;
+            sum.projected += p.projected;
+            sum.margin += p.margin * p.margin;
+        }
+        sum.margin = sum.margin.sqrt();
+        sum
+    }
+}
+
+/// One service's projected month and quarter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceForecast {
+    pub service: String,
+    /// Cloud provider, for merged multi-cloud data
+    pub provider: Option<String>,
+    pub month: Projection,
+    pub quarter: Projection,
+}
+
+/// Month-end and quarter-end projections, overall and per service
+#[derive(Debug, Clone)]
+pub struct Forecast {
+    pub method: Method,
+    /// Days of history the projections were fitted to
+    pub window: usize,
+    /// First projected day; days before it are actual costs
+    pub today: NaiveDate,
+    /// Day after the last day of the month (exclusive)
+    pub month_end: NaiveDate,
+    /// Day after the last day of the quarter (exclusive)
+    pub quarter_end: NaiveDate,
+    pub currency: String,
+    pub month: Projection,
+    pub quarter: Projection,
+    /// Biggest projected month first
+    pub services: Vec<ServiceForecast>,
+}
+
+impl Forecast {
+    /// Project from `today` on, fetching the quarter so far and the
+    /// forecaster's window of history
+    pub async fn load(
+        source: &dyn CostSource,
+        forecaster: Forecaster,
+        today: NaiveDate,
+    ) -> Result<Self> {
+        let daily = source
+            .daily(Self::history_start(forecaster, today)?, today)
+            .await?;
+        forecaster.forecast(&daily, today)
+    }
+
+    /// First day of daily costs a forecast from `today` needs
+    pub fn history_start(forecaster: Forecaster, today: NaiveDate) -> Result<NaiveDate> {
+        let window = today - Duration::days(forecaster.window as i64);
+        Ok(periods::quarter_start(today)?.min(window))
+    }
+
+    /// Projection of one service, if it has spend this quarter
+    pub fn of_service(&self, service: &str, provider: Option<&str>) -> Option<&ServiceForecast> {
+        self.services
+            .iter()
+            .find(|s| s.service == service && s.provider.as_deref() == provider)
+    }
+}
+
+/// `series`' costs on the days of `[start, end)` covered by `daily`
+fn days<'a>(
+    daily: &DailyCosts,
+    series: &'a DailySeries,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> &'a [f64] {
+    let index =
+        |date: NaiveDate| ((date - daily.start).num_days().max(0) as usize).min(series.costs.len());
+    let (start, end) = (index(start), index(end));
+    &series.costs[start..end.max(start)]
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
@@ -0,0 +1,19 @@
+//! Analysis helpers that work on already-fetched cost data
+
+pub mod accounts;
+pub mod allocation;
+pub mod anomaly;
+pub mod budgets;
+pub mod compare;
+pub mod coverage;
+pub mod forecast;
+pub mod tags;
+
+pub use accounts::{AccountServiceRow, AccountTotal, AccountsReport};
+pub use allocation::{Allocation, AllocationRules, CostLine, Reconciliation, Split, TeamCost};
+pub use anomaly::{Anomalies, Anomaly, Baseline, Detector};
+pub use budgets::{LocalBudget, LocalBudgets, Scope, ScopeValue};
+pub use compare::{CostComparison, ServiceChange, ServiceDelta, CHANGE_EPSILON};
+pub use coverage::{CoverageMonth, CoverageRow, TagCoverage};
+pub use forecast::{Forecast, Forecaster, Method, Projection, ServiceForecast};
+pub use tags::{TagSpend, TagValueCost};
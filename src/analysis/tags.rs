@@ -0,0 +1,76 @@
+//! Spend by cost allocation tag value, and how much of it is untagged
+
+use crate::aws::CostData;
+
+/// Spend of one value of a tag key
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagValueCost {
+    /// Tag value, `None` for spend without the tag
+    pub value: Option<String>,
+    /// Cloud provider, for merged multi-cloud data
+    pub provider: Option<String>,
+    pub cost: f64,
+    pub percentage: f64,
+}
+
+/// A [`crate::aws::GroupBy::Tag`] breakdown split into tag values
+#[derive(Debug, Clone)]
+pub struct TagSpend {
+    pub key: String,
+    pub period: String,
+    pub total_cost: f64,
+    pub currency: String,
+    /// Values by cost descending, untagged spend included
+    pub values: Vec<TagValueCost>,
+}
+
+impl TagSpend {
+    /// Read `data`'s `key$value` rows; an empty value (or a row from a
+    /// source without the tag) counts as untagged
+    pub fn new(key: &str, data: &CostData) -> Self {
+        let values = data
+            .breakdown
+            .iter()
+            .map(|row| TagValueCost {
+                value: tag_value(key, &row.service).map(str::to_string),
+                provider: row.provider.clone(),
+                cost: row.cost,
+                percentage: row.percentage,
+            })
+            .collect();
+
+        Self {
+            key: key.to_string(),
+            period: data.period.clone(),
+            total_cost: data.total_cost,
+            currency: data.currency.clone(),
+            values,
+        }
+    }
+
+    /// Spend without the tag
+    pub fn untagged_cost(&self) -> f64 {
+        self.values
+            .iter()
+            .filter(|v| v.value.is_none())
+            .map(|v| v.cost)
+            .sum()
+    }
+
+    /// Share of spend without the tag, 0-100
+    pub fn untagged_percentage(&self) -> f64 {
+        if self.total_cost > 0.0 {
+            self.untagged_cost() / self.total_cost * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Value of a Cost Explorer `key$value` group label, `None` when untagged
+pub fn tag_value<'a>(key: &str, label: &'a str) -> Option<&'a str> {
+    label
+        .strip_prefix(key)
+        .and_then(|rest| rest.strip_prefix('$'))
+        .filter(|value| !value.is_empty())
+}
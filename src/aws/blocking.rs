@@ -0,0 +1,115 @@
+//! Blocking wrapper around the async Cost Explorer client
+//!
+//! Owns a small current-thread tokio runtime and drives each call to
+//! completion. Don't use it from inside another tokio runtime; call the
+//! async [`super::CostExplorerClient`] directly there instead.
+
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use tokio::runtime::{Builder, Runtime};
+
+use super::cost_explorer::{GetCostAndUsageResponse, MonthlyTrend};
+use super::{CostData, CostMatrix, CostQuery, Credentials, DailyCosts, Dimension};
+
+/// Synchronous Cost Explorer client
+pub struct CostExplorerClient {
+    inner: super::CostExplorerClient,
+    runtime: Runtime,
+}
+
+impl CostExplorerClient {
+    /// Create a new blocking Cost Explorer client
+    pub fn new(credentials: Credentials) -> Result<Self> {
+        Self::from_async(super::CostExplorerClient::new(credentials))
+    }
+
+    /// Wrap an already configured async client
+    pub fn from_async(inner: super::CostExplorerClient) -> Result<Self> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| anyhow!("Failed to create tokio runtime: {}", e))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Get cost and usage data
+    pub fn get_cost_and_usage(&self, query: &CostQuery) -> Result<GetCostAndUsageResponse> {
+        self.runtime.block_on(self.inner.get_cost_and_usage(query))
+    }
+
+    /// Run a query and aggregate it into [`CostData`]
+    pub fn get_cost_data(&self, query: &CostQuery, period_name: &str) -> Result<CostData> {
+        self.runtime
+            .block_on(self.inner.get_cost_data(query, period_name))
+    }
+
+    /// Run a query with two groupings into a [`CostMatrix`]
+    pub fn get_cost_matrix(&self, query: &CostQuery, period_name: &str) -> Result<CostMatrix> {
+        self.runtime
+            .block_on(self.inner.get_cost_matrix(query, period_name))
+    }
+
+    /// Get daily costs of every service over `[start, end)`
+    pub fn get_daily_costs(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        self.runtime.block_on(self.inner.get_daily_costs(start, end))
+    }
+
+    /// Get monthly costs broken down by service for the current month
+    pub fn get_current_month_costs(&self) -> Result<CostData> {
+        self.runtime.block_on(self.inner.get_current_month_costs())
+    }
+
+    /// Get costs for the previous month
+    pub fn get_previous_month_costs(&self) -> Result<CostData> {
+        self.runtime.block_on(self.inner.get_previous_month_costs())
+    }
+
+    /// Get the last N calendar months of costs
+    pub fn get_monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        self.runtime.block_on(self.inner.get_monthly_trend(months))
+    }
+
+    /// Get the costs of a service's resources over the last 14 days at most
+    pub fn get_resource_costs(
+        &self,
+        service: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<CostData> {
+        self.runtime
+            .block_on(self.inner.get_resource_costs(service, start, end))
+    }
+
+    /// List tag keys with spend in `[start, end)`, or the values of `tag_key`
+    pub fn get_tags(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        tag_key: Option<&str>,
+    ) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.get_tags(start, end, tag_key))
+    }
+
+    /// List the values of a dimension with usage in `[start, end)`
+    pub fn get_dimension_values(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        dimension: Dimension,
+    ) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.get_dimension_values(start, end, dimension))
+    }
+
+    /// List cost category names, or the values of `name`
+    pub fn get_cost_categories(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        name: Option<&str>,
+    ) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(self.inner.get_cost_categories(start, end, name))
+    }
+}
@@ -0,0 +1,560 @@
+//! AWS Budgets API client with SigV4 signing
+//!
+//! Budgets are account-scoped, so the account ID is looked up once with STS
+//! `GetCallerIdentity` unless it is given up front.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Datelike, Local, Months, NaiveDate};
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+use super::concurrency::{
+    is_throttled, throttle_backoff, RequestLimiter, DEFAULT_MAX_CONCURRENCY, MAX_THROTTLE_RETRIES,
+};
+use super::recording::{Capture, Recorder, Replayer};
+use super::signing::{send_signed, Endpoint};
+use super::Credentials;
+use crate::periods;
+
+/// Share of the limit at which a budget is flagged, in percent
+pub const WARNING_PERCENTAGE: f64 = 80.0;
+
+/// A budget and its spend in the current period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Budget {
+    pub name: String,
+    /// `COST`, `USAGE`, `RI_UTILIZATION`, ...
+    pub budget_type: String,
+    /// `DAILY`, `MONTHLY`, `QUARTERLY` or `ANNUALLY`
+    pub time_unit: String,
+    /// First day of the current period (inclusive)
+    pub start: NaiveDate,
+    /// Day after the last day of the current period (exclusive)
+    pub end: NaiveDate,
+    pub limit: f64,
+    /// Currency, or the usage unit of usage budgets
+    pub unit: String,
+    /// Spend so far this period
+    pub actual: f64,
+    /// Spend expected by the end of the period
+    #[serde(default)]
+    pub forecasted: Option<f64>,
+    /// Limit and spend of past periods, oldest first
+    #[serde(default)]
+    pub history: Vec<BudgetPeriod>,
+    /// Alert thresholds in percent of the limit, lowest first; empty for
+    /// the default [`WARNING_PERCENTAGE`]
+    #[serde(default)]
+    pub thresholds: Vec<f64>,
+    /// What a budget from the config covers, e.g. "service Amazon EC2";
+    /// `None` for AWS Budgets
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+/// Limit and spend of one budget period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetPeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub limit: f64,
+    pub actual: f64,
+}
+
+/// How a budget is doing, worst last
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BudgetStatus {
+    OnTrack,
+    /// Spend has passed the lowest alert threshold below the limit
+    Warning,
+    /// Spend is forecast to pass the limit
+    ForecastOver,
+    /// Spend has passed the limit
+    Exceeded,
+}
+
+impl Budget {
+    /// Spend so far as a share of the limit, in percent
+    pub fn percentage(&self) -> f64 {
+        percentage(self.actual, self.limit)
+    }
+
+    /// Forecast spend as a share of the limit, in percent
+    pub fn forecast_percentage(&self) -> Option<f64> {
+        self.forecasted.map(|f| percentage(f, self.limit))
+    }
+
+    /// Display name of the current period, e.g. "March 2026" or "Q1 2026"
+    pub fn period_name(&self) -> String {
+        match self.time_unit.as_str() {
+            "QUARTERLY" => periods::quarter_name(self.start),
+            "ANNUALLY" => self.start.year().to_string(),
+            _ => periods::range_name(self.start, self.end),
+        }
+    }
+
+    /// Whether the budget is defined in the config rather than in AWS
+    pub fn is_local(&self) -> bool {
+        self.scope.is_some()
+    }
+
+    /// Alert thresholds in percent of the limit
+    pub fn alert_thresholds(&self) -> &[f64] {
+        if self.thresholds.is_empty() {
+            &[WARNING_PERCENTAGE, 100.0]
+        } else {
+            &self.thresholds
+        }
+    }
+
+    /// Share of the limit at which the budget is flagged, in percent
+    pub fn warning_percentage(&self) -> f64 {
+        self.alert_thresholds()
+            .iter()
+            .copied()
+            .filter(|&t| t < 100.0)
+            .reduce(f64::min)
+            .unwrap_or(100.0)
+    }
+
+    /// Highest alert threshold spend has passed, if any
+    pub fn crossed_threshold(&self) -> Option<f64> {
+        let percentage = self.percentage();
+        self.alert_thresholds()
+            .iter()
+            .copied()
+            .filter(|&t| percentage >= t)
+            .reduce(f64::max)
+    }
+
+    pub fn status(&self) -> BudgetStatus {
+        if self.percentage() >= 100.0 {
+            BudgetStatus::Exceeded
+        } else if self.forecast_percentage().is_some_and(|p| p >= 100.0) {
+            BudgetStatus::ForecastOver
+        } else if self.percentage() >= self.warning_percentage() {
+            BudgetStatus::Warning
+        } else {
+            BudgetStatus::OnTrack
+        }
+    }
+}
+
+impl BudgetStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetStatus::OnTrack => "on track",
+            BudgetStatus::Warning => "warning",
+            BudgetStatus::ForecastOver => "forecast over",
+            BudgetStatus::Exceeded => "exceeded",
+        }
+    }
+}
+
+/// AWS Budgets API client
+pub struct BudgetsClient {
+    credentials: Credentials,
+    client: Client,
+    limiter: RequestLimiter,
+    account_id: OnceCell<String>,
+    /// Fixed "today" for the current budget period, defaults to the local date
+    today: Option<NaiveDate>,
+    capture: Option<Capture>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DescribeBudgetsResponse {
+    #[serde(default)]
+    budgets: Vec<BudgetResponse>,
+    next_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BudgetResponse {
+    budget_name: String,
+    budget_type: String,
+    time_unit: String,
+    budget_limit: Option<Spend>,
+    calculated_spend: Option<CalculatedSpend>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Spend {
+    amount: String,
+    unit: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CalculatedSpend {
+    actual_spend: Option<Spend>,
+    forecasted_spend: Option<Spend>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PerformanceHistoryResponse {
+    budget_performance_history: Option<PerformanceHistory>,
+    next_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PerformanceHistory {
+    #[serde(default)]
+    budgeted_and_actual_amounts_list: Vec<BudgetedAndActual>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BudgetedAndActual {
+    budgeted_amount: Option<Spend>,
+    actual_amount: Option<Spend>,
+    time_period: Option<EpochPeriod>,
+}
+
+/// A time period as epoch seconds, the way the Budgets API sends it
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EpochPeriod {
+    start: f64,
+    end: f64,
+}
+
+impl BudgetsClient {
+    /// Create a new Budgets client
+    pub fn new(credentials: Credentials) -> Self {
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            credentials,
+            client,
+            limiter: RequestLimiter::new(DEFAULT_MAX_CONCURRENCY),
+            account_id: OnceCell::new(),
+            today: None,
+            capture: None,
+        }
+    }
+
+    /// Use `account_id` instead of looking it up with STS
+    pub fn with_account_id(self, account_id: impl Into<String>) -> Self {
+        let _ = self.account_id.set(account_id.into());
+        self
+    }
+
+    /// Pin the date the current budget period is worked out from
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = Some(today);
+        self
+    }
+
+    /// Write every request and response to `recorder`
+    pub fn record_to(mut self, recorder: Recorder) -> Self {
+        self.capture = Some(Capture::Record(recorder));
+        self
+    }
+
+    /// Serve responses from a recording instead of the network. "Today" is
+    /// pinned to the recording date unless already set.
+    pub fn replay_from(mut self, replayer: Replayer) -> Self {
+        self.today.get_or_insert(replayer.manifest().recorded_on);
+        self.capture = Some(Capture::Replay(replayer));
+        self
+    }
+
+    /// The date the current budget period is worked out from
+    pub fn today(&self) -> NaiveDate {
+        self.today.unwrap_or_else(|| Local::now().date_naive())
+    }
+
+    /// ID of the account the credentials belong to
+    pub async fn account_id(&self) -> Result<&str> {
+        self.account_id
+            .get_or_try_init(|| async {
+                let body = self
+                    .execute_request(
+                        &Endpoint {
+                            host: "sts.amazonaws.com",
+                            region: "us-east-1",
+                            service: "sts",
+                        },
+                        "GetCallerIdentity",
+                        &[
+                            (
+                                "content-type",
+                                "application/x-www-form-urlencoded; charset=utf-8",
+                            ),
+                            ("accept", "application/json"),
+                        ],
+                        // Recorded as JSON, sent form-encoded
+                        &json!({ "Action": "GetCallerIdentity", "Version": "2011-06-15" }),
+                        "Action=GetCallerIdentity&Version=2011-06-15",
+                    )
+                    .await?;
+                let response: Value = serde_json::from_str(&body)
+                    .map_err(|e| anyhow!("Failed to parse response: {} - Body: {}", e, body))?;
+                response["GetCallerIdentityResponse"]["GetCallerIdentityResult"]["Account"]
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("GetCallerIdentity returned no account: {}", body))
+            })
+            .await
+            .map(String::as_str)
+    }
+
+    /// Every budget of the account with its spend this period, following
+    /// `NextToken` for you. Budgets without a fixed limit are skipped.
+    pub async fn describe_budgets(&self) -> Result<Vec<Budget>> {
+        let mut request = json!({ "AccountId": self.account_id().await?, "MaxResults": 100 });
+        let today = self.today();
+        let mut budgets = Vec::new();
+        loop {
+            let body = self.budgets_request("DescribeBudgets", &request).await?;
+            let page: DescribeBudgetsResponse = serde_json::from_str(&body)
+                .map_err(|e| anyhow!("Failed to parse response: {} - Body: {}", e, body))?;
+
+            for budget in page.budgets {
+                let Some(limit) = budget.budget_limit else {
+                    debug!("Skipping budget {} without a limit", budget.budget_name);
+                    continue;
+                };
+                let (start, end) = budget_period(&budget.time_unit, today)?;
+                let spend = budget.calculated_spend;
+                budgets.push(Budget {
+                    name: budget.budget_name,
+                    budget_type: budget.budget_type,
+                    time_unit: budget.time_unit,
+                    start,
+                    end,
+                    limit: amount(&limit)?,
+                    unit: limit.unit,
+                    actual: match spend.as_ref().and_then(|s| s.actual_spend.as_ref()) {
+                        Some(actual) => amount(actual)?,
+                        None => 0.0,
+                    },
+                    forecasted: spend
+                        .as_ref()
+                        .and_then(|s| s.forecasted_spend.as_ref())
+                        .map(amount)
+                        .transpose()?,
+                    history: Vec::new(),
+                    thresholds: Vec::new(),
+                    scope: None,
+                });
+            }
+
+            match page.next_token {
+                Some(token) if !token.is_empty() => request["NextToken"] = json!(token),
+                _ => return Ok(budgets),
+            }
+        }
+    }
+
+    /// Limit and spend of each period of the budget `name` in
+    /// `[start, end)`, oldest first
+    pub async fn describe_budget_performance_history(
+        &self,
+        name: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<BudgetPeriod>> {
+        let mut request = json!({
+            "AccountId": self.account_id().await?,
+            "BudgetName": name,
+            "TimePeriod": { "Start": epoch(start), "End": epoch(end) },
+            "MaxResults": 100,
+        });
+        let mut periods = Vec::new();
+        loop {
+            let body = self
+                .budgets_request("DescribeBudgetPerformanceHistory", &request)
+                .await?;
+            let page: PerformanceHistoryResponse = serde_json::from_str(&body)
+                .map_err(|e| anyhow!("Failed to parse response: {} - Body: {}", e, body))?;
+
+            let amounts = page
+                .budget_performance_history
+                .map(|h| h.budgeted_and_actual_amounts_list)
+                .unwrap_or_default();
+            for entry in amounts {
+                let Some(period) = entry.time_period else {
+                    continue;
+                };
+                periods.push(BudgetPeriod {
+                    start: date(period.start)?,
+                    end: date(period.end)?,
+                    limit: entry
+                        .budgeted_amount
+                        .as_ref()
+                        .map(amount)
+                        .transpose()?
+                        .unwrap_or(0.0),
+                    actual: entry
+                        .actual_amount
+                        .as_ref()
+                        .map(amount)
+                        .transpose()?
+                        .unwrap_or(0.0),
+                });
+            }
+
+            match page.next_token {
+                Some(token) if !token.is_empty() => request["NextToken"] = json!(token),
+                _ => break,
+            }
+        }
+        periods.sort_by_key(|p| p.start);
+        Ok(periods)
+    }
+
+    /// Every budget with its history over the last `months` months. A budget
+    /// whose history fails to load is kept without it.
+    pub async fn get_budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        let mut budgets = self.describe_budgets().await?;
+        let end = periods::next_month_start(self.today())?;
+        let start = end
+            .checked_sub_months(Months::new(months))
+            .ok_or_else(|| anyhow!("Failed to calculate budget history start"))?;
+
+        let histories = join_all(
+            budgets
+                .iter()
+                .map(|b| self.describe_budget_performance_history(&b.name, start, end)),
+        )
+        .await;
+        for (budget, history) in budgets.iter_mut().zip(histories) {
+            match history {
+                Ok(history) => budget.history = history,
+                Err(e) => warn!("No history for budget {}: {}", budget.name, e),
+            }
+        }
+        Ok(budgets)
+    }
+
+    /// Send a Budgets API action
+    async fn budgets_request(&self, action: &str, request: &Value) -> Result<String> {
+        let target = format!("AWSBudgetServiceGateway.{}", action);
+        self.execute_request(
+            &Endpoint {
+                host: "budgets.amazonaws.com",
+                region: "us-east-1",
+                service: "budgets",
+            },
+            action,
+            &[
+                ("content-type", "application/x-amz-json-1.1"),
+                ("x-amz-target", target.as_str()),
+            ],
+            request,
+            &request.to_string(),
+        )
+        .await
+    }
+
+    /// Execute a request, waiting for a concurrency slot and retrying with
+    /// backoff when throttled. `request` is what a recording matches on.
+    async fn execute_request(
+        &self,
+        endpoint: &Endpoint<'_>,
+        action: &str,
+        headers: &[(&str, &str)],
+        request: &Value,
+        body: &str,
+    ) -> Result<String> {
+        let request = request.to_string();
+        let mut attempt = 0;
+        loop {
+            let outcome = match &self.capture {
+                Some(Capture::Replay(replayer)) => replayer.respond(action, &request),
+                _ => {
+                    let _permit = self.limiter.acquire().await;
+                    debug!("Executing {} API request: {}", endpoint.service, action);
+                    send_signed(&self.client, &self.credentials, endpoint, headers, body)
+                        .await
+                        .map(|(status, response)| (status.as_u16(), response))
+                }
+            };
+            if let (Some(Capture::Record(recorder)), Ok((status, response))) =
+                (&self.capture, &outcome)
+            {
+                if let Err(e) = recorder.record(action, &request, *status, response) {
+                    warn!("Failed to record {} exchange: {}", action, e);
+                }
+            }
+
+            let (status, response_body) = outcome?;
+            if (200..300).contains(&status) {
+                return Ok(response_body);
+            }
+            if attempt < MAX_THROTTLE_RETRIES && is_throttled(status, &response_body) {
+                let delay = throttle_backoff(attempt);
+                warn!("{} throttled, retrying in {:?}", action, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+            bail!(
+                "API request failed with status {}: {}",
+                status,
+                response_body
+            );
+        }
+    }
+}
+
+/// The budget period of `time_unit` containing `today`
+pub fn budget_period(time_unit: &str, today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    match time_unit {
+        "DAILY" => Ok((today, today + chrono::Duration::days(1))),
+        "MONTHLY" => Ok((
+            periods::month_start(today)?,
+            periods::next_month_start(today)?,
+        )),
+        "QUARTERLY" => Ok((
+            periods::quarter_start(today)?,
+            periods::next_quarter_start(today)?,
+        )),
+        "ANNUALLY" => {
+            let start = NaiveDate::from_ymd_opt(today.year(), 1, 1)
+                .ok_or_else(|| anyhow!("Failed to calculate start of year"))?;
+            Ok((start, start.with_year(today.year() + 1).unwrap_or(start)))
+        }
+        other => bail!("Unknown budget time unit {}", other),
+    }
+}
+
+fn amount(spend: &Spend) -> Result<f64> {
+    spend
+        .amount
+        .parse()
+        .map_err(|e| anyhow!("Invalid budget amount {:?}: {}", spend.amount, e))
+}
+
+fn epoch(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map_or(0, |time| time.and_utc().timestamp())
+}
+
+fn date(epoch: f64) -> Result<NaiveDate> {
+    DateTime::from_timestamp(epoch as i64, 0)
+        .map(|time| time.date_naive())
+        .ok_or_else(|| anyhow!("Invalid budget timestamp {}", epoch))
+}
+
+fn percentage(part: f64, limit: f64) -> f64 {
+    if limit > 0.0 {
+        part / limit * 100.0
+    } else {
+        0.0
+    }
+}
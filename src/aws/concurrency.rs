@@ -0,0 +1,74 @@
+//! Bounded concurrency for Cost Explorer requests
+//!
+//! Cost Explorer throttles aggressively, so every request made through a
+//! client passes through a shared [`RequestLimiter`] regardless of how many
+//! tasks are issuing queries.
+
+use anyhow::Result;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::debug;
+
+/// Default number of requests allowed in flight at once
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Maximum number of retries for a throttled request
+pub const MAX_THROTTLE_RETRIES: u32 = 4;
+
+/// Semaphore limiting in-flight requests
+#[derive(Debug)]
+pub struct RequestLimiter {
+    permits: Semaphore,
+}
+
+impl RequestLimiter {
+    /// Create a limiter allowing `max` concurrent requests (at least one)
+    pub fn new(max: usize) -> Self {
+        Self {
+            permits: Semaphore::new(max.max(1)),
+        }
+    }
+
+    /// Wait until a request slot is available; the slot is released when
+    /// the returned permit is dropped
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.permits
+            .acquire()
+            .await
+            .expect("request limiter semaphore is never closed")
+    }
+}
+
+/// Delay before retry `attempt` (0-based) of a throttled request
+pub fn throttle_backoff(attempt: u32) -> Duration {
+    // Exponential backoff from 500ms with a little jitter so parallel
+    // queries don't retry in lockstep
+    let base = 500u64 << attempt.min(5);
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos() as u64 % 250)
+        .unwrap_or(0);
+    Duration::from_millis(base + jitter)
+}
+
+/// Whether an API error response indicates throttling
+pub fn is_throttled(status: u16, body: &str) -> bool {
+    status == 429
+        || body.contains("ThrottlingException")
+        || body.contains("LimitExceededException")
+        || body.contains("TooManyRequestsException")
+}
+
+/// Await a query and log how long it took
+pub async fn timed<T>(label: &str, query: impl Future<Output = Result<T>>) -> Result<T> {
+    let started = Instant::now();
+    let result = query.await;
+    debug!(
+        "Query '{}' {} in {:?}",
+        label,
+        if result.is_ok() { "finished" } else { "failed" },
+        started.elapsed()
+    );
+    result
+}
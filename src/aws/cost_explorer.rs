@@ -1,21 +1,41 @@
 //! AWS Cost Explorer API client with SigV4 signing
+//!
+//! The client is async; [`super::blocking`] wraps it for synchronous callers.
 
 use anyhow::{anyhow, Result};
-use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
-use aws_sigv4::sign::v4;
-use aws_smithy_runtime_api::client::identity::Identity;
-use chrono::{Datelike, Duration, Local, NaiveDate};
-use reqwest::blocking::Client;
+use chrono::{Local, NaiveDate};
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
-use tracing::debug;
-
+use serde_json::{json, Value};
+use std::time::Instant;
+use tracing::{debug, warn};
+
+use super::budgets::BudgetsClient;
+use super::concurrency::{
+    is_throttled, throttle_backoff, RequestLimiter, DEFAULT_MAX_CONCURRENCY, MAX_THROTTLE_RETRIES,
+};
+use super::query::{CostQuery, Dimension, Filter, Granularity, GroupBy, Metric};
+use super::recording::{Capture, Recorder, Replayer};
+use super::signing::{send_signed, Endpoint};
 use super::Credentials;
+use crate::periods;
+
+/// Days of resource-level data `GetCostAndUsageWithResources` can return,
+/// counting today
+pub const RESOURCE_DAYS: i64 = 14;
 
 /// Cost Explorer API client
 pub struct CostExplorerClient {
     credentials: Credentials,
     client: Client,
+    limiter: RequestLimiter,
+    /// Fixed "today" for date-relative queries, defaults to the local date
+    today: Option<NaiveDate>,
+    capture: Option<Capture>,
+    /// Metric every cost query aggregates
+    metric: Metric,
+    /// Filter applied to every cost query
+    filter: Option<Filter>,
 }
 
 /// Time period for cost queries
@@ -38,12 +58,16 @@ pub struct GroupDefinition {
 /// Cost and usage request
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "PascalCase")]
-struct GetCostAndUsageRequest {
-    time_period: TimePeriod,
-    granularity: String,
-    metrics: Vec<String>,
+pub(crate) struct GetCostAndUsageRequest {
+    pub(crate) time_period: TimePeriod,
+    pub(crate) granularity: String,
+    pub(crate) metrics: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) group_by: Option<Vec<GroupDefinition>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    group_by: Option<Vec<GroupDefinition>>,
+    pub(crate) filter: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) next_page_token: Option<String>,
 }
 
 /// Cost and usage response
@@ -51,12 +75,13 @@ struct GetCostAndUsageRequest {
 #[serde(rename_all = "PascalCase")]
 pub struct GetCostAndUsageResponse {
     pub results_by_time: Vec<ResultByTime>,
+    #[serde(default)]
+    pub next_page_token: Option<String>,
 }
 
 /// Results grouped by time period
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-#[allow(dead_code)]
 pub struct ResultByTime {
     pub time_period: TimePeriodResponse,
     pub total: Option<std::collections::HashMap<String, MetricValue>>,
@@ -66,7 +91,6 @@ pub struct ResultByTime {
 /// Time period in response
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "PascalCase")]
-#[allow(dead_code)]
 pub struct TimePeriodResponse {
     pub start: String,
     pub end: String,
@@ -88,21 +112,149 @@ pub struct MetricValue {
     pub unit: Option<String>,
 }
 
+/// One page of a `GetTags`, `GetDimensionValues` or `GetCostCategories`
+/// response; each action fills in its own list
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ListPage {
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    dimension_values: Vec<DimensionValue>,
+    #[serde(default)]
+    cost_category_names: Vec<String>,
+    #[serde(default)]
+    cost_category_values: Vec<String>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+/// A value in a `GetDimensionValues` response
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DimensionValue {
+    value: String,
+}
+
 /// Processed cost data for display
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostData {
     pub period: String,
+    /// First day covered by the data (inclusive)
+    pub start: NaiveDate,
+    /// Day after the last day covered by the data (exclusive)
+    pub end: NaiveDate,
     pub total_cost: f64,
     pub currency: String,
     pub breakdown: Vec<ServiceCost>,
 }
 
+impl CostData {
+    /// Number of days covered by this period
+    pub fn days(&self) -> i64 {
+        (self.end - self.start).num_days().max(1)
+    }
+}
+
 /// Cost breakdown by service
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceCost {
     pub service: String,
     pub cost: f64,
     pub percentage: f64,
+    /// Cloud provider, set when the data spans more than one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// Monthly costs over a range of calendar months
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonthlyTrend {
+    /// Months with data, oldest to newest
+    #[serde(default)]
+    pub months: Vec<CostData>,
+    /// Months that are missing or only partially loaded
+    #[serde(default)]
+    pub gaps: Vec<TrendGap>,
+}
+
+/// A month in a trend range that could not be loaded completely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendGap {
+    pub period: String,
+    pub start: NaiveDate,
+    /// Whether some data for the month is still present in `months`
+    pub partial: bool,
+    pub reason: String,
+}
+
+/// Costs grouped by two groupings at once, e.g. services by tag value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostMatrix {
+    pub period: String,
+    /// First day covered by the data (inclusive)
+    pub start: NaiveDate,
+    /// Day after the last day covered by the data (exclusive)
+    pub end: NaiveDate,
+    pub total_cost: f64,
+    pub currency: String,
+    /// Non-empty cells, by cost descending
+    pub cells: Vec<MatrixCell>,
+}
+
+/// Cost of one pair of group values in a [`CostMatrix`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixCell {
+    /// Value of the first grouping
+    pub row: String,
+    /// Value of the second grouping
+    pub column: String,
+    pub cost: f64,
+    /// Cloud provider, set when the data spans more than one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+/// Costs of each service on each day of a range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyCosts {
+    /// First day covered by the data (inclusive)
+    pub start: NaiveDate,
+    /// Day after the last day covered by the data (exclusive)
+    pub end: NaiveDate,
+    pub currency: String,
+    /// Services by total cost descending
+    pub series: Vec<DailySeries>,
+}
+
+impl DailyCosts {
+    /// Number of days in the range
+    pub fn days(&self) -> usize {
+        (self.end - self.start).num_days().max(0) as usize
+    }
+
+    /// The `index`th day of the range
+    pub fn date(&self, index: usize) -> NaiveDate {
+        self.start + chrono::Duration::days(index as i64)
+    }
+}
+
+/// One service's cost on each day of a [`DailyCosts`] range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySeries {
+    pub service: String,
+    /// Cost of each day, starting at [`DailyCosts::start`]
+    pub costs: Vec<f64>,
+    /// Cloud provider, set when the data spans more than one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+impl DailySeries {
+    /// Cost over the whole range
+    pub fn total(&self) -> f64 {
+        self.costs.iter().sum()
+    }
 }
 
 impl CostExplorerClient {
@@ -113,257 +265,695 @@ impl CostExplorerClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        Self { credentials, client }
+        Self {
+            credentials,
+            client,
+            limiter: RequestLimiter::new(DEFAULT_MAX_CONCURRENCY),
+            today: None,
+            capture: None,
+            metric: Metric::default(),
+            filter: None,
+        }
     }
 
-    /// Get the Cost Explorer endpoint URL
-    fn endpoint(&self) -> String {
-        format!("https://ce.{}.amazonaws.com", self.credentials.region)
+    /// Pin the date used for current/previous month and trend queries
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = Some(today);
+        self
     }
 
-    /// Sign and execute a request to Cost Explorer API
-    fn execute_request(&self, action: &str, body: &str) -> Result<String> {
-        let endpoint = self.endpoint();
-        let now = SystemTime::now();
+    /// Write every request and response to `recorder`
+    pub fn record_to(mut self, recorder: Recorder) -> Self {
+        self.capture = Some(Capture::Record(recorder));
+        self
+    }
 
-        // Create AWS credentials and convert to Identity
-        let aws_creds = aws_credential_types::Credentials::new(
-            &self.credentials.access_key_id,
-            &self.credentials.secret_access_key,
-            self.credentials.session_token.clone(),
-            None,
-            "aws-costs-tui",
-        );
-        let identity: Identity = aws_creds.into();
+    /// Serve responses from a recording instead of the network. "Today" is
+    /// pinned to the recording date unless already set.
+    pub fn replay_from(mut self, replayer: Replayer) -> Self {
+        self.today.get_or_insert(replayer.manifest().recorded_on);
+        self.capture = Some(Capture::Replay(replayer));
+        self
+    }
 
-        let signing_settings = SigningSettings::default();
-        let signing_params = v4::SigningParams::builder()
-            .identity(&identity)
-            .region(&self.credentials.region)
-            .name("ce") // Cost Explorer service name
-            .time(now)
-            .settings(signing_settings)
-            .build()
-            .map_err(|e| anyhow!("Failed to build signing params: {}", e))?;
+    /// The date queries are relative to
+    pub fn today(&self) -> NaiveDate {
+        self.today.unwrap_or_else(|| Local::now().date_naive())
+    }
 
-        // Format headers for signing
-        let x_amz_target = format!("AWSInsightsIndexService.{}", action);
+    /// A Budgets client with the same credentials, date and recording
+    pub fn budgets_client(&self) -> BudgetsClient {
+        let client = BudgetsClient::new(self.credentials.clone()).with_today(self.today());
+        match &self.capture {
+            Some(Capture::Record(recorder)) => client.record_to(recorder.clone()),
+            Some(Capture::Replay(replayer)) => client.replay_from(replayer.clone()),
+            None => client,
+        }
+    }
+
+    /// Aggregate `metric` instead of unblended cost
+    pub fn with_metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Only count costs matching `filter`, in every view
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// A query over `[start, end)` with the client's metric and filter
+    pub fn query(&self, start: NaiveDate, end: NaiveDate) -> CostQuery {
+        let query = CostQuery::new(start, end).metric(self.metric);
+        match &self.filter {
+            Some(filter) => query.filter(filter.clone()),
+            None => query,
+        }
+    }
+
+    /// Limit how many requests this client keeps in flight at once
+    pub fn with_max_concurrency(mut self, max: usize) -> Self {
+        self.limiter = RequestLimiter::new(max);
+        self
+    }
+
+    /// Region requests are sent to
+    pub fn region(&self) -> &str {
+        &self.credentials.region
+    }
+
+    /// Execute a request, waiting for a concurrency slot and retrying with
+    /// backoff when Cost Explorer throttles us
+    async fn execute_request(&self, action: &str, body: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            let started = Instant::now();
+            let outcome = match &self.capture {
+                Some(Capture::Replay(replayer)) => replayer
+                    .respond(action, body)
+                    .map(|(status, response)| (status_code(status), response)),
+                _ => {
+                    let _permit = self.limiter.acquire().await;
+                    self.send_signed_request(action, body).await
+                }
+            };
+            if let (Some(Capture::Record(recorder)), Ok((status, response))) =
+                (&self.capture, &outcome)
+            {
+                if let Err(e) = recorder.record(action, body, status.as_u16(), response) {
+                    warn!("Failed to record {} exchange: {}", action, e);
+                }
+            }
+            debug!("{} request took {:?}", action, started.elapsed());
+
+            match outcome {
+                Ok((status, response_body)) if status.is_success() => return Ok(response_body),
+                Ok((status, response_body))
+                    if attempt < MAX_THROTTLE_RETRIES
+                        && is_throttled(status.as_u16(), &response_body) =>
+                {
+                    let delay = throttle_backoff(attempt);
+                    warn!("{} throttled, retrying in {:?}", action, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Ok((status, response_body)) => {
+                    return Err(anyhow!(
+                        "API request failed with status {}: {}",
+                        status,
+                        response_body
+                    ));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Sign and send a single request to Cost Explorer API
+    async fn send_signed_request(
+        &self,
+        action: &str,
+        body: &str,
+    ) -> Result<(reqwest::StatusCode, String)> {
         let host = format!("ce.{}.amazonaws.com", self.credentials.region);
+        let endpoint = Endpoint {
+            host: &host,
+            region: &self.credentials.region,
+            service: "ce", // Cost Explorer service name
+        };
+        let x_amz_target = format!("AWSInsightsIndexService.{}", action);
 
-        // Create the signable request
-        let signable_request = SignableRequest::new(
-            "POST",
+        debug!("Executing Cost Explorer API request: {}", action);
+        send_signed(
+            &self.client,
+            &self.credentials,
             &endpoint,
-            [
+            &[
                 ("content-type", "application/x-amz-json-1.1"),
                 ("x-amz-target", x_amz_target.as_str()),
-                ("host", host.as_str()),
-            ]
-            .into_iter(),
-            SignableBody::Bytes(body.as_bytes()),
+            ],
+            body,
         )
-        .map_err(|e| anyhow!("Failed to create signable request: {}", e))?;
-
-        // Sign the request
-        let (signing_instructions, _signature) = sign(signable_request, &signing_params.into())
-            .map_err(|e| anyhow!("Failed to sign request: {}", e))?
-            .into_parts();
-
-        // Build the actual request with signed headers
-        let mut request_builder = self
-            .client
-            .post(&endpoint)
-            .header("content-type", "application/x-amz-json-1.1")
-            .header("x-amz-target", &x_amz_target)
-            .body(body.to_string());
-
-        // Apply signing headers
-        for (name, value) in signing_instructions.headers() {
-            let header_name: &str = name.as_ref();
-            let header_value: &str = value.as_ref();
-            request_builder = request_builder.header(header_name, header_value);
+        .await
+    }
+
+    /// Get cost and usage data
+    ///
+    /// Follows pagination and fails if any page fails.
+    pub async fn get_cost_and_usage(&self, query: &CostQuery) -> Result<GetCostAndUsageResponse> {
+        let mut request = query.to_request();
+        let (results_by_time, error) = self.fetch_all_pages(&mut request).await;
+        if let Some(e) = error {
+            return Err(e);
         }
 
-        debug!("Executing Cost Explorer API request: {}", action);
+        Ok(GetCostAndUsageResponse {
+            results_by_time,
+            next_page_token: None,
+        })
+    }
+
+    /// Run a query and aggregate it into [`CostData`], keyed by the first
+    /// grouping (or a single "Total" row when ungrouped)
+    pub async fn get_cost_data(&self, query: &CostQuery, period_name: &str) -> Result<CostData> {
+        let response = self.get_cost_and_usage(query).await?;
+        Ok(build_cost_data(
+            &response.results_by_time,
+            query.start,
+            query.end,
+            period_name,
+            query.metric,
+        ))
+    }
 
-        let response = request_builder
-            .send()
-            .map_err(|e| anyhow!("Request failed: {}", e))?;
+    /// Run a query with two groupings and keep both keys of every group
+    pub async fn get_cost_matrix(&self, query: &CostQuery, period_name: &str) -> Result<CostMatrix> {
+        if query.group_by.len() != 2 {
+            return Err(anyhow!(
+                "A cost matrix needs two groupings, got {}",
+                query.group_by.len()
+            ));
+        }
+        let response = self.get_cost_and_usage(query).await?;
+        Ok(build_cost_matrix(
+            &response.results_by_time,
+            query.start,
+            query.end,
+            period_name,
+            query.metric,
+        ))
+    }
 
-        let status = response.status();
-        let response_body = response
-            .text()
-            .map_err(|e| anyhow!("Failed to read response: {}", e))?;
+    /// Daily costs of every service over `[start, end)`, from one
+    /// `DAILY` query grouped by service
+    pub async fn get_daily_costs(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let query = self
+            .query(start, end)
+            .granularity(Granularity::Daily)
+            .group_by(GroupBy::Dimension(Dimension::Service));
+        let response = self.get_cost_and_usage(&query).await?;
+        Ok(build_daily_costs(
+            &response.results_by_time,
+            start,
+            end,
+            query.metric,
+        ))
+    }
 
-        if !status.is_success() {
+    /// Costs of `service`'s resources over `[start, end)`, by resource ID.
+    ///
+    /// Uses `GetCostAndUsageWithResources`, which only covers the last
+    /// [`RESOURCE_DAYS`] days at daily granularity (and must be enabled in
+    /// the Cost Explorer settings), so the range is clipped to that window;
+    /// the returned [`CostData`] spans the days actually covered.
+    pub async fn get_resource_costs(
+        &self,
+        service: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<CostData> {
+        let (window_start, window_end) = self.resource_window();
+        let (start, end) = (start.max(window_start), end.min(window_end));
+        if start >= end {
             return Err(anyhow!(
-                "API request failed with status {}: {}",
-                status,
-                response_body
+                "Cost Explorer only has resource-level costs for the last {} days ({})",
+                RESOURCE_DAYS,
+                periods::range_name(window_start, window_end)
             ));
         }
 
-        Ok(response_body)
+        let query = self
+            .query(start, end)
+            .granularity(Granularity::Daily)
+            .group_by(GroupBy::Dimension(Dimension::ResourceId))
+            .and_filter(Filter::dimension(Dimension::Service, [service]));
+        let mut request = query.to_request();
+        let (results, error) = self
+            .fetch_all_pages_of("GetCostAndUsageWithResources", &mut request)
+            .await;
+        if let Some(e) = error {
+            return Err(e);
+        }
+        Ok(build_cost_data(
+            &results,
+            start,
+            end,
+            &periods::range_name(start, end),
+            query.metric,
+        ))
+    }
+
+    /// The `[start, end)` range `GetCostAndUsageWithResources` accepts
+    pub fn resource_window(&self) -> (NaiveDate, NaiveDate) {
+        periods::last_days(self.today(), RESOURCE_DAYS)
     }
 
-    /// Get cost and usage data
-    pub fn get_cost_and_usage(
+    /// Cost allocation tag keys with spend in `[start, end)`, or the values
+    /// of `tag_key` if given. Untagged spend shows up as an empty value.
+    pub async fn get_tags(
         &self,
-        time_period: TimePeriod,
-        granularity: &str,
-        group_by_service: bool,
-    ) -> Result<GetCostAndUsageResponse> {
-        let group_by = if group_by_service {
-            Some(vec![GroupDefinition {
-                group_type: "DIMENSION".to_string(),
-                key: "SERVICE".to_string(),
-            }])
-        } else {
-            None
-        };
+        start: NaiveDate,
+        end: NaiveDate,
+        tag_key: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut request = json!({ "TimePeriod": time_period(start, end) });
+        if let Some(key) = tag_key {
+            request["TagKey"] = json!(key);
+        }
+        let pages = self.list_all_pages("GetTags", request).await?;
+        Ok(pages.into_iter().flat_map(|page| page.tags).collect())
+    }
 
-        let request = GetCostAndUsageRequest {
-            time_period,
-            granularity: granularity.to_string(),
-            metrics: vec!["UnblendedCost".to_string()],
-            group_by,
-        };
+    /// Values of a dimension (accounts, regions, services...) with usage in
+    /// `[start, end)`
+    pub async fn get_dimension_values(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        dimension: Dimension,
+    ) -> Result<Vec<String>> {
+        let request = json!({
+            "TimePeriod": time_period(start, end),
+            "Dimension": dimension.as_str(),
+            "Context": "COST_AND_USAGE",
+        });
+        let pages = self.list_all_pages("GetDimensionValues", request).await?;
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| page.dimension_values)
+            .map(|v| v.value)
+            .collect())
+    }
+
+    /// Cost category names in effect during `[start, end)`, or the values
+    /// of `name` if given
+    pub async fn get_cost_categories(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        name: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut request = json!({ "TimePeriod": time_period(start, end) });
+        if let Some(name) = name {
+            request["CostCategoryName"] = json!(name);
+        }
+        let pages = self.list_all_pages("GetCostCategories", request).await?;
+        Ok(pages
+            .into_iter()
+            .flat_map(|page| match name {
+                Some(_) => page.cost_category_values,
+                None => page.cost_category_names,
+            })
+            .collect())
+    }
+
+    /// Send a listing request, following `NextPageToken` until exhausted
+    async fn list_all_pages(&self, action: &str, mut request: Value) -> Result<Vec<ListPage>> {
+        let mut pages = Vec::new();
+        loop {
+            let body = request.to_string();
+            let response_body = self
+                .execute_request(action, &body)
+                .await
+                .map_err(|e| anyhow!("{} page {} failed: {}", action, pages.len() + 1, e))?;
+            let page: ListPage = serde_json::from_str(&response_body).map_err(|e| {
+                anyhow!("Failed to parse response: {} - Body: {}", e, response_body)
+            })?;
+
+            match page.next_page_token.clone() {
+                Some(token) if !token.is_empty() => {
+                    debug!("Fetching {} page {}", action, pages.len() + 2);
+                    request["NextPageToken"] = json!(token);
+                    pages.push(page);
+                }
+                _ => {
+                    pages.push(page);
+                    return Ok(pages);
+                }
+            }
+        }
+    }
 
-        let body = serde_json::to_string(&request)
+    /// Fetch a single page of cost and usage data
+    async fn fetch_page(
+        &self,
+        action: &str,
+        request: &GetCostAndUsageRequest,
+    ) -> Result<GetCostAndUsageResponse> {
+        let body = serde_json::to_string(request)
             .map_err(|e| anyhow!("Failed to serialize request: {}", e))?;
 
-        let response_body = self.execute_request("GetCostAndUsage", &body)?;
+        let response_body = self.execute_request(action, &body).await?;
 
-        let response: GetCostAndUsageResponse = serde_json::from_str(&response_body)
-            .map_err(|e| anyhow!("Failed to parse response: {} - Body: {}", e, response_body))?;
+        serde_json::from_str(&response_body)
+            .map_err(|e| anyhow!("Failed to parse response: {} - Body: {}", e, response_body))
+    }
 
-        Ok(response)
+    /// Follow `NextPageToken` until exhausted, merging groups by time period.
+    ///
+    /// Returns whatever was collected along with the error that stopped
+    /// pagination early, so callers can decide how to treat partial data.
+    async fn fetch_all_pages(
+        &self,
+        request: &mut GetCostAndUsageRequest,
+    ) -> (Vec<ResultByTime>, Option<anyhow::Error>) {
+        self.fetch_all_pages_of("GetCostAndUsage", request).await
     }
 
-    /// Get monthly costs broken down by service for the current month
-    pub fn get_current_month_costs(&self) -> Result<CostData> {
-        let today = Local::now().date_naive();
-        let start_of_month = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
-            .ok_or_else(|| anyhow!("Failed to calculate start of month"))?;
-        
-        // End date is tomorrow (exclusive)
-        let end_date = today + Duration::days(1);
-
-        let time_period = TimePeriod {
-            start: start_of_month.format("%Y-%m-%d").to_string(),
-            end: end_date.format("%Y-%m-%d").to_string(),
-        };
+    /// [`Self::fetch_all_pages`] for any action with the same request and
+    /// response shape
+    async fn fetch_all_pages_of(
+        &self,
+        action: &str,
+        request: &mut GetCostAndUsageRequest,
+    ) -> (Vec<ResultByTime>, Option<anyhow::Error>) {
+        let mut merged: Vec<ResultByTime> = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let response = match self.fetch_page(action, request).await {
+                Ok(response) => response,
+                Err(e) => return (merged, Some(anyhow!("page {} failed: {}", page, e))),
+            };
+
+            for result in response.results_by_time {
+                match merged
+                    .iter_mut()
+                    .find(|r| r.time_period.start == result.time_period.start)
+                {
+                    Some(existing) => {
+                        if let Some(groups) = result.groups {
+                            existing.groups.get_or_insert_with(Vec::new).extend(groups);
+                        }
+                    }
+                    None => merged.push(result),
+                }
+            }
 
-        self.get_costs_for_period(time_period, &format!("{}", today.format("%B %Y")))
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => {
+                    debug!("Fetching {} page {}", action, page + 1);
+                    request.next_page_token = Some(token);
+                    page += 1;
+                }
+                _ => return (merged, None),
+            }
+        }
+    }
+
+    /// Get monthly costs broken down by service for the current month
+    pub async fn get_current_month_costs(&self) -> Result<CostData> {
+        let today = self.today();
+        let (start, end) = periods::month_to_date(today)?;
+        self.get_costs_for_period(start, end, &periods::month_name(start)).await
     }
 
     /// Get costs for the previous month
-    pub fn get_previous_month_costs(&self) -> Result<CostData> {
-        let today = Local::now().date_naive();
-        let first_of_current = NaiveDate::from_ymd_opt(today.year(), today.month(), 1)
-            .ok_or_else(|| anyhow!("Failed to calculate first of current month"))?;
-        
-        let last_of_previous = first_of_current - Duration::days(1);
-        let first_of_previous = NaiveDate::from_ymd_opt(
-            last_of_previous.year(),
-            last_of_previous.month(),
-            1,
-        )
-        .ok_or_else(|| anyhow!("Failed to calculate first of previous month"))?;
+    pub async fn get_previous_month_costs(&self) -> Result<CostData> {
+        let today = self.today();
+        let (start, end) = periods::previous_month(today)?;
+        self.get_costs_for_period(start, end, &periods::month_name(start)).await
+    }
 
-        let time_period = TimePeriod {
-            start: first_of_previous.format("%Y-%m-%d").to_string(),
-            end: first_of_current.format("%Y-%m-%d").to_string(),
+    /// Get the last N calendar months of costs (including the current month)
+    /// using a single MONTHLY request across the whole range.
+    ///
+    /// Months that come back empty or are cut short by a failed page are
+    /// reported in [`MonthlyTrend::gaps`] rather than silently dropped.
+    pub async fn get_monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let today = self.today();
+        let ranges = periods::trend_months(today, months)?;
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return Ok(MonthlyTrend::default());
         };
 
-        self.get_costs_for_period(time_period, &format!("{}", first_of_previous.format("%B %Y")))
-    }
-
-    /// Get last N months of costs
-    pub fn get_monthly_trend(&self, months: u32) -> Result<Vec<CostData>> {
-        let mut results = Vec::new();
-        let today = Local::now().date_naive();
+        let query = self.query(first.0, last.1).group_by(GroupBy::Dimension(Dimension::Service));
+        let mut request = query.to_request();
 
-        for i in 0..months {
-            let target_month = today - Duration::days(30 * i as i64);
-            let first_of_month = NaiveDate::from_ymd_opt(target_month.year(), target_month.month(), 1)
-                .ok_or_else(|| anyhow!("Failed to calculate month"))?;
-            
-            let next_month = if target_month.month() == 12 {
-                NaiveDate::from_ymd_opt(target_month.year() + 1, 1, 1)
-            } else {
-                NaiveDate::from_ymd_opt(target_month.year(), target_month.month() + 1, 1)
+        let (results, error) = self.fetch_all_pages(&mut request).await;
+        if results.is_empty() {
+            if let Some(e) = error {
+                return Err(e);
             }
-            .ok_or_else(|| anyhow!("Failed to calculate next month"))?;
+        }
 
-            // For current month, end at tomorrow
-            let end_date = if i == 0 {
-                std::cmp::min(today + Duration::days(1), next_month)
-            } else {
-                next_month
-            };
+        let mut trend = MonthlyTrend::default();
+        for (start, end) in ranges {
+            let period_name = periods::month_name(start);
+            let key = start.format("%Y-%m-%d").to_string();
+            let result = results.iter().find(|r| r.time_period.start == key);
+
+            if let Some(result) = result {
+                trend.months.push(build_cost_data(
+                    std::slice::from_ref(result),
+                    start,
+                    end,
+                    &period_name,
+                    query.metric,
+                ));
+            }
 
-            let time_period = TimePeriod {
-                start: first_of_month.format("%Y-%m-%d").to_string(),
-                end: end_date.format("%Y-%m-%d").to_string(),
+            let reason = match (&error, result) {
+                (Some(e), _) => Some(format!("incomplete: {}", e)),
+                (None, None) => Some("no data returned".to_string()),
+                (None, Some(_)) => None,
             };
-
-            let period_name = first_of_month.format("%B %Y").to_string();
-            match self.get_costs_for_period(time_period, &period_name) {
-                Ok(data) => results.push(data),
-                Err(e) => debug!("Failed to get costs for {}: {}", period_name, e),
+            if let Some(reason) = reason {
+                warn!("Trend month {} is incomplete: {}", period_name, reason);
+                trend.gaps.push(TrendGap {
+                    period: period_name,
+                    start,
+                    partial: result.is_some(),
+                    reason,
+                });
             }
         }
 
-        results.reverse(); // Oldest to newest
-        Ok(results)
+        Ok(trend)
     }
 
     /// Get costs for a specific period with service breakdown
-    fn get_costs_for_period(&self, time_period: TimePeriod, period_name: &str) -> Result<CostData> {
-        let response = self.get_cost_and_usage(time_period, "MONTHLY", true)?;
-
-        let mut total_cost = 0.0;
-        let mut currency = "USD".to_string();
-        let mut service_costs: Vec<ServiceCost> = Vec::new();
-
-        for result in &response.results_by_time {
-            if let Some(groups) = &result.groups {
-                for group in groups {
-                    let service_name = group.keys.first().cloned().unwrap_or_default();
-                    if let Some(metric) = group.metrics.get("UnblendedCost") {
-                        let cost: f64 = metric.amount.parse().unwrap_or(0.0);
-                        if cost > 0.001 {
-                            total_cost += cost;
-                            if let Some(unit) = &metric.unit {
-                                currency = unit.clone();
-                            }
-                            service_costs.push(ServiceCost {
+    async fn get_costs_for_period(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        period_name: &str,
+    ) -> Result<CostData> {
+        let query = self.query(start, end).group_by(GroupBy::Dimension(Dimension::Service));
+        self.get_cost_data(&query, period_name).await
+    }
+}
+
+/// `TimePeriod` JSON for listing requests
+fn time_period(start: NaiveDate, end: NaiveDate) -> Value {
+    json!({
+        "Start": start.format("%Y-%m-%d").to_string(),
+        "End": end.format("%Y-%m-%d").to_string(),
+    })
+}
+
+fn status_code(status: u16) -> reqwest::StatusCode {
+    reqwest::StatusCode::from_u16(status).unwrap_or(reqwest::StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// Aggregate groups from Cost Explorer results into [`CostData`]
+fn build_cost_data(
+    results: &[ResultByTime],
+    start: NaiveDate,
+    end: NaiveDate,
+    period_name: &str,
+    metric: Metric,
+) -> CostData {
+    let mut total_cost = 0.0;
+    let mut currency = "USD".to_string();
+    let mut service_costs: Vec<ServiceCost> = Vec::new();
+
+    for result in results {
+        // Ungrouped queries only carry a total
+        if result.groups.as_ref().is_none_or(|g| g.is_empty()) {
+            if let Some(metric) = result.total.as_ref().and_then(|t| t.get(metric.as_str())) {
+                let cost: f64 = metric.amount.parse().unwrap_or(0.0);
+                total_cost += cost;
+                if let Some(unit) = &metric.unit {
+                    currency = unit.clone();
+                }
+                match service_costs.iter_mut().find(|s| s.service == "Total") {
+                    Some(row) => row.cost += cost,
+                    None => service_costs.push(ServiceCost {
+                        service: "Total".to_string(),
+                        cost,
+                        percentage: 0.0,
+                        provider: None,
+                    }),
+                }
+            }
+        }
+        if let Some(groups) = &result.groups {
+            for group in groups {
+                let service_name = group.keys.first().cloned().unwrap_or_default();
+                if let Some(metric) = group.metrics.get(metric.as_str()) {
+                    let cost: f64 = metric.amount.parse().unwrap_or(0.0);
+                    if cost > 0.001 {
+                        total_cost += cost;
+                        if let Some(unit) = &metric.unit {
+                            currency = unit.clone();
+                        }
+                        // Finer granularities repeat each group once per time bucket
+                        match service_costs.iter_mut().find(|s| s.service == service_name) {
+                            Some(existing) => existing.cost += cost,
+                            None => service_costs.push(ServiceCost {
                                 service: service_name,
                                 cost,
                                 percentage: 0.0, // Will calculate after
-                            });
+                                provider: None,
+                            }),
                         }
                     }
                 }
             }
         }
+    }
 
-        // Calculate percentages and sort by cost descending
-        for service in &mut service_costs {
-            service.percentage = if total_cost > 0.0 {
-                (service.cost / total_cost) * 100.0
-            } else {
-                0.0
+    // Calculate percentages and sort by cost descending
+    for service in &mut service_costs {
+        service.percentage = if total_cost > 0.0 {
+            (service.cost / total_cost) * 100.0
+        } else {
+            0.0
+        };
+    }
+    service_costs.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(std::cmp::Ordering::Equal));
+
+    CostData {
+        period: period_name.to_string(),
+        start,
+        end,
+        total_cost,
+        currency,
+        breakdown: service_costs,
+    }
+}
+
+/// Aggregate two-key groups from Cost Explorer results into a [`CostMatrix`]
+fn build_cost_matrix(
+    results: &[ResultByTime],
+    start: NaiveDate,
+    end: NaiveDate,
+    period_name: &str,
+    metric: Metric,
+) -> CostMatrix {
+    let mut currency = "USD".to_string();
+    let mut cells: Vec<MatrixCell> = Vec::new();
+
+    for group in results.iter().flat_map(|r| r.groups.iter().flatten()) {
+        let Some(value) = group.metrics.get(metric.as_str()) else {
+            continue;
+        };
+        let cost: f64 = value.amount.parse().unwrap_or(0.0);
+        if cost.abs() <= 0.001 {
+            continue;
+        }
+        if let Some(unit) = &value.unit {
+            currency = unit.clone();
+        }
+        let row = group.keys.first().cloned().unwrap_or_default();
+        let column = group.keys.get(1).cloned().unwrap_or_default();
+        match cells.iter_mut().find(|c| c.row == row && c.column == column) {
+            Some(cell) => cell.cost += cost,
+            None => cells.push(MatrixCell {
+                row,
+                column,
+                cost,
+                provider: None,
+            }),
+        }
+    }
+    cells.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(std::cmp::Ordering::Equal));
+
+    CostMatrix {
+        period: period_name.to_string(),
+        start,
+        end,
+        total_cost: cells.iter().map(|c| c.cost).sum(),
+        currency,
+        cells,
+    }
+}
+
+/// Spread daily Cost Explorer results into one series per service
+fn build_daily_costs(
+    results: &[ResultByTime],
+    start: NaiveDate,
+    end: NaiveDate,
+    metric: Metric,
+) -> DailyCosts {
+    let days = (end - start).num_days().max(0) as usize;
+    let mut currency = "USD".to_string();
+    let mut series: Vec<DailySeries> = Vec::new();
+
+    for result in results {
+        let Ok(date) = NaiveDate::parse_from_str(&result.time_period.start, "%Y-%m-%d") else {
+            continue;
+        };
+        let Some(day) = usize::try_from((date - start).num_days())
+            .ok()
+            .filter(|&day| day < days)
+        else {
+            continue;
+        };
+        for group in result.groups.iter().flatten() {
+            let Some(value) = group.metrics.get(metric.as_str()) else {
+                continue;
+            };
+            let cost: f64 = value.amount.parse().unwrap_or(0.0);
+            if cost.abs() <= 0.001 {
+                continue;
+            }
+            if let Some(unit) = &value.unit {
+                currency = unit.clone();
+            }
+            let service = group.keys.first().cloned().unwrap_or_default();
+            let index = match series.iter().position(|s| s.service == service) {
+                Some(index) => index,
+                None => {
+                    series.push(DailySeries {
+                        service,
+                        costs: vec![0.0; days],
+                        provider: None,
+                    });
+                    series.len() - 1
+                }
             };
+            series[index].costs[day] += cost;
         }
-        service_costs.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(std::cmp::Ordering::Equal));
+    }
+    series.sort_by(|a, b| b.total().partial_cmp(&a.total()).unwrap_or(std::cmp::Ordering::Equal));
 
-        Ok(CostData {
-            period: period_name.to_string(),
-            total_cost,
-            currency,
-            breakdown: service_costs,
-        })
+    DailyCosts {
+        start,
+        end,
+        currency,
+        series,
     }
 }
@@ -22,6 +22,11 @@ pub struct Credentials {
 
 impl Credentials {
     /// Load credentials for a given profile
+    ///
+    /// The region comes from `region`, then `AWS_REGION`/`AWS_DEFAULT_REGION`,
+    /// then the profile's config, falling back to `us-east-1`. Credentials are
+    /// taken from the environment (default profile only), then
+    /// `~/.aws/credentials`, then `~/.aws/config`.
     pub fn load(profile: &str, region: Option<&str>) -> Result<Self> {
         // Determine region
         let region = region
@@ -56,6 +61,16 @@ impl Credentials {
             profile
         ))
     }
+
+    /// Empty credentials for clients that never sign a request (e.g. replays)
+    pub fn anonymous(region: &str) -> Self {
+        Self {
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+            session_token: None,
+            region: region.to_string(),
+        }
+    }
 }
 
 /// Load credentials from environment variables
@@ -203,29 +218,126 @@ fn get_profile_region(profile: &str) -> Option<String> {
     None
 }
 
+/// Sections of `name` in the AWS config directory, empty if it can't be read
+fn read_ini(name: &str) -> HashMap<String, HashMap<String, String>> {
+    aws_config_dir()
+        .and_then(|dir| Ok(fs::read_to_string(dir.join(name))?))
+        .map(|content| parse_ini_file(&content))
+        .unwrap_or_default()
+}
+
 /// List available AWS profiles
-#[allow(dead_code)]
 pub fn list_profiles() -> Vec<String> {
-    let mut profiles = Vec::new();
-
-    if let Ok(config_dir) = aws_config_dir() {
-        // Read from credentials file
-        if let Ok(content) = fs::read_to_string(config_dir.join("credentials")) {
-            let sections = parse_ini_file(&content);
-            profiles.extend(sections.keys().cloned());
-        }
-
-        // Read from config file
-        if let Ok(content) = fs::read_to_string(config_dir.join("config")) {
-            let sections = parse_ini_file(&content);
-            for key in sections.keys() {
-                if !profiles.contains(key) {
-                    profiles.push(key.clone());
-                }
-            }
+    let mut profiles: Vec<String> = read_ini("credentials").into_keys().collect();
+    for key in read_ini("config").into_keys() {
+        if !profiles.contains(&key) {
+            profiles.push(key);
         }
     }
 
     profiles.sort();
     profiles
 }
+
+/// Where a profile's credentials come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialType {
+    /// `AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`, used for the default profile
+    Environment,
+    /// Long-lived access keys
+    AccessKeys,
+    /// Access keys with a session token
+    SessionToken,
+    /// IAM Identity Center (`sso_session` or `sso_start_url`)
+    Sso,
+    /// `role_arn`, assumed from another profile
+    AssumeRole,
+    /// `credential_process`
+    Process,
+    /// Nothing to sign requests with
+    Missing,
+}
+
+impl CredentialType {
+    /// Short description, e.g. "access keys"
+    pub fn label(&self) -> &'static str {
+        match self {
+            CredentialType::Environment => "environment",
+            CredentialType::AccessKeys => "access keys",
+            CredentialType::SessionToken => "session token",
+            CredentialType::Sso => "SSO",
+            CredentialType::AssumeRole => "assume role",
+            CredentialType::Process => "credential process",
+            CredentialType::Missing => "none",
+        }
+    }
+
+    /// Whether [`Credentials::load`] can use them
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            self,
+            CredentialType::Environment | CredentialType::AccessKeys | CredentialType::SessionToken
+        )
+    }
+}
+
+/// A profile with its region and credential type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    /// Region from the profile's config, if set
+    pub region: Option<String>,
+    pub credential_type: CredentialType,
+}
+
+/// Describe the profiles of [`list_profiles`], checked in the same order as
+/// [`Credentials::load`]; the default profile is included when the
+/// environment has credentials
+pub fn describe_profiles() -> Vec<Profile> {
+    let credentials = read_ini("credentials");
+    let config = read_ini("config");
+    let from_env = env::var("AWS_ACCESS_KEY_ID").is_ok() && env::var("AWS_SECRET_ACCESS_KEY").is_ok();
+
+    let mut names = list_profiles();
+    if from_env && !names.iter().any(|n| n == "default") {
+        names.insert(0, "default".to_string());
+    }
+
+    let keys = |section: Option<&HashMap<String, String>>| {
+        let section = section?;
+        if !section.contains_key("aws_access_key_id") || !section.contains_key("aws_secret_access_key") {
+            return None;
+        }
+        if section.contains_key("aws_session_token") {
+            Some(CredentialType::SessionToken)
+        } else {
+            Some(CredentialType::AccessKeys)
+        }
+    };
+
+    names
+        .into_iter()
+        .map(|name| {
+            let section = config.get(&name);
+            let has = |key: &str| section.is_some_and(|s| s.contains_key(key));
+            let credential_type = if name == "default" && from_env {
+                CredentialType::Environment
+            } else if let Some(keys) = keys(credentials.get(&name)).or_else(|| keys(section)) {
+                keys
+            } else if has("sso_session") || has("sso_start_url") {
+                CredentialType::Sso
+            } else if has("role_arn") {
+                CredentialType::AssumeRole
+            } else if has("credential_process") {
+                CredentialType::Process
+            } else {
+                CredentialType::Missing
+            };
+            Profile {
+                region: section.and_then(|s| s.get("region").cloned()),
+                name,
+                credential_type,
+            }
+        })
+        .collect()
+}
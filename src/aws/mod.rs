@@ -1,7 +1,19 @@
-//! AWS module for credentials and Cost Explorer API
+//! AWS module for credentials, Cost Explorer and Budgets APIs
 
+pub mod blocking;
+pub mod budgets;
+pub mod concurrency;
 pub mod credentials;
 pub mod cost_explorer;
+pub mod query;
+pub mod recording;
+mod signing;
 
-pub use credentials::Credentials;
-pub use cost_explorer::{CostExplorerClient, CostData};
+pub use budgets::{Budget, BudgetPeriod, BudgetStatus, BudgetsClient};
+pub use credentials::{describe_profiles, list_profiles, CredentialType, Credentials, Profile};
+pub use cost_explorer::{
+    CostData, CostExplorerClient, CostMatrix, DailyCosts, DailySeries, MatrixCell, MonthlyTrend,
+    ServiceCost, TrendGap, RESOURCE_DAYS,
+};
+pub use query::{CostQuery, Dimension, Filter, Granularity, GroupBy, Metric};
+pub use recording::{Recorder, Replayer};
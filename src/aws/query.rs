@@ -0,0 +1,262 @@
+//! Builder for Cost Explorer `GetCostAndUsage` queries
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::cost_explorer::{GetCostAndUsageRequest, GroupDefinition, TimePeriod};
+
+/// Time granularity of returned results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Granularity {
+    Daily,
+    #[default]
+    Monthly,
+    Hourly,
+}
+
+impl Granularity {
+    /// Cost Explorer API name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Granularity::Daily => "DAILY",
+            Granularity::Monthly => "MONTHLY",
+            Granularity::Hourly => "HOURLY",
+        }
+    }
+}
+
+/// Cost metric to aggregate, named as in the Cost Explorer API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum Metric {
+    #[default]
+    UnblendedCost,
+    BlendedCost,
+    AmortizedCost,
+    NetUnblendedCost,
+    NetAmortizedCost,
+}
+
+impl Metric {
+    /// Cost Explorer API name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Metric::UnblendedCost => "UnblendedCost",
+            Metric::BlendedCost => "BlendedCost",
+            Metric::AmortizedCost => "AmortizedCost",
+            Metric::NetUnblendedCost => "NetUnblendedCost",
+            Metric::NetAmortizedCost => "NetAmortizedCost",
+        }
+    }
+}
+
+/// Cost Explorer dimensions usable for grouping and filtering
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Service,
+    LinkedAccount,
+    Region,
+    UsageType,
+    InstanceType,
+    Operation,
+    PurchaseType,
+    RecordType,
+    /// Individual resource (instance ID, bucket, ARN...). Only local
+    /// CUR/FOCUS exports have this for every day; Cost Explorer needs
+    /// `GetCostAndUsageWithResources` and rejects it in `GetCostAndUsage`
+    ResourceId,
+}
+
+impl Dimension {
+    pub const ALL: [Dimension; 9] = [
+        Dimension::Service,
+        Dimension::LinkedAccount,
+        Dimension::Region,
+        Dimension::UsageType,
+        Dimension::InstanceType,
+        Dimension::Operation,
+        Dimension::PurchaseType,
+        Dimension::RecordType,
+        Dimension::ResourceId,
+    ];
+
+    /// Cost Explorer API name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Dimension::Service => "SERVICE",
+            Dimension::LinkedAccount => "LINKED_ACCOUNT",
+            Dimension::Region => "REGION",
+            Dimension::UsageType => "USAGE_TYPE",
+            Dimension::InstanceType => "INSTANCE_TYPE",
+            Dimension::Operation => "OPERATION",
+            Dimension::PurchaseType => "PURCHASE_TYPE",
+            Dimension::RecordType => "RECORD_TYPE",
+            Dimension::ResourceId => "RESOURCE_ID",
+        }
+    }
+}
+
+/// How to group query results
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GroupBy {
+    Dimension(Dimension),
+    /// Cost allocation tag key
+    Tag(String),
+    /// Cost category name
+    CostCategory(String),
+}
+
+impl GroupBy {
+    pub(crate) fn definition(&self) -> GroupDefinition {
+        let (group_type, key) = match self {
+            GroupBy::Dimension(d) => ("DIMENSION", d.as_str().to_string()),
+            GroupBy::Tag(key) => ("TAG", key.clone()),
+            GroupBy::CostCategory(name) => ("COST_CATEGORY", name.clone()),
+        };
+        GroupDefinition {
+            group_type: group_type.to_string(),
+            key,
+        }
+    }
+}
+
+/// Filter expression, mirroring Cost Explorer's `Expression` type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Not(Box<Filter>),
+    Dimension { key: Dimension, values: Vec<String> },
+    Tag { key: String, values: Vec<String> },
+    CostCategory { key: String, values: Vec<String> },
+}
+
+impl Filter {
+    /// Match any of `values` for a dimension
+    pub fn dimension(key: Dimension, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Filter::Dimension {
+            key,
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Match any of `values` for a cost allocation tag
+    pub fn tag(
+        key: impl Into<String>,
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Filter::Tag {
+            key: key.into(),
+            values: values.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Serialize into the Cost Explorer JSON shape
+    pub(crate) fn to_json(&self) -> Value {
+        match self {
+            Filter::And(filters) => {
+                json!({ "And": filters.iter().map(Filter::to_json).collect::<Vec<_>>() })
+            }
+            Filter::Or(filters) => {
+                json!({ "Or": filters.iter().map(Filter::to_json).collect::<Vec<_>>() })
+            }
+            Filter::Not(filter) => json!({ "Not": filter.to_json() }),
+            Filter::Dimension { key, values } => {
+                json!({ "Dimensions": { "Key": key.as_str(), "Values": values } })
+            }
+            Filter::Tag { key, values } => json!({ "Tags": { "Key": key, "Values": values } }),
+            Filter::CostCategory { key, values } => {
+                json!({ "CostCategories": { "Key": key, "Values": values } })
+            }
+        }
+    }
+}
+
+/// A `GetCostAndUsage` query
+///
+/// ```
+/// use aws_costs_tui::aws::{CostQuery, Dimension, Filter, Granularity, GroupBy};
+/// use chrono::NaiveDate;
+///
+/// let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+/// let query = CostQuery::new(start, end)
+///     .granularity(Granularity::Daily)
+///     .group_by(GroupBy::Dimension(Dimension::Service))
+///     .filter(Filter::dimension(Dimension::Region, ["us-east-1"]));
+/// assert_eq!(query.group_by.len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostQuery {
+    /// First day to include
+    pub start: NaiveDate,
+    /// Day after the last day to include
+    pub end: NaiveDate,
+    pub granularity: Granularity,
+    pub metric: Metric,
+    /// Up to two groupings, as allowed by Cost Explorer
+    pub group_by: Vec<GroupBy>,
+    pub filter: Option<Filter>,
+}
+
+impl CostQuery {
+    /// Monthly unblended cost over `[start, end)` with no grouping
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            start,
+            end,
+            granularity: Granularity::default(),
+            metric: Metric::default(),
+            group_by: Vec::new(),
+            filter: None,
+        }
+    }
+
+    /// Set the result granularity
+    pub fn granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Set the cost metric
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// Add a grouping
+    pub fn group_by(mut self, group_by: GroupBy) -> Self {
+        self.group_by.push(group_by);
+        self
+    }
+
+    /// Restrict results with a filter expression
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Restrict results further, keeping the filter already set
+    pub fn and_filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(match self.filter.take() {
+            Some(existing) => Filter::And(vec![existing, filter]),
+            None => filter,
+        });
+        self
+    }
+
+    pub(crate) fn to_request(&self) -> GetCostAndUsageRequest {
+        GetCostAndUsageRequest {
+            time_period: TimePeriod {
+                start: self.start.format("%Y-%m-%d").to_string(),
+                end: self.end.format("%Y-%m-%d").to_string(),
+            },
+            granularity: self.granularity.as_str().to_string(),
+            metrics: vec![self.metric.as_str().to_string()],
+            group_by: (!self.group_by.is_empty())
+                .then(|| self.group_by.iter().map(GroupBy::definition).collect()),
+            filter: self.filter.as_ref().map(Filter::to_json),
+            next_page_token: None,
+        }
+    }
+}
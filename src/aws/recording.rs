@@ -0,0 +1,185 @@
+//! Record and replay raw Cost Explorer and Budgets HTTP exchanges
+//!
+//! A recording directory holds a `manifest.json` describing when and where
+//! the capture was made, plus one JSON file per distinct request. Requests
+//! are matched on action and canonical JSON body, so captures can be edited
+//! by hand and still replay. Signing headers are never written and any
+//! credential material is scrubbed from bodies before they hit disk.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::Credentials;
+
+/// Name of the file describing a recording
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Placeholder written in place of scrubbed secrets
+const REDACTED: &str = "<redacted>";
+
+/// Metadata about a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Local date the capture was taken; replays pin "today" to it so
+    /// date-relative queries produce the same requests
+    pub recorded_on: NaiveDate,
+    pub region: String,
+    pub tool_version: String,
+}
+
+/// One recorded request/response pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+    pub action: String,
+    pub request: Value,
+    pub status: u16,
+    pub response: String,
+}
+
+/// Writes every exchange made by a client into a directory
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    dir: PathBuf,
+    secrets: Vec<String>,
+}
+
+impl Recorder {
+    /// Start a recording in `dir`, creating it if needed
+    pub fn create(dir: &Path, credentials: &Credentials, today: NaiveDate) -> Result<Self> {
+        fs::create_dir_all(dir).with_context(|| format!("Could not create {:?}", dir))?;
+
+        let manifest = Manifest {
+            recorded_on: today,
+            region: credentials.region.clone(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+        fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&manifest)?,
+        )
+        .with_context(|| format!("Could not write manifest in {:?}", dir))?;
+
+        let secrets = [
+            Some(&credentials.access_key_id),
+            Some(&credentials.secret_access_key),
+            credentials.session_token.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            secrets,
+        })
+    }
+
+    /// Write an exchange to disk, replacing any earlier identical request
+    pub fn record(&self, action: &str, body: &str, status: u16, response: &str) -> Result<()> {
+        let request = canonical_request(&self.scrub(body))?;
+        let exchange = Exchange {
+            action: action.to_string(),
+            request,
+            status,
+            response: self.scrub(response),
+        };
+        let path = self.dir.join(exchange_file_name(action, &exchange.request));
+        fs::write(&path, serde_json::to_string_pretty(&exchange)?)
+            .with_context(|| format!("Could not write {:?}", path))
+    }
+
+    fn scrub(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |acc, secret| {
+            acc.replace(secret.as_str(), REDACTED)
+        })
+    }
+}
+
+/// Serves recorded exchanges instead of calling the network
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    manifest: Manifest,
+    exchanges: HashMap<(String, String), Exchange>,
+}
+
+impl Replayer {
+    /// Load a recording directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let manifest: Manifest = serde_json::from_str(
+            &fs::read_to_string(&manifest_path)
+                .with_context(|| format!("Could not read {:?}", manifest_path))?,
+        )
+        .with_context(|| format!("Invalid manifest {:?}", manifest_path))?;
+
+        let mut exchanges = HashMap::new();
+        for entry in fs::read_dir(dir).with_context(|| format!("Could not read {:?}", dir))? {
+            let path = entry?.path();
+            let is_exchange = path.extension().is_some_and(|e| e == "json")
+                && path.file_name().is_some_and(|n| n != MANIFEST_FILE);
+            if !is_exchange {
+                continue;
+            }
+
+            let exchange: Exchange = serde_json::from_str(&fs::read_to_string(&path)?)
+                .with_context(|| format!("Invalid exchange {:?}", path))?;
+            let key = (exchange.action.clone(), exchange.request.to_string());
+            exchanges.insert(key, exchange);
+        }
+
+        Ok(Self {
+            manifest,
+            exchanges,
+        })
+    }
+
+    /// Metadata about the recording
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Look up the recorded response for a request
+    pub fn respond(&self, action: &str, body: &str) -> Result<(u16, String)> {
+        let request = canonical_request(body)?;
+        self.exchanges
+            .get(&(action.to_string(), request.to_string()))
+            .map(|e| (e.status, e.response.clone()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "No recorded exchange for {} with request {} (expected {})",
+                    action,
+                    request,
+                    exchange_file_name(action, &request)
+                )
+            })
+    }
+}
+
+/// Raw exchange capture mode of a client
+pub(crate) enum Capture {
+    Record(Recorder),
+    Replay(Replayer),
+}
+
+/// Parse a request body so key order and whitespace don't affect matching
+fn canonical_request(body: &str) -> Result<Value> {
+    serde_json::from_str(body).map_err(|e| anyhow!("Request body is not JSON: {}", e))
+}
+
+/// Stable file name for a request: action plus an FNV-1a hash of the body
+pub fn exchange_file_name(action: &str, request: &Value) -> String {
+    let hash = request
+        .to_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{}-{:016x}.json", action, hash)
+}
@@ -0,0 +1,87 @@
+//! SigV4 request signing shared by the AWS API clients
+
+use anyhow::{anyhow, Result};
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use aws_smithy_runtime_api::client::identity::Identity;
+use reqwest::{Client, StatusCode};
+use std::time::SystemTime;
+
+use super::Credentials;
+
+/// Where a signed request goes
+pub(crate) struct Endpoint<'a> {
+    /// e.g. `ce.us-east-1.amazonaws.com`
+    pub host: &'a str,
+    /// Region the request is signed for
+    pub region: &'a str,
+    /// Service name the request is signed for, e.g. `ce`
+    pub service: &'a str,
+}
+
+/// Sign a POST of `body` with `headers` to `endpoint` and send it, returning
+/// the status and response body
+pub(crate) async fn send_signed(
+    client: &Client,
+    credentials: &Credentials,
+    endpoint: &Endpoint<'_>,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> Result<(StatusCode, String)> {
+    let url = format!("https://{}", endpoint.host);
+
+    // Create AWS credentials and convert to Identity
+    let aws_creds = aws_credential_types::Credentials::new(
+        &credentials.access_key_id,
+        &credentials.secret_access_key,
+        credentials.session_token.clone(),
+        None,
+        "aws-costs-tui",
+    );
+    let identity: Identity = aws_creds.into();
+
+    let signing_params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(endpoint.region)
+        .name(endpoint.service)
+        .time(SystemTime::now())
+        .settings(SigningSettings::default())
+        .build()
+        .map_err(|e| anyhow!("Failed to build signing params: {}", e))?;
+
+    // Create the signable request
+    let signable_request = SignableRequest::new(
+        "POST",
+        &url,
+        headers.iter().copied().chain([("host", endpoint.host)]),
+        SignableBody::Bytes(body.as_bytes()),
+    )
+    .map_err(|e| anyhow!("Failed to create signable request: {}", e))?;
+
+    // Sign the request
+    let (signing_instructions, _signature) = sign(signable_request, &signing_params.into())
+        .map_err(|e| anyhow!("Failed to sign request: {}", e))?
+        .into_parts();
+
+    // Build the actual request with signed headers
+    let mut request_builder = client.post(&url).body(body.to_string());
+    for (name, value) in headers {
+        request_builder = request_builder.header(*name, *value);
+    }
+    for (name, value) in signing_instructions.headers() {
+        request_builder = request_builder.header(name, value);
+    }
+
+    let response = request_builder
+        .send()
+        .await
+        .map_err(|e| anyhow!("Request failed: {}", e))?;
+
+    let status = response.status();
+    let response_body = response
+        .text()
+        .await
+        .map_err(|e| anyhow!("Failed to read response: {}", e))?;
+
+    Ok((status, response_body))
+}
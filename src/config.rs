@@ -0,0 +1,451 @@
+//! User settings from a TOML file
+//!
+//! Read from `$XDG_CONFIG_HOME/aws-costs/config.toml` (`~/.config/aws-costs/`
+//! when unset), or the file given with `--config`:
+//!
+//! ```toml
+//! profiles = ["prod", "staging"]  # used when --profile isn't given
+//! metric = "AmortizedCost"        # or UnblendedCost, BlendedCost, NetAmortizedCost...
+//! months = 12                     # months in the trend view
+//! group_by = "SERVICE"            # rows of the month views, e.g. REGION or TAG:team
+//! top = 10                        # services charted in the trend view
+//! filter = "production"           # saved filter every query is limited to
+//! view = "regions"                # view to start with
+//! theme = "paper"                 # dark (default), light, high-contrast,
+//!                                 # monochrome or one of [themes]
+//! palette = "colorblind"          # service colors: the theme's or colorblind
+//! colors = "256"                  # truecolor, 256, 16 or none (default detected)
+//!
+//! [thresholds]                    # costs above these turn red, orange, yellow
+//! high = 5000
+//! medium = 500
+//! low = 50
+//!
+//! [filters.production]            # every grouping must match one of its values
+//! LINKED_ACCOUNT = ["111111111111", "222222222222"]
+//! "TAG:environment" = ["prod"]
+//!
+//! [views.regions]                 # overrides the settings above
+//! group_by = "REGION"
+//! months = 3
+//!
+//! [keys]                          # replaces the default keys of an action
+//! next_tab = ["l", "Tab"]
+//! prev_tab = ["h", "Shift-Tab"]
+//! refresh = "Ctrl-r"
+//!
+//! [themes.paper]                  # a built-in theme with some colors replaced
+//! base = "light"
+//! text = "black"
+//! selection = "#ffe8b0"
+//! ```
+//!
+//! Mistakes are reported with the line they are on.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+use crate::aws::{Dimension, Filter, GroupBy, Metric};
+use crate::keymap::{Action, Binding, Bindings, Keymap};
+use crate::source::parse_group_by_label;
+use crate::theme::{ColorDepth, Palette, Theme, ThemeColors, BUILT_IN};
+
+/// Months in the trend view unless configured
+pub const DEFAULT_MONTHS: u32 = 6;
+
+/// Services charted in the trend view unless configured
+pub const DEFAULT_TOP: usize = 8;
+
+/// Most months Cost Explorer keeps (with multi-year data enabled)
+const MAX_MONTHS: u32 = 38;
+
+/// Costs above which amounts are shown red, orange and yellow; green below
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "ThresholdValues")]
+pub struct Thresholds {
+    pub high: f64,
+    pub medium: f64,
+    pub low: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            high: 1000.0,
+            medium: 100.0,
+            low: 10.0,
+        }
+    }
+}
+
+/// Thresholds as written, any of them left at its default
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThresholdValues {
+    high: Option<f64>,
+    medium: Option<f64>,
+    low: Option<f64>,
+}
+
+impl TryFrom<ThresholdValues> for Thresholds {
+    type Error = String;
+
+    fn try_from(values: ThresholdValues) -> std::result::Result<Self, String> {
+        let default = Thresholds::default();
+        let thresholds = Thresholds {
+            high: values.high.unwrap_or(default.high),
+            medium: values.medium.unwrap_or(default.medium),
+            low: values.low.unwrap_or(default.low),
+        };
+        let Thresholds { high, medium, low } = thresholds;
+        if !high.is_finite() || low < 0.0 || low >= medium || medium >= high {
+            return Err(format!(
+                "thresholds must rise from low to medium to high, got {} / {} / {}",
+                low, medium, high
+            ));
+        }
+        Ok(thresholds)
+    }
+}
+
+/// Settings a named view overrides
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct View {
+    #[serde(default)]
+    pub metric: Option<Metric>,
+    #[serde(default, deserialize_with = "months")]
+    pub months: Option<u32>,
+    #[serde(default, deserialize_with = "group_by")]
+    pub group_by: Option<GroupBy>,
+    #[serde(default, deserialize_with = "top")]
+    pub top: Option<usize>,
+    #[serde(default)]
+    pub thresholds: Option<Thresholds>,
+    /// Name of a saved filter
+    #[serde(default)]
+    pub filter: Option<Spanned<String>>,
+}
+
+/// The config file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profiles used when `--profile` isn't given
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    #[serde(default)]
+    pub metric: Option<Metric>,
+    #[serde(default, deserialize_with = "months")]
+    pub months: Option<u32>,
+    #[serde(default, deserialize_with = "group_by")]
+    pub group_by: Option<GroupBy>,
+    #[serde(default, deserialize_with = "top")]
+    pub top: Option<usize>,
+    #[serde(default)]
+    pub thresholds: Option<Thresholds>,
+    /// Name of the saved filter used outside views
+    #[serde(default)]
+    pub filter: Option<Spanned<String>>,
+    /// Name of the view to start with
+    #[serde(default)]
+    pub view: Option<Spanned<String>>,
+    #[serde(default, deserialize_with = "filters")]
+    pub filters: BTreeMap<String, Filter>,
+    #[serde(default)]
+    pub views: BTreeMap<String, View>,
+    /// Keys replacing the defaults of their actions
+    #[serde(default)]
+    pub keys: BTreeMap<Action, Spanned<Bindings>>,
+    /// Name of a built-in theme or one of `themes`
+    #[serde(default)]
+    pub theme: Option<Spanned<String>>,
+    /// Colors services are charted in
+    #[serde(default)]
+    pub palette: Option<Palette>,
+    /// Colors the terminal shows, detected when unset
+    #[serde(default)]
+    pub colors: Option<ColorDepth>,
+    #[serde(default)]
+    pub themes: BTreeMap<String, ThemeColors>,
+}
+
+/// Settings in effect once a view's overrides are applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub metric: Metric,
+    /// Months in the trend view
+    pub months: u32,
+    /// Rows of the month views
+    pub group_by: GroupBy,
+    /// Services charted in the trend view
+    pub top: usize,
+    pub thresholds: Thresholds,
+    /// Name of the saved filter every query is limited to
+    pub filter_name: Option<String>,
+    pub filter: Option<Filter>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            metric: Metric::default(),
+            months: DEFAULT_MONTHS,
+            group_by: GroupBy::Dimension(Dimension::Service),
+            top: DEFAULT_TOP,
+            thresholds: Thresholds::default(),
+            filter_name: None,
+            filter: None,
+        }
+    }
+}
+
+impl Config {
+    /// `aws-costs/config.toml` in the XDG config directory
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+        Some(dir.join("aws-costs").join("config.toml"))
+    }
+
+    /// Read the config at [`Config::default_path`], if there is one
+    pub fn load_default() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Read a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read config {:?}", path))?;
+        Self::parse(&content).with_context(|| format!("Invalid config {:?}", path))
+    }
+
+    /// Parse and check a config written in TOML
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        config.validate(content)?;
+        Ok(config)
+    }
+
+    /// Check that saved filters and views are defined where they are used
+    fn validate(&self, content: &str) -> Result<()> {
+        let filters = std::iter::once(&self.filter)
+            .chain(self.views.values().map(|view| &view.filter))
+            .flatten();
+        for filter in filters {
+            if !self.filters.contains_key(filter.get_ref()) {
+                bail!(
+                    "line {}: no saved filter named {:?} in [filters]",
+                    line(content, filter),
+                    filter.get_ref()
+                );
+            }
+        }
+        if let Some(view) = &self.view {
+            if !self.views.contains_key(view.get_ref()) {
+                bail!(
+                    "line {}: no view named {:?} in [views]",
+                    line(content, view),
+                    view.get_ref()
+                );
+            }
+        }
+        if let Err((action, message)) = Keymap::with_overrides(&self.key_overrides()) {
+            bail!("line {}: {}", line(content, &self.keys[&action]), message);
+        }
+        for colors in self.themes.values() {
+            if let (Err(message), Some(base)) = (colors.theme(), &colors.base) {
+                bail!("line {}: {}", line(content, base), message);
+            }
+        }
+        if let Some(theme) = &self.theme {
+            let name = theme.get_ref();
+            if !self.themes.contains_key(name) && !BUILT_IN.contains(&name.as_str()) {
+                bail!(
+                    "line {}: no theme named {:?}; built in are {}",
+                    line(content, theme),
+                    name,
+                    BUILT_IN.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn key_overrides(&self) -> BTreeMap<Action, Vec<Binding>> {
+        self.keys
+            .iter()
+            .map(|(&action, bindings)| (action, bindings.get_ref().0.clone()))
+            .collect()
+    }
+
+    /// The default keymap with the config's `[keys]`
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::with_overrides(&self.key_overrides())
+            .map_err(|(action, message)| anyhow!("[keys] {}: {}", action, message))
+    }
+
+    /// The theme named `name`, or the config's `theme` when `None`
+    ///
+    /// Themes defined in `[themes]` take precedence over built-in ones of
+    /// the same name.
+    pub fn theme(&self, name: Option<&str>) -> Result<Theme> {
+        let name = name
+            .or(self.theme.as_ref().map(|t| t.get_ref().as_str()))
+            .unwrap_or("dark");
+        let theme = match self.themes.get(name) {
+            Some(colors) => colors
+                .theme()
+                .map_err(|e| anyhow!("[themes.{}] {}", name, e))?,
+            None => Theme::built_in(name).ok_or_else(|| {
+                let mut names: Vec<&str> = BUILT_IN.to_vec();
+                names.extend(self.themes.keys().map(String::as_str));
+                anyhow!(
+                    "No theme named {:?}, expected one of {}",
+                    name,
+                    names.join(", ")
+                )
+            })?,
+        };
+        Ok(theme.with_palette(self.palette.unwrap_or_default()))
+    }
+
+    /// The config's `colors`, or what the terminal is detected to show
+    pub fn color_depth(&self) -> ColorDepth {
+        self.colors.unwrap_or_else(ColorDepth::detect)
+    }
+
+    /// The settings of `view`, or of the config's `view` when `None`
+    pub fn settings(&self, view: Option<&str>) -> Result<Settings> {
+        let base = View {
+            metric: self.metric,
+            months: self.months,
+            group_by: self.group_by.clone(),
+            top: self.top,
+            thresholds: self.thresholds,
+            filter: self.filter.clone(),
+        };
+        let view = match view.or(self.view.as_ref().map(|v| v.get_ref().as_str())) {
+            Some(name) => Some(self.views.get(name).ok_or_else(|| {
+                let names: Vec<&str> = self.views.keys().map(String::as_str).collect();
+                match names.is_empty() {
+                    true => anyhow!("No view named {:?}; the config has no [views]", name),
+                    false => anyhow!(
+                        "No view named {:?}; the config has {}",
+                        name,
+                        names.join(", ")
+                    ),
+                }
+            })?),
+            None => None,
+        };
+
+        let mut settings = Settings::default();
+        for layer in std::iter::once(&base).chain(view) {
+            if let Some(metric) = layer.metric {
+                settings.metric = metric;
+            }
+            if let Some(months) = layer.months {
+                settings.months = months;
+            }
+            if let Some(group_by) = &layer.group_by {
+                settings.group_by = group_by.clone();
+            }
+            if let Some(top) = layer.top {
+                settings.top = top;
+            }
+            if let Some(thresholds) = layer.thresholds {
+                settings.thresholds = thresholds;
+            }
+            if let Some(name) = &layer.filter {
+                settings.filter = self.filters.get(name.get_ref()).cloned();
+                settings.filter_name = Some(name.get_ref().clone());
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Line of `content` a value starts on, counting from 1
+fn line<T>(content: &str, value: &Spanned<T>) -> usize {
+    content[..value.span().start.min(content.len())]
+        .matches('\n')
+        .count()
+        + 1
+}
+
+fn months<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u32>, D::Error> {
+    let months = u32::deserialize(deserializer)?;
+    if !(1..=MAX_MONTHS).contains(&months) {
+        return Err(D::Error::custom(format!(
+            "months must be between 1 and {}, got {}",
+            MAX_MONTHS, months
+        )));
+    }
+    Ok(Some(months))
+}
+
+fn top<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<usize>, D::Error> {
+    match usize::deserialize(deserializer)? {
+        0 => Err(D::Error::custom("top must be at least 1")),
+        top => Ok(Some(top)),
+    }
+}
+
+fn group_by<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<GroupBy>, D::Error> {
+    let label = String::deserialize(deserializer)?;
+    parse_group_by_label(&label)
+        .map(Some)
+        .map_err(|e| D::Error::custom(format!("{:#}", e)))
+}
+
+/// A saved filter: grouping labels, each with the values it may take
+struct FilterTable(Filter);
+
+impl<'de> Deserialize<'de> for FilterTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let table = BTreeMap::<String, Vec<String>>::deserialize(deserializer)?;
+        let mut conditions = Vec::new();
+        for (label, values) in table {
+            if values.is_empty() {
+                return Err(D::Error::custom(format!(
+                    "{} needs at least one value",
+                    label
+                )));
+            }
+            let group_by =
+                parse_group_by_label(&label).map_err(|e| D::Error::custom(format!("{:#}", e)))?;
+            conditions.push(match group_by {
+                GroupBy::Dimension(key) => Filter::Dimension { key, values },
+                GroupBy::Tag(key) => Filter::Tag { key, values },
+                GroupBy::CostCategory(key) => Filter::CostCategory { key, values },
+            });
+        }
+        match conditions.len() {
+            0 => Err(D::Error::custom("a filter needs at least one grouping")),
+            1 => Ok(FilterTable(conditions.remove(0))),
+            _ => Ok(FilterTable(Filter::And(conditions))),
+        }
+    }
+}
+
+fn filters<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Filter>, D::Error> {
+    let filters = BTreeMap::<String, FilterTable>::deserialize(deserializer)?;
+    Ok(filters
+        .into_iter()
+        .map(|(name, table)| (name, table.0))
+        .collect())
+}
@@ -0,0 +1,427 @@
+//! Actions and the keys bound to them
+//!
+//! The TUI turns each key press into a [`Key`] and looks the keys pressed so
+//! far up in a [`Keymap`]; a binding can be a chord of several keys such as
+//! `gg`. Bindings are written as in the config's `[keys]` section: a single
+//! character (`j`, `G`, `?`), a named key (`Enter`, `Esc`, `Tab`,
+//! `Shift-Tab`, `Up`, `PageDown`, `Space`, `F5`, `Ctrl-r`...), or a chord of
+//! those separated by spaces or, for characters, run together (`gg`).
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Something a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    Quit,
+    Back,
+    Help,
+    NextTab,
+    PrevTab,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    DrillDown,
+    Copy,
+    Refresh,
+    Tags,
+    Coverage,
+    Anomalies,
+    Budgets,
+    Profiles,
+    Export,
+    Prorate,
+}
+
+impl Action {
+    /// Every action, in the order the help lists them
+    pub const ALL: [Action; 19] = [
+        Action::Quit,
+        Action::Back,
+        Action::Help,
+        Action::Refresh,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::Down,
+        Action::Up,
+        Action::Top,
+        Action::Bottom,
+        Action::DrillDown,
+        Action::Copy,
+        Action::Export,
+        Action::Prorate,
+        Action::Tags,
+        Action::Coverage,
+        Action::Anomalies,
+        Action::Budgets,
+        Action::Profiles,
+    ];
+
+    /// Name in the config's `[keys]` section
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Back => "back",
+            Action::Help => "help",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::DrillDown => "drill_down",
+            Action::Copy => "copy",
+            Action::Refresh => "refresh",
+            Action::Tags => "tags",
+            Action::Coverage => "coverage",
+            Action::Anomalies => "anomalies",
+            Action::Budgets => "budgets",
+            Action::Profiles => "profiles",
+            Action::Export => "export",
+            Action::Prorate => "prorate",
+        }
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Close the panel or drill-down; quit from the main views",
+            Action::Help => "Show or hide this help",
+            Action::NextTab => "Next tab; services/accounts in the coverage report",
+            Action::PrevTab => "Previous tab",
+            Action::Down => "Next row",
+            Action::Up => "Previous row",
+            Action::Top => "First row",
+            Action::Bottom => "Last row",
+            Action::DrillDown => "Resources of the selected service; pick in a panel",
+            Action::Copy => "Copy the selected resource ID",
+            Action::Refresh => "Reload every tab",
+            Action::Tags => "Tag explorer",
+            Action::Coverage => "Tag coverage report (with --require-tag)",
+            Action::Anomalies => "Daily cost spikes",
+            Action::Budgets => "Budgets",
+            Action::Profiles => "Profile switcher",
+            Action::Export => "Export CSV (Teams tab, coverage report)",
+            Action::Prorate => "Prorate the previous month (Compare tab)",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Action::ALL.iter().map(Action::name).collect();
+                format!(
+                    "Unknown action {:?}, expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// One key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    /// A letter with Ctrl held
+    Ctrl(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    BackTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Delete,
+    F(u8),
+}
+
+impl Key {
+    /// A named key, any case
+    fn named(name: &str) -> Option<Key> {
+        let lower = name.to_ascii_lowercase();
+        let key = match lower.as_str() {
+            "enter" | "return" => Key::Enter,
+            "esc" | "escape" => Key::Esc,
+            "backspace" => Key::Backspace,
+            "tab" => Key::Tab,
+            "backtab" | "shift-tab" => Key::BackTab,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdn" => Key::PageDown,
+            "delete" | "del" => Key::Delete,
+            "space" => Key::Char(' '),
+            _ => {
+                if let Some(letter) = lower.strip_prefix("ctrl-") {
+                    let mut chars = letter.chars();
+                    return match (chars.next(), chars.next()) {
+                        (Some(c), None) if c.is_ascii_alphabetic() => Some(Key::Ctrl(c)),
+                        _ => None,
+                    };
+                }
+                let n = lower.strip_prefix('f')?.parse().ok()?;
+                return (1..=12).contains(&n).then_some(Key::F(n));
+            }
+        };
+        Some(key)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(' ') => f.write_str("Space"),
+            Key::Char(c) => write!(f, "{}", c),
+            Key::Ctrl(c) => write!(f, "Ctrl-{}", c),
+            Key::Enter => f.write_str("⏎"),
+            Key::Esc => f.write_str("Esc"),
+            Key::Backspace => f.write_str("Backspace"),
+            Key::Tab => f.write_str("Tab"),
+            Key::BackTab => f.write_str("Shift-Tab"),
+            Key::Up => f.write_str("↑"),
+            Key::Down => f.write_str("↓"),
+            Key::Left => f.write_str("←"),
+            Key::Right => f.write_str("→"),
+            Key::Home => f.write_str("Home"),
+            Key::End => f.write_str("End"),
+            Key::PageUp => f.write_str("PgUp"),
+            Key::PageDown => f.write_str("PgDn"),
+            Key::Delete => f.write_str("Del"),
+            Key::F(n) => write!(f, "F{}", n),
+        }
+    }
+}
+
+/// The keys of a binding, pressed one after the other
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Binding(pub Vec<Key>);
+
+impl Binding {
+    /// Whether pressing `self` is the start of pressing `other`, or the same
+    pub fn overlaps(&self, other: &Binding) -> bool {
+        self.0.starts_with(&other.0) || other.0.starts_with(&self.0)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut keys = Vec::new();
+        for token in s.split_whitespace() {
+            match Key::named(token) {
+                Some(key) => keys.push(key),
+                None if token.contains('-') && token.chars().count() > 1 => {
+                    return Err(format!("Unknown key {:?} in {:?}", token, s));
+                }
+                // Characters run together are a chord: "gg"
+                None => keys.extend(token.chars().map(Key::Char)),
+            }
+        }
+        if keys.is_empty() {
+            return Err(
+                "A key binding can't be empty; use \"Space\" for the space bar".to_string(),
+            );
+        }
+        Ok(Binding(keys))
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut previous_char = false;
+        for (i, key) in self.0.iter().enumerate() {
+            let is_char = matches!(key, Key::Char(c) if *c != ' ');
+            if i > 0 && !(is_char && previous_char) {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", key)?;
+            previous_char = is_char;
+        }
+        Ok(())
+    }
+}
+
+/// The bindings of one action in the config: a binding or a list of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings(pub Vec<Binding>);
+
+impl<'de> Deserialize<'de> for Bindings {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BindingsVisitor;
+
+        impl<'de> Visitor<'de> for BindingsVisitor {
+            type Value = Bindings;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a key such as \"j\", \"Ctrl-r\" or \"gg\", or a list of them")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Bindings, E> {
+                Ok(Bindings(vec![s.parse().map_err(E::custom)?]))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bindings, A::Error> {
+                let mut bindings = Vec::new();
+                while let Some(s) = seq.next_element::<String>()? {
+                    bindings.push(s.parse().map_err(de::Error::custom)?);
+                }
+                Ok(Bindings(bindings))
+            }
+        }
+
+        deserializer.deserialize_any(BindingsVisitor)
+    }
+}
+
+/// What the keys pressed so far amount to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    /// A complete binding
+    Action(Action),
+    /// The start of a chord; wait for the next key
+    Pending,
+    /// Nothing is bound to them
+    Unbound,
+}
+
+/// Key bindings, each to one action
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Binding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::Quit, "q"),
+            (Action::Back, "Esc"),
+            (Action::Help, "?"),
+            (Action::Refresh, "r"),
+            (Action::NextTab, "Right"),
+            (Action::NextTab, "Tab"),
+            (Action::PrevTab, "Left"),
+            (Action::PrevTab, "Shift-Tab"),
+            (Action::Down, "Down"),
+            (Action::Down, "j"),
+            (Action::Up, "Up"),
+            (Action::Up, "k"),
+            (Action::Top, "gg"),
+            (Action::Top, "Home"),
+            (Action::Bottom, "G"),
+            (Action::Bottom, "End"),
+            (Action::DrillDown, "Enter"),
+            (Action::Copy, "y"),
+            (Action::Export, "e"),
+            (Action::Prorate, "P"),
+            (Action::Tags, "t"),
+            (Action::Coverage, "c"),
+            (Action::Anomalies, "a"),
+            (Action::Budgets, "b"),
+            (Action::Profiles, "p"),
+        ];
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|(action, keys)| (keys.parse().expect("default binding"), action))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// The defaults with each of `overrides` replacing its action's keys;
+    /// defaults that clash with an override are dropped. Fails with the
+    /// action whose keys clash with another override's
+    pub fn with_overrides(
+        overrides: &BTreeMap<Action, Vec<Binding>>,
+    ) -> Result<Self, (Action, String)> {
+        let mut bindings: Vec<(Binding, Action)> = Vec::new();
+        for (&action, keys) in overrides {
+            for binding in keys {
+                if let Some((other, other_action)) =
+                    bindings.iter().find(|(b, _)| b.overlaps(binding))
+                {
+                    let message = match other == binding {
+                        true => format!("{} is already bound to {}", binding, other_action),
+                        false => format!(
+                            "{} overlaps {} bound to {}; one is the start of the other",
+                            binding, other, other_action
+                        ),
+                    };
+                    return Err((action, message));
+                }
+                bindings.push((binding.clone(), action));
+            }
+        }
+        let defaults: Vec<(Binding, Action)> = Keymap::default()
+            .bindings
+            .into_iter()
+            .filter(|(binding, action)| {
+                !overrides.contains_key(action)
+                    && !bindings.iter().any(|(b, _)| b.overlaps(binding))
+            })
+            .collect();
+        bindings.extend(defaults);
+        Ok(Keymap { bindings })
+    }
+
+    /// Bindings of `action`, the preferred one first
+    pub fn bindings(&self, action: Action) -> Vec<&Binding> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| *a == action)
+            .map(|(binding, _)| binding)
+            .collect()
+    }
+
+    /// What `keys`, pressed in order, are bound to
+    pub fn lookup(&self, keys: &[Key]) -> Lookup {
+        let mut lookup = Lookup::Unbound;
+        for (binding, action) in &self.bindings {
+            if binding.0 == keys {
+                return Lookup::Action(*action);
+            }
+            if binding.0.starts_with(keys) {
+                lookup = Lookup::Pending;
+            }
+        }
+        lookup
+    }
+}
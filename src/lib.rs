@@ -0,0 +1,51 @@
+//! AWS Costs - query AWS Cost Explorer and analyze the results
+//!
+//! The `aws-costs` binary is a consumer of this library; other tools can use
+//! the same credential resolution, signed query layer and data model.
+//!
+//! - [`aws::Credentials`] resolves credentials and region the same way the
+//!   AWS CLI does (environment, `~/.aws/credentials`, `~/.aws/config`)
+//! - [`aws::CostExplorerClient`] is an async, SigV4-signed Cost Explorer
+//!   client; [`aws::blocking::CostExplorerClient`] wraps it for sync code
+//! - [`aws::CostQuery`] builds `GetCostAndUsage` queries
+//! - [`aws::CostData`] is the aggregated cost model shared by every view
+//! - [`analysis`] holds comparisons and other post-processing
+//! - [`config`] reads the user's TOML settings, saved filters and views
+//! - [`keymap`] binds keys and chords to the TUI's actions
+//! - [`theme`] holds the TUI's colors and the terminal's color depth
+//! - [`text`] fits names into fixed-width columns
+//!
+//! The terminal UI lives in `ui` behind the `tui` feature (enabled by
+//! default). Library users can depend on this crate with
+//! `default-features = false` to avoid pulling in ratatui and crossterm.
+//!
+//! ```no_run
+//! use aws_costs_tui::aws::{CostExplorerClient, CostQuery, Credentials, Dimension, GroupBy};
+//! use chrono::NaiveDate;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let credentials = Credentials::load("default", None)?;
+//! let client = CostExplorerClient::new(credentials);
+//!
+//! let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+//! let end = NaiveDate::from_ymd_opt(2026, 2, 1).unwrap();
+//! let query = CostQuery::new(start, end).group_by(GroupBy::Dimension(Dimension::Service));
+//! let costs = client.get_cost_data(&query, "January 2026").await?;
+//!
+//! for service in &costs.breakdown {
+//!     println!("{}: ${:.2}", service.service, service.cost);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod analysis;
+pub mod aws;
+pub mod config;
+pub mod keymap;
+pub mod periods;
+pub mod source;
+pub mod text;
+pub mod theme;
+#[cfg(feature = "tui")]
+pub mod ui;
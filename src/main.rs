@@ -3,24 +3,54 @@
 //! A beautiful terminal interface to view your AWS costs broken down by service,
 //! with colorful charts and trend visualization.
 
-mod aws;
-mod ui;
-
-use anyhow::Result;
+use anyhow::{Context, Result};
+use aws_costs_tui::analysis::{
+    AccountsReport, Allocation, AllocationRules, Anomalies, Baseline, Detector, Forecast, Forecaster,
+    LocalBudgets, Method, TagCoverage, TagSpend, CHANGE_EPSILON,
+};
+use aws_costs_tui::aws::{self, CostData, Dimension, GroupBy};
+use aws_costs_tui::config::{Config, Settings};
+use aws_costs_tui::keymap::Keymap;
+use aws_costs_tui::periods;
+use aws_costs_tui::theme::Theme;
+use aws_costs_tui::text;
+use aws_costs_tui::source::{
+    AccountsSource, CostSource, CurSource, Fixture, FixtureSource, FocusCost, FocusSource, GroupedSource,
+    MergedSource, SyntheticSource,
+};
+#[cfg(feature = "parquet")]
+use aws_costs_tui::source::ParquetSource;
 use clap::Parser;
+use chrono::{Local, NaiveDate};
+use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::info;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 /// AWS Costs TUI - View your AWS costs in the terminal
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "aws-costs")]
 #[command(author = "Ajay")]
 #[command(version = "0.1.0")]
 #[command(about = "Terminal UI for viewing AWS Cost Explorer data with charts", long_about = None)]
 struct Args {
-    /// AWS profile to use (defaults to 'default' or AWS_PROFILE env var)
-    #[arg(short, long, env = "AWS_PROFILE", default_value = "default")]
-    profile: String,
+    /// AWS profile to use (defaults to AWS_PROFILE, the config's profiles,
+    /// or 'default'); repeatable or comma-separated to show several accounts
+    /// side by side
+    #[arg(short, long, env = "AWS_PROFILE", value_delimiter = ',')]
+    profile: Vec<String>,
+
+    /// Config file (defaults to $XDG_CONFIG_HOME/aws-costs/config.toml)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Named view from the config file to start with
+    #[arg(long, value_name = "NAME")]
+    view: Option<String>,
+
+    /// Color theme: dark, light, high-contrast, monochrome or one from the config
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
 
     /// AWS region (defaults to profile region, AWS_REGION, or us-east-1)
     #[arg(short, long, env = "AWS_REGION")]
@@ -33,10 +63,128 @@ struct Args {
     /// Just print costs without TUI (useful for scripts)
     #[arg(long, default_value = "false")]
     no_tui: bool,
+
+    /// Maximum number of Cost Explorer requests in flight at once
+    #[arg(long, default_value_t = aws::concurrency::DEFAULT_MAX_CONCURRENCY)]
+    max_concurrency: usize,
+
+    /// Use deterministic synthetic data instead of AWS (no account needed)
+    #[arg(long, default_value = "false", conflicts_with_all = ["fixture", "replay", "cur"])]
+    demo: bool,
+
+    /// Replay cost data from a JSON fixture instead of calling AWS
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["replay", "cur"])]
+    fixture: Option<PathBuf>,
+
+    /// Read a Cost and Usage Report export (CSV/CSV.gz file, manifest or directory)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["replay", "record"])]
+    cur: Option<PathBuf>,
+
+    /// Read a CUR 2.0 / Data Exports Parquet export (file or directory)
+    #[cfg(feature = "parquet")]
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["demo", "fixture", "cur", "replay", "record"])]
+    parquet: Option<PathBuf>,
+
+    /// Read a FinOps FOCUS 1.x export (CSV/CSV.gz file or directory); repeatable
+    #[arg(long, value_name = "PATH")]
+    focus: Vec<PathBuf>,
+
+    /// FOCUS cost column to sum
+    #[arg(long, value_name = "COLUMN", default_value = "billed", value_parser = ["billed", "effective"])]
+    focus_cost: String,
+
+    /// Show --focus data alongside the AWS source (Cost Explorer, --cur, ...)
+    /// instead of on its own
+    #[arg(long, default_value = "false", requires = "focus")]
+    merge: bool,
+
+    /// Record raw Cost Explorer requests/responses (credentials scrubbed) into DIR
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["demo", "fixture", "replay"])]
+    record: Option<PathBuf>,
+
+    /// Serve Cost Explorer responses from a DIR made by --record, offline
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Capture every query from the selected source into a JSON fixture and exit
+    #[arg(long, value_name = "FILE")]
+    save_fixture: Option<PathBuf>,
+
+    /// With --no-tui, also print the month's spend by value of a tag key and
+    /// the untagged share; repeatable
+    #[arg(long, value_name = "KEY", requires = "no_tui")]
+    tag: Vec<String>,
+
+    /// With --no-tui, list the month's cost allocation tag keys
+    #[arg(long, default_value = "false", requires = "no_tui")]
+    list_tags: bool,
+
+    /// Tag key every resource should carry, for the tag coverage report
+    /// (`c` in the TUI); repeatable or comma-separated
+    #[arg(long, value_name = "KEY", value_delimiter = ',')]
+    require_tag: Vec<String>,
+
+    /// With --no-tui, also write the tag coverage report to FILE as CSV
+    #[arg(long, value_name = "FILE", requires_all = ["no_tui", "require_tag"])]
+    coverage_csv: Option<PathBuf>,
+
+    /// Showback/chargeback rules (TOML) splitting spend across teams, shown
+    /// in the Teams tab
+    #[arg(long, value_name = "FILE")]
+    allocation_rules: Option<PathBuf>,
+
+    /// With --no-tui, also write per-team totals to FILE as CSV
+    #[arg(long, value_name = "FILE", requires_all = ["no_tui", "allocation_rules"])]
+    teams_csv: Option<PathBuf>,
+
+    /// With --no-tui, also list this month's daily cost spikes
+    #[arg(long, default_value = "false", requires = "no_tui")]
+    anomalies: bool,
+
+    /// Days a service's daily cost is compared with: every day of the
+    /// window, or the same weekday in each week of it
+    #[arg(long, value_name = "BASELINE", default_value = "weekday", value_parser = ["rolling", "weekday"])]
+    anomaly_baseline: String,
+
+    /// Days of history behind each anomaly baseline
+    #[arg(long, value_name = "DAYS", default_value_t = 28, value_parser = clap::value_parser!(u32).range(7..=365))]
+    anomaly_window: u32,
+
+    /// Flag days more than this many median absolute deviations above
+    /// their baseline; lower flags more
+    #[arg(long, value_name = "MADS", default_value_t = 3.5)]
+    anomaly_sensitivity: f64,
+
+    /// With --no-tui, also project month-end and quarter-end costs
+    #[arg(long, default_value = "false", requires = "no_tui")]
+    forecast: bool,
+
+    /// How costs are projected; `auto` picks whichever best predicts the
+    /// last week
+    #[arg(long, value_name = "METHOD", default_value = "auto", value_parser = ["auto", "run-rate", "weekday", "smoothing"])]
+    forecast_method: String,
+
+    /// Days of history each projection is fitted to
+    #[arg(long, value_name = "DAYS", default_value_t = 28, value_parser = clap::value_parser!(u32).range(7..=365))]
+    forecast_window: u32,
+
+    /// With --no-tui, also list AWS Budgets with their spend against the
+    /// limit
+    #[arg(long, default_value = "false", requires = "no_tui")]
+    budgets: bool,
+
+    /// Budgets (TOML) tracked against the fetched costs, for accounts
+    /// without AWS Budgets; shown beside them
+    #[arg(long, value_name = "FILE")]
+    local_budgets: Option<PathBuf>,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+/// Seed for `--demo` data, fixed so screenshots are reproducible
+const DEMO_SEED: u64 = 42;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = Args::parse();
 
     // Setup logging
     let filter = if args.debug {
@@ -51,41 +199,387 @@ fn main() -> Result<()> {
         .init();
 
     info!("Starting AWS Costs TUI");
-    info!("Using profile: {}", args.profile);
 
-    // Load credentials
-    let credentials = aws::Credentials::load(&args.profile, args.region.as_deref())?;
-    info!("Loaded credentials for region: {}", credentials.region);
+    let config = match &args.config {
+        Some(path) => Config::load(path)?,
+        None => Config::load_default()?,
+    };
+    let settings = config.settings(args.view.as_deref())?;
+    let keymap = config.keymap()?;
+    let theme = config.theme(args.theme.as_deref())?.for_depth(config.color_depth());
+    if args.profile.is_empty() {
+        args.profile = match config.profiles.is_empty() {
+            true => vec!["default".to_string()],
+            false => config.profiles.clone(),
+        };
+    }
 
-    // Create Cost Explorer client
-    let client = aws::CostExplorerClient::new(credentials);
+    let source = create_source(&args, &settings)?;
+    info!("Using data source: {}", source.name());
+    let rules = args.allocation_rules.as_deref().map(AllocationRules::load).transpose()?;
+    let local_budgets = args.local_budgets.as_deref().map(LocalBudgets::load).transpose()?;
+    let detector = anomaly_detector(&args)?;
+    let forecaster = forecaster(&args);
 
-    if args.no_tui {
+    if let Some(path) = &args.save_fixture {
+        // Exports and recordings have their own "today", so every period
+        // follows the source's current month rather than the clock
+        let month = source.current_month().await?;
+        let (start, end) = (month.start, month.end);
+        let today = end.pred_opt().unwrap_or(end);
+        let mut breakdowns = vec![
+            (start, end, GroupBy::Dimension(Dimension::LinkedAccount)),
+            (start, end, GroupBy::Dimension(Dimension::Region)),
+        ];
+        // Capture every tag key so the fixture's tag explorer works offline
+        match source.tag_keys(start, end).await {
+            Ok(keys) => breakdowns.extend(keys.into_iter().map(|k| (start, end, GroupBy::Tag(k)))),
+            Err(e) => info!("Not capturing tag breakdowns: {}", e),
+        }
+        // Required tags get what the coverage report asks for
+        let months = periods::trend_months(today, settings.months)?;
+        let mut matrices = Vec::new();
+        for key in &args.require_tag {
+            let tag = GroupBy::Tag(key.clone());
+            breakdowns.extend(months.iter().map(|&(start, end)| (start, end, tag.clone())));
+            for dimension in [Dimension::Service, Dimension::LinkedAccount] {
+                matrices.push((start, end, GroupBy::Dimension(dimension), tag.clone()));
+            }
+        }
+        // Allocation rules get the matrices the Teams tab asks for
+        if let Some(rules) = &rules {
+            let services = GroupBy::Dimension(Dimension::Service);
+            let accounts = GroupBy::Dimension(Dimension::LinkedAccount);
+            match &rules.tag {
+                Some(key) => {
+                    let tag = GroupBy::Tag(key.clone());
+                    matrices.push((start, end, services, tag.clone()));
+                    matrices.push((start, end, accounts, tag));
+                }
+                None => matrices.push((start, end, services, accounts)),
+            }
+        }
+        // Local budgets get their period's spend by their scope
+        if let Some(local_budgets) = &local_budgets {
+            for budget in &local_budgets.budgets {
+                let (period_start, period_end) = budget.period_dates(today)?;
+                breakdowns.push((period_start, end.min(period_end), budget.scope()?.group_by()));
+            }
+        }
+        let mut fixture = Fixture::capture(source.as_ref(), settings.months, &breakdowns).await?;
+        fixture.capture_matrices(source.as_ref(), &matrices).await?;
+        // Daily costs with enough history for anomaly detection and forecasts
+        let history = chrono::Duration::days(detector.window.max(21) as i64);
+        let first = (start - history).min(Forecast::history_start(forecaster, end - chrono::Duration::days(1))?);
+        if let Err(e) = fixture.capture_daily(source.as_ref(), first, end).await {
+            info!("Not capturing daily costs: {}", e);
+        }
+        if let Err(e) = fixture.capture_budgets(source.as_ref(), settings.months).await {
+            info!("Not capturing budgets: {}", e);
+        }
+        fixture.save(path)?;
+        println!("💾 Saved fixture to {}", path.display());
+    } else if args.no_tui {
         // Simple text output mode
-        run_text_mode(&client)?;
+        run_text_mode(source.as_ref(), &args, &settings, rules.as_ref(), local_budgets.as_ref(), detector, forecaster)
+            .await?;
     } else {
         // TUI mode
-        run_tui_mode(&client)?;
+        let switcher = profile_connector(&args, &settings).map(|connect| (args.profile.join(","), connect));
+        let options = TuiOptions {
+            settings,
+            keymap,
+            theme,
+            required_tags: args.require_tag.clone(),
+            rules,
+            local_budgets,
+            detector,
+            forecaster,
+            switcher,
+        };
+        run_tui_mode(source, options).await?;
     }
 
     Ok(())
 }
 
-fn run_tui_mode(client: &aws::CostExplorerClient) -> Result<()> {
-    let mut app = ui::App::new();
-    
-    // Load data before starting TUI
-    app.load_data(client);
-    
-    // Run the TUI
-    app.run()
+/// Anomaly detection settings from the command line flags
+fn anomaly_detector(args: &Args) -> Result<Detector> {
+    if args.anomaly_sensitivity.is_nan() || args.anomaly_sensitivity <= 0.0 {
+        anyhow::bail!("--anomaly-sensitivity must be above 0, got {}", args.anomaly_sensitivity);
+    }
+    Ok(Detector {
+        baseline: match args.anomaly_baseline.as_str() {
+            "rolling" => Baseline::Rolling,
+            _ => Baseline::Weekday,
+        },
+        window: args.anomaly_window as usize,
+        sensitivity: args.anomaly_sensitivity,
+        ..Detector::default()
+    })
+}
+
+/// Forecast settings from the command line flags
+fn forecaster(args: &Args) -> Forecaster {
+    Forecaster {
+        method: match args.forecast_method.as_str() {
+            "run-rate" => Some(Method::RunRate),
+            "weekday" => Some(Method::Weekday),
+            "smoothing" => Some(Method::Smoothing),
+            _ => None,
+        },
+        window: args.forecast_window as usize,
+    }
+}
+
+/// Pick the data source from the command line flags, regrouped as the
+/// settings ask (fixtures keep the raw grouping)
+fn create_source(args: &Args, settings: &Settings) -> Result<Arc<dyn CostSource>> {
+    if (settings.metric != aws::Metric::default() || settings.filter.is_some())
+        && (!reads_cost_explorer(args) || !args.focus.is_empty())
+    {
+        tracing::warn!("The configured metric and filter only apply to Cost Explorer");
+    }
+    let source = create_merged_source(args, settings)?;
+    if settings.group_by == GroupBy::Dimension(Dimension::Service) || args.save_fixture.is_some() {
+        return Ok(source);
+    }
+    Ok(Arc::new(GroupedSource::new(source, settings.group_by.clone())))
+}
+
+/// The AWS source, FOCUS exports, or both merged
+fn create_merged_source(args: &Args, settings: &Settings) -> Result<Arc<dyn CostSource>> {
+    let cost = match args.focus_cost.as_str() {
+        "effective" => FocusCost::Effective,
+        _ => FocusCost::Billed,
+    };
+    let mut sources: Vec<Arc<dyn CostSource>> = Vec::new();
+
+    // Merged sources must agree on the current month, so FOCUS exports
+    // follow the AWS source's "today"
+    let mut today = None;
+    if args.focus.is_empty() || args.merge {
+        let (aws, aws_today) = create_aws_source(args, settings)?;
+        sources.push(aws);
+        today = aws_today;
+    }
+
+    for path in &args.focus {
+        let mut focus = FocusSource::load(path)?.with_cost(cost);
+        if let Some(today) = today {
+            focus = focus.with_today(today);
+        }
+        info!("Reading {} FOCUS file(s), today is {}", focus.files().len(), focus.today());
+        sources.push(Arc::new(focus));
+    }
+
+    if sources.len() == 1 {
+        return Ok(sources.remove(0));
+    }
+    Ok(Arc::new(MergedSource::new(sources)))
+}
+
+/// The AWS source (demo data, a fixture, a CUR export, a Parquet export or
+/// Cost Explorer) and the date its current month is relative to, if known
+fn create_aws_source(args: &Args, settings: &Settings) -> Result<(Arc<dyn CostSource>, Option<NaiveDate>)> {
+    if args.demo {
+        let today = Local::now().date_naive();
+        if args.profile.len() > 1 {
+            // A differently seeded demo account per profile
+            let accounts = args.profile.iter().zip(DEMO_SEED..).fold(
+                AccountsSource::default(),
+                |accounts, (profile, seed)| {
+                    accounts.with_account(profile, Arc::new(SyntheticSource::new(seed).with_today(today)))
+                },
+            );
+            return Ok((Arc::new(accounts), Some(today)));
+        }
+        return Ok((Arc::new(SyntheticSource::new(DEMO_SEED).with_today(today)), Some(today)));
+    }
+
+    if let Some(path) = &args.fixture {
+        let fixture = FixtureSource::load(path)?;
+        let today = fixture.fixture().current_month.as_ref().and_then(|m| m.end.pred_opt());
+        return Ok((Arc::new(fixture), today));
+    }
+
+    if let Some(path) = &args.cur {
+        let cur = CurSource::load(path)?;
+        info!("Reading {} CUR file(s), today is {}", cur.files().len(), cur.today());
+        let today = cur.today();
+        return Ok((Arc::new(cur), Some(today)));
+    }
+
+    #[cfg(feature = "parquet")]
+    if let Some(path) = &args.parquet {
+        let export = ParquetSource::load(path)?;
+        info!("Reading {} Parquet file(s), today is {}", export.files().len(), export.today());
+        let today = export.today();
+        return Ok((Arc::new(export), Some(today)));
+    }
+
+    if let Some(dir) = &args.replay {
+        let replayer = aws::Replayer::load(dir)?;
+        info!(
+            "Replaying {:?} recorded on {}",
+            dir,
+            replayer.manifest().recorded_on
+        );
+        let credentials = aws::Credentials::anonymous(&replayer.manifest().region);
+        let client = aws::CostExplorerClient::new(credentials).replay_from(replayer);
+        let today = client.today();
+        return Ok((Arc::new(client), Some(today)));
+    }
+
+    if let [profile] = args.profile.as_slice() {
+        let client = cost_explorer(args, settings, profile)?;
+        let today = client.today();
+        return Ok((Arc::new(client), Some(today)));
+    }
+
+    if args.record.is_some() {
+        anyhow::bail!("--record takes a single --profile");
+    }
+    // One client per profile; a profile without credentials is reported in
+    // the Accounts tab instead of stopping the others
+    let mut accounts = AccountsSource::default();
+    for profile in &args.profile {
+        match cost_explorer(args, settings, profile) {
+            Ok(client) => accounts = accounts.with_account(profile, Arc::new(client)),
+            Err(e) => {
+                tracing::warn!("Leaving out profile {}: {:#}", profile, e);
+                accounts = accounts.with_failed_account(profile, format!("{:#}", e));
+            }
+        }
+    }
+    Ok((Arc::new(accounts), Some(Local::now().date_naive())))
+}
+
+/// Builds the source for a profile picked in the TUI
+type Connect = Box<dyn Fn(&str) -> Result<Arc<dyn CostSource>>>;
+
+/// Whether the AWS source is Cost Explorer itself rather than demo data, an
+/// export or a recording
+fn reads_cost_explorer(args: &Args) -> bool {
+    #[cfg(feature = "parquet")]
+    if args.parquet.is_some() {
+        return false;
+    }
+    !(args.demo || args.fixture.is_some() || args.cur.is_some() || args.replay.is_some())
+}
+
+/// Connects to other profiles from the TUI's profile switcher, when the
+/// source is Cost Explorer itself and not recorded or merged
+fn profile_connector(args: &Args, settings: &Settings) -> Option<Connect> {
+    if !reads_cost_explorer(args) || args.record.is_some() || !args.focus.is_empty() {
+        return None;
+    }
+    let args = args.clone();
+    let settings = settings.clone();
+    Some(Box::new(move |profile| {
+        let source: Arc<dyn CostSource> = Arc::new(cost_explorer(&args, &settings, profile)?);
+        if settings.group_by == GroupBy::Dimension(Dimension::Service) {
+            return Ok(source);
+        }
+        Ok(Arc::new(GroupedSource::new(source, settings.group_by.clone())))
+    }))
+}
+
+/// Cost Explorer client for `profile` with the configured metric and
+/// filter, recording if asked to
+fn cost_explorer(args: &Args, settings: &Settings, profile: &str) -> Result<aws::CostExplorerClient> {
+    info!("Using profile: {}", profile);
+
+    // Load credentials
+    let credentials = aws::Credentials::load(profile, args.region.as_deref())?;
+    info!("Loaded credentials for region: {}", credentials.region);
+
+    // Create Cost Explorer client
+    let mut client = aws::CostExplorerClient::new(credentials.clone())
+        .with_max_concurrency(args.max_concurrency)
+        .with_metric(settings.metric);
+    if let Some(filter) = &settings.filter {
+        client = client.with_filter(filter.clone());
+    }
+
+    if let Some(dir) = &args.record {
+        let recorder = aws::Recorder::create(dir, &credentials, client.today())?;
+        info!("Recording exchanges to {:?}", dir);
+        client = client.record_to(recorder);
+    }
+
+    Ok(client)
+}
+
+/// Everything the TUI is set up with besides its source
+#[cfg_attr(not(feature = "tui"), allow(dead_code))]
+struct TuiOptions {
+    settings: Settings,
+    keymap: Keymap,
+    theme: Theme,
+    /// Tag keys the coverage report checks
+    required_tags: Vec<String>,
+    rules: Option<AllocationRules>,
+    local_budgets: Option<LocalBudgets>,
+    detector: Detector,
+    forecaster: Forecaster,
+    /// The profiles in use and how to connect to others, when switching is
+    /// possible
+    switcher: Option<(String, Connect)>,
+}
+
+#[cfg(feature = "tui")]
+async fn run_tui_mode(source: Arc<dyn CostSource>, options: TuiOptions) -> Result<()> {
+    let TuiOptions {
+        settings,
+        keymap,
+        theme,
+        required_tags,
+        rules,
+        local_budgets,
+        detector,
+        forecaster,
+        switcher,
+    } = options;
+    let mut app = aws_costs_tui::ui::App::new()
+        .with_settings(settings)
+        .with_keymap(keymap)
+        .with_theme(theme)
+        .with_required_tags(required_tags)
+        .with_anomaly_detector(detector)
+        .with_forecaster(forecaster);
+    if let Some(rules) = rules {
+        app = app.with_allocation_rules(rules);
+    }
+    if let Some(local_budgets) = local_budgets {
+        app = app.with_local_budgets(local_budgets);
+    }
+    if let Some((profile, connect)) = switcher {
+        app = app.with_profiles(profile, aws::describe_profiles(), connect);
+    }
+
+    // Run the TUI; data loads in the background while it is on screen
+    app.run(source).await
+}
+
+#[cfg(not(feature = "tui"))]
+async fn run_tui_mode(_source: Arc<dyn CostSource>, _options: TuiOptions) -> Result<()> {
+    anyhow::bail!("aws-costs was built without the `tui` feature; use --no-tui")
 }
 
-fn run_text_mode(client: &aws::CostExplorerClient) -> Result<()> {
+async fn run_text_mode(
+    source: &dyn CostSource,
+    args: &Args,
+    settings: &Settings,
+    rules: Option<&AllocationRules>,
+    local_budgets: Option<&LocalBudgets>,
+    detector: Detector,
+    forecaster: Forecaster,
+) -> Result<()> {
     println!("☁️  AWS Cost Explorer\n");
 
     // Get current month costs
-    match client.get_current_month_costs() {
+    match source.current_month().await {
         Ok(data) => {
             println!("📅 {}", data.period);
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -94,17 +588,59 @@ fn run_text_mode(client: &aws::CostExplorerClient) -> Result<()> {
                 data.total_cost, data.currency
             );
 
+            // Merged multi-cloud data gets a provider column
+            let show_provider = data.breakdown.iter().any(|s| s.provider.is_some());
+            let provider_width = if show_provider { 14 } else { 0 };
+
             println!("📋 Service Breakdown:");
-            println!("{:<40} {:>12} {:>8}", "Service", "Cost", "%");
-            println!("{}", "─".repeat(62));
+            println!(
+                "{:<provider_width$}{:<40} {:>12} {:>8}",
+                if show_provider { "Provider" } else { "" },
+                "Service",
+                "Cost",
+                "%"
+            );
+            println!("{}", "─".repeat(62 + provider_width));
 
             for service in &data.breakdown {
                 let name = truncate(&service.service, 38);
+                let provider = truncate(service.provider.as_deref().unwrap_or(""), 13);
                 println!(
-                    "{:<40} {:>10.2} {:>7.1}%",
-                    name, service.cost, service.percentage
+                    "{:<provider_width$}{:<40} {:>10.2} {:>7.1}%",
+                    if show_provider { provider.as_str() } else { "" },
+                    name,
+                    service.cost,
+                    service.percentage
                 );
             }
+
+            if !source.accounts().is_empty() {
+                print_accounts(source, &data).await?;
+            }
+            if args.list_tags {
+                print_tag_keys(source, &data).await?;
+            }
+            for key in &args.tag {
+                print_tag_spend(source, &data, key).await?;
+            }
+            if !args.require_tag.is_empty() {
+                print_tag_coverage(source, &data, args, settings.months).await?;
+            }
+            if let Some(rules) = rules {
+                print_allocation(source, &data, rules, args).await?;
+            }
+            if args.anomalies {
+                print_anomalies(source, &data, detector).await?;
+            }
+            if args.forecast {
+                print_forecast(source, &data, forecaster).await?;
+            }
+            if args.budgets {
+                print_budgets(source, settings.months).await?;
+            }
+            if let Some(local_budgets) = local_budgets {
+                print_local_budgets(source, &data, local_budgets).await?;
+            }
         }
         Err(e) => {
             eprintln!("❌ Error: {}", e);
@@ -119,15 +655,371 @@ fn run_text_mode(client: &aws::CostExplorerClient) -> Result<()> {
     Ok(())
 }
 
-fn truncate(s: &str, max_len: usize) -> String {
-    let s = s
-        .trim_start_matches("Amazon ")
-        .trim_start_matches("AWS ")
-        .trim_start_matches("Amazon");
-    
-    if s.len() > max_len {
-        format!("{}...", &s[..max_len - 3])
+/// Each account's total for the month of `month`, and its services with a
+/// column per account
+async fn print_accounts(source: &dyn CostSource, month: &CostData) -> Result<()> {
+    let report = AccountsReport::load(source, month.start, month.end).await?;
+    println!("\n🏢 Accounts ({}):", report.period);
+    println!("{:<40} {:>12} {:>8}", "Account", "Cost", "%");
+    println!("{}", "─".repeat(62));
+    let total = report.total_cost();
+    for account in &report.accounts {
+        match (account.cost, &account.error) {
+            (Some(cost), _) => println!(
+                "{:<40} {:>12.2} {:>7.1}%",
+                truncate(&account.account, 38),
+                cost,
+                if total > 0.0 { cost / total * 100.0 } else { 0.0 }
+            ),
+            (None, error) => println!(
+                "{:<40} ❌ {}",
+                truncate(&account.account, 38),
+                error.as_deref().unwrap_or("failed")
+            ),
+        }
+    }
+
+    let header: String = report
+        .accounts
+        .iter()
+        .map(|a| format!(" {:>12}", truncate(&a.account, 12)))
+        .collect();
+    println!("\n{:<40}{} {:>12}", "Service", header, "Total");
+    println!("{}", "─".repeat(53 + 13 * report.accounts.len()));
+    for row in &report.services {
+        let costs: String = row.costs.iter().map(|c| format!(" {:>12.2}", c)).collect();
+        println!("{:<40}{} {:>12.2}", truncate(&row.service, 38), costs, row.total);
+    }
+    Ok(())
+}
+
+/// Tag keys with spend in the month of `month`
+async fn print_tag_keys(source: &dyn CostSource, month: &CostData) -> Result<()> {
+    let keys = source.tag_keys(month.start, month.end).await?;
+    println!("\n🏷  Tag Keys ({}):", keys.len());
+    for key in keys {
+        println!("  {}", key);
+    }
+    Ok(())
+}
+
+/// Spend by value of `key` over the month of `month`, untagged included
+async fn print_tag_spend(source: &dyn CostSource, month: &CostData, key: &str) -> Result<()> {
+    let data = source
+        .breakdown(month.start, month.end, &GroupBy::Tag(key.to_string()))
+        .await?;
+    let spend = TagSpend::new(key, &data);
+
+    println!("\n🏷  Spend by tag {}:", key);
+    println!("{:<40} {:>12} {:>8}", "Value", "Cost", "%");
+    println!("{}", "─".repeat(62));
+    for value in &spend.values {
+        let name = match (&value.provider, &value.value) {
+            (Some(provider), Some(v)) => format!("{} · {}", provider, v),
+            (Some(provider), None) => format!("{} · (untagged)", provider),
+            (None, Some(v)) => v.clone(),
+            (None, None) => "(untagged)".to_string(),
+        };
+        println!(
+            "{:<40} {:>10.2} {:>7.1}%",
+            truncate(&name, 38),
+            value.cost,
+            value.percentage
+        );
+    }
+    println!(
+        "🚫 Untagged: {:.1}% (${:.2} of ${:.2})",
+        spend.untagged_percentage(),
+        spend.untagged_cost(),
+        spend.total_cost
+    );
+    Ok(())
+}
+
+/// Coverage of the required tag keys over the month of `month`, worst
+/// offenders first, with the trend over the last `trend_months` months
+async fn print_tag_coverage(
+    source: &dyn CostSource,
+    month: &CostData,
+    args: &Args,
+    trend_months: u32,
+) -> Result<()> {
+    let today = month.end.pred_opt().unwrap_or(month.end);
+    let months = periods::trend_months(today, trend_months)?;
+    let keys = &args.require_tag;
+    let report = TagCoverage::load(source, keys, month.start, month.end, &months).await?;
+
+    println!("\n📐 Tag Coverage ({}):", report.period);
+    for (i, key) in keys.iter().enumerate() {
+        let change = match report.change(i) {
+            Some(change) if change.abs() < 0.05 => "  unchanged since last month".to_string(),
+            Some(change) => format!("  {:+.1} pts since last month", change),
+            None => String::new(),
+        };
+        println!(
+            "  {:<24} {:>6.1}%  (${:.2} untagged){}",
+            truncate(key, 22),
+            report.coverage(i),
+            report.total_cost - report.tagged[i],
+            change
+        );
+    }
+
+    let key_header: String = keys.iter().map(|k| format!(" {:>12}", truncate(k, 12))).collect();
+    for (title, rows) in [("Services", &report.services), ("Accounts", &report.accounts)] {
+        println!("\n🚨 Worst {}:", title);
+        println!("{:<40} {:>12}{} {:>12}", title.trim_end_matches('s'), "Cost", key_header, "Untagged");
+        println!("{}", "─".repeat(66 + 13 * keys.len()));
+        for row in rows.iter().filter(|r| r.untagged_cost() > 0.005).take(10) {
+            let coverage: String = (0..keys.len())
+                .map(|i| format!(" {:>11.1}%", row.coverage(i)))
+                .collect();
+            println!(
+                "{:<40} {:>12.2}{} {:>12.2}",
+                truncate(&row.name, 38),
+                row.cost,
+                coverage,
+                row.untagged_cost()
+            );
+        }
+    }
+
+    println!("\n📈 Coverage Trend:");
+    println!("{:<16}{}", "Month", key_header);
+    for month in &report.trend {
+        let coverage: String = month.coverage.iter().map(|c| format!(" {:>11.1}%", c)).collect();
+        println!("{:<16}{}", month.period, coverage);
+    }
+
+    if let Some(path) = &args.coverage_csv {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Could not create {}", path.display()))?;
+        report.write_csv(file)?;
+        println!("\n💾 Saved tag coverage to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Spend per team over the month of `month` under `rules`, checked against
+/// the month's bill
+async fn print_allocation(
+    source: &dyn CostSource,
+    month: &CostData,
+    rules: &AllocationRules,
+    args: &Args,
+) -> Result<()> {
+    let allocation = Allocation::load(source, rules, month.start, month.end).await?;
+
+    println!("\n👥 Teams ({}):", allocation.period);
+    let bucket_header: String = allocation
+        .buckets
+        .iter()
+        .map(|b| format!(" {:>12}", truncate(b, 12)))
+        .collect();
+    println!("{:<30} {:>12}{} {:>12} {:>7}", "Team", "Direct", bucket_header, "Total", "%");
+    println!("{}", "─".repeat(64 + 13 * allocation.buckets.len()));
+    let share = |cost: f64| {
+        if allocation.total_cost > 0.0 {
+            cost / allocation.total_cost * 100.0
+        } else {
+            0.0
+        }
+    };
+    for team in &allocation.teams {
+        let shared: String = team.shared.iter().map(|c| format!(" {:>12.2}", c)).collect();
+        println!(
+            "{:<30} {:>12.2}{} {:>12.2} {:>6.1}%",
+            truncate(&team.name, 28),
+            team.direct,
+            shared,
+            team.total(),
+            share(team.total())
+        );
+    }
+    if allocation.unallocated.abs() >= CHANGE_EPSILON {
+        println!(
+            "{:<30} {:>12}{} {:>12.2} {:>6.1}%",
+            "(unallocated)",
+            "",
+            " ".repeat(13 * allocation.buckets.len()),
+            allocation.unallocated,
+            share(allocation.unallocated)
+        );
+    }
+
+    let check = allocation.reconcile(month.total_cost);
+    if check.is_balanced() {
+        println!("✅ Reconciled: ${:.2} allocated of a ${:.2} bill", check.allocated, check.bill);
     } else {
-        s.to_string()
+        println!(
+            "⚠️  Not reconciled: ${:.2} allocated + ${:.2} unallocated of a ${:.2} bill (${:.2} off)",
+            check.allocated,
+            check.unallocated,
+            check.bill,
+            check.difference()
+        );
     }
+
+    if let Some(path) = &args.teams_csv {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Could not create {}", path.display()))?;
+        allocation.write_csv(file)?;
+        println!("\n💾 Saved team costs to {}", path.display());
+    }
+    Ok(())
+}
+
+/// Daily spikes over the month of `month`, biggest excess first
+async fn print_anomalies(source: &dyn CostSource, month: &CostData, detector: Detector) -> Result<()> {
+    let report = Anomalies::load(source, detector, month.start, month.end).await?;
+
+    println!(
+        "\n🚨 Anomalies ({}, {} median of {} days, above {:.1} MADs):",
+        periods::range_name(report.start, report.end),
+        detector.baseline.name(),
+        detector.window,
+        detector.sensitivity
+    );
+    if report.anomalies.is_empty() {
+        println!("✅ No spikes found");
+        return Ok(());
+    }
+    println!("{:<12} {:>4} {:<40} {:>10} {:>10} {:>8}", "Date", "Days", "Service", "Expected", "Actual", "Change");
+    println!("{}", "─".repeat(89));
+    for anomaly in &report.anomalies {
+        let change = match anomaly.change_percentage() {
+            Some(change) => format!("+{:.0}%", change),
+            None => "new".to_string(),
+        };
+        let name = match &anomaly.provider {
+            Some(provider) => format!("{} · {}", provider, anomaly.service),
+            None => anomaly.service.clone(),
+        };
+        println!(
+            "{:<12} {:>4} {:<40} {:>10.2} {:>10.2} {:>8}",
+            anomaly.date.format("%Y-%m-%d"),
+            anomaly.days,
+            truncate(&name, 38),
+            anomaly.expected,
+            anomaly.actual,
+            change
+        );
+    }
+    println!(
+        "💸 ${:.2} {} over baseline across {} spike{}",
+        report.total_excess(),
+        report.currency,
+        report.anomalies.len(),
+        if report.anomalies.len() == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
+
+/// Month-end and quarter-end projections from the month of `month`, overall
+/// and per service
+async fn print_forecast(source: &dyn CostSource, month: &CostData, forecaster: Forecaster) -> Result<()> {
+    // Today's costs are still coming in, so they're projected too
+    let today = month.end - chrono::Duration::days(1);
+    let forecast = Forecast::load(source, forecaster, today).await?;
+    let band = |low: f64, high: f64| format!("{:.2} – {:.2}", low, high);
+
+    println!(
+        "\n📈 Forecast ({}, fitted to {} days, 90% bands):",
+        forecast.method.name(),
+        forecast.window
+    );
+    for (name, projection) in [
+        (periods::month_name(month.start), &forecast.month),
+        (periods::quarter_name(today), &forecast.quarter),
+    ] {
+        println!(
+            "{:<14} ${:.2} {}  ({})",
+            name,
+            projection.total(),
+            forecast.currency,
+            band(projection.low(), projection.high())
+        );
+    }
+    println!();
+    println!("{:<40} {:>10} {:>21} {:>10} {:>21}", "Service", "Month-end", "Band", "Quarter", "Band");
+    println!("{}", "─".repeat(106));
+    for service in &forecast.services {
+        let name = match &service.provider {
+            Some(provider) => format!("{} · {}", provider, service.service),
+            None => service.service.clone(),
+        };
+        println!(
+            "{:<40} {:>10.2} {:>21} {:>10.2} {:>21}",
+            truncate(&name, 38),
+            service.month.total(),
+            band(service.month.low(), service.month.high()),
+            service.quarter.total(),
+            band(service.quarter.low(), service.quarter.high())
+        );
+    }
+    Ok(())
+}
+
+/// Every budget's spend and forecast against its limit this period, with
+/// `months` months of history
+async fn print_budgets(source: &dyn CostSource, months: u32) -> Result<()> {
+    let budgets = source.budgets(months).await?;
+    println!("\n💰 Budgets ({}):", budgets.len());
+    println!(
+        "{:<24} {:<12} {:>12} {:>12} {:>6} {:>12}  Status",
+        "Budget", "Period", "Limit", "Actual", "%", "Forecast"
+    );
+    println!("{}", "─".repeat(98));
+    for budget in &budgets {
+        let forecast = budget.forecasted.map_or("—".to_string(), |f| format!("{:.2}", f));
+        println!(
+            "{:<24} {:<12} {:>12.2} {:>12.2} {:>5.0}% {:>12}  {}",
+            truncate(&budget.name, 23),
+            truncate(&budget.period_name(), 12),
+            budget.limit,
+            budget.actual,
+            budget.percentage(),
+            forecast,
+            budget.status().label()
+        );
+    }
+    Ok(())
+}
+
+/// Budgets from `--local-budgets` for the periods containing the last day
+/// of `month`
+async fn print_local_budgets(
+    source: &dyn CostSource,
+    month: &CostData,
+    local_budgets: &LocalBudgets,
+) -> Result<()> {
+    let today = month.end - chrono::Duration::days(1);
+    let budgets = local_budgets.evaluate(source, today).await?;
+    println!("\n📋 Local budgets ({}):", budgets.len());
+    println!(
+        "{:<20} {:<28} {:<12} {:>10} {:>10} {:>6} {:>10} {:>6}  Status",
+        "Budget", "Scope", "Period", "Limit", "Actual", "%", "Forecast", "Alert"
+    );
+    println!("{}", "─".repeat(122));
+    for budget in &budgets {
+        let forecast = budget.forecasted.map_or("—".to_string(), |f| format!("{:.2}", f));
+        let alert = budget.crossed_threshold().map_or("—".to_string(), |t| format!("{:.0}%", t));
+        println!(
+            "{:<20} {:<28} {:<12} {:>10.2} {:>10.2} {:>5.0}% {:>10} {:>6}  {}",
+            truncate(&budget.name, 19),
+            truncate(budget.scope.as_deref().unwrap_or(""), 27),
+            truncate(&budget.period_name(), 12),
+            budget.limit,
+            budget.actual,
+            budget.percentage(),
+            forecast,
+            alert,
+            budget.status().label()
+        );
+    }
+    Ok(())
+}
+
+fn truncate(s: &str, max_len: usize) -> String {
+    text::truncate(s, max_len, "...")
 }
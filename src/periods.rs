@@ -0,0 +1,100 @@
+//! Calendar period helpers shared by every cost source
+//!
+//! All ranges are `[start, end)`: `end` is the first day *not* included,
+//! matching Cost Explorer's `TimePeriod`.
+
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+
+/// First day of the month containing `date`
+pub fn month_start(date: NaiveDate) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year(), date.month(), 1)
+        .ok_or_else(|| anyhow!("Failed to calculate start of month"))
+}
+
+/// First day of the month after the one containing `date`
+pub fn next_month_start(date: NaiveDate) -> Result<NaiveDate> {
+    month_start(date)?
+        .checked_add_months(Months::new(1))
+        .ok_or_else(|| anyhow!("Failed to calculate next month"))
+}
+
+/// Number of days in the month containing `date`
+pub fn days_in_month(date: NaiveDate) -> Result<i64> {
+    Ok((next_month_start(date)? - month_start(date)?).num_days())
+}
+
+/// First day of the calendar quarter containing `date`
+pub fn quarter_start(date: NaiveDate) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(date.year(), (date.month() - 1) / 3 * 3 + 1, 1)
+        .ok_or_else(|| anyhow!("Failed to calculate start of quarter"))
+}
+
+/// First day of the calendar quarter after the one containing `date`
+pub fn next_quarter_start(date: NaiveDate) -> Result<NaiveDate> {
+    quarter_start(date)?
+        .checked_add_months(Months::new(3))
+        .ok_or_else(|| anyhow!("Failed to calculate next quarter"))
+}
+
+/// The current month up to and including `today`
+pub fn month_to_date(today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    // End date is tomorrow (exclusive)
+    Ok((month_start(today)?, today + Duration::days(1)))
+}
+
+/// The full calendar month before the one containing `today`
+pub fn previous_month(today: NaiveDate) -> Result<(NaiveDate, NaiveDate)> {
+    let first_of_current = month_start(today)?;
+    let first_of_previous = first_of_current
+        .checked_sub_months(Months::new(1))
+        .ok_or_else(|| anyhow!("Failed to calculate first of previous month"))?;
+    Ok((first_of_previous, first_of_current))
+}
+
+/// The last `days` days up to and including `today`
+pub fn last_days(today: NaiveDate, days: i64) -> (NaiveDate, NaiveDate) {
+    (today - Duration::days(days - 1), today + Duration::days(1))
+}
+
+/// Calendar month ranges for the last `months` months, oldest first. The
+/// current month ends tomorrow (exclusive) rather than at the end of the
+/// month.
+pub fn trend_months(today: NaiveDate, months: u32) -> Result<Vec<(NaiveDate, NaiveDate)>> {
+    let current_month = month_start(today)?;
+    let tomorrow = today + Duration::days(1);
+
+    (0..months)
+        .rev()
+        .map(|i| {
+            let start = current_month
+                .checked_sub_months(Months::new(i))
+                .ok_or_else(|| anyhow!("Failed to calculate month"))?;
+            let next = next_month_start(start)?;
+            Ok((start, std::cmp::min(next, tomorrow)))
+        })
+        .collect()
+}
+
+/// Display name for the month starting at `start`, e.g. "January 2026"
+pub fn month_name(start: NaiveDate) -> String {
+    start.format("%B %Y").to_string()
+}
+
+/// Display name for the quarter containing `date`, e.g. "Q1 2026"
+pub fn quarter_name(date: NaiveDate) -> String {
+    format!("Q{} {}", (date.month() - 1) / 3 + 1, date.year())
+}
+
+/// Display name for an arbitrary range: the month name when it covers a
+/// single calendar month (or the start of one), otherwise the date span
+pub fn range_name(start: NaiveDate, end: NaiveDate) -> String {
+    match next_month_start(start) {
+        Ok(next) if start.day() == 1 && end <= next => month_name(start),
+        _ => format!(
+            "{} – {}",
+            start.format("%Y-%m-%d"),
+            (end - Duration::days(1)).format("%Y-%m-%d")
+        ),
+    }
+}
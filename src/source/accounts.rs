@@ -0,0 +1,198 @@
+//! [`CostSource`] spanning several standalone accounts, e.g. one per AWS
+//! profile
+//!
+//! Every query goes to all accounts concurrently and the results are summed.
+//! An account that fails, or whose credentials couldn't be loaded, is left
+//! out with a warning; a query only fails when every account does.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use futures::future::join_all;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::Arc;
+use tracing::warn;
+
+use super::merged::{merge, merge_daily, merge_matrices, merge_trends, Part};
+use super::CostSource;
+use crate::aws::{Budget, CostData, CostMatrix, DailyCosts, Dimension, GroupBy, MonthlyTrend};
+
+/// One account's costs by service, or why they couldn't be loaded
+#[derive(Debug, Clone)]
+pub struct AccountCosts {
+    pub account: String,
+    pub costs: std::result::Result<CostData, String>,
+}
+
+struct Account {
+    name: String,
+    source: std::result::Result<Arc<dyn CostSource>, String>,
+}
+
+/// Several accounts presented as one
+#[derive(Default)]
+pub struct AccountsSource {
+    accounts: Vec<Account>,
+}
+
+impl AccountsSource {
+    /// Add an account, named e.g. after its profile
+    pub fn with_account(mut self, name: impl Into<String>, source: Arc<dyn CostSource>) -> Self {
+        self.accounts.push(Account {
+            name: name.into(),
+            source: Ok(source),
+        });
+        self
+    }
+
+    /// Add an account that couldn't be set up, e.g. for lack of credentials;
+    /// it is reported as failed by [`CostSource::account_breakdown`]
+    pub fn with_failed_account(mut self, name: impl Into<String>, error: impl Display) -> Self {
+        self.accounts.push(Account {
+            name: name.into(),
+            source: Err(error.to_string()),
+        });
+        self
+    }
+
+    /// Run `query` against every account, keeping the ones that answer
+    async fn each<'a, T, Fut>(
+        &'a self,
+        query: impl Fn(&'a dyn CostSource) -> Fut,
+    ) -> Result<Vec<Part<T>>>
+    where
+        Fut: Future<Output = Result<T>> + 'a,
+    {
+        let mut errors: Vec<String> = Vec::new();
+        let mut queries = Vec::new();
+        for account in &self.accounts {
+            match &account.source {
+                Ok(source) => {
+                    let query = query(source.as_ref());
+                    queries.push(async move { (account.name.as_str(), query.await) });
+                }
+                Err(e) => errors.push(format!("{}: {}", account.name, e)),
+            }
+        }
+
+        let mut parts = Vec::new();
+        for (name, result) in join_all(queries).await {
+            match result {
+                Ok(data) => parts.push(Part {
+                    name: name.to_string(),
+                    provider: None,
+                    data,
+                }),
+                Err(e) => {
+                    warn!("Leaving out account {}: {:#}", name, e);
+                    errors.push(format!("{}: {:#}", name, e));
+                }
+            }
+        }
+        if parts.is_empty() {
+            bail!("Every account failed: {}", errors.join("; "));
+        }
+        Ok(parts)
+    }
+}
+
+#[async_trait]
+impl CostSource for AccountsSource {
+    fn name(&self) -> String {
+        format!("{} accounts", self.accounts.len())
+    }
+
+    fn accounts(&self) -> Vec<String> {
+        self.accounts.iter().map(|a| a.name.clone()).collect()
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        Ok(merge(self.each(|s| s.current_month()).await?))
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        Ok(merge(self.each(|s| s.previous_month()).await?))
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        Ok(merge_trends(self.each(|s| s.monthly_trend(months)).await?))
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        Ok(merge(
+            self.each(|s| s.breakdown(start, end, group_by)).await?,
+        ))
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let parts = self.each(|s| s.matrix(start, end, rows, columns)).await?;
+        Ok(merge_matrices(parts, start, end))
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let parts = self.each(|s| s.daily(start, end)).await?;
+        Ok(merge_daily(parts, start, end))
+    }
+
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        Ok(merge(
+            self.each(|s| s.resources(service, start, end)).await?,
+        ))
+    }
+
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let parts = self.each(|s| s.tag_keys(start, end)).await?;
+        let keys: BTreeSet<String> = parts.into_iter().flat_map(|p| p.data).collect();
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Budgets of every account, named after their account
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        let parts = self.each(|s| s.budgets(months)).await?;
+        Ok(parts
+            .into_iter()
+            .flat_map(|part| {
+                part.data.into_iter().map(move |mut budget| {
+                    budget.name = format!("{}: {}", part.name, budget.name);
+                    budget
+                })
+            })
+            .collect())
+    }
+
+    async fn account_breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<AccountCosts>> {
+        let services = GroupBy::Dimension(Dimension::Service);
+        let services = &services;
+        Ok(join_all(self.accounts.iter().map(|account| async move {
+            let costs = match &account.source {
+                Ok(source) => source
+                    .breakdown(start, end, services)
+                    .await
+                    .map_err(|e| format!("{:#}", e)),
+                Err(e) => Err(e.clone()),
+            };
+            AccountCosts {
+                account: account.name.clone(),
+                costs,
+            }
+        }))
+        .await)
+    }
+}
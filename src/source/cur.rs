@@ -0,0 +1,508 @@
+//! [`CostSource`] reading Cost and Usage Report (CUR) CSV exports from disk
+//!
+//! Accepts a single `.csv` / `.csv.gz` file, a CUR manifest
+//! (`*-Manifest.json`) listing the report's files, or a directory synced from
+//! the report's S3 prefix. Files are streamed row by row for every query, so
+//! only the aggregated totals are ever held in memory.
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::files::{
+    export_today, is_csv_file, latest_first_row_date, open, parse_date, spawn_scan, walk, Columns,
+};
+use super::{cost_data, provider_cost_matrix, provider_daily_costs, CostSource};
+use crate::aws::{CostData, CostMatrix, DailyCosts, Dimension, GroupBy, MonthlyTrend, TrendGap};
+use crate::periods;
+
+const USAGE_START: &str = "lineItem/UsageStartDate";
+const UNBLENDED_COST: &str = "lineItem/UnblendedCost";
+const CURRENCY: &str = "lineItem/CurrencyCode";
+const BILLING_PERIOD_START: &str = "bill/BillingPeriodStartDate";
+
+/// Value used when a line item has no value for the grouped column
+const NO_VALUE: &str = "(none)";
+
+/// Cost source aggregating CUR line items
+#[derive(Debug, Clone)]
+pub struct CurSource {
+    path: PathBuf,
+    files: Vec<PathBuf>,
+    today: NaiveDate,
+}
+
+impl CurSource {
+    /// Open a CUR file, manifest or directory.
+    ///
+    /// "Today" defaults to the last day of the newest billing period in the
+    /// report, or the real date if that period hasn't ended yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let files = resolve_files(path)?;
+        if files.is_empty() {
+            bail!("No CUR files (.csv or .csv.gz) found in {:?}", path);
+        }
+
+        let today = export_today(latest_first_row_date(&files, BILLING_PERIOD_START)?)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            files,
+            today,
+        })
+    }
+
+    /// Pin "today" for month-to-date and trend queries
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Data files that will be read, in order
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// The date queries are relative to
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    /// Sum line items used in `[start, end)`, grouped by `group_by`.
+    ///
+    /// Tag and cost category groups are labelled `key$value` like Cost
+    /// Explorer, with an empty value for untagged spend.
+    pub fn aggregate(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        let currency = self.scan(
+            start,
+            end,
+            std::slice::from_ref(group_by),
+            |_, keys, cost| {
+                *totals.entry(keys[0].clone()).or_default() += cost;
+            },
+        )?;
+
+        let mut data = cost_data(
+            totals.into_iter().collect(),
+            start,
+            end,
+            periods::range_name(start, end),
+        );
+        data.currency = currency;
+        Ok(data)
+    }
+
+    /// Sum line items used in `[start, end)`, grouped by `rows` and
+    /// `columns` together
+    pub fn aggregate_matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let mut totals: HashMap<(String, String), f64> = HashMap::new();
+        let groupings = [rows.clone(), columns.clone()];
+        let currency = self.scan(start, end, &groupings, |_, keys, cost| {
+            *totals
+                .entry((keys[0].clone(), keys[1].clone()))
+                .or_default() += cost;
+        })?;
+
+        let cells = totals
+            .into_iter()
+            .map(|((row, column), cost)| (None, row, column, cost))
+            .collect();
+        let mut matrix = provider_cost_matrix(cells, start, end, periods::range_name(start, end));
+        matrix.currency = currency;
+        Ok(matrix)
+    }
+
+    /// Sum line items used in `[start, end)` by service and usage day
+    pub fn aggregate_daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let mut totals: HashMap<(String, NaiveDate), f64> = HashMap::new();
+        let service = GroupBy::Dimension(Dimension::Service);
+        let currency = self.scan(start, end, &[service], |date, keys, cost| {
+            *totals.entry((keys[0].clone(), date)).or_default() += cost;
+        })?;
+
+        let rows = totals
+            .into_iter()
+            .map(|((service, date), cost)| (None, service, date, cost))
+            .collect();
+        let mut daily = provider_daily_costs(rows, start, end);
+        daily.currency = currency;
+        Ok(daily)
+    }
+
+    /// Tag keys with a `resourceTags/...` column in any file, without the
+    /// `user:` prefix CUR adds to user-defined tags
+    pub fn scan_tag_keys(&self) -> Result<Vec<String>> {
+        let mut keys = BTreeSet::new();
+        for path in &self.files {
+            let mut reader = csv::Reader::from_reader(open(path)?);
+            for header in reader.headers()? {
+                if let Some(key) = header.strip_prefix("resourceTags/") {
+                    keys.insert(key.strip_prefix("user:").unwrap_or(key).to_string());
+                }
+            }
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Per-service totals for each month range, in one pass over the files
+    fn aggregate_months(&self, ranges: &[(NaiveDate, NaiveDate)]) -> Result<MonthlyTrend> {
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return Ok(MonthlyTrend::default());
+        };
+
+        let service = GroupBy::Dimension(Dimension::Service);
+        let mut months: BTreeMap<NaiveDate, HashMap<String, f64>> = BTreeMap::new();
+        let currency = self.scan(first.0, last.1, &[service], |date, keys, cost| {
+            if let Ok(month) = periods::month_start(date) {
+                *months
+                    .entry(month)
+                    .or_default()
+                    .entry(keys[0].clone())
+                    .or_default() += cost;
+            }
+        })?;
+
+        let mut trend = MonthlyTrend::default();
+        for &(start, end) in ranges {
+            let period = periods::month_name(start);
+            match months.remove(&start) {
+                Some(totals) => {
+                    let mut data = cost_data(totals.into_iter().collect(), start, end, period);
+                    data.currency = currency.clone();
+                    trend.months.push(data);
+                }
+                None => trend.gaps.push(TrendGap {
+                    period,
+                    start,
+                    partial: false,
+                    reason: "no line items in CUR".to_string(),
+                }),
+            }
+        }
+        Ok(trend)
+    }
+
+    /// Stream every line item used in `[start, end)` to `visit` along with
+    /// its key for each of `groupings` and unblended cost; returns the
+    /// report currency
+    fn scan(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        groupings: &[GroupBy],
+        mut visit: impl FnMut(NaiveDate, &[String], f64),
+    ) -> Result<String> {
+        let mut currency = None;
+        let mut keys = Vec::with_capacity(groupings.len());
+
+        for path in &self.files {
+            let mut reader = csv::Reader::from_reader(open(path)?);
+            let columns = Columns::new(reader.headers()?);
+            let usage_start = columns
+                .require(USAGE_START, path)
+                .context("is it a CUR export?")?;
+            let cost_column = columns.require(UNBLENDED_COST, path)?;
+            let currency_column = columns.find(CURRENCY);
+            let groupings: Vec<Grouping> = groupings
+                .iter()
+                .map(|group_by| Grouping::new(&columns, group_by))
+                .collect();
+
+            let mut record = csv::StringRecord::new();
+            while reader
+                .read_record(&mut record)
+                .with_context(|| format!("Could not read {:?}", path))?
+            {
+                let Some(date) = record.get(usage_start).and_then(parse_date) else {
+                    continue;
+                };
+                if date < start || date >= end {
+                    continue;
+                }
+
+                let cost = match record.get(cost_column).unwrap_or("").trim() {
+                    "" => 0.0,
+                    value => value.parse::<f64>().map_err(|e| {
+                        let line = record.position().map(|p| p.line()).unwrap_or(0);
+                        anyhow!("{:?} line {}: invalid cost {:?}: {}", path, line, value, e)
+                    })?,
+                };
+
+                if currency.is_none() {
+                    currency = currency_column
+                        .and_then(|i| record.get(i))
+                        .filter(|c| !c.is_empty())
+                        .map(str::to_string);
+                }
+
+                keys.clear();
+                keys.extend(groupings.iter().map(|g| g.key(&record)));
+                visit(date, &keys, cost);
+            }
+        }
+
+        Ok(currency.unwrap_or_else(|| "USD".to_string()))
+    }
+}
+
+#[async_trait]
+impl CostSource for CurSource {
+    fn name(&self) -> String {
+        format!("CUR: {}", self.path.display())
+    }
+
+    fn provider(&self) -> Option<String> {
+        Some("AWS".to_string())
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        let (start, end) = periods::month_to_date(self.today)?;
+        spawn_scan(self, move |cur| {
+            cur.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        let (start, end) = periods::previous_month(self.today)?;
+        spawn_scan(self, move |cur| {
+            cur.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let ranges = periods::trend_months(self.today, months)?;
+        spawn_scan(self, move |cur| cur.aggregate_months(&ranges)).await
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let group_by = group_by.clone();
+        spawn_scan(self, move |cur| cur.aggregate(start, end, &group_by)).await
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let (rows, columns) = (rows.clone(), columns.clone());
+        spawn_scan(self, move |cur| {
+            cur.aggregate_matrix(start, end, &rows, &columns)
+        })
+        .await
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        spawn_scan(self, move |cur| cur.aggregate_daily(start, end)).await
+    }
+
+    /// Every tag column in the report, whether or not `[start, end)` has
+    /// tagged line items
+    async fn tag_keys(&self, _start: NaiveDate, _end: NaiveDate) -> Result<Vec<String>> {
+        spawn_scan(self, |cur| cur.scan_tag_keys()).await
+    }
+}
+
+/// How to derive a line item's group key in one file
+struct Grouping {
+    /// Candidate columns, first non-empty value wins
+    columns: Vec<usize>,
+    /// `key` for `key$value` labels of tags and cost categories
+    prefix: Option<String>,
+}
+
+impl Grouping {
+    fn new(columns: &Columns, group_by: &GroupBy) -> Self {
+        let (candidates, prefix): (Vec<String>, Option<String>) = match group_by {
+            GroupBy::Dimension(dimension) => (
+                dimension_columns(*dimension)
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect(),
+                None,
+            ),
+            // Tag keys are case-sensitive, so match these headers exactly
+            GroupBy::Tag(key) => {
+                let headers = [
+                    format!("resourceTags/{}", key),
+                    format!("resourceTags/user:{}", key),
+                ];
+                let columns = headers
+                    .iter()
+                    .filter_map(|h| columns.exact.get(h).copied())
+                    .collect();
+                return Self {
+                    columns,
+                    prefix: Some(key.clone()),
+                };
+            }
+            GroupBy::CostCategory(name) => {
+                let columns = columns
+                    .exact
+                    .get(&format!("costCategory/{}", name))
+                    .copied()
+                    .into_iter()
+                    .collect();
+                return Self {
+                    columns,
+                    prefix: Some(name.clone()),
+                };
+            }
+        };
+
+        Self {
+            columns: candidates.iter().filter_map(|c| columns.find(c)).collect(),
+            prefix,
+        }
+    }
+
+    fn key(&self, record: &csv::StringRecord) -> String {
+        let value = self
+            .columns
+            .iter()
+            .filter_map(|&i| record.get(i))
+            .find(|v| !v.is_empty())
+            .unwrap_or("");
+
+        match &self.prefix {
+            Some(prefix) => format!("{}${}", prefix, value),
+            None if value.is_empty() => NO_VALUE.to_string(),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// CUR columns holding each Cost Explorer dimension, most specific first
+fn dimension_columns(dimension: Dimension) -> &'static [&'static str] {
+    match dimension {
+        Dimension::Service => &["product/ProductName", "lineItem/ProductCode"],
+        Dimension::LinkedAccount => &["lineItem/UsageAccountId"],
+        Dimension::Region => &["product/region", "product/regionCode"],
+        Dimension::UsageType => &["lineItem/UsageType"],
+        Dimension::InstanceType => &["product/instanceType"],
+        Dimension::Operation => &["lineItem/Operation"],
+        Dimension::PurchaseType => &["pricing/term"],
+        Dimension::RecordType => &["lineItem/LineItemType"],
+        Dimension::ResourceId => &["lineItem/ResourceId"],
+    }
+}
+
+/// CUR manifest, as written next to each report version
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    report_keys: Vec<String>,
+    #[serde(default)]
+    billing_period: Option<ManifestPeriod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPeriod {
+    start: String,
+}
+
+fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with("Manifest.json"))
+}
+
+/// Expand a file, manifest or directory into the data files to read
+fn resolve_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return if is_csv_file(path) {
+            Ok(vec![path.to_path_buf()])
+        } else {
+            Ok(read_manifest(path)?.1)
+        };
+    }
+
+    let mut manifests = Vec::new();
+    let mut data_files = Vec::new();
+    walk(path, &mut |file| {
+        if is_manifest(file) {
+            manifests.push(file.to_path_buf());
+        } else if is_csv_file(file) {
+            data_files.push(file.to_path_buf());
+        }
+    })?;
+
+    if manifests.is_empty() {
+        data_files.sort();
+        return Ok(data_files);
+    }
+
+    // S3 syncs keep every report version; the manifest closest to the root of
+    // each billing period points at the current one
+    let mut by_period: BTreeMap<String, (usize, Vec<PathBuf>)> = BTreeMap::new();
+    for manifest in manifests {
+        let (period, files) = read_manifest(&manifest)?;
+        let period = period.unwrap_or_else(|| manifest.display().to_string());
+        let depth = manifest.components().count();
+        match by_period.get(&period) {
+            Some((existing, _)) if *existing <= depth => {}
+            _ => {
+                by_period.insert(period, (depth, files));
+            }
+        }
+    }
+    Ok(by_period
+        .into_values()
+        .flat_map(|(_, files)| files)
+        .collect())
+}
+
+/// Billing period and resolved data files of a manifest
+fn read_manifest(path: &Path) -> Result<(Option<String>, Vec<PathBuf>)> {
+    let text = fs::read_to_string(path).with_context(|| format!("Could not read {:?}", path))?;
+    let manifest: Manifest =
+        serde_json::from_str(&text).with_context(|| format!("Invalid CUR manifest {:?}", path))?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+
+    let files = manifest
+        .report_keys
+        .iter()
+        .map(|key| resolve_key(dir, key))
+        .collect::<Result<_>>()?;
+    Ok((manifest.billing_period.map(|p| p.start), files))
+}
+
+/// Find an S3 report key on disk by trying ever shorter suffixes of it
+/// relative to the manifest's directory
+fn resolve_key(dir: &Path, key: &str) -> Result<PathBuf> {
+    let parts: Vec<&str> = key.split('/').filter(|p| !p.is_empty()).collect();
+    (0..parts.len())
+        .map(|skip| dir.join(parts[skip..].join("/")))
+        .find(|candidate| candidate.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "CUR file {} listed in manifest not found under {:?}",
+                key,
+                dir
+            )
+        })
+}
@@ -0,0 +1,138 @@
+//! Shared helpers for sources that stream local billing export files
+
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use flate2::read::MultiGzDecoder;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+/// Header lookup for one CSV file
+pub(crate) struct Columns {
+    pub(crate) exact: HashMap<String, usize>,
+    normalized: HashMap<String, usize>,
+}
+
+impl Columns {
+    pub(crate) fn new(headers: &csv::StringRecord) -> Self {
+        Self::from_names(headers.iter())
+    }
+
+    /// Lookup over column names in file order
+    pub(crate) fn from_names<'a>(names: impl Iterator<Item = &'a str>) -> Self {
+        let mut exact = HashMap::new();
+        let mut normalized = HashMap::new();
+        for (i, name) in names.enumerate() {
+            exact.entry(name.to_string()).or_insert(i);
+            normalized.entry(normalize(name)).or_insert(i);
+        }
+        Self { exact, normalized }
+    }
+
+    /// Index of a fixed column, ignoring case and `/`/`_` separators so
+    /// both `lineItem/UsageStartDate` and `line_item_usage_start_date` match
+    pub(crate) fn find(&self, name: &str) -> Option<usize> {
+        self.exact
+            .get(name)
+            .or_else(|| self.normalized.get(&normalize(name)))
+            .copied()
+    }
+
+    /// First of `names` present in the file
+    pub(crate) fn find_any(&self, names: &[&str]) -> Option<usize> {
+        names.iter().find_map(|name| self.find(name))
+    }
+
+    pub(crate) fn require(&self, name: &str, path: &Path) -> Result<usize> {
+        self.find(name)
+            .ok_or_else(|| anyhow!("{:?} is missing the {} column", path, name))
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Date part of an ISO 8601 timestamp such as `2026-03-01T00:00:00Z`
+pub(crate) fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Open a data file, decompressing `.gz` on the fly
+pub(crate) fn open(path: &Path) -> Result<Box<dyn Read>> {
+    let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+    let reader = BufReader::new(file);
+    if path.extension().is_some_and(|e| e == "gz") {
+        Ok(Box::new(MultiGzDecoder::new(reader)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Whether a path looks like a `.csv` or `.csv.gz` export
+pub(crate) fn is_csv_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".csv") || n.ends_with(".csv.gz"))
+}
+
+/// Visit every file under `dir`, recursively
+pub(crate) fn walk(dir: &Path, visit: &mut impl FnMut(&Path)) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Could not read {:?}", dir))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            walk(&path, visit)?;
+        } else {
+            visit(&path);
+        }
+    }
+    Ok(())
+}
+
+/// Newest date in `column` across the first row of each CSV file
+pub(crate) fn latest_first_row_date(files: &[PathBuf], column: &str) -> Result<Option<NaiveDate>> {
+    let mut latest = None;
+    for path in files {
+        let mut reader = csv::Reader::from_reader(open(path)?);
+        let Some(index) = Columns::new(reader.headers()?).find(column) else {
+            continue;
+        };
+        if let Some(record) = reader.records().next() {
+            let date = record?.get(index).and_then(parse_date);
+            latest = latest.max(date);
+        }
+    }
+    Ok(latest)
+}
+
+/// "Today" for an export whose newest billing period starts on `period`:
+/// the last day of that month, or the real date if it hasn't ended yet
+pub(crate) fn export_today(period: Option<NaiveDate>) -> Result<NaiveDate> {
+    let today = chrono::Local::now().date_naive();
+    Ok(match period {
+        Some(period) => {
+            let last_day = crate::periods::next_month_start(period)?
+                .pred_opt()
+                .unwrap_or(period);
+            today.min(last_day)
+        }
+        None => today,
+    })
+}
+
+/// Run a blocking file scan on tokio's blocking pool
+pub(crate) async fn spawn_scan<S, T, F>(source: &S, f: F) -> Result<T>
+where
+    S: Clone + Send + 'static,
+    T: Send + 'static,
+    F: FnOnce(&S) -> Result<T> + Send + 'static,
+{
+    let source = source.clone();
+    tokio::task::spawn_blocking(move || f(&source))
+        .await
+        .map_err(|e| anyhow!("File scan panicked: {}", e))?
+}
@@ -0,0 +1,331 @@
+//! [`CostSource`] that replays previously captured results from JSON
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::{group_by_label, CostSource};
+use crate::aws::{Budget, CostData, CostMatrix, DailyCosts, DailySeries, GroupBy, MonthlyTrend};
+
+/// Captured answers to every [`CostSource`] query
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Where the data came from, shown in the UI
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The captured source's provider; fixtures saved before it was
+    /// recorded were all captured from AWS
+    #[serde(default = "aws_provider")]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub current_month: Option<CostData>,
+    #[serde(default)]
+    pub previous_month: Option<CostData>,
+    #[serde(default)]
+    pub trend: MonthlyTrend,
+    #[serde(default)]
+    pub breakdowns: Vec<FixtureBreakdown>,
+    #[serde(default)]
+    pub matrices: Vec<FixtureMatrix>,
+    /// Daily costs by service, for anomaly detection
+    #[serde(default)]
+    pub daily: Option<DailyCosts>,
+    /// AWS Budgets with their history
+    #[serde(default)]
+    pub budgets: Option<Vec<Budget>>,
+}
+
+fn aws_provider() -> Option<String> {
+    Some("AWS".to_string())
+}
+
+/// A captured breakdown query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureBreakdown {
+    /// Grouping label, e.g. `LINKED_ACCOUNT` or `TAG:team`
+    pub group_by: String,
+    pub data: CostData,
+}
+
+/// A captured two-way grouping query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureMatrix {
+    /// Grouping labels of the rows and columns
+    pub rows: String,
+    pub columns: String,
+    pub data: CostMatrix,
+}
+
+impl Fixture {
+    /// Read a fixture from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read fixture {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid fixture {:?}", path))
+    }
+
+    /// Write the fixture as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content).with_context(|| format!("Could not write fixture {:?}", path))
+    }
+
+    /// Run every query against `source` and capture the results
+    pub async fn capture(
+        source: &dyn CostSource,
+        trend_months: u32,
+        breakdowns: &[(NaiveDate, NaiveDate, GroupBy)],
+    ) -> Result<Self> {
+        let mut fixture = Fixture {
+            name: Some(source.name()),
+            provider: source.provider(),
+            current_month: Some(source.current_month().await?),
+            previous_month: Some(source.previous_month().await?),
+            trend: source.monthly_trend(trend_months).await?,
+            breakdowns: Vec::new(),
+            matrices: Vec::new(),
+            daily: None,
+            budgets: None,
+        };
+        for (start, end, group_by) in breakdowns {
+            fixture.breakdowns.push(FixtureBreakdown {
+                group_by: group_by_label(group_by),
+                data: source.breakdown(*start, *end, group_by).await?,
+            });
+        }
+        Ok(fixture)
+    }
+
+    /// Run two-way grouping queries against `source` and add their results
+    pub async fn capture_matrices(
+        &mut self,
+        source: &dyn CostSource,
+        matrices: &[(NaiveDate, NaiveDate, GroupBy, GroupBy)],
+    ) -> Result<()> {
+        for (start, end, rows, columns) in matrices {
+            self.matrices.push(FixtureMatrix {
+                rows: group_by_label(rows),
+                columns: group_by_label(columns),
+                data: source.matrix(*start, *end, rows, columns).await?,
+            });
+        }
+        Ok(())
+    }
+
+    /// Capture `source`'s daily costs over `[start, end)`
+    pub async fn capture_daily(
+        &mut self,
+        source: &dyn CostSource,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<()> {
+        self.daily = Some(source.daily(start, end).await?);
+        Ok(())
+    }
+
+    /// Capture `source`'s budgets with `months` months of history
+    pub async fn capture_budgets(&mut self, source: &dyn CostSource, months: u32) -> Result<()> {
+        self.budgets = Some(source.budgets(months).await?);
+        Ok(())
+    }
+}
+
+/// Serves queries from a [`Fixture`] without touching the network
+#[derive(Debug, Clone)]
+pub struct FixtureSource {
+    fixture: Fixture,
+}
+
+impl FixtureSource {
+    pub fn new(fixture: Fixture) -> Self {
+        Self { fixture }
+    }
+
+    /// Load a fixture file
+    pub fn load(path: &Path) -> Result<Self> {
+        Fixture::load(path).map(Self::new)
+    }
+
+    /// The recorded data being served
+    pub fn fixture(&self) -> &Fixture {
+        &self.fixture
+    }
+}
+
+#[async_trait]
+impl CostSource for FixtureSource {
+    fn name(&self) -> String {
+        self.fixture
+            .name
+            .as_ref()
+            .map(|n| format!("fixture: {}", n))
+            .unwrap_or_else(|| "fixture".to_string())
+    }
+
+    /// The provider of the source it was captured from; rows from merged
+    /// and FOCUS captures keep their own
+    fn provider(&self) -> Option<String> {
+        self.fixture.provider.clone()
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        self.fixture
+            .current_month
+            .clone()
+            .ok_or_else(|| anyhow!("Fixture has no current month data"))
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        self.fixture
+            .previous_month
+            .clone()
+            .ok_or_else(|| anyhow!("Fixture has no previous month data"))
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let mut trend = self.fixture.trend.clone();
+        let skip = trend.months.len().saturating_sub(months as usize);
+        trend.months.drain(..skip);
+        if let Some(first) = trend.months.first().map(|m| m.start) {
+            trend.gaps.retain(|g| g.start >= first);
+        }
+        Ok(trend)
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let label = group_by_label(group_by);
+        let candidates: Vec<&CostData> = self
+            .fixture
+            .breakdowns
+            .iter()
+            .filter(|b| b.group_by == label)
+            .map(|b| &b.data)
+            .collect();
+        if let Some(data) = candidates
+            .iter()
+            .find(|data| data.start == start && data.end == end)
+        {
+            return Ok((*data).clone());
+        }
+        if candidates.is_empty() {
+            bail!("Fixture has no {} breakdown", label);
+        }
+        bail!(
+            "Fixture has no {} breakdown for {} (captured: {})",
+            label,
+            span(start, end),
+            captured(candidates.iter().map(|data| (data.start, data.end)))
+        )
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let (rows, columns) = (group_by_label(rows), group_by_label(columns));
+        let candidates: Vec<&CostMatrix> = self
+            .fixture
+            .matrices
+            .iter()
+            .filter(|m| m.rows == rows && m.columns == columns)
+            .map(|m| &m.data)
+            .collect();
+        if let Some(data) = candidates
+            .iter()
+            .find(|data| data.start == start && data.end == end)
+        {
+            return Ok((*data).clone());
+        }
+        if candidates.is_empty() {
+            bail!("Fixture has no {} by {} matrix", rows, columns);
+        }
+        bail!(
+            "Fixture has no {} by {} matrix for {} (captured: {})",
+            rows,
+            columns,
+            span(start, end),
+            captured(candidates.iter().map(|data| (data.start, data.end)))
+        )
+    }
+
+    /// The captured days that fall in `[start, end)`
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let daily = self
+            .fixture
+            .daily
+            .as_ref()
+            .ok_or_else(|| anyhow!("Fixture has no daily costs"))?;
+        let (from, to) = (start.max(daily.start), end.min(daily.end));
+        if from >= to {
+            bail!(
+                "Fixture has no daily costs for {} (captured: {})",
+                span(start, end),
+                span(daily.start, daily.end)
+            );
+        }
+
+        let days = (from - daily.start).num_days() as usize..(to - daily.start).num_days() as usize;
+        let mut series: Vec<DailySeries> = daily
+            .series
+            .iter()
+            .map(|s| DailySeries {
+                costs: s.costs.get(days.clone()).unwrap_or_default().to_vec(),
+                ..s.clone()
+            })
+            .filter(|s| s.total() > 0.0)
+            .collect();
+        series.sort_by(|a, b| b.total().total_cmp(&a.total()));
+        Ok(DailyCosts {
+            start: from,
+            end: to,
+            currency: daily.currency.clone(),
+            series,
+        })
+    }
+
+    /// Keys of the captured tag breakdowns
+    async fn tag_keys(&self, _start: NaiveDate, _end: NaiveDate) -> Result<Vec<String>> {
+        let mut keys: Vec<String> = self
+            .fixture
+            .breakdowns
+            .iter()
+            .filter_map(|b| b.group_by.strip_prefix("TAG:"))
+            .map(str::to_string)
+            .collect();
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    /// The captured budgets and all of their history
+    async fn budgets(&self, _months: u32) -> Result<Vec<Budget>> {
+        self.fixture
+            .budgets
+            .clone()
+            .ok_or_else(|| anyhow!("Fixture has no budgets"))
+    }
+}
+
+/// `[start, end)` as inclusive dates
+fn span(start: NaiveDate, end: NaiveDate) -> String {
+    format!("{} – {}", start, end.pred_opt().unwrap_or(end))
+}
+
+/// The ranges a fixture has for a query, to say what was asked for instead
+fn captured(ranges: impl Iterator<Item = (NaiveDate, NaiveDate)>) -> String {
+    ranges
+        .map(|(start, end)| span(start, end))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,466 @@
+//! [`CostSource`] reading FinOps FOCUS 1.x CSV exports
+//!
+//! FOCUS (<https://focus.finops.org>) gives AWS, Azure, GCP and other
+//! providers a common billing schema, so the same views can show non-AWS or
+//! multi-cloud spend. Every breakdown row carries the provider it came from.
+//! Like the CUR reader, files are streamed for each query.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::files::{
+    export_today, is_csv_file, latest_first_row_date, open, parse_date, spawn_scan, walk, Columns,
+};
+use super::{provider_cost_data, provider_cost_matrix, provider_daily_costs, CostSource};
+use crate::aws::{CostData, CostMatrix, DailyCosts, Dimension, GroupBy, MonthlyTrend, TrendGap};
+use crate::periods;
+
+const CHARGE_PERIOD_START: &str = "ChargePeriodStart";
+const BILLING_PERIOD_START: &str = "BillingPeriodStart";
+const BILLING_CURRENCY: &str = "BillingCurrency";
+const TAGS: &str = "Tags";
+
+/// Provider columns, newest spec version first (`ProviderName` is
+/// deprecated in favour of `ServiceProviderName` as of FOCUS 1.3)
+const PROVIDER_COLUMNS: [&str; 3] = ["ServiceProviderName", "ProviderName", "PublisherName"];
+
+/// Value used when a row has no value for the grouped column
+const NO_VALUE: &str = "(none)";
+
+/// Which FOCUS cost column to sum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FocusCost {
+    /// `BilledCost`: what appears on the invoice, like Cost Explorer's
+    /// unblended cost
+    #[default]
+    Billed,
+    /// `EffectiveCost`: amortized cost after commitment discounts
+    Effective,
+}
+
+impl FocusCost {
+    pub fn column(&self) -> &'static str {
+        match self {
+            FocusCost::Billed => "BilledCost",
+            FocusCost::Effective => "EffectiveCost",
+        }
+    }
+}
+
+/// Cost source aggregating FOCUS rows
+#[derive(Debug, Clone)]
+pub struct FocusSource {
+    path: PathBuf,
+    files: Vec<PathBuf>,
+    today: NaiveDate,
+    cost: FocusCost,
+}
+
+impl FocusSource {
+    /// Open a FOCUS `.csv`/`.csv.gz` file or a directory of them.
+    ///
+    /// "Today" defaults to the last day of the newest billing period, or the
+    /// real date if that period hasn't ended yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let files = if path.is_dir() {
+            let mut files = Vec::new();
+            walk(path, &mut |file| {
+                if is_csv_file(file) {
+                    files.push(file.to_path_buf());
+                }
+            })?;
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+        if files.is_empty() {
+            bail!("No FOCUS files (.csv or .csv.gz) found in {:?}", path);
+        }
+
+        let today = export_today(latest_first_row_date(&files, BILLING_PERIOD_START)?)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            files,
+            today,
+            cost: FocusCost::default(),
+        })
+    }
+
+    /// Pin "today" for month-to-date and trend queries
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Choose the cost column to sum
+    pub fn with_cost(mut self, cost: FocusCost) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Data files that will be read, in order
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// The date queries are relative to
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    /// Sum rows charged in `[start, end)`, grouped by provider and `group_by`.
+    ///
+    /// Dimensions map to `ServiceName`, `SubAccountId`, `RegionId`,
+    /// `ChargeCategory` (record type), `PricingCategory` (purchase type) and
+    /// `ResourceId`;
+    /// tags are read from the `Tags` JSON column and labelled `key$value`.
+    pub fn aggregate(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let mut totals: HashMap<(String, String), f64> = HashMap::new();
+        let currency = self.scan(
+            start,
+            end,
+            std::slice::from_ref(group_by),
+            |_, provider, keys, cost| {
+                *totals
+                    .entry((provider.to_string(), keys[0].clone()))
+                    .or_default() += cost;
+            },
+        )?;
+
+        let mut data =
+            provider_cost_data(rows(totals), start, end, periods::range_name(start, end));
+        data.currency = currency;
+        Ok(data)
+    }
+
+    /// Sum rows charged in `[start, end)`, grouped by provider, `rows` and
+    /// `columns`
+    pub fn aggregate_matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let mut totals: HashMap<(String, String, String), f64> = HashMap::new();
+        let groupings = [rows.clone(), columns.clone()];
+        let currency = self.scan(start, end, &groupings, |_, provider, keys, cost| {
+            *totals
+                .entry((provider.to_string(), keys[0].clone(), keys[1].clone()))
+                .or_default() += cost;
+        })?;
+
+        let cells = totals
+            .into_iter()
+            .map(|((provider, row, column), cost)| (Some(provider), row, column, cost))
+            .collect();
+        let mut matrix = provider_cost_matrix(cells, start, end, periods::range_name(start, end));
+        matrix.currency = currency;
+        Ok(matrix)
+    }
+
+    /// Sum rows charged in `[start, end)` by provider, service and charge day
+    pub fn aggregate_daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let mut totals: HashMap<(String, String, NaiveDate), f64> = HashMap::new();
+        let service = GroupBy::Dimension(Dimension::Service);
+        let currency = self.scan(start, end, &[service], |date, provider, keys, cost| {
+            *totals
+                .entry((provider.to_string(), keys[0].clone(), date))
+                .or_default() += cost;
+        })?;
+
+        let rows = totals
+            .into_iter()
+            .map(|((provider, service, date), cost)| (Some(provider), service, date, cost))
+            .collect();
+        let mut daily = provider_daily_costs(rows, start, end);
+        daily.currency = currency;
+        Ok(daily)
+    }
+
+    /// Keys of the `Tags` JSON objects of rows charged in `[start, end)`
+    pub fn scan_tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let mut keys = BTreeSet::new();
+        for path in &self.files {
+            let mut reader = csv::Reader::from_reader(open(path)?);
+            let columns = Columns::new(reader.headers()?);
+            let charge_start = columns
+                .require(CHARGE_PERIOD_START, path)
+                .context("is it a FOCUS export?")?;
+            let Some(tags) = columns.find(TAGS) else {
+                continue;
+            };
+
+            let mut record = csv::StringRecord::new();
+            while reader
+                .read_record(&mut record)
+                .with_context(|| format!("Could not read {:?}", path))?
+            {
+                let in_range = record
+                    .get(charge_start)
+                    .and_then(parse_date)
+                    .is_some_and(|date| date >= start && date < end);
+                let object = record
+                    .get(tags)
+                    .filter(|t| in_range && !t.is_empty())
+                    .and_then(|t| serde_json::from_str::<serde_json::Value>(t).ok());
+                if let Some(serde_json::Value::Object(object)) = object {
+                    keys.extend(object.into_iter().map(|(key, _)| key));
+                }
+            }
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Per-service totals for each month range, in one pass over the files
+    fn aggregate_months(&self, ranges: &[(NaiveDate, NaiveDate)]) -> Result<MonthlyTrend> {
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return Ok(MonthlyTrend::default());
+        };
+
+        let service = GroupBy::Dimension(Dimension::Service);
+        let mut months: BTreeMap<NaiveDate, HashMap<(String, String), f64>> = BTreeMap::new();
+        let currency = self.scan(first.0, last.1, &[service], |date, provider, keys, cost| {
+            if let Ok(month) = periods::month_start(date) {
+                *months
+                    .entry(month)
+                    .or_default()
+                    .entry((provider.to_string(), keys[0].clone()))
+                    .or_default() += cost;
+            }
+        })?;
+
+        let mut trend = MonthlyTrend::default();
+        for &(start, end) in ranges {
+            let period = periods::month_name(start);
+            match months.remove(&start) {
+                Some(totals) => {
+                    let mut data = provider_cost_data(rows(totals), start, end, period);
+                    data.currency = currency.clone();
+                    trend.months.push(data);
+                }
+                None => trend.gaps.push(TrendGap {
+                    period,
+                    start,
+                    partial: false,
+                    reason: "no FOCUS rows".to_string(),
+                }),
+            }
+        }
+        Ok(trend)
+    }
+
+    /// Stream every row charged in `[start, end)` to `visit` with its
+    /// provider, key for each of `groupings` and cost; returns the billing
+    /// currency
+    fn scan(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        groupings: &[GroupBy],
+        mut visit: impl FnMut(NaiveDate, &str, &[String], f64),
+    ) -> Result<String> {
+        let mut currency = None;
+        let mut keys = Vec::with_capacity(groupings.len());
+
+        for path in &self.files {
+            let mut reader = csv::Reader::from_reader(open(path)?);
+            let columns = Columns::new(reader.headers()?);
+            let charge_start = columns
+                .require(CHARGE_PERIOD_START, path)
+                .context("is it a FOCUS export?")?;
+            let cost_column = columns.require(self.cost.column(), path)?;
+            let currency_column = columns.find(BILLING_CURRENCY);
+            let provider_column = columns.find_any(&PROVIDER_COLUMNS);
+            let groupings: Vec<Grouping> = groupings
+                .iter()
+                .map(|group_by| Grouping::new(&columns, group_by))
+                .collect();
+
+            let mut record = csv::StringRecord::new();
+            while reader
+                .read_record(&mut record)
+                .with_context(|| format!("Could not read {:?}", path))?
+            {
+                let Some(date) = record.get(charge_start).and_then(parse_date) else {
+                    continue;
+                };
+                if date < start || date >= end {
+                    continue;
+                }
+
+                let cost = match record.get(cost_column).unwrap_or("").trim() {
+                    "" => 0.0,
+                    value => value.parse::<f64>().with_context(|| {
+                        let line = record.position().map(|p| p.line()).unwrap_or(0);
+                        format!("{:?} line {}: invalid cost {:?}", path, line, value)
+                    })?,
+                };
+
+                if currency.is_none() {
+                    currency = currency_column
+                        .and_then(|i| record.get(i))
+                        .filter(|c| !c.is_empty())
+                        .map(str::to_string);
+                }
+
+                let provider = provider_column
+                    .and_then(|i| record.get(i))
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or("Unknown");
+                keys.clear();
+                keys.extend(groupings.iter().map(|g| g.key(&record)));
+                visit(date, provider, &keys, cost);
+            }
+        }
+
+        Ok(currency.unwrap_or_else(|| "USD".to_string()))
+    }
+}
+
+#[async_trait]
+impl CostSource for FocusSource {
+    fn name(&self) -> String {
+        format!("FOCUS: {}", self.path.display())
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        let (start, end) = periods::month_to_date(self.today)?;
+        spawn_scan(self, move |focus| {
+            focus.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        let (start, end) = periods::previous_month(self.today)?;
+        spawn_scan(self, move |focus| {
+            focus.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let ranges = periods::trend_months(self.today, months)?;
+        spawn_scan(self, move |focus| focus.aggregate_months(&ranges)).await
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let group_by = group_by.clone();
+        spawn_scan(self, move |focus| focus.aggregate(start, end, &group_by)).await
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let (rows, columns) = (rows.clone(), columns.clone());
+        spawn_scan(self, move |focus| {
+            focus.aggregate_matrix(start, end, &rows, &columns)
+        })
+        .await
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        spawn_scan(self, move |focus| focus.aggregate_daily(start, end)).await
+    }
+
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        spawn_scan(self, move |focus| focus.scan_tag_keys(start, end)).await
+    }
+}
+
+fn rows(totals: HashMap<(String, String), f64>) -> Vec<(Option<String>, String, f64)> {
+    totals
+        .into_iter()
+        .map(|((provider, key), cost)| (Some(provider), key, cost))
+        .collect()
+}
+
+/// How to derive a row's group key in one file
+enum Grouping {
+    /// Value of the first of the dimension's columns the file has; empty
+    /// values group as [`NO_VALUE`] rather than falling back to the next one
+    Column(Option<usize>),
+    /// A key from the `Tags` JSON object, labelled `key$value`
+    Tag { column: Option<usize>, key: String },
+    /// Nothing in FOCUS to group by; everything lands in one `name$` group
+    Constant(String),
+}
+
+impl Grouping {
+    fn new(columns: &Columns, group_by: &GroupBy) -> Self {
+        match group_by {
+            GroupBy::Dimension(dimension) => match dimension_columns(*dimension) {
+                [] => Grouping::Constant(NO_VALUE.to_string()),
+                names => Grouping::Column(columns.find_any(names)),
+            },
+            GroupBy::Tag(key) => Grouping::Tag {
+                column: columns.find(TAGS),
+                key: key.clone(),
+            },
+            GroupBy::CostCategory(name) => Grouping::Constant(format!("{}$", name)),
+        }
+    }
+
+    fn key(&self, record: &csv::StringRecord) -> String {
+        match self {
+            Grouping::Column(column) => match column.and_then(|i| record.get(i)) {
+                Some(value) if !value.is_empty() => value.to_string(),
+                _ => NO_VALUE.to_string(),
+            },
+            Grouping::Tag { column, key } => {
+                let value = column
+                    .and_then(|i| record.get(i))
+                    .and_then(|tags| tag_value(tags, key))
+                    .unwrap_or_default();
+                format!("{}${}", key, value)
+            }
+            Grouping::Constant(value) => value.clone(),
+        }
+    }
+}
+
+/// Look up `key` in a FOCUS `Tags` JSON object such as `{"team": "data"}`
+fn tag_value(tags: &str, key: &str) -> Option<String> {
+    if tags.is_empty() {
+        return None;
+    }
+    let tags: serde_json::Value = serde_json::from_str(tags).ok()?;
+    match tags.get(key)? {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// FOCUS columns holding each Cost Explorer dimension
+fn dimension_columns(dimension: Dimension) -> &'static [&'static str] {
+    match dimension {
+        Dimension::Service => &["ServiceName"],
+        Dimension::LinkedAccount => &["SubAccountId"],
+        Dimension::Region => &["RegionId", "RegionName"],
+        Dimension::UsageType => &["SkuId"],
+        Dimension::PurchaseType => &["PricingCategory"],
+        Dimension::RecordType => &["ChargeCategory"],
+        Dimension::ResourceId => &["ResourceId"],
+        Dimension::InstanceType | Dimension::Operation => &[],
+    }
+}
@@ -0,0 +1,122 @@
+//! [`CostSource`] whose month views and trend are grouped by something other
+//! than service, e.g. region, linked account or a tag
+//!
+//! The month and trend queries of the wrapped source only supply their
+//! periods; each period is then broken down by the grouping. Every other
+//! query is passed through unchanged.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use futures::future::try_join_all;
+use std::sync::Arc;
+
+use super::{group_by_label, AccountCosts, CostSource};
+use crate::aws::{Budget, CostData, CostMatrix, DailyCosts, GroupBy, MonthlyTrend};
+
+/// Another source with its month views grouped by `group_by`
+pub struct GroupedSource {
+    source: Arc<dyn CostSource>,
+    group_by: GroupBy,
+}
+
+impl GroupedSource {
+    pub fn new(source: Arc<dyn CostSource>, group_by: GroupBy) -> Self {
+        Self { source, group_by }
+    }
+
+    /// The grouping the month views use
+    pub fn group_by(&self) -> &GroupBy {
+        &self.group_by
+    }
+
+    /// Costs over the period of `data`, broken down by the grouping
+    async fn regroup(&self, data: CostData) -> Result<CostData> {
+        let mut grouped = self
+            .source
+            .breakdown(data.start, data.end, &self.group_by)
+            .await?;
+        grouped.period = data.period;
+        Ok(grouped)
+    }
+}
+
+#[async_trait]
+impl CostSource for GroupedSource {
+    fn name(&self) -> String {
+        format!(
+            "{} by {}",
+            self.source.name(),
+            group_by_label(&self.group_by)
+        )
+    }
+
+    fn provider(&self) -> Option<String> {
+        self.source.provider()
+    }
+
+    fn accounts(&self) -> Vec<String> {
+        self.source.accounts()
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        self.regroup(self.source.current_month().await?).await
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        self.regroup(self.source.previous_month().await?).await
+    }
+
+    /// The wrapped source's months and gaps, each month regrouped
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let trend = self.source.monthly_trend(months).await?;
+        let months = try_join_all(trend.months.into_iter().map(|m| self.regroup(m))).await?;
+        Ok(MonthlyTrend {
+            months,
+            gaps: trend.gaps,
+        })
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        self.source.breakdown(start, end, group_by).await
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        self.source.matrix(start, end, rows, columns).await
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        self.source.daily(start, end).await
+    }
+
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        self.source.resources(service, start, end).await
+    }
+
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        self.source.tag_keys(start, end).await
+    }
+
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        self.source.budgets(months).await
+    }
+
+    async fn account_breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<AccountCosts>> {
+        self.source.account_breakdown(start, end).await
+    }
+}
@@ -0,0 +1,79 @@
+//! [`CostSource`] backed by the live Cost Explorer API
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use super::CostSource;
+use crate::aws::{
+    Budget, CostData, CostExplorerClient, CostMatrix, DailyCosts, GroupBy, MonthlyTrend,
+};
+use crate::periods;
+
+#[async_trait]
+impl CostSource for CostExplorerClient {
+    fn name(&self) -> String {
+        format!("Cost Explorer ({})", self.region())
+    }
+
+    fn provider(&self) -> Option<String> {
+        Some("AWS".to_string())
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        self.get_current_month_costs().await
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        self.get_previous_month_costs().await
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        self.get_monthly_trend(months).await
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let query = self.query(start, end).group_by(group_by.clone());
+        self.get_cost_data(&query, &periods::range_name(start, end))
+            .await
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let query = self
+            .query(start, end)
+            .group_by(rows.clone())
+            .group_by(columns.clone());
+        self.get_cost_matrix(&query, &periods::range_name(start, end))
+            .await
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        self.get_daily_costs(start, end).await
+    }
+
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        self.get_resource_costs(service, start, end).await
+    }
+
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let mut keys = self.get_tags(start, end, None).await?;
+        keys.sort();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        self.budgets_client().get_budgets(months).await
+    }
+}
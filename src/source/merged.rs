@@ -0,0 +1,343 @@
+//! [`CostSource`] combining several sources, e.g. Cost Explorer plus FOCUS
+//! exports from other clouds
+//!
+//! Every query goes to all sources concurrently and the results are summed.
+//! Rows keep the provider their source reported, falling back to the
+//! source's [`CostSource::provider`], so the breakdown can show where each
+//! line came from.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use futures::future::{join_all, try_join_all};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::warn;
+
+use super::{provider_cost_data, provider_cost_matrix, provider_daily_costs, CostSource};
+use crate::aws::{Budget, CostData, CostMatrix, DailyCosts, GroupBy, MonthlyTrend, TrendGap};
+use crate::periods;
+
+/// Several sources presented as one
+pub struct MergedSource {
+    sources: Vec<Arc<dyn CostSource>>,
+}
+
+impl MergedSource {
+    pub fn new(sources: Vec<Arc<dyn CostSource>>) -> Self {
+        Self { sources }
+    }
+
+    /// Label `results[i]` with `sources[i]`
+    fn parts<T>(&self, results: Vec<T>) -> Vec<Part<T>> {
+        self.sources
+            .iter()
+            .zip(results)
+            .map(|(source, data)| Part::of(source.as_ref(), data))
+            .collect()
+    }
+}
+
+/// One source's result: the name used in messages and the provider its
+/// unlabelled rows belong to
+pub(super) struct Part<T> {
+    pub name: String,
+    pub provider: Option<String>,
+    pub data: T,
+}
+
+impl<T> Part<T> {
+    fn of(source: &dyn CostSource, data: T) -> Self {
+        Self {
+            name: source.name(),
+            provider: source.provider(),
+            data,
+        }
+    }
+}
+
+#[async_trait]
+impl CostSource for MergedSource {
+    fn name(&self) -> String {
+        self.sources
+            .iter()
+            .map(|s| s.name())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        let results = try_join_all(self.sources.iter().map(|s| s.current_month())).await?;
+        Ok(merge(self.parts(results)))
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        let results = try_join_all(self.sources.iter().map(|s| s.previous_month())).await?;
+        Ok(merge(self.parts(results)))
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let trends = try_join_all(self.sources.iter().map(|s| s.monthly_trend(months))).await?;
+        Ok(merge_trends(self.parts(trends)))
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let results = try_join_all(
+            self.sources
+                .iter()
+                .map(|s| s.breakdown(start, end, group_by)),
+        )
+        .await?;
+        Ok(merge(self.parts(results)))
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let results = try_join_all(
+            self.sources
+                .iter()
+                .map(|s| s.matrix(start, end, rows, columns)),
+        )
+        .await?;
+        Ok(merge_matrices(self.parts(results), start, end))
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let results = try_join_all(self.sources.iter().map(|s| s.daily(start, end))).await?;
+        Ok(merge_daily(self.parts(results), start, end))
+    }
+
+    /// Resources from every source that has resource-level data; an error
+    /// only if none of them do
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        let results = join_all(
+            self.sources
+                .iter()
+                .map(|s| s.resources(service, start, end)),
+        )
+        .await;
+
+        let mut parts = Vec::new();
+        let mut first_error = None;
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(data) => parts.push(Part::of(source.as_ref(), data)),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if parts.is_empty() => Err(e),
+            _ => Ok(merge(parts)),
+        }
+    }
+
+    /// Keys known to any source; fails only if every source does
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let results = join_all(self.sources.iter().map(|s| s.tag_keys(start, end))).await;
+
+        let mut keys = BTreeSet::new();
+        let mut first_error = None;
+        let mut any_ok = false;
+        for result in results {
+            match result {
+                Ok(found) => {
+                    any_ok = true;
+                    keys.extend(found);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if !any_ok => Err(e),
+            _ => Ok(keys.into_iter().collect()),
+        }
+    }
+
+    /// Budgets of every source that has them; fails only if every source
+    /// does
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        let results = join_all(self.sources.iter().map(|s| s.budgets(months))).await;
+
+        let mut budgets = Vec::new();
+        let mut first_error = None;
+        let mut any_ok = false;
+        for result in results {
+            match result {
+                Ok(found) => {
+                    any_ok = true;
+                    budgets.extend(found);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if !any_ok => Err(e),
+            _ => Ok(budgets),
+        }
+    }
+}
+
+/// Sum the parts into one [`CostData`], keeping rows from different
+/// providers apart
+pub(super) fn merge(parts: Vec<Part<CostData>>) -> CostData {
+    let mut totals: HashMap<(Option<String>, String), f64> = HashMap::new();
+    let mut total_cost = 0.0;
+    let mut first: Option<CostData> = None;
+
+    for Part {
+        name,
+        provider: fallback,
+        data,
+    } in parts
+    {
+        total_cost += data.total_cost;
+        for row in &data.breakdown {
+            let provider = row.provider.clone().or_else(|| fallback.clone());
+            *totals.entry((provider, row.service.clone())).or_default() += row.cost;
+        }
+
+        match &first {
+            Some(first) if first.currency != data.currency => warn!(
+                "Merging {} costs from {} into {} totals without conversion",
+                data.currency, name, first.currency
+            ),
+            Some(_) => {}
+            None => first = Some(data),
+        }
+    }
+
+    let Some(first) = first else {
+        return CostData {
+            period: String::new(),
+            start: NaiveDate::MIN,
+            end: NaiveDate::MIN,
+            total_cost: 0.0,
+            currency: "USD".to_string(),
+            breakdown: Vec::new(),
+        };
+    };
+
+    let rows = totals
+        .into_iter()
+        .map(|((provider, service), cost)| (provider, service, cost))
+        .collect();
+    let mut merged = provider_cost_data(rows, first.start, first.end, first.period);
+    merged.currency = first.currency;
+    // Source totals include credits and refunds that aren't breakdown rows
+    merged.total_cost = total_cost;
+    for row in &mut merged.breakdown {
+        row.percentage = if total_cost > 0.0 {
+            row.cost / total_cost * 100.0
+        } else {
+            0.0
+        };
+    }
+    merged
+}
+
+/// Sum the parts' trends month by month; a gap is partial when another part
+/// has data for the month
+pub(super) fn merge_trends(parts: Vec<Part<MonthlyTrend>>) -> MonthlyTrend {
+    let mut by_month: BTreeMap<NaiveDate, Vec<Part<CostData>>> = BTreeMap::new();
+    let mut gaps: Vec<(String, TrendGap)> = Vec::new();
+    for part in parts {
+        for month in part.data.months {
+            by_month.entry(month.start).or_default().push(Part {
+                name: part.name.clone(),
+                provider: part.provider.clone(),
+                data: month,
+            });
+        }
+        gaps.extend(
+            part.data
+                .gaps
+                .into_iter()
+                .map(|gap| (part.name.clone(), gap)),
+        );
+    }
+
+    let mut merged = MonthlyTrend::default();
+    for (name, mut gap) in gaps {
+        if by_month.contains_key(&gap.start) {
+            gap.partial = true;
+        }
+        gap.reason = format!("{}: {}", name, gap.reason);
+        merged.gaps.push(gap);
+    }
+    merged.months = by_month.into_values().map(merge).collect();
+    merged
+}
+
+/// Sum the parts' matrices cell by cell
+pub(super) fn merge_matrices(
+    parts: Vec<Part<CostMatrix>>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> CostMatrix {
+    let mut totals: HashMap<(Option<String>, String, String), f64> = HashMap::new();
+    let mut total_cost = 0.0;
+    for part in &parts {
+        total_cost += part.data.total_cost;
+        for cell in &part.data.cells {
+            let provider = cell.provider.clone().or_else(|| part.provider.clone());
+            *totals
+                .entry((provider, cell.row.clone(), cell.column.clone()))
+                .or_default() += cell.cost;
+        }
+    }
+
+    let cells = totals
+        .into_iter()
+        .map(|((provider, row, column), cost)| (provider, row, column, cost))
+        .collect();
+    let mut merged = provider_cost_matrix(cells, start, end, periods::range_name(start, end));
+    if let Some(first) = parts.first() {
+        merged.currency = first.data.currency.clone();
+    }
+    merged.total_cost = total_cost;
+    merged
+}
+
+/// Sum the parts' daily costs service by service
+pub(super) fn merge_daily(
+    parts: Vec<Part<DailyCosts>>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> DailyCosts {
+    let mut rows = Vec::new();
+    for part in &parts {
+        for series in &part.data.series {
+            let provider = series.provider.clone().or_else(|| part.provider.clone());
+            rows.extend(series.costs.iter().enumerate().map(|(day, &cost)| {
+                (
+                    provider.clone(),
+                    series.service.clone(),
+                    part.data.date(day),
+                    cost,
+                )
+            }));
+        }
+    }
+
+    let mut merged = provider_daily_costs(rows, start, end);
+    if let Some(first) = parts.first() {
+        merged.currency = first.data.currency.clone();
+    }
+    merged
+}
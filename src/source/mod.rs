@@ -0,0 +1,291 @@
+//! Pluggable cost data sources
+//!
+//! The UI and text mode only talk to a [`CostSource`], so the same views can
+//! be driven by the live Cost Explorer API, local CUR or FOCUS exports (alone
+//! or merged), CUR 2.0 Parquet exports (`parquet` feature), a recorded JSON
+//! fixture, deterministic synthetic data for demos and tests, or several
+//! standalone accounts side by side. Any of them can be regrouped, so the
+//! month views show e.g. regions instead of services.
+
+pub mod accounts;
+pub mod cur;
+mod files;
+pub mod fixture;
+pub mod focus;
+pub mod grouped;
+pub mod live;
+pub mod merged;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod synthetic;
+
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::aws::{
+    Budget, CostData, CostMatrix, DailyCosts, DailySeries, Dimension, GroupBy, MatrixCell, MonthlyTrend, ServiceCost,
+};
+
+#[cfg(feature = "parquet")]
+pub use self::parquet::ParquetSource;
+pub use accounts::{AccountCosts, AccountsSource};
+pub use cur::CurSource;
+pub use fixture::{Fixture, FixtureMatrix, FixtureSource};
+pub use focus::{FocusCost, FocusSource};
+pub use grouped::GroupedSource;
+pub use merged::MergedSource;
+pub use synthetic::SyntheticSource;
+
+/// Something that can answer the app's cost queries
+#[async_trait]
+pub trait CostSource: Send + Sync {
+    /// Short description shown in the UI (e.g. "demo data")
+    fn name(&self) -> String;
+
+    /// Cloud provider behind every row, for single-provider sources. Used to
+    /// label rows when data from several sources is merged.
+    fn provider(&self) -> Option<String> {
+        None
+    }
+
+    /// Names of the standalone accounts behind the data, for sources that
+    /// span several; empty otherwise
+    fn accounts(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Month-to-date costs broken down by service
+    async fn current_month(&self) -> Result<CostData>;
+
+    /// Costs for the previous full calendar month, by service
+    async fn previous_month(&self) -> Result<CostData>;
+
+    /// The last `months` calendar months (including the current one), by
+    /// service, oldest first
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend>;
+
+    /// Costs over `[start, end)` broken down by an arbitrary grouping
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData>;
+
+    /// Costs over `[start, end)` grouped by `rows` and `columns` together,
+    /// e.g. each service's spend by tag value
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let _ = (start, end, rows, columns);
+        bail!("{} can't group by two keys", self.name())
+    }
+
+    /// Each service's cost on each day of `[start, end)`, for anomaly
+    /// detection
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let _ = (start, end);
+        bail!("{} has no daily costs", self.name())
+    }
+
+    /// Costs over `[start, end)` of one service's resources, by resource ID.
+    /// Only sources with line-item data can answer this.
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        let _ = (service, start, end);
+        bail!("{} has no resource-level data", self.name())
+    }
+
+    /// Cost allocation tag keys with spend in `[start, end)`, sorted, for
+    /// [`GroupBy::Tag`] breakdowns
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let _ = (start, end);
+        bail!("{} can't list tag keys", self.name())
+    }
+
+    /// Budgets with their spend this period and limit and spend over the
+    /// last `months` months
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        let _ = months;
+        bail!("{} has no budgets", self.name())
+    }
+
+    /// Each of [`CostSource::accounts`]' costs over `[start, end)` by
+    /// service, including the ones that failed
+    async fn account_breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<AccountCosts>> {
+        let _ = (start, end);
+        bail!("{} covers a single account", self.name())
+    }
+}
+
+/// Label used for a grouping in fixtures and the UI, e.g. `SERVICE` or `TAG:team`
+pub fn group_by_label(group_by: &GroupBy) -> String {
+    match group_by {
+        GroupBy::Dimension(d) => d.as_str().to_string(),
+        GroupBy::Tag(key) => format!("TAG:{}", key),
+        GroupBy::CostCategory(name) => format!("COST_CATEGORY:{}", name),
+    }
+}
+
+/// Parse a label made by [`group_by_label`]
+pub fn parse_group_by_label(label: &str) -> Result<GroupBy> {
+    if let Some(key) = label.strip_prefix("TAG:").filter(|k| !k.is_empty()) {
+        return Ok(GroupBy::Tag(key.to_string()));
+    }
+    if let Some(name) = label.strip_prefix("COST_CATEGORY:").filter(|n| !n.is_empty()) {
+        return Ok(GroupBy::CostCategory(name.to_string()));
+    }
+    Dimension::ALL
+        .into_iter()
+        .find(|d| d.as_str() == label)
+        .map(GroupBy::Dimension)
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown grouping {:?}, expected a dimension such as REGION, TAG:<key> or COST_CATEGORY:<name>",
+                label
+            )
+        })
+}
+
+/// Build [`CostData`] from named costs, sorted by cost descending
+pub(crate) fn cost_data(
+    costs: Vec<(String, f64)>,
+    start: NaiveDate,
+    end: NaiveDate,
+    period: String,
+) -> CostData {
+    let costs = costs
+        .into_iter()
+        .map(|(service, cost)| (None, service, cost))
+        .collect();
+    provider_cost_data(costs, start, end, period)
+}
+
+/// Build [`CostData`] from `(provider, name, cost)` rows, sorted by cost
+/// descending
+pub(crate) fn provider_cost_data(
+    costs: Vec<(Option<String>, String, f64)>,
+    start: NaiveDate,
+    end: NaiveDate,
+    period: String,
+) -> CostData {
+    let total_cost: f64 = costs.iter().map(|(_, _, c)| c).sum();
+    let mut breakdown: Vec<ServiceCost> = costs
+        .into_iter()
+        .filter(|(_, _, cost)| *cost > 0.001)
+        .map(|(provider, service, cost)| ServiceCost {
+            service,
+            cost,
+            percentage: if total_cost > 0.0 {
+                (cost / total_cost) * 100.0
+            } else {
+                0.0
+            },
+            provider,
+        })
+        .collect();
+    breakdown.sort_by(|a, b| {
+        b.cost
+            .partial_cmp(&a.cost)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    CostData {
+        period,
+        start,
+        end,
+        total_cost,
+        currency: "USD".to_string(),
+        breakdown,
+    }
+}
+
+/// Build a [`CostMatrix`] from `(provider, row, column, cost)` cells, sorted
+/// by cost descending
+pub(crate) fn provider_cost_matrix(
+    cells: Vec<(Option<String>, String, String, f64)>,
+    start: NaiveDate,
+    end: NaiveDate,
+    period: String,
+) -> CostMatrix {
+    let total_cost = cells.iter().map(|(_, _, _, c)| c).sum();
+    let mut cells: Vec<MatrixCell> = cells
+        .into_iter()
+        .filter(|(_, _, _, cost)| *cost > 0.001)
+        .map(|(provider, row, column, cost)| MatrixCell {
+            row,
+            column,
+            cost,
+            provider,
+        })
+        .collect();
+    cells.sort_by(|a, b| {
+        b.cost
+            .partial_cmp(&a.cost)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    CostMatrix {
+        period,
+        start,
+        end,
+        total_cost,
+        currency: "USD".to_string(),
+        cells,
+    }
+}
+
+/// Build [`DailyCosts`] from `(provider, service, date, cost)` rows; rows
+/// outside `[start, end)` are dropped
+pub(crate) fn provider_daily_costs(
+    rows: Vec<(Option<String>, String, NaiveDate, f64)>,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> DailyCosts {
+    let days = (end - start).num_days().max(0) as usize;
+    let mut series: Vec<DailySeries> = Vec::new();
+    for (provider, service, date, cost) in rows {
+        let Some(day) = usize::try_from((date - start).num_days())
+            .ok()
+            .filter(|&day| day < days)
+        else {
+            continue;
+        };
+        let index = match series
+            .iter()
+            .position(|s| s.service == service && s.provider == provider)
+        {
+            Some(index) => index,
+            None => {
+                series.push(DailySeries {
+                    service,
+                    costs: vec![0.0; days],
+                    provider,
+                });
+                series.len() - 1
+            }
+        };
+        series[index].costs[day] += cost;
+    }
+    series.retain(|s| s.total() > 0.001);
+    series.sort_by(|a, b| {
+        b.total()
+            .partial_cmp(&a.total())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    DailyCosts {
+        start,
+        end,
+        currency: "USD".to_string(),
+        series,
+    }
+}
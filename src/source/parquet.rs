@@ -0,0 +1,786 @@
+//! [`CostSource`] reading CUR 2.0 / Data Exports Parquet files from disk
+//!
+//! Accepts a single `.parquet` file or a directory synced from the export's
+//! S3 prefix (`.../data/BILLING_PERIOD=2026-03/*.parquet`). Only the columns
+//! a query needs are decoded, and billing periods outside the query range are
+//! skipped by partition directory, then by row group statistics, before a
+//! row filter on the usage date runs inside the reader.
+//!
+//! Unlike Cost Explorer, every line item keeps its `line_item_resource_id`,
+//! so [`Dimension::ResourceId`] breakdowns cover the whole retention period.
+
+use anyhow::{bail, Context, Result};
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Date32Type, Float64Type, Int64Type};
+use arrow_array::{Array, ArrayRef, BooleanArray, Date32Array, MapArray, RecordBatch, StringArray};
+use arrow_cast::cast;
+use arrow_schema::{ArrowError, DataType, Schema, TimeUnit};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+use parquet::arrow::arrow_reader::{ArrowPredicateFn, ParquetRecordBatchReaderBuilder, RowFilter};
+use parquet::arrow::ProjectionMask;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use super::files::{export_today, parse_date, spawn_scan, walk, Columns};
+use super::{cost_data, provider_cost_matrix, provider_daily_costs, CostSource};
+use crate::aws::{CostData, CostMatrix, DailyCosts, Dimension, GroupBy, MonthlyTrend, TrendGap};
+use crate::periods;
+
+const USAGE_START: &str = "line_item_usage_start_date";
+const UNBLENDED_COST: &str = "line_item_unblended_cost";
+const CURRENCY: &str = "line_item_currency_code";
+const BILLING_PERIOD_START: &str = "bill_billing_period_start_date";
+
+/// Hive-style partition directory Data Exports writes each period under
+const PARTITION_PREFIX: &str = "BILLING_PERIOD=";
+
+/// Value used when a line item has no value for the grouped column
+const NO_VALUE: &str = "(none)";
+
+/// Cost source aggregating CUR 2.0 line items from Parquet
+#[derive(Debug, Clone)]
+pub struct ParquetSource {
+    path: PathBuf,
+    files: Vec<PathBuf>,
+    today: NaiveDate,
+}
+
+impl ParquetSource {
+    /// Open a Parquet file or a directory of them.
+    ///
+    /// "Today" defaults to the last day of the newest billing period, or the
+    /// real date if that period hasn't ended yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        let files = if path.is_dir() {
+            let mut files = Vec::new();
+            walk(path, &mut |file| {
+                if file.extension().is_some_and(|e| e == "parquet") {
+                    files.push(file.to_path_buf());
+                }
+            })?;
+            files.sort();
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+        if files.is_empty() {
+            bail!("No Parquet files found in {:?}", path);
+        }
+
+        let today = export_today(latest_billing_period(&files)?)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            files,
+            today,
+        })
+    }
+
+    /// Pin "today" for month-to-date and trend queries
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Data files that will be read, in order
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// The date queries are relative to
+    pub fn today(&self) -> NaiveDate {
+        self.today
+    }
+
+    /// Sum line items used in `[start, end)`, grouped by `group_by`.
+    ///
+    /// Tags come from the `resource_tags` map (`user_` prefix optional) and
+    /// cost categories from `cost_category`, labelled `key$value` like Cost
+    /// Explorer. Athena-style flattened columns such as
+    /// `resource_tags_user_team` work too.
+    pub fn aggregate(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        self.aggregate_where(start, end, group_by, None)
+    }
+
+    /// Costs of `service`'s line items in `[start, end)`, by resource ID
+    pub fn resource_costs(
+        &self,
+        service: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<CostData> {
+        let resource = GroupBy::Dimension(Dimension::ResourceId);
+        self.aggregate_where(start, end, &resource, Some(service))
+    }
+
+    /// Sum line items used in `[start, end)`, grouped by `rows` and
+    /// `columns` together
+    pub fn aggregate_matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let mut totals: HashMap<(String, String), f64> = HashMap::new();
+        let groupings = [rows.clone(), columns.clone()];
+        let currency = self.scan(start, end, &groupings, None, |_, keys, cost| {
+            *totals
+                .entry((keys[0].clone(), keys[1].clone()))
+                .or_default() += cost;
+        })?;
+
+        let cells = totals
+            .into_iter()
+            .map(|((row, column), cost)| (None, row, column, cost))
+            .collect();
+        let mut matrix = provider_cost_matrix(cells, start, end, periods::range_name(start, end));
+        matrix.currency = currency;
+        Ok(matrix)
+    }
+
+    /// Sum line items used in `[start, end)` by service and usage day
+    pub fn aggregate_daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let mut totals: HashMap<(String, NaiveDate), f64> = HashMap::new();
+        let service = GroupBy::Dimension(Dimension::Service);
+        let currency = self.scan(start, end, &[service], None, |date, keys, cost| {
+            *totals.entry((keys[0].clone(), date)).or_default() += cost;
+        })?;
+
+        let rows = totals
+            .into_iter()
+            .map(|((service, date), cost)| (None, service, date, cost))
+            .collect();
+        let mut daily = provider_daily_costs(rows, start, end);
+        daily.currency = currency;
+        Ok(daily)
+    }
+
+    /// Tag keys in billing periods overlapping `[start, end)`: the keys of
+    /// the `resource_tags` map, or flattened `resource_tags_*` columns,
+    /// without the `user_` prefix of user-defined tags
+    pub fn scan_tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        let mut keys = BTreeSet::new();
+        for path in &self.files {
+            if let Some(period) = partition_period(path) {
+                if period >= end || periods::next_month_start(period)? <= start {
+                    continue;
+                }
+            }
+            file_tag_keys(path, start, end, &mut keys)?;
+        }
+        Ok(keys.into_iter().collect())
+    }
+
+    fn aggregate_where(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+        service: Option<&str>,
+    ) -> Result<CostData> {
+        let mut totals: HashMap<String, f64> = HashMap::new();
+        let groupings = std::slice::from_ref(group_by);
+        let currency = self.scan(start, end, groupings, service, |_, keys, cost| {
+            *totals.entry(keys[0].clone()).or_default() += cost;
+        })?;
+
+        let mut data = cost_data(
+            totals.into_iter().collect(),
+            start,
+            end,
+            periods::range_name(start, end),
+        );
+        data.currency = currency;
+        Ok(data)
+    }
+
+    /// Per-service totals for each month range, in one pass over the files
+    fn aggregate_months(&self, ranges: &[(NaiveDate, NaiveDate)]) -> Result<MonthlyTrend> {
+        let (Some(first), Some(last)) = (ranges.first(), ranges.last()) else {
+            return Ok(MonthlyTrend::default());
+        };
+
+        let service = GroupBy::Dimension(Dimension::Service);
+        let mut months: BTreeMap<NaiveDate, HashMap<String, f64>> = BTreeMap::new();
+        let currency = self.scan(first.0, last.1, &[service], None, |date, keys, cost| {
+            if let Ok(month) = periods::month_start(date) {
+                *months
+                    .entry(month)
+                    .or_default()
+                    .entry(keys[0].clone())
+                    .or_default() += cost;
+            }
+        })?;
+
+        let mut trend = MonthlyTrend::default();
+        for &(start, end) in ranges {
+            let period = periods::month_name(start);
+            match months.remove(&start) {
+                Some(totals) => {
+                    let mut data = cost_data(totals.into_iter().collect(), start, end, period);
+                    data.currency = currency.clone();
+                    trend.months.push(data);
+                }
+                None => trend.gaps.push(TrendGap {
+                    period,
+                    start,
+                    partial: false,
+                    reason: "no line items in Parquet export".to_string(),
+                }),
+            }
+        }
+        Ok(trend)
+    }
+
+    /// Stream every line item used in `[start, end)` (of `service`, if
+    /// given) to `visit` with its key for each of `groupings`; returns the
+    /// billing currency
+    fn scan(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        groupings: &[GroupBy],
+        service: Option<&str>,
+        mut visit: impl FnMut(NaiveDate, &[String], f64),
+    ) -> Result<String> {
+        let mut currency = None;
+
+        for path in &self.files {
+            if let Some(period) = partition_period(path) {
+                if period >= end || periods::next_month_start(period)? <= start {
+                    continue;
+                }
+            }
+            scan_file(
+                path,
+                start,
+                end,
+                groupings,
+                service,
+                &mut currency,
+                &mut visit,
+            )?;
+        }
+
+        Ok(currency.unwrap_or_else(|| "USD".to_string()))
+    }
+}
+
+#[async_trait]
+impl CostSource for ParquetSource {
+    fn name(&self) -> String {
+        format!("CUR 2.0: {}", self.path.display())
+    }
+
+    fn provider(&self) -> Option<String> {
+        Some("AWS".to_string())
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        let (start, end) = periods::month_to_date(self.today)?;
+        spawn_scan(self, move |source| {
+            source.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        let (start, end) = periods::previous_month(self.today)?;
+        spawn_scan(self, move |source| {
+            source.aggregate(start, end, &GroupBy::Dimension(Dimension::Service))
+        })
+        .await
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let ranges = periods::trend_months(self.today, months)?;
+        spawn_scan(self, move |source| source.aggregate_months(&ranges)).await
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let group_by = group_by.clone();
+        spawn_scan(self, move |source| source.aggregate(start, end, &group_by)).await
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let (rows, columns) = (rows.clone(), columns.clone());
+        spawn_scan(self, move |source| {
+            source.aggregate_matrix(start, end, &rows, &columns)
+        })
+        .await
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        spawn_scan(self, move |source| source.aggregate_daily(start, end)).await
+    }
+
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        let service = service.to_string();
+        spawn_scan(self, move |source| {
+            source.resource_costs(&service, start, end)
+        })
+        .await
+    }
+
+    async fn tag_keys(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<String>> {
+        spawn_scan(self, move |source| source.scan_tag_keys(start, end)).await
+    }
+}
+
+/// Add the tag keys of one file to `keys`
+fn file_tag_keys(
+    path: &Path,
+    start: NaiveDate,
+    end: NaiveDate,
+    keys: &mut BTreeSet<String>,
+) -> Result<()> {
+    let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .with_context(|| format!("Could not read Parquet file {:?}", path))?;
+    let schema = builder.schema().clone();
+    let columns = Columns::from_names(schema.fields().iter().map(|f| f.name().as_str()));
+    let user_key = |key: &str| key.strip_prefix("user_").unwrap_or(key).to_string();
+
+    for field in schema.fields() {
+        if let Some(key) = field.name().strip_prefix("resource_tags_") {
+            keys.insert(user_key(key));
+        }
+    }
+
+    let Some(index) = columns
+        .find("resource_tags")
+        .filter(|&i| matches!(schema.field(i).data_type(), DataType::Map(..)))
+    else {
+        return Ok(());
+    };
+    let row_groups = row_groups_in_range(&builder, &columns, start, end)?;
+    let mask = ProjectionMask::roots(builder.parquet_schema(), [index]);
+    let reader = builder
+        .with_row_groups(row_groups)
+        .with_projection(mask)
+        .build()?;
+    for batch in reader {
+        let batch = batch.with_context(|| format!("Could not read {:?}", path))?;
+        let map = batch.column(0).as_map();
+        keys.extend(to_strings(map.keys())?.iter().flatten().map(user_key));
+    }
+    Ok(())
+}
+
+/// Read the matching rows of one file, decoding only the columns needed
+fn scan_file(
+    path: &Path,
+    start: NaiveDate,
+    end: NaiveDate,
+    groupings: &[GroupBy],
+    service: Option<&str>,
+    currency: &mut Option<String>,
+    visit: &mut impl FnMut(NaiveDate, &[String], f64),
+) -> Result<()> {
+    let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+        .with_context(|| format!("Could not read Parquet file {:?}", path))?;
+    let schema = builder.schema().clone();
+    let columns = Columns::from_names(schema.fields().iter().map(|f| f.name().as_str()));
+
+    let usage_start = columns
+        .require(USAGE_START, path)
+        .context("is it a CUR 2.0 export?")?;
+    let cost_column = columns.require(UNBLENDED_COST, path)?;
+    let currency_column = columns.find(CURRENCY);
+    let groupings: Vec<Grouping> = groupings
+        .iter()
+        .map(|group_by| Grouping::new(&columns, &schema, group_by))
+        .collect();
+    let filter = service.map(|name| {
+        let service = GroupBy::Dimension(Dimension::Service);
+        (Grouping::new(&columns, &schema, &service), name)
+    });
+
+    let mut roots = vec![usage_start, cost_column];
+    roots.extend(currency_column);
+    for grouping in &groupings {
+        roots.extend(grouping.roots(&schema));
+    }
+    if let Some((service, _)) = &filter {
+        roots.extend(service.roots(&schema));
+    }
+    roots.sort_unstable();
+    roots.dedup();
+
+    let row_groups = row_groups_in_range(&builder, &columns, start, end)?;
+    let (first, last) = (
+        Date32Type::from_naive_date(start),
+        Date32Type::from_naive_date(end),
+    );
+    let in_range = ArrowPredicateFn::new(
+        ProjectionMask::roots(builder.parquet_schema(), [usage_start]),
+        move |batch: RecordBatch| {
+            let dates = to_dates(batch.column(0))?;
+            Ok(dates
+                .iter()
+                .map(|d| Some(d.is_some_and(|d| d >= first && d < last)))
+                .collect::<BooleanArray>())
+        },
+    );
+    let mask = ProjectionMask::roots(builder.parquet_schema(), roots);
+    let reader = builder
+        .with_row_groups(row_groups)
+        .with_projection(mask)
+        .with_row_filter(RowFilter::new(vec![Box::new(in_range)]))
+        .build()?;
+
+    let usage_name = schema.field(usage_start).name();
+    let cost_name = schema.field(cost_column).name();
+    for batch in reader {
+        let batch = batch.with_context(|| format!("Could not read {:?}", path))?;
+        let dates = to_dates(column(&batch, usage_name)?)?;
+        let costs = cast(column(&batch, cost_name)?, &DataType::Float64)?;
+        let costs = costs.as_primitive::<Float64Type>();
+        let values = groupings
+            .iter()
+            .map(|grouping| grouping.values(&batch))
+            .collect::<Result<Vec<_>>>()?;
+        let services = match &filter {
+            Some((service, _)) => Some(service.values(&batch)?),
+            None => None,
+        };
+
+        if currency.is_none() {
+            if let Some(i) = currency_column {
+                let codes = to_strings(column(&batch, schema.field(i).name())?)?;
+                *currency = codes
+                    .iter()
+                    .flatten()
+                    .find(|c| !c.is_empty())
+                    .map(str::to_string);
+            }
+        }
+
+        for row in 0..batch.num_rows() {
+            let Some(date) = dates.value_as_date(row).filter(|_| dates.is_valid(row)) else {
+                continue;
+            };
+            if let (Some(services), Some((service, name))) = (&services, &filter) {
+                if service.key(services, row) != *name {
+                    continue;
+                }
+            }
+            let cost = if costs.is_valid(row) {
+                costs.value(row)
+            } else {
+                0.0
+            };
+            let keys: Vec<String> = groupings
+                .iter()
+                .zip(&values)
+                .map(|(grouping, values)| grouping.key(values, row))
+                .collect();
+            visit(date, &keys, cost);
+        }
+    }
+    Ok(())
+}
+
+/// Row groups whose billing periods overlap `[start, end)`, judged from the
+/// column statistics; files without them are read in full
+fn row_groups_in_range(
+    builder: &ParquetRecordBatchReaderBuilder<File>,
+    columns: &Columns,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<usize>> {
+    let metadata = builder.metadata();
+    let all: Vec<usize> = (0..metadata.num_row_groups()).collect();
+    let Some(index) = columns.find(BILLING_PERIOD_START) else {
+        return Ok(all);
+    };
+    let schema = builder.schema();
+    let converter =
+        StatisticsConverter::try_new(schema.field(index).name(), schema, builder.parquet_schema())?;
+    let mins = to_dates(&converter.row_group_mins(metadata.row_groups().iter())?)?;
+    let maxes = to_dates(&converter.row_group_maxes(metadata.row_groups().iter())?)?;
+
+    // A period starting on `p` covers usage in `[p, next month)`
+    let first_period = periods::month_start(start)?;
+    Ok(all
+        .into_iter()
+        .filter(|&i| {
+            let min = mins.is_valid(i).then(|| mins.value_as_date(i)).flatten();
+            let max = maxes.is_valid(i).then(|| maxes.value_as_date(i)).flatten();
+            min.is_none_or(|min| min < end) && max.is_none_or(|max| max >= first_period)
+        })
+        .collect())
+}
+
+/// Newest billing period start across all files, from partition
+/// directories or, failing that, row group statistics
+fn latest_billing_period(files: &[PathBuf]) -> Result<Option<NaiveDate>> {
+    let mut latest = None;
+    for path in files {
+        if let Some(period) = partition_period(path) {
+            latest = latest.max(Some(period));
+            continue;
+        }
+
+        let file = File::open(path).with_context(|| format!("Could not open {:?}", path))?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+            .with_context(|| format!("Could not read Parquet file {:?}", path))?;
+        let schema = builder.schema();
+        let columns = Columns::from_names(schema.fields().iter().map(|f| f.name().as_str()));
+        let Some(index) = columns.find(BILLING_PERIOD_START) else {
+            continue;
+        };
+        let converter = StatisticsConverter::try_new(
+            schema.field(index).name(),
+            schema,
+            builder.parquet_schema(),
+        )?;
+        let maxes = to_dates(&converter.row_group_maxes(builder.metadata().row_groups().iter())?)?;
+        latest = latest.max(maxes.iter().flatten().max().map(Date32Type::to_naive_date));
+    }
+    Ok(latest)
+}
+
+/// Billing period of a file under a `BILLING_PERIOD=YYYY-MM` directory
+fn partition_period(path: &Path) -> Option<NaiveDate> {
+    path.components().find_map(|component| {
+        let month = component
+            .as_os_str()
+            .to_str()?
+            .strip_prefix(PARTITION_PREFIX)?;
+        NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d").ok()
+    })
+}
+
+fn column<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef> {
+    batch
+        .column_by_name(name)
+        .with_context(|| format!("Column {} missing from batch", name))
+}
+
+/// Dates of a timestamp, date or ISO 8601 string column
+fn to_dates(array: &ArrayRef) -> Result<Date32Array, ArrowError> {
+    if is_string(array.data_type()) {
+        let strings = to_strings(array)?;
+        return Ok(strings
+            .iter()
+            .map(|v| v.and_then(parse_date).map(Date32Type::from_naive_date))
+            .collect());
+    }
+    // Billing timestamps are UTC; dividing the raw values avoids needing a
+    // time zone database to cast zoned timestamps
+    if let DataType::Timestamp(unit, _) = array.data_type() {
+        let per_day = match unit {
+            TimeUnit::Second => 86_400,
+            TimeUnit::Millisecond => 86_400_000,
+            TimeUnit::Microsecond => 86_400_000_000,
+            TimeUnit::Nanosecond => 86_400_000_000_000,
+        };
+        let values = cast(array, &DataType::Int64)?;
+        return Ok(values
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|v| v.map(|v| v.div_euclid(per_day) as i32))
+            .collect());
+    }
+    Ok(cast(array, &DataType::Date32)?
+        .as_primitive::<Date32Type>()
+        .clone())
+}
+
+fn to_strings(array: &ArrayRef) -> Result<StringArray, ArrowError> {
+    Ok(cast(array, &DataType::Utf8)?.as_string::<i32>().clone())
+}
+
+fn is_string(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => true,
+        DataType::Dictionary(_, value) => is_string(value),
+        _ => false,
+    }
+}
+
+/// Where to find one candidate value of a group key in a file
+enum Lookup {
+    /// A plain string column
+    Column(String),
+    /// A key of a `map<string, string>` column such as `resource_tags`
+    MapKey { column: String, key: String },
+}
+
+/// A lookup's column decoded for one batch
+enum Values {
+    Strings(StringArray),
+    Map(Box<MapValues>),
+    Missing,
+}
+
+/// A map column with its keys and values cast to strings
+struct MapValues {
+    map: MapArray,
+    keys: StringArray,
+    values: StringArray,
+}
+
+impl Values {
+    fn get(&self, row: usize, lookup: &Lookup) -> Option<&str> {
+        match (self, lookup) {
+            (Values::Strings(strings), _) => strings.is_valid(row).then(|| strings.value(row)),
+            (Values::Map(map), Lookup::MapKey { key, .. }) => {
+                let MapValues { map, keys, values } = map.as_ref();
+                if map.is_null(row) {
+                    return None;
+                }
+                let offsets = map.value_offsets();
+                (offsets[row] as usize..offsets[row + 1] as usize)
+                    .find(|&i| keys.value(i) == key && values.is_valid(i))
+                    .map(|i| values.value(i))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How to derive a line item's group key in one file
+struct Grouping {
+    /// Candidate lookups, first non-empty value wins
+    lookups: Vec<Lookup>,
+    /// `key` for `key$value` labels of tags and cost categories
+    prefix: Option<String>,
+}
+
+impl Grouping {
+    fn new(columns: &Columns, schema: &Schema, group_by: &GroupBy) -> Self {
+        let (candidates, prefix): (Vec<(String, Option<String>)>, Option<String>) = match group_by {
+            GroupBy::Dimension(dimension) => (
+                dimension_lookups(*dimension)
+                    .iter()
+                    .map(|(column, key)| (column.to_string(), key.map(str::to_string)))
+                    .collect(),
+                None,
+            ),
+            GroupBy::Tag(key) => (
+                vec![
+                    ("resource_tags".to_string(), Some(key.clone())),
+                    ("resource_tags".to_string(), Some(format!("user_{}", key))),
+                    (format!("resource_tags_user_{}", key), None),
+                    (format!("resource_tags_{}", key), None),
+                ],
+                Some(key.clone()),
+            ),
+            GroupBy::CostCategory(name) => (
+                vec![
+                    ("cost_category".to_string(), Some(name.clone())),
+                    (format!("cost_category_{}", name), None),
+                ],
+                Some(name.clone()),
+            ),
+        };
+
+        let lookups = candidates
+            .into_iter()
+            .filter_map(|(column, key)| {
+                let field = schema.field(columns.find(&column)?);
+                let column = field.name().clone();
+                match key {
+                    Some(key) if matches!(field.data_type(), DataType::Map(..)) => {
+                        Some(Lookup::MapKey { column, key })
+                    }
+                    Some(_) => None,
+                    None => Some(Lookup::Column(column)),
+                }
+            })
+            .collect();
+        Self { lookups, prefix }
+    }
+
+    /// Top-level columns the lookups read
+    fn roots<'a>(&'a self, schema: &'a Schema) -> impl Iterator<Item = usize> + 'a {
+        self.lookups.iter().filter_map(|lookup| match lookup {
+            Lookup::Column(column) | Lookup::MapKey { column, .. } => schema.index_of(column).ok(),
+        })
+    }
+
+    /// Decode the lookups' columns of a batch
+    fn values(&self, batch: &RecordBatch) -> Result<Vec<Values>> {
+        self.lookups
+            .iter()
+            .map(|lookup| {
+                let (Lookup::Column(name) | Lookup::MapKey { column: name, .. }) = lookup;
+                let Some(array) = batch.column_by_name(name) else {
+                    return Ok(Values::Missing);
+                };
+                Ok(match lookup {
+                    Lookup::Column(_) => Values::Strings(to_strings(array)?),
+                    Lookup::MapKey { .. } => {
+                        let map = array.as_map().clone();
+                        let keys = to_strings(map.keys())?;
+                        let values = to_strings(map.values())?;
+                        Values::Map(Box::new(MapValues { map, keys, values }))
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn key(&self, values: &[Values], row: usize) -> String {
+        let value = self
+            .lookups
+            .iter()
+            .zip(values)
+            .filter_map(|(lookup, values)| values.get(row, lookup))
+            .find(|v| !v.is_empty())
+            .unwrap_or("");
+
+        match &self.prefix {
+            Some(prefix) => format!("{}${}", prefix, value),
+            None if value.is_empty() => NO_VALUE.to_string(),
+            None => value.to_string(),
+        }
+    }
+}
+
+/// CUR 2.0 columns (and `product` map keys) holding each Cost Explorer
+/// dimension, most specific first. Flattened legacy names are included for
+/// exports made by the Athena integration.
+fn dimension_lookups(dimension: Dimension) -> &'static [(&'static str, Option<&'static str>)] {
+    match dimension {
+        Dimension::Service => &[
+            ("product_product_name", None),
+            ("product", Some("product_name")),
+            ("line_item_product_code", None),
+        ],
+        Dimension::LinkedAccount => &[("line_item_usage_account_id", None)],
+        Dimension::Region => &[
+            ("product_region_code", None),
+            ("product_region", None),
+            ("product", Some("region")),
+        ],
+        Dimension::UsageType => &[("line_item_usage_type", None)],
+        Dimension::InstanceType => &[("product_instance_type", None)],
+        Dimension::Operation => &[("line_item_operation", None)],
+        Dimension::PurchaseType => &[("pricing_term", None)],
+        Dimension::RecordType => &[("line_item_line_item_type", None)],
+        Dimension::ResourceId => &[("line_item_resource_id", None)],
+    }
+}
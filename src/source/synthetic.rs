@@ -0,0 +1,568 @@
+//! Deterministic synthetic cost data for demos, screenshots and tests
+//!
+//! The same seed and "today" always produce the same numbers, so the TUI can
+//! run without an AWS account and tests can assert on exact values.
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{Datelike, Local, NaiveDate};
+
+use super::{cost_data, provider_cost_matrix, provider_daily_costs, CostSource};
+use crate::aws::budgets::budget_period;
+use crate::aws::{
+    Budget, BudgetPeriod, CostData, CostMatrix, DailyCosts, Dimension, GroupBy, MonthlyTrend,
+    RESOURCE_DAYS,
+};
+use crate::periods;
+
+/// Services with their baseline full-month cost in USD
+const SERVICES: [(&str, f64); 13] = [
+    ("Amazon Elastic Compute Cloud - Compute", 1850.0),
+    ("Amazon Relational Database Service", 920.0),
+    ("Amazon Simple Storage Service", 410.0),
+    ("AWS Lambda", 160.0),
+    ("Amazon CloudFront", 140.0),
+    ("Amazon DynamoDB", 120.0),
+    ("Amazon CloudWatch", 85.0),
+    ("Amazon Virtual Private Cloud", 64.0),
+    ("Elastic Load Balancing", 58.0),
+    ("Amazon SageMaker", 45.0),
+    ("Amazon Route 53", 12.0),
+    ("AWS Key Management Service", 6.5),
+    ("Amazon Simple Queue Service", 3.2),
+];
+
+/// Index into [`SERVICES`] of a service that only runs in some months, so
+/// comparisons show new and disappeared services
+const INTERMITTENT_SERVICE: usize = 9;
+
+/// Injected daily spikes as (index into [`SERVICES`], days before today,
+/// multiplier), so anomaly detection has something to find
+const SPIKES: [(usize, i64, f64); 2] = [(3, 1, 3.4), (6, 6, 2.6)];
+
+/// Cost allocation tag keys the demo data is tagged with
+const TAG_KEYS: [&str; 3] = ["cost-center", "environment", "team"];
+
+/// Demo budgets as (name, time unit, index into [`SERVICES`] or `None` for
+/// every service, limit as a share of the baseline cost over a period)
+const BUDGETS: [(&str, &str, Option<usize>, f64); 5] = [
+    ("Monthly total", "MONTHLY", None, 1.2),
+    ("EC2 compute", "MONTHLY", Some(0), 0.9),
+    ("Storage", "QUARTERLY", Some(2), 0.97),
+    ("Serverless", "MONTHLY", Some(3), 0.4),
+    ("Annual total", "ANNUALLY", None, 1.3),
+];
+
+/// Synthetic source generating plausible, reproducible costs
+#[derive(Debug, Clone)]
+pub struct SyntheticSource {
+    seed: u64,
+    today: NaiveDate,
+}
+
+impl SyntheticSource {
+    /// Generate data for the real current date
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            today: Local::now().date_naive(),
+        }
+    }
+
+    /// Pin "today" so output doesn't depend on the wall clock
+    pub fn with_today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Full-month cost of every service active in the month starting at `month`
+    fn month_service_costs(&self, month: NaiveDate) -> Vec<(&'static str, f64)> {
+        let month_index = month.year() as u64 * 12 + month.month0() as u64;
+        // Slow growth over time so the trend chart isn't flat
+        let growth = 1.0 + 0.02 * (month_index % 24) as f64;
+
+        SERVICES
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != INTERMITTENT_SERVICE || !month_index.is_multiple_of(3))
+            .map(|(i, (name, base))| {
+                let noise = 0.85 + 0.3 * unit_random(self.seed, month_index, i as u64);
+                (*name, round_cents(base * growth * noise))
+            })
+            .collect()
+    }
+
+    /// Service costs over `[start, end)`, prorating each month by the days
+    /// it overlaps the range
+    fn service_costs(&self, start: NaiveDate, end: NaiveDate) -> Result<Vec<(String, f64)>> {
+        let mut totals: Vec<(String, f64)> = Vec::new();
+        let mut month = periods::month_start(start)?;
+
+        while month < end {
+            let next = periods::next_month_start(month)?;
+            let overlap_start = std::cmp::max(month, start);
+            let overlap_end = std::cmp::min(next, end);
+            let fraction = (overlap_end - overlap_start).num_days() as f64
+                / periods::days_in_month(month)? as f64;
+
+            for (name, cost) in self.month_service_costs(month) {
+                let cost = round_cents(cost * fraction);
+                match totals.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, total)) => *total += cost,
+                    None => totals.push((name.to_string(), cost)),
+                }
+            }
+            month = next;
+        }
+
+        Ok(totals)
+    }
+
+    /// Each service's cost on each day of `[start, end)`: its month's cost
+    /// spread over the days, busier on weekdays, plus the [`SPIKES`]
+    fn daily_costs(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(String, NaiveDate, f64)>> {
+        let mut rows = Vec::new();
+        let mut month = periods::month_start(start)?;
+
+        while month < end {
+            let next = periods::next_month_start(month)?;
+            let rates = [
+                self.daily_rates(periods::previous_month(month)?.0)?,
+                self.daily_rates(month)?,
+                self.daily_rates(next)?,
+            ];
+            let days = periods::days_in_month(month)? as f64;
+            let mut date = month.max(start);
+            while date < next.min(end) {
+                let day = date.num_days_from_ce() as u64;
+                // Blend into the neighbouring month's rate so months don't
+                // jump at the boundary
+                let position = ((date - month).num_days() as f64 + 0.5) / days - 0.5;
+                let (neighbour, weight) = if position < 0.0 {
+                    (&rates[0], -position)
+                } else {
+                    (&rates[2], position)
+                };
+                // Weekdays average out with weekends to the monthly cost
+                let weekday = if date.weekday().number_from_monday() > 5 {
+                    0.8
+                } else {
+                    1.08
+                };
+                for &(index, rate) in &rates[1] {
+                    let rate = match neighbour.iter().find(|(i, _)| *i == index) {
+                        Some(&(_, other)) => rate + (other - rate) * weight / 2.0,
+                        None => rate,
+                    };
+                    let noise = 0.92 + 0.16 * unit_random(self.seed ^ 0xDA11, day, index as u64);
+                    let spike = SPIKES
+                        .iter()
+                        .find(|(service, ago, _)| {
+                            *service == index && self.today - chrono::Duration::days(*ago) == date
+                        })
+                        .map_or(1.0, |(_, _, multiplier)| *multiplier);
+                    let cost = round_cents(rate * weekday * noise * spike);
+                    rows.push((SERVICES[index].0.to_string(), date, cost));
+                }
+                date += chrono::Duration::days(1);
+            }
+            month = next;
+        }
+
+        Ok(rows)
+    }
+
+    /// Average daily cost of every service active in the month starting at
+    /// `month`, by index into [`SERVICES`]
+    fn daily_rates(&self, month: NaiveDate) -> Result<Vec<(usize, f64)>> {
+        let days = periods::days_in_month(month)? as f64;
+        Ok(self
+            .month_service_costs(month)
+            .into_iter()
+            .filter_map(|(name, cost)| {
+                let index = SERVICES.iter().position(|(n, _)| *n == name)?;
+                Some((index, cost / days))
+            })
+            .collect())
+    }
+
+    /// Cost of one service, or of every service, over `[start, end)`
+    fn budget_spend(
+        &self,
+        service: Option<usize>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<f64> {
+        Ok(self
+            .service_costs(start, end)?
+            .into_iter()
+            .filter(|(name, _)| service.is_none_or(|i| SERVICES[i].0 == name))
+            .map(|(_, cost)| cost)
+            .sum())
+    }
+
+    /// Split the range total across synthetic values for a grouping
+    fn grouped_costs(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<Vec<(String, f64)>> {
+        let services = self.service_costs(start, end)?;
+        if group_by == &GroupBy::Dimension(Dimension::Service) {
+            return Ok(services);
+        }
+
+        let total: f64 = services.iter().map(|(_, c)| c).sum();
+        let shares: Vec<(String, f64)> = match group_by {
+            GroupBy::Dimension(Dimension::LinkedAccount) => vec![
+                ("111111111111".into(), 0.55),
+                ("222222222222".into(), 0.30),
+                ("333333333333".into(), 0.15),
+            ],
+            GroupBy::Dimension(Dimension::Region) => vec![
+                ("us-east-1".into(), 0.62),
+                ("us-west-2".into(), 0.21),
+                ("eu-west-1".into(), 0.12),
+                ("global".into(), 0.05),
+            ],
+            GroupBy::Dimension(Dimension::PurchaseType) => vec![
+                ("On Demand Instances".into(), 0.7),
+                ("Savings Plans".into(), 0.3),
+            ],
+            GroupBy::Dimension(Dimension::RecordType) => {
+                vec![("Usage".into(), 0.93), ("Tax".into(), 0.07)]
+            }
+            GroupBy::Dimension(dimension) => vec![
+                (format!("{}-A", dimension.as_str()), 0.5),
+                (format!("{}-B", dimension.as_str()), 0.3),
+                (format!("{}-C", dimension.as_str()), 0.2),
+            ],
+            // Cost Explorer reports tag groups as `key$value`, with an
+            // empty value for untagged spend
+            GroupBy::Tag(key) => tag_shares(key)
+                .iter()
+                .map(|(value, share)| (format!("{}${}", key, value), *share))
+                .collect(),
+            GroupBy::CostCategory(name) => vec![
+                (format!("{}$Engineering", name), 0.7),
+                (format!("{}$Shared", name), 0.3),
+            ],
+        };
+
+        Ok(shares
+            .into_iter()
+            .map(|(key, share)| (key, round_cents(total * share)))
+            .collect())
+    }
+
+    /// Split each `rows` value across the `columns` values. Untagged shares
+    /// vary by row, so some services and accounts are tagged better than
+    /// others, but still add up to the tag breakdown's untagged spend.
+    fn matrix_costs(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<Vec<(String, String, f64)>> {
+        let rows = self.grouped_costs(start, end, rows)?;
+        let column_costs = self.grouped_costs(start, end, columns)?;
+        let total: f64 = column_costs.iter().map(|(_, c)| c).sum();
+        if total <= 0.0 {
+            return Ok(Vec::new());
+        }
+        let shares: Vec<(&str, f64)> = column_costs
+            .iter()
+            .map(|(column, cost)| (column.as_str(), cost / total))
+            .collect();
+
+        let untagged = match columns {
+            GroupBy::Tag(key) => Some(format!("{}$", key)),
+            _ => None,
+        };
+        let untagged_share: f64 = shares
+            .iter()
+            .filter(|(column, _)| Some(*column) == untagged.as_deref())
+            .map(|(_, share)| share)
+            .sum();
+        let key_hash = untagged
+            .as_deref()
+            .map_or(0, |key| key.bytes().map(u64::from).sum::<u64>());
+        let skews: Vec<f64> = (0..rows.len())
+            .map(|i| 0.2 + 2.5 * unit_random(self.seed, key_hash, i as u64))
+            .collect();
+        let weighted: f64 = rows.iter().zip(&skews).map(|((_, c), s)| c * s).sum();
+        let scale = if weighted > 0.0 {
+            rows.iter().map(|(_, c)| c).sum::<f64>() / weighted
+        } else {
+            1.0
+        };
+
+        let mut cells = Vec::new();
+        for ((row, cost), skew) in rows.iter().zip(skews) {
+            let row_untagged = (untagged_share * skew * scale).min(0.95);
+            for (column, share) in &shares {
+                let share = if Some(*column) == untagged.as_deref() {
+                    row_untagged
+                } else if untagged_share < 1.0 {
+                    share * (1.0 - row_untagged) / (1.0 - untagged_share)
+                } else {
+                    0.0
+                };
+                cells.push((row.clone(), column.to_string(), round_cents(cost * share)));
+            }
+        }
+        Ok(cells)
+    }
+}
+
+#[async_trait]
+impl CostSource for SyntheticSource {
+    fn name(&self) -> String {
+        "demo data".to_string()
+    }
+
+    fn provider(&self) -> Option<String> {
+        Some("AWS".to_string())
+    }
+
+    async fn current_month(&self) -> Result<CostData> {
+        let (start, end) = periods::month_to_date(self.today)?;
+        let costs = self.service_costs(start, end)?;
+        Ok(cost_data(costs, start, end, periods::month_name(start)))
+    }
+
+    async fn previous_month(&self) -> Result<CostData> {
+        let (start, end) = periods::previous_month(self.today)?;
+        let costs = self.service_costs(start, end)?;
+        Ok(cost_data(costs, start, end, periods::month_name(start)))
+    }
+
+    async fn monthly_trend(&self, months: u32) -> Result<MonthlyTrend> {
+        let mut trend = MonthlyTrend::default();
+        for (start, end) in periods::trend_months(self.today, months)? {
+            let costs = self.service_costs(start, end)?;
+            trend
+                .months
+                .push(cost_data(costs, start, end, periods::month_name(start)));
+        }
+        Ok(trend)
+    }
+
+    async fn breakdown(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        group_by: &GroupBy,
+    ) -> Result<CostData> {
+        let costs = self.grouped_costs(start, end, group_by)?;
+        Ok(cost_data(
+            costs,
+            start,
+            end,
+            periods::range_name(start, end),
+        ))
+    }
+
+    async fn matrix(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        rows: &GroupBy,
+        columns: &GroupBy,
+    ) -> Result<CostMatrix> {
+        let cells = self
+            .matrix_costs(start, end, rows, columns)?
+            .into_iter()
+            .map(|(row, column, cost)| (None, row, column, cost))
+            .collect();
+        Ok(provider_cost_matrix(
+            cells,
+            start,
+            end,
+            periods::range_name(start, end),
+        ))
+    }
+
+    async fn daily(&self, start: NaiveDate, end: NaiveDate) -> Result<DailyCosts> {
+        let rows = self
+            .daily_costs(start, end)?
+            .into_iter()
+            .map(|(service, date, cost)| (None, service, date, cost))
+            .collect();
+        Ok(provider_daily_costs(rows, start, end))
+    }
+
+    /// Splits the service's cost across made-up resources, limited to the
+    /// same window as Cost Explorer's resource-level data
+    async fn resources(&self, service: &str, start: NaiveDate, end: NaiveDate) -> Result<CostData> {
+        let (window_start, window_end) = periods::last_days(self.today, RESOURCE_DAYS);
+        let (start, end) = (start.max(window_start), end.min(window_end));
+        if start >= end {
+            bail!(
+                "Cost Explorer only has resource-level costs for the last {} days ({})",
+                RESOURCE_DAYS,
+                periods::range_name(window_start, window_end)
+            );
+        }
+
+        let total = self
+            .service_costs(start, end)?
+            .into_iter()
+            .find(|(name, _)| name == service)
+            .map(|(_, cost)| cost)
+            .unwrap_or(0.0);
+        let ids = resource_ids(service);
+        // Each resource costs about half as much as the one before
+        let weights: Vec<f64> = (0..ids.len()).map(|i| 0.5f64.powi(i as i32)).collect();
+        let sum: f64 = weights.iter().sum();
+        let costs = ids
+            .into_iter()
+            .zip(weights)
+            .map(|(id, weight)| (id, round_cents(total * weight / sum)))
+            .collect();
+        Ok(cost_data(
+            costs,
+            start,
+            end,
+            periods::range_name(start, end),
+        ))
+    }
+
+    async fn tag_keys(&self, _start: NaiveDate, _end: NaiveDate) -> Result<Vec<String>> {
+        Ok(TAG_KEYS.iter().map(|k| k.to_string()).collect())
+    }
+
+    /// [`BUDGETS`], forecast at this period's daily run rate
+    async fn budgets(&self, months: u32) -> Result<Vec<Budget>> {
+        let tomorrow = self.today + chrono::Duration::days(1);
+        let history_start = periods::trend_months(self.today, months)?
+            .first()
+            .map_or(self.today, |(start, _)| *start);
+
+        let mut budgets = Vec::new();
+        for (name, time_unit, service, share) in BUDGETS {
+            let (start, end) = budget_period(time_unit, self.today)?;
+            let baseline = match service {
+                Some(i) => SERVICES[i].1,
+                None => SERVICES.iter().map(|(_, cost)| cost).sum(),
+            };
+            let months_per_period = match time_unit {
+                "QUARTERLY" => 3.0,
+                "ANNUALLY" => 12.0,
+                _ => 1.0,
+            };
+            let limit = (baseline * months_per_period * share / 10.0).round() * 10.0;
+
+            let actual = round_cents(self.budget_spend(service, start, tomorrow)?);
+            let elapsed = (tomorrow - start).num_days() as f64;
+            let forecasted = actual / elapsed * (end - start).num_days() as f64;
+
+            let mut history = Vec::new();
+            let mut day = self.today;
+            loop {
+                let (start, end) = budget_period(time_unit, day)?;
+                if end <= history_start {
+                    break;
+                }
+                history.push(BudgetPeriod {
+                    start,
+                    end,
+                    limit,
+                    actual: round_cents(self.budget_spend(service, start, end.min(tomorrow))?),
+                });
+                day = start - chrono::Duration::days(1);
+            }
+            history.reverse();
+
+            budgets.push(Budget {
+                name: name.to_string(),
+                budget_type: "COST".to_string(),
+                time_unit: time_unit.to_string(),
+                start,
+                end,
+                limit,
+                unit: "USD".to_string(),
+                actual,
+                forecasted: Some(round_cents(forecasted)),
+                history,
+                thresholds: Vec::new(),
+                scope: None,
+            });
+        }
+        Ok(budgets)
+    }
+}
+
+/// Values of a demo tag key and their share of spend; `""` is untagged
+fn tag_shares(key: &str) -> &'static [(&'static str, f64)] {
+    match key {
+        "environment" => &[("prod", 0.71), ("staging", 0.16), ("dev", 0.07), ("", 0.06)],
+        "cost-center" => &[("cc-1001", 0.42), ("cc-2040", 0.27), ("", 0.31)],
+        _ => &[
+            ("platform", 0.38),
+            ("data", 0.27),
+            ("web", 0.17),
+            ("", 0.18),
+        ],
+    }
+}
+
+/// Plausible resource IDs for a demo service, most expensive first
+fn resource_ids(service: &str) -> Vec<String> {
+    const ACCOUNT: &str = "us-east-1:111111111111";
+    match service {
+        s if s.starts_with("Amazon Elastic Compute Cloud") => vec![
+            "i-0a1b2c3d4e5f60718".into(),
+            "i-0f9e8d7c6b5a40312".into(),
+            "i-05c4b3a29180f7e6d".into(),
+            "vol-0123456789abcdef0".into(),
+        ],
+        "Amazon Relational Database Service" => ["orders", "users", "analytics-replica"]
+            .iter()
+            .map(|db| format!("arn:aws:rds:{}:db:{}", ACCOUNT, db))
+            .collect(),
+        "Amazon Simple Storage Service" => vec![
+            "acme-data-lake".into(),
+            "acme-logs".into(),
+            "acme-static-assets".into(),
+        ],
+        "AWS Lambda" => ["api", "thumbnailer", "nightly-cleanup"]
+            .iter()
+            .map(|f| format!("arn:aws:lambda:{}:function:{}", ACCOUNT, f))
+            .collect(),
+        _ => {
+            let slug: String = service
+                .trim_start_matches("Amazon ")
+                .trim_start_matches("AWS ")
+                .to_lowercase()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-");
+            (1..=2)
+                .map(|i| format!("arn:aws:{}:{}:resource/demo-{}", slug, ACCOUNT, i))
+                .collect()
+        }
+    }
+}
+
+/// Deterministic value in `[0, 1)` for a (seed, month, service) triple
+fn unit_random(seed: u64, month: u64, service: u64) -> f64 {
+    // splitmix64
+    let mut z = seed
+        .wrapping_add(month.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(service.wrapping_mul(0xBF58_476D_1CE4_E5B9));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
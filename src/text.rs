@@ -0,0 +1,23 @@
+//! Fitting names into fixed-width columns
+
+/// `name` without a leading `Amazon ` or `AWS `, cut to at most `max_len`
+/// characters with `ellipsis` at the end when it is longer
+///
+/// Counts characters rather than bytes, so service, tag, team and account
+/// names in any script are cut between characters.
+pub fn truncate(name: &str, max_len: usize, ellipsis: &str) -> String {
+    let name = name
+        .trim_start_matches("Amazon ")
+        .trim_start_matches("AWS ")
+        .trim_start_matches("Amazon");
+
+    if name.chars().count() <= max_len {
+        return name.to_string();
+    }
+    let keep = max_len.saturating_sub(ellipsis.chars().count());
+    name.chars()
+        .take(keep)
+        .chain(ellipsis.chars())
+        .take(max_len)
+        .collect()
+}
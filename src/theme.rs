@@ -0,0 +1,615 @@
+//! Colors the TUI draws with
+//!
+//! A [`Theme`] names the colors the views use: neutrals for text, borders
+//! and the selected row, the accent hues, and the palette services are
+//! charted in. The built-in themes are `dark` (the default), `light`,
+//! `high-contrast` and `monochrome`; the config's `[themes]` section defines
+//! more on top of one of them. [`ColorDepth`] says how many colors the
+//! terminal shows, and [`Theme::for_depth`] brings a theme down to that.
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+/// Names of the 16 ANSI colors, as they are written in the config
+const ANSI_NAMES: [&str; 16] = [
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "gray",
+    "darkgray",
+    "lightred",
+    "lightgreen",
+    "lightyellow",
+    "lightblue",
+    "lightmagenta",
+    "lightcyan",
+    "white",
+];
+
+/// The 16 ANSI colors as xterm shows them, to find the nearest one
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Names of the built-in themes
+pub const BUILT_IN: [&str; 4] = ["dark", "light", "high-contrast", "monochrome"];
+
+/// A terminal color
+///
+/// Written as an ANSI name (`red`, `darkgray`, `lightcyan`...), `#rrggbb`,
+/// an index into the 256-color palette (`"208"`), or `default` for the
+/// terminal's own foreground or background.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// The terminal's default
+    Reset,
+    /// One of the 16 ANSI colors, 0 (black) to 15 (white)
+    Ansi(u8),
+    /// One of the 256 indexed colors
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// This color on a terminal with `depth` colors
+    pub fn for_depth(self, depth: ColorDepth) -> Color {
+        match (depth, self) {
+            (ColorDepth::None, _) => Color::Reset,
+            (ColorDepth::TrueColor, color) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_indexed(r, g, b)),
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => Color::Ansi(nearest_ansi(r, g, b)),
+            (ColorDepth::Ansi16, Color::Indexed(i)) => {
+                let (r, g, b) = indexed_rgb(i);
+                Color::Ansi(nearest_ansi(r, g, b))
+            }
+            (_, color) => color,
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Color::Reset => write!(f, "default"),
+            Color::Ansi(n) => write!(f, "{}", ANSI_NAMES[n as usize % 16]),
+            Color::Indexed(i) => write!(f, "{}", i),
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let name = s.trim().to_ascii_lowercase().replace(['-', '_', ' '], "");
+        if name == "default" || name == "reset" {
+            return Ok(Color::Reset);
+        }
+        if let Some(n) = ANSI_NAMES.iter().position(|&ansi| ansi == name) {
+            return Ok(Color::Ansi(n as u8));
+        }
+        if let Some(hex) = name.strip_prefix('#') {
+            if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+                return Ok(Color::Rgb(channel(0), channel(2), channel(4)));
+            }
+        }
+        if let Ok(index) = name.parse::<u8>() {
+            return Ok(Color::Indexed(index));
+        }
+        Err(format!(
+            "Unknown color {:?}, expected #rrggbb, 0-255, default or one of {}",
+            s,
+            ANSI_NAMES.join(", ")
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Nearest color of the 6x6x6 cube or grayscale ramp of the 256-color palette
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        v => (v - 35) / 40,
+    };
+    let (cr, cg, cb) = (level(r), level(g), level(b));
+    let cube = 16 + 36 * cr + 6 * cg + cb;
+
+    let average = (r as u16 + g as u16 + b as u16) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    match distance(indexed_rgb(gray), (r, g, b)) < distance(indexed_rgb(cube), (r, g, b)) {
+        true => gray,
+        false => cube,
+    }
+}
+
+/// Nearest of the 16 ANSI colors
+///
+/// Colored input only matches colored ANSI colors and grays only grays, so
+/// a pastel red stays red rather than turning gray.
+fn nearest_ansi(r: u8, g: u8, b: u8) -> u8 {
+    let saturation = r.max(g).max(b) - r.min(g).min(b);
+    let grays = [0, 7, 8, 15];
+    (0..16)
+        .filter(|n| grays.contains(n) == (saturation < 64))
+        .min_by_key(|&n| distance(ANSI_RGB[n as usize], (r, g, b)))
+        .unwrap()
+}
+
+/// What an indexed color looks like in xterm
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_RGB[index as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+            let i = index - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + 10 * (index - 232);
+            (v, v, v)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// How many colors the terminal shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// No colors at all (`NO_COLOR`, or a `dumb` terminal)
+    None,
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// The depth the environment asks for
+    pub fn detect() -> Self {
+        let var = |name: &str| env::var(name).ok();
+        Self::from_env(
+            var("NO_COLOR").as_deref(),
+            var("COLORTERM").as_deref(),
+            var("TERM").as_deref(),
+        )
+    }
+
+    /// The depth for these values of `NO_COLOR`, `COLORTERM` and `TERM`
+    ///
+    /// A non-empty `NO_COLOR` turns colors off, as <https://no-color.org>
+    /// asks; `COLORTERM=truecolor` (or `24bit`) allows any color, and a
+    /// `TERM` ending in `256color` the 256-color palette.
+    pub fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if no_color.is_some_and(|v| !v.is_empty()) {
+            return ColorDepth::None;
+        }
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return ColorDepth::TrueColor;
+        }
+        match term.unwrap_or_default() {
+            "dumb" => ColorDepth::None,
+            term if term.contains("256color") => ColorDepth::Ansi256,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(ColorDepth::TrueColor),
+            "256" => Ok(ColorDepth::Ansi256),
+            "16" => Ok(ColorDepth::Ansi16),
+            "none" => Ok(ColorDepth::None),
+            _ => Err(format!(
+                "Unknown color depth {:?}, expected truecolor, 256, 16 or none",
+                s
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ColorDepth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Colors services are charted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    /// The theme's own
+    #[default]
+    Theme,
+    /// Okabe-Ito colors, told apart with any kind of color blindness
+    Colorblind,
+}
+
+/// The colors of every view
+///
+/// Generic so the TUI can hold the same theme in its own color type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme<C = Color> {
+    /// Names, amounts and other text
+    pub text: C,
+    /// Secondary amounts and notes
+    pub label: C,
+    /// Headers and hints
+    pub muted: C,
+    /// Row numbers and the least important text
+    pub dim: C,
+    pub border: C,
+    /// Background of the selected row; the terminal's default reverses it
+    pub selection: C,
+    /// Unfilled part of gauges
+    pub track: C,
+    /// Text drawn on an accent color
+    pub on_accent: C,
+    /// AWS orange of the title and provider names
+    pub brand: C,
+    pub red: C,
+    pub orange: C,
+    pub yellow: C,
+    pub green: C,
+    pub teal: C,
+    pub cyan: C,
+    pub blue: C,
+    pub purple: C,
+    pub lavender: C,
+    pub pink: C,
+    /// Service colors, used in turn
+    pub services: Vec<C>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Bright colors on a dark background
+    pub fn dark() -> Self {
+        Self {
+            text: Color::Ansi(15),
+            label: Color::Rgb(170, 170, 170),
+            muted: Color::Ansi(7),
+            dim: Color::Ansi(8),
+            border: Color::Rgb(90, 90, 90),
+            selection: Color::Rgb(60, 60, 80),
+            track: Color::Rgb(40, 40, 50),
+            on_accent: Color::Ansi(0),
+            brand: Color::Rgb(255, 153, 0),
+            red: Color::Rgb(255, 107, 107),
+            orange: Color::Rgb(255, 184, 77),
+            yellow: Color::Rgb(255, 230, 109),
+            green: Color::Rgb(108, 255, 108),
+            teal: Color::Rgb(78, 205, 196),
+            cyan: Color::Rgb(129, 236, 236),
+            blue: Color::Rgb(77, 182, 255),
+            purple: Color::Rgb(170, 128, 255),
+            lavender: Color::Rgb(162, 155, 254),
+            pink: Color::Rgb(255, 159, 243),
+            services: vec![
+                Color::Rgb(255, 107, 107), // Coral Red
+                Color::Rgb(78, 205, 196),  // Turquoise
+                Color::Rgb(255, 230, 109), // Yellow
+                Color::Rgb(170, 128, 255), // Purple
+                Color::Rgb(255, 159, 243), // Pink
+                Color::Rgb(108, 255, 108), // Lime Green
+                Color::Rgb(255, 184, 77),  // Orange
+                Color::Rgb(77, 182, 255),  // Sky Blue
+                Color::Rgb(255, 138, 101), // Salmon
+                Color::Rgb(129, 236, 236), // Cyan
+                Color::Rgb(162, 155, 254), // Lavender
+                Color::Rgb(0, 184, 148),   // Teal
+            ],
+        }
+    }
+
+    /// Deeper colors for a light background
+    pub fn light() -> Self {
+        Self {
+            text: Color::Rgb(30, 30, 30),
+            label: Color::Rgb(90, 90, 90),
+            muted: Color::Rgb(100, 100, 110),
+            dim: Color::Rgb(150, 150, 150),
+            border: Color::Rgb(170, 170, 170),
+            selection: Color::Rgb(205, 215, 240),
+            track: Color::Rgb(225, 225, 230),
+            on_accent: Color::Ansi(15),
+            brand: Color::Rgb(200, 100, 0),
+            red: Color::Rgb(200, 40, 40),
+            orange: Color::Rgb(200, 110, 0),
+            yellow: Color::Rgb(150, 115, 0),
+            green: Color::Rgb(30, 135, 30),
+            teal: Color::Rgb(0, 128, 120),
+            cyan: Color::Rgb(0, 125, 160),
+            blue: Color::Rgb(20, 90, 200),
+            purple: Color::Rgb(120, 60, 200),
+            lavender: Color::Rgb(95, 85, 190),
+            pink: Color::Rgb(185, 45, 145),
+            services: vec![
+                Color::Rgb(200, 50, 50),
+                Color::Rgb(0, 140, 130),
+                Color::Rgb(165, 125, 0),
+                Color::Rgb(120, 70, 210),
+                Color::Rgb(190, 60, 160),
+                Color::Rgb(40, 150, 40),
+                Color::Rgb(210, 120, 0),
+                Color::Rgb(20, 110, 200),
+                Color::Rgb(200, 90, 60),
+                Color::Rgb(0, 140, 170),
+                Color::Rgb(100, 90, 200),
+                Color::Rgb(0, 130, 100),
+            ],
+        }
+    }
+
+    /// Saturated colors and white text, for low vision or glare
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Color::Ansi(15),
+            label: Color::Ansi(15),
+            muted: Color::Ansi(15),
+            dim: Color::Ansi(7),
+            border: Color::Ansi(15),
+            selection: Color::Rgb(0, 0, 170),
+            track: Color::Rgb(70, 70, 70),
+            on_accent: Color::Ansi(0),
+            brand: Color::Rgb(255, 170, 0),
+            red: Color::Rgb(255, 85, 85),
+            orange: Color::Rgb(255, 170, 0),
+            yellow: Color::Rgb(255, 255, 0),
+            green: Color::Rgb(0, 255, 0),
+            teal: Color::Rgb(0, 255, 200),
+            cyan: Color::Rgb(0, 255, 255),
+            blue: Color::Rgb(80, 170, 255),
+            purple: Color::Rgb(200, 130, 255),
+            lavender: Color::Rgb(180, 180, 255),
+            pink: Color::Rgb(255, 120, 255),
+            services: vec![
+                Color::Rgb(255, 85, 85),
+                Color::Rgb(0, 255, 255),
+                Color::Rgb(255, 255, 0),
+                Color::Rgb(200, 130, 255),
+                Color::Rgb(255, 120, 255),
+                Color::Rgb(0, 255, 0),
+                Color::Rgb(255, 170, 0),
+                Color::Rgb(80, 170, 255),
+                Color::Rgb(255, 150, 110),
+                Color::Rgb(170, 255, 255),
+                Color::Rgb(180, 180, 255),
+                Color::Rgb(0, 220, 170),
+            ],
+        }
+    }
+
+    /// The terminal's own colors, with the selected row reversed
+    pub fn monochrome() -> Self {
+        Self {
+            text: Color::Reset,
+            label: Color::Reset,
+            muted: Color::Reset,
+            dim: Color::Reset,
+            border: Color::Reset,
+            selection: Color::Reset,
+            track: Color::Reset,
+            on_accent: Color::Reset,
+            brand: Color::Reset,
+            red: Color::Reset,
+            orange: Color::Reset,
+            yellow: Color::Reset,
+            green: Color::Reset,
+            teal: Color::Reset,
+            cyan: Color::Reset,
+            blue: Color::Reset,
+            purple: Color::Reset,
+            lavender: Color::Reset,
+            pink: Color::Reset,
+            services: vec![Color::Reset],
+        }
+    }
+
+    /// A built-in theme by name
+    pub fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    /// Chart services in `palette`
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        if palette == Palette::Colorblind {
+            // Okabe & Ito (2002); their black is the theme's text color
+            self.services = vec![
+                Color::Rgb(230, 159, 0),   // Orange
+                Color::Rgb(86, 180, 233),  // Sky Blue
+                Color::Rgb(0, 158, 115),   // Bluish Green
+                Color::Rgb(240, 228, 66),  // Yellow
+                Color::Rgb(0, 114, 178),   // Blue
+                Color::Rgb(213, 94, 0),    // Vermillion
+                Color::Rgb(204, 121, 167), // Reddish Purple
+                self.text,
+            ];
+        }
+        self
+    }
+
+    /// The theme with each color brought down to what the terminal shows
+    ///
+    /// Below 256 colors the selected row is reversed instead: no ANSI color
+    /// is reliably dimmer than the text on it.
+    pub fn for_depth(self, depth: ColorDepth) -> Self {
+        if depth == ColorDepth::None {
+            return Self::monochrome();
+        }
+        let mut theme = self.map(|color| color.for_depth(depth));
+        if depth == ColorDepth::Ansi16 {
+            theme.selection = Color::Reset;
+        }
+        theme
+    }
+}
+
+impl<C> Theme<C> {
+    /// The same theme in another color type
+    pub fn map<D>(self, f: impl Fn(C) -> D) -> Theme<D> {
+        Theme {
+            text: f(self.text),
+            label: f(self.label),
+            muted: f(self.muted),
+            dim: f(self.dim),
+            border: f(self.border),
+            selection: f(self.selection),
+            track: f(self.track),
+            on_accent: f(self.on_accent),
+            brand: f(self.brand),
+            red: f(self.red),
+            orange: f(self.orange),
+            yellow: f(self.yellow),
+            green: f(self.green),
+            teal: f(self.teal),
+            cyan: f(self.cyan),
+            blue: f(self.blue),
+            purple: f(self.purple),
+            lavender: f(self.lavender),
+            pink: f(self.pink),
+            services: self.services.into_iter().map(f).collect(),
+        }
+    }
+
+    /// Color of the `index`th service
+    pub fn service(&self, index: usize) -> &C {
+        &self.services[index % self.services.len()]
+    }
+}
+
+/// A theme from the config: a built-in one with some colors replaced
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeColors {
+    /// Built-in theme the others start from (default `dark`)
+    #[serde(default)]
+    pub base: Option<toml::Spanned<String>>,
+    pub text: Option<Color>,
+    pub label: Option<Color>,
+    pub muted: Option<Color>,
+    pub dim: Option<Color>,
+    pub border: Option<Color>,
+    pub selection: Option<Color>,
+    pub track: Option<Color>,
+    pub on_accent: Option<Color>,
+    pub brand: Option<Color>,
+    pub red: Option<Color>,
+    pub orange: Option<Color>,
+    pub yellow: Option<Color>,
+    pub green: Option<Color>,
+    pub teal: Option<Color>,
+    pub cyan: Option<Color>,
+    pub blue: Option<Color>,
+    pub purple: Option<Color>,
+    pub lavender: Option<Color>,
+    pub pink: Option<Color>,
+    #[serde(default, deserialize_with = "services")]
+    pub services: Option<Vec<Color>>,
+}
+
+impl ThemeColors {
+    /// The base theme with these colors in place of its own
+    ///
+    /// Fails when the base isn't a built-in theme.
+    pub fn theme(&self) -> Result<Theme, String> {
+        let base = self.base.as_ref().map_or("dark", |b| b.get_ref().as_str());
+        let mut theme = Theme::built_in(base).ok_or_else(|| {
+            format!(
+                "no built-in theme named {:?}, expected one of {}",
+                base,
+                BUILT_IN.join(", ")
+            )
+        })?;
+        let slots = [
+            (&mut theme.text, self.text),
+            (&mut theme.label, self.label),
+            (&mut theme.muted, self.muted),
+            (&mut theme.dim, self.dim),
+            (&mut theme.border, self.border),
+            (&mut theme.selection, self.selection),
+            (&mut theme.track, self.track),
+            (&mut theme.on_accent, self.on_accent),
+            (&mut theme.brand, self.brand),
+            (&mut theme.red, self.red),
+            (&mut theme.orange, self.orange),
+            (&mut theme.yellow, self.yellow),
+            (&mut theme.green, self.green),
+            (&mut theme.teal, self.teal),
+            (&mut theme.cyan, self.cyan),
+            (&mut theme.blue, self.blue),
+            (&mut theme.purple, self.purple),
+            (&mut theme.lavender, self.lavender),
+            (&mut theme.pink, self.pink),
+        ];
+        for (slot, color) in slots {
+            if let Some(color) = color {
+                *slot = color;
+            }
+        }
+        if let Some(services) = &self.services {
+            theme.services = services.clone();
+        }
+        Ok(theme)
+    }
+}
+
+fn services<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<Color>>, D::Error> {
+    let services = Vec::<Color>::deserialize(deserializer)?;
+    if services.is_empty() {
+        return Err(de::Error::custom("services needs at least one color"));
+    }
+    Ok(Some(services))
+}
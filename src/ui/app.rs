@@ -1,40 +1,195 @@
 //! Application state and TUI rendering
 
-use crate::aws::{CostData, CostExplorerClient};
+use crate::analysis::{
+    AccountsReport, Allocation, AllocationRules, Anomalies, CostComparison, CoverageRow, Detector, Forecast,
+    Forecaster, LocalBudgets, ServiceChange, TagCoverage, TagSpend, CHANGE_EPSILON,
+};
+use crate::aws::concurrency::timed;
+use crate::aws::{Budget, BudgetStatus, CostData, Dimension, GroupBy, Metric, MonthlyTrend, Profile, TrendGap, RESOURCE_DAYS};
+use crate::config::{Settings, Thresholds};
+use crate::keymap::{Action, Key, Keymap, Lookup};
+use crate::source::CostSource;
+use crate::text;
+use crate::theme::{self, Theme};
 use anyhow::Result;
+use chrono::NaiveDate;
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    clipboard::CopyToClipboard,
+    cursor::Show,
+    event::{
+        DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
     widgets::{
-        Bar, BarChart, BarGroup, Block, Borders, Cell, Padding, Paragraph, Row, Table, Tabs,
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Clear, Dataset, Gauge,
+        GraphType, LineGauge, Padding, Paragraph, Row, Table, Tabs, Wrap,
     },
     Frame, Terminal,
 };
-use std::collections::HashMap;
+use futures::StreamExt;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+/// Number of tabs in the views bar, not counting Teams and Accounts
+const TAB_COUNT: usize = 4;
+
+/// Index of the Teams tab, shown when allocation rules are loaded
+const TEAMS_TAB: usize = 4;
+
+/// Background queries feeding the views
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Query {
+    CurrentMonth,
+    PreviousMonth,
+    Trend,
+    Resources,
+    TagKeys,
+    TagSpend,
+    Coverage,
+    Teams,
+    Anomalies,
+    Forecast,
+    Budgets,
+    LocalBudgets,
+    Accounts,
+}
+
+/// Result of a background query, delivered to the event loop
+enum DataUpdate {
+    CurrentMonth(Result<CostData>),
+    PreviousMonth(Result<CostData>),
+    Trend(Result<MonthlyTrend>),
+    /// Resources of the named service
+    Resources(String, Result<CostData>),
+    TagKeys(Result<Vec<String>>),
+    /// Spend by value of the named tag key
+    TagSpend(String, Result<CostData>),
+    /// Tag coverage of the named period
+    Coverage(String, Result<TagCoverage>),
+    /// Spend per team over the current month
+    Teams(Result<Allocation>),
+    /// Daily spikes over the current month
+    Anomalies(Result<Anomalies>),
+    Forecast(Result<Forecast>),
+    Budgets(Result<Vec<Budget>>),
+    /// Budgets from the config, evaluated for the current periods
+    LocalBudgets(Result<Vec<Budget>>),
+    /// Current month of each account, for sources spanning several
+    Accounts(Result<AccountsReport>),
+}
+
+/// A query result and the profile it was loaded for
+type Tagged = (String, DataUpdate);
+
+/// Sends query results back to the event loop, marked with their profile
+#[derive(Clone)]
+struct Updates {
+    profile: String,
+    tx: UnboundedSender<Tagged>,
+}
+
+impl Updates {
+    /// Whether the event loop is still there to receive `update`
+    fn send(&self, update: DataUpdate) -> bool {
+        self.tx.send((self.profile.clone(), update)).is_ok()
+    }
+}
+
+/// Builds the source for a profile picked in the profile switcher
+type Connect = Box<dyn Fn(&str) -> Result<Arc<dyn CostSource>>>;
+
+/// Everything loaded for one profile, kept while another one is shown
+#[derive(Default)]
+struct Loaded {
+    current_month: Option<CostData>,
+    previous_month: Option<CostData>,
+    monthly_trend: Vec<CostData>,
+    trend_gaps: Vec<TrendGap>,
+    error: Option<String>,
+    pending: HashSet<Query>,
+    source_name: String,
+    loader: Option<(Arc<dyn CostSource>, Updates)>,
+    allocation: Option<Allocation>,
+    allocation_error: Option<String>,
+    teams_exported: Option<String>,
+    anomalies: Option<Anomalies>,
+    anomalies_error: Option<String>,
+    forecast: Option<Forecast>,
+    budgets: Option<Vec<Budget>>,
+    budgets_error: Option<String>,
+    local_budgets: Option<Vec<Budget>>,
+    local_budgets_error: Option<String>,
+    account_names: Vec<String>,
+    accounts: Option<AccountsReport>,
+    accounts_error: Option<String>,
+}
+
+/// Profile switcher popup
+struct ProfileSwitcher {
+    /// Highlighted row
+    selected: usize,
+    /// Why the last profile picked couldn't be connected to
+    error: Option<String>,
+}
 
-/// Service colors for consistent coloring across views
-const SERVICE_COLORS: [Color; 12] = [
-    Color::Rgb(255, 107, 107),  // Coral Red
-    Color::Rgb(78, 205, 196),   // Turquoise
-    Color::Rgb(255, 230, 109),  // Yellow
-    Color::Rgb(170, 128, 255),  // Purple
-    Color::Rgb(255, 159, 243),  // Pink
-    Color::Rgb(108, 255, 108),  // Lime Green
-    Color::Rgb(255, 184, 77),   // Orange
-    Color::Rgb(77, 182, 255),   // Sky Blue
-    Color::Rgb(255, 138, 101),  // Salmon
-    Color::Rgb(129, 236, 236),  // Cyan
-    Color::Rgb(162, 155, 254),  // Lavender
-    Color::Rgb(0, 184, 148),    // Teal
-];
+/// Resource-level drill-down into one service of a month view
+struct ResourceView {
+    service: String,
+    /// Period of the month view it was opened from
+    period: String,
+    /// Range asked for; sources may return less (Cost Explorer only keeps
+    /// the last 14 days)
+    requested: (NaiveDate, NaiveDate),
+    data: Option<CostData>,
+    error: Option<String>,
+    /// Service row to go back to
+    return_row: usize,
+    /// Resource ID last copied to the clipboard
+    copied: Option<String>,
+}
+
+/// Tag explorer popup: pick a tag key, then see spend by its values
+struct TagExplorer {
+    /// Month the spend covers
+    period: String,
+    range: (NaiveDate, NaiveDate),
+    keys: Option<Vec<String>>,
+    /// Highlighted key in the list
+    selected_key: usize,
+    /// Key whose values are shown
+    key: Option<String>,
+    spend: Option<TagSpend>,
+    /// Highlighted value row
+    selected_value: usize,
+    error: Option<String>,
+}
+
+/// Tag coverage popup: required tag keys by service or account
+struct CoverageView {
+    /// Month the report covers
+    period: String,
+    report: Option<TagCoverage>,
+    /// Rank accounts instead of services
+    accounts: bool,
+    /// Highlighted row
+    selected: usize,
+    error: Option<String>,
+    /// Outcome of the last CSV export
+    exported: Option<String>,
+}
 
 /// Application state
 pub struct App {
@@ -42,20 +197,109 @@ pub struct App {
     current_month: Option<CostData>,
     /// Previous month costs
     previous_month: Option<CostData>,
-    /// Monthly trend data (last 6 months)
+    /// Monthly trend data (last `settings.months` months)
     monthly_trend: Vec<CostData>,
+    /// Trend months that are missing or incomplete
+    trend_gaps: Vec<TrendGap>,
     /// Selected tab index
     selected_tab: usize,
     /// Selected row in the table
     selected_row: usize,
+    /// Prorate the baseline in the compare view
+    prorate: bool,
     /// Error message if any
     error: Option<String>,
-    /// Loading state
-    loading: bool,
+    /// Queries that haven't reported back yet
+    pending: HashSet<Query>,
+    /// Description of where the data comes from
+    source_name: String,
+    /// Resources of the selected service, while drilled down
+    resources: Option<ResourceView>,
+    /// Source and channel for queries started from the UI
+    loader: Option<(Arc<dyn CostSource>, Updates)>,
+    /// Text waiting to be sent to the terminal's clipboard
+    clipboard: Option<String>,
+    /// Tag explorer popup, while open
+    tags: Option<TagExplorer>,
+    /// Tag keys the coverage report checks for
+    required_tags: Vec<String>,
+    /// Tag coverage popup, while open
+    coverage: Option<CoverageView>,
+    /// Showback rules behind the Teams tab
+    allocation_rules: Option<AllocationRules>,
+    /// Current month's spend per team
+    allocation: Option<Allocation>,
+    allocation_error: Option<String>,
+    /// Outcome of the last team CSV export
+    teams_exported: Option<String>,
+    /// Settings for flagging daily spikes
+    detector: Detector,
+    /// Current month's daily spikes
+    anomalies: Option<Anomalies>,
+    anomalies_error: Option<String>,
+    /// Highlighted row of the anomalies popup, while open
+    anomaly_panel: Option<usize>,
+    /// Settings for projecting costs
+    forecaster: Forecaster,
+    /// Current month's month-end and quarter-end projections
+    forecast: Option<Forecast>,
+    /// AWS Budgets with their recent history
+    budgets: Option<Vec<Budget>>,
+    budgets_error: Option<String>,
+    /// Budgets from the config, tracked against fetched costs
+    local_budget_rules: Option<LocalBudgets>,
+    local_budgets: Option<Vec<Budget>>,
+    local_budgets_error: Option<String>,
+    /// First budget shown in the budgets popup, while open
+    budget_panel: Option<usize>,
+    /// Standalone accounts behind the source; an Accounts tab when not empty
+    account_names: Vec<String>,
+    /// Current month side by side per account
+    accounts: Option<AccountsReport>,
+    accounts_error: Option<String>,
+    /// Profile whose data is shown
+    profile: String,
+    /// Profiles offered by the profile switcher
+    profiles: Vec<Profile>,
+    /// Connects to a profile picked in the switcher; no switcher without it
+    connect: Option<Connect>,
+    /// Data of the other profiles loaded so far, to switch back instantly
+    cached: BTreeMap<String, Loaded>,
+    /// Profile switcher popup, while open
+    switcher: Option<ProfileSwitcher>,
+    /// Trend range, top-N, cost colors and grouping from the config
+    settings: Settings,
+    /// Keys bound to each action
+    keymap: Keymap,
+    /// Colors of every view
+    theme: Theme<Color>,
+    /// Keys of a chord pressed so far
+    chord: Vec<Key>,
+    /// Help overlay (`?`), while open
+    help: bool,
+    /// Last position of the mouse pointer, for the trend chart's tooltip
+    pointer: Option<(u16, u16)>,
+    /// Clickable areas of the last frame, recorded while rendering
+    hits: RefCell<Hits>,
     /// Should quit
     should_quit: bool,
 }
 
+/// Where the last frame drew what a click can pick
+#[derive(Default)]
+struct Hits {
+    /// Line of the views bar, and the columns of each tab on it
+    tabs: Option<(u16, Vec<Range<u16>>)>,
+    /// Rows of the main table, and the index of the first one shown
+    rows: Option<(Rect, usize)>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl App {
     /// Create a new app
     pub fn new() -> Self {
@@ -63,471 +307,3156 @@ impl App {
             current_month: None,
             previous_month: None,
             monthly_trend: Vec::new(),
+            trend_gaps: Vec::new(),
             selected_tab: 0,
             selected_row: 0,
+            prorate: false,
             error: None,
-            loading: true,
+            pending: HashSet::new(),
+            source_name: String::new(),
+            resources: None,
+            loader: None,
+            clipboard: None,
+            tags: None,
+            required_tags: Vec::new(),
+            coverage: None,
+            allocation_rules: None,
+            allocation: None,
+            allocation_error: None,
+            teams_exported: None,
+            detector: Detector::default(),
+            anomalies: None,
+            anomalies_error: None,
+            anomaly_panel: None,
+            forecaster: Forecaster::default(),
+            forecast: None,
+            budgets: None,
+            budgets_error: None,
+            local_budget_rules: None,
+            local_budgets: None,
+            local_budgets_error: None,
+            budget_panel: None,
+            account_names: Vec::new(),
+            accounts: None,
+            accounts_error: None,
+            profile: String::new(),
+            profiles: Vec::new(),
+            connect: None,
+            cached: BTreeMap::new(),
+            switcher: None,
+            settings: Settings::default(),
+            keymap: Keymap::default(),
+            theme: Theme::default().map(color_of),
+            chord: Vec::new(),
+            help: false,
+            pointer: None,
+            hits: RefCell::default(),
             should_quit: false,
         }
     }
 
-    /// Load data from AWS
-    pub fn load_data(&mut self, client: &CostExplorerClient) {
-        self.loading = true;
+    /// Use `settings` from the config instead of the defaults: the trend's
+    /// months and services, cost colors, and what the month views list
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Bind keys with `keymap` instead of the defaults
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Draw with `theme` instead of the dark one, already brought down to
+    /// the terminal's colors
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme.map(color_of);
+        self
+    }
+
+    /// Tag keys the coverage report (`c`) checks for
+    pub fn with_required_tags(mut self, keys: Vec<String>) -> Self {
+        self.required_tags = keys;
+        self
+    }
+
+    /// Split the current month across teams with `rules`, in a Teams tab
+    pub fn with_allocation_rules(mut self, rules: AllocationRules) -> Self {
+        self.allocation_rules = Some(rules);
+        self
+    }
+
+    /// Track `budgets` from the config beside AWS Budgets
+    pub fn with_local_budgets(mut self, budgets: LocalBudgets) -> Self {
+        self.local_budget_rules = Some(budgets);
+        self
+    }
+
+    /// Flag daily spikes with `detector` instead of the default settings
+    pub fn with_anomaly_detector(mut self, detector: Detector) -> Self {
+        self.detector = detector;
+        self
+    }
+
+    /// Project costs with `forecaster` instead of the default settings
+    pub fn with_forecaster(mut self, forecaster: Forecaster) -> Self {
+        self.forecaster = forecaster;
+        self
+    }
+
+    /// Offer `profiles` in a profile switcher (`p`), starting on `current`;
+    /// `connect` builds the source of a profile when it is first picked
+    pub fn with_profiles(
+        mut self,
+        current: impl Into<String>,
+        profiles: Vec<Profile>,
+        connect: impl Fn(&str) -> Result<Arc<dyn CostSource>> + 'static,
+    ) -> Self {
+        self.profile = current.into();
+        self.profiles = profiles;
+        self.connect = Some(Box::new(connect));
+        self
+    }
+
+    /// Number of tabs in the views bar
+    fn tab_count(&self) -> usize {
+        TAB_COUNT + usize::from(self.allocation_rules.is_some()) + usize::from(!self.account_names.is_empty())
+    }
+
+    /// Whether the Teams tab is selected
+    fn on_teams_tab(&self) -> bool {
+        self.allocation_rules.is_some() && self.selected_tab == TEAMS_TAB
+    }
+
+    /// Whether the Accounts tab, after Teams if that is shown, is selected
+    fn on_accounts_tab(&self) -> bool {
+        !self.account_names.is_empty() && self.selected_tab == TAB_COUNT + usize::from(self.allocation_rules.is_some())
+    }
+
+    /// Start loading data in the background
+    ///
+    /// Each query runs as its own task and reports back over `updates`; for
+    /// the live source the client's request limiter keeps the number of
+    /// in-flight API calls within Cost Explorer's throttling limits.
+    fn spawn_loaders(&mut self, source: Arc<dyn CostSource>, updates: Updates) {
         self.error = None;
+        self.source_name = source.name();
+        self.set_loading();
+        self.loader = Some((source.clone(), updates.clone()));
+        self.account_names = source.accounts();
+        self.spawn_budgets();
 
-        // Load current month
-        match client.get_current_month_costs() {
-            Ok(data) => self.current_month = Some(data),
-            Err(e) => {
-                self.error = Some(format!("Failed to load current month: {}", e));
-                self.loading = false;
-                return;
-            }
-        }
+        let (s, tx) = (source.clone(), updates.clone());
+        tokio::spawn(async move {
+            let data = timed("current month", s.current_month()).await;
+            let _ = tx.send(DataUpdate::CurrentMonth(data));
+        });
 
-        // Load previous month
-        match client.get_previous_month_costs() {
-            Ok(data) => self.previous_month = Some(data),
-            Err(e) => {
-                // Non-fatal, just log
-                tracing::warn!("Failed to load previous month: {}", e);
-            }
-        }
+        let (s, tx) = (source.clone(), updates.clone());
+        tokio::spawn(async move {
+            let data = timed("previous month", s.previous_month()).await;
+            let _ = tx.send(DataUpdate::PreviousMonth(data));
+        });
 
-        // Load monthly trend (last 6 months)
-        match client.get_monthly_trend(6) {
-            Ok(data) => self.monthly_trend = data,
-            Err(e) => {
-                tracing::warn!("Failed to load monthly trend: {}", e);
-            }
+        let months = self.settings.months;
+        tokio::spawn(async move {
+            let trend = timed("monthly trend", source.monthly_trend(months)).await;
+            let _ = updates.send(DataUpdate::Trend(trend));
+        });
+    }
+
+    /// Apply a finished query to the profile it was loaded for, whether it
+    /// is shown or cached
+    fn apply_tagged(&mut self, (profile, update): Tagged) {
+        if profile == self.profile {
+            return self.apply_update(update);
+        }
+        // Popups belong to the profile on screen
+        if matches!(
+            update,
+            DataUpdate::Resources(..) | DataUpdate::TagKeys(_) | DataUpdate::TagSpend(..) | DataUpdate::Coverage(..)
+        ) {
+            return;
         }
+        let Some(mut loaded) = self.cached.remove(&profile) else {
+            return;
+        };
+        self.swap_loaded(&mut loaded);
+        self.apply_update(update);
+        self.swap_loaded(&mut loaded);
+        self.cached.insert(profile, loaded);
+    }
 
-        self.loading = false;
+    /// Exchange the data on screen with `loaded`
+    fn swap_loaded(&mut self, loaded: &mut Loaded) {
+        use std::mem::swap;
+        swap(&mut self.current_month, &mut loaded.current_month);
+        swap(&mut self.previous_month, &mut loaded.previous_month);
+        swap(&mut self.monthly_trend, &mut loaded.monthly_trend);
+        swap(&mut self.trend_gaps, &mut loaded.trend_gaps);
+        swap(&mut self.error, &mut loaded.error);
+        swap(&mut self.pending, &mut loaded.pending);
+        swap(&mut self.source_name, &mut loaded.source_name);
+        swap(&mut self.loader, &mut loaded.loader);
+        swap(&mut self.allocation, &mut loaded.allocation);
+        swap(&mut self.allocation_error, &mut loaded.allocation_error);
+        swap(&mut self.teams_exported, &mut loaded.teams_exported);
+        swap(&mut self.anomalies, &mut loaded.anomalies);
+        swap(&mut self.anomalies_error, &mut loaded.anomalies_error);
+        swap(&mut self.forecast, &mut loaded.forecast);
+        swap(&mut self.budgets, &mut loaded.budgets);
+        swap(&mut self.budgets_error, &mut loaded.budgets_error);
+        swap(&mut self.local_budgets, &mut loaded.local_budgets);
+        swap(&mut self.local_budgets_error, &mut loaded.local_budgets_error);
+        swap(&mut self.account_names, &mut loaded.account_names);
+        swap(&mut self.accounts, &mut loaded.accounts);
+        swap(&mut self.accounts_error, &mut loaded.accounts_error);
     }
 
-    /// Handle keyboard input
-    fn handle_input(&mut self) -> Result<()> {
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-                        KeyCode::Tab | KeyCode::Right => {
-                            self.selected_tab = (self.selected_tab + 1) % 3;
-                            self.selected_row = 0;
-                        }
-                        KeyCode::BackTab | KeyCode::Left => {
-                            self.selected_tab = if self.selected_tab == 0 {
-                                2
-                            } else {
-                                self.selected_tab - 1
-                            };
-                            self.selected_row = 0;
-                        }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            let max_rows = self.get_current_breakdown_len();
-                            if self.selected_row < max_rows.saturating_sub(1) {
-                                self.selected_row += 1;
-                            }
-                        }
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if self.selected_row > 0 {
-                                self.selected_row -= 1;
-                            }
-                        }
-                        KeyCode::Home | KeyCode::Char('g') => {
-                            self.selected_row = 0;
-                        }
-                        KeyCode::End | KeyCode::Char('G') => {
-                            self.selected_row = self.get_current_breakdown_len().saturating_sub(1);
+    /// Apply a finished query to the app state
+    fn apply_update(&mut self, update: DataUpdate) {
+        match update {
+            DataUpdate::CurrentMonth(result) => {
+                self.pending.remove(&Query::CurrentMonth);
+                match result {
+                    Ok(data) => {
+                        self.spawn_allocation(data.start, data.end);
+                        self.spawn_anomalies(data.start, data.end);
+                        self.spawn_forecast(data.end - chrono::Duration::days(1));
+                        self.spawn_local_budgets(data.end - chrono::Duration::days(1));
+                        self.spawn_accounts(data.start, data.end);
+                        self.current_month = Some(data);
+                    }
+                    Err(e) => self.error = Some(format!("Failed to load current month: {}", e)),
+                }
+            }
+            DataUpdate::PreviousMonth(result) => {
+                self.pending.remove(&Query::PreviousMonth);
+                match result {
+                    Ok(data) => self.previous_month = Some(data),
+                    // Non-fatal, just log
+                    Err(e) => tracing::warn!("Failed to load previous month: {}", e),
+                }
+            }
+            DataUpdate::Trend(result) => {
+                self.pending.remove(&Query::Trend);
+                match result {
+                    Ok(trend) => {
+                        self.monthly_trend = trend.months;
+                        self.trend_gaps = trend.gaps;
+                    }
+                    Err(e) => tracing::warn!("Failed to load monthly trend: {}", e),
+                }
+            }
+            DataUpdate::Resources(service, result) => {
+                // Ignore results for a drill-down that was since closed
+                let Some(view) = self.resources.as_mut().filter(|v| v.service == service) else {
+                    return;
+                };
+                self.pending.remove(&Query::Resources);
+                match result {
+                    Ok(data) => view.data = Some(data),
+                    Err(e) => view.error = Some(format!("{:#}", e)),
+                }
+            }
+            DataUpdate::TagKeys(result) => {
+                let Some(explorer) = self.tags.as_mut() else {
+                    return;
+                };
+                self.pending.remove(&Query::TagKeys);
+                match result {
+                    Ok(keys) => explorer.keys = Some(keys),
+                    Err(e) => explorer.error = Some(format!("{:#}", e)),
+                }
+            }
+            DataUpdate::TagSpend(key, result) => {
+                // Ignore results for a key that is no longer shown
+                let Some(explorer) = self
+                    .tags
+                    .as_mut()
+                    .filter(|t| t.key.as_deref() == Some(key.as_str()))
+                else {
+                    return;
+                };
+                self.pending.remove(&Query::TagSpend);
+                match result {
+                    Ok(data) => explorer.spend = Some(TagSpend::new(&key, &data)),
+                    Err(e) => explorer.error = Some(format!("{:#}", e)),
+                }
+            }
+            DataUpdate::Coverage(period, result) => {
+                let Some(view) = self.coverage.as_mut().filter(|v| v.period == period) else {
+                    return;
+                };
+                self.pending.remove(&Query::Coverage);
+                match result {
+                    Ok(report) => view.report = Some(report),
+                    Err(e) => view.error = Some(format!("{:#}", e)),
+                }
+            }
+            DataUpdate::Teams(result) => {
+                self.pending.remove(&Query::Teams);
+                match result {
+                    Ok(allocation) => {
+                        self.allocation = Some(allocation);
+                        self.allocation_error = None;
+                    }
+                    Err(e) => self.allocation_error = Some(format!("{:#}", e)),
+                }
+            }
+            DataUpdate::Anomalies(result) => {
+                self.pending.remove(&Query::Anomalies);
+                match result {
+                    Ok(anomalies) => {
+                        self.anomalies = Some(anomalies);
+                        self.anomalies_error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to detect anomalies: {:#}", e);
+                        self.anomalies = None;
+                        self.anomalies_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            DataUpdate::Forecast(result) => {
+                self.pending.remove(&Query::Forecast);
+                match result {
+                    Ok(forecast) => self.forecast = Some(forecast),
+                    Err(e) => {
+                        // Non-fatal, the summary just goes without it
+                        tracing::warn!("Failed to forecast costs: {:#}", e);
+                        self.forecast = None;
+                    }
+                }
+            }
+            DataUpdate::Budgets(result) => {
+                self.pending.remove(&Query::Budgets);
+                match result {
+                    Ok(budgets) => {
+                        self.budgets = Some(budgets);
+                        self.budgets_error = None;
+                    }
+                    Err(e) => {
+                        // Non-fatal, most sources have no budgets
+                        tracing::info!("No budgets: {:#}", e);
+                        self.budgets = None;
+                        self.budgets_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            DataUpdate::Accounts(result) => {
+                self.pending.remove(&Query::Accounts);
+                match result {
+                    Ok(report) => {
+                        if self.account_names.is_empty() {
+                            self.account_names = report.accounts.iter().map(|a| a.account.clone()).collect();
                         }
-                        _ => {}
+                        self.accounts = Some(report);
+                        self.accounts_error = None;
+                    }
+                    Err(e) => {
+                        self.accounts = None;
+                        self.accounts_error = Some(format!("Failed to load accounts: {:#}", e));
+                    }
+                }
+            }
+            DataUpdate::LocalBudgets(result) => {
+                self.pending.remove(&Query::LocalBudgets);
+                match result {
+                    Ok(budgets) => {
+                        self.local_budgets = Some(budgets);
+                        self.local_budgets_error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to evaluate local budgets: {:#}", e);
+                        self.local_budgets = None;
+                        self.local_budgets_error = Some(format!("{:#}", e));
                     }
                 }
             }
         }
-        Ok(())
     }
 
-    fn get_current_breakdown_len(&self) -> usize {
-        match self.selected_tab {
-            0 => self.current_month.as_ref().map(|d| d.breakdown.len()).unwrap_or(0),
-            1 => self.previous_month.as_ref().map(|d| d.breakdown.len()).unwrap_or(0),
-            2 => self.get_top_services_across_months().len(),
-            _ => 0,
-        }
+    /// Mark every view as waiting for data
+    pub fn set_loading(&mut self) {
+        self.pending = HashSet::from([Query::CurrentMonth, Query::PreviousMonth, Query::Trend]);
     }
 
-    /// Get top services across all months for trend view
-    fn get_top_services_across_months(&self) -> Vec<String> {
-        let mut service_totals: HashMap<String, f64> = HashMap::new();
-        
-        for month in &self.monthly_trend {
-            for service in &month.breakdown {
-                *service_totals.entry(service.service.clone()).or_default() += service.cost;
-            }
+    /// Supply the result of the current month query
+    pub fn set_current_month(&mut self, result: Result<CostData>) {
+        self.apply_update(DataUpdate::CurrentMonth(result));
+    }
+
+    /// Supply the result of the previous month query
+    pub fn set_previous_month(&mut self, result: Result<CostData>) {
+        self.apply_update(DataUpdate::PreviousMonth(result));
+    }
+
+    /// Supply the result of the monthly trend query
+    pub fn set_trend(&mut self, result: Result<MonthlyTrend>) {
+        self.apply_update(DataUpdate::Trend(result));
+    }
+
+    /// Supply the result of the resource query for the open drill-down
+    pub fn set_resources(&mut self, result: Result<CostData>) {
+        if let Some(service) = self.resources.as_ref().map(|v| v.service.clone()) {
+            self.apply_update(DataUpdate::Resources(service, result));
         }
-        
-        let mut services: Vec<_> = service_totals.into_iter().collect();
-        services.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        services.into_iter().take(8).map(|(name, _)| name).collect()
     }
 
-    /// Run the TUI
-    pub fn run(&mut self) -> Result<()> {
-        // Setup terminal
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+    /// Supply the result of the tag key query for the open tag explorer
+    pub fn set_tag_keys(&mut self, result: Result<Vec<String>>) {
+        self.apply_update(DataUpdate::TagKeys(result));
+    }
 
-        // Main loop
-        while !self.should_quit {
-            terminal.draw(|f| self.render(f))?;
-            self.handle_input()?;
+    /// Supply the spend of the tag key picked in the tag explorer
+    pub fn set_tag_spend(&mut self, result: Result<CostData>) {
+        if let Some(key) = self.tags.as_ref().and_then(|t| t.key.clone()) {
+            self.apply_update(DataUpdate::TagSpend(key, result));
         }
+    }
 
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-        terminal.show_cursor()?;
+    /// Supply the report for the open tag coverage popup
+    pub fn set_tag_coverage(&mut self, result: Result<TagCoverage>) {
+        if let Some(period) = self.coverage.as_ref().map(|v| v.period.clone()) {
+            self.apply_update(DataUpdate::Coverage(period, result));
+        }
+    }
 
-        Ok(())
+    /// Supply the current month's spend per team
+    pub fn set_allocation(&mut self, result: Result<Allocation>) {
+        self.apply_update(DataUpdate::Teams(result));
     }
 
-    /// Render the UI
-    fn render(&self, frame: &mut Frame) {
-        let area = frame.area();
+    /// Supply the current month's daily spikes
+    pub fn set_anomalies(&mut self, result: Result<Anomalies>) {
+        self.apply_update(DataUpdate::Anomalies(result));
+    }
 
-        // Main layout: header, tabs, content, footer
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .margin(1)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Length(3), // Tabs
-                Constraint::Min(10),   // Content
-                Constraint::Length(3), // Footer
-            ])
-            .split(area);
+    /// Supply the current month's month-end and quarter-end projections
+    pub fn set_forecast(&mut self, result: Result<Forecast>) {
+        self.apply_update(DataUpdate::Forecast(result));
+    }
 
-        // Header
-        self.render_header(frame, chunks[0]);
+    /// Supply the budgets and their history
+    pub fn set_budgets(&mut self, result: Result<Vec<Budget>>) {
+        self.apply_update(DataUpdate::Budgets(result));
+    }
 
-        // Tabs
-        self.render_tabs(frame, chunks[1]);
+    /// Supply the current month of each account; adds the Accounts tab
+    pub fn set_accounts(&mut self, result: Result<AccountsReport>) {
+        self.apply_update(DataUpdate::Accounts(result));
+    }
 
-        // Content based on selected tab
+    /// Supply the budgets from the config, evaluated for the current periods
+    pub fn set_local_budgets(&mut self, result: Result<Vec<Budget>>) {
+        self.apply_update(DataUpdate::LocalBudgets(result));
+    }
+
+    /// Set the data source description shown in the header
+    pub fn set_source_name(&mut self, name: impl Into<String>) {
+        self.source_name = name.into();
+    }
+
+    /// Switch to a view by index, wrapping around the tab count
+    pub fn select_tab(&mut self, tab: usize) {
+        self.close_resources();
+        self.selected_tab = tab % self.tab_count();
+        self.selected_row = 0;
+    }
+
+    /// The month shown in the current tab, if it is a month view
+    fn month_view(&self) -> Option<&CostData> {
         match self.selected_tab {
-            0 => self.render_current_month(frame, chunks[2]),
-            1 => self.render_previous_month(frame, chunks[2]),
-            2 => self.render_trend(frame, chunks[2]),
-            _ => {}
+            0 => self.current_month.as_ref(),
+            1 => self.previous_month.as_ref(),
+            _ => None,
         }
-
-        // Footer
-        self.render_footer(frame, chunks[3]);
     }
 
-    fn render_header(&self, frame: &mut Frame, area: Rect) {
-        let title = Paragraph::new(vec![Line::from(vec![
-            Span::styled("☁️  ", Style::default()),
-            Span::styled("AWS", Style::default().fg(Color::Rgb(255, 153, 0)).bold()),
-            Span::styled(" Cost Explorer ", Style::default().fg(Color::White).bold()),
-            Span::styled("TUI", Style::default().fg(Color::Rgb(78, 205, 196)).bold()),
-        ])])
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(255, 153, 0))),
-        );
-        frame.render_widget(title, area);
+    /// Drill into the resources of the selected service
+    fn open_resources(&mut self) {
+        let Some(data) = self.month_view() else {
+            return;
+        };
+        let Some(row) = data.breakdown.get(self.selected_row) else {
+            return;
+        };
+        let (service, start, end) = (row.service.clone(), data.start, data.end);
+
+        self.resources = Some(ResourceView {
+            service: service.clone(),
+            period: data.period.clone(),
+            requested: (start, end),
+            data: None,
+            error: None,
+            return_row: self.selected_row,
+            copied: None,
+        });
+        self.selected_row = 0;
+        self.pending.insert(Query::Resources);
+
+        if let Some((source, updates)) = &self.loader {
+            let (source, updates) = (source.clone(), updates.clone());
+            tokio::spawn(async move {
+                let data = timed("resources", source.resources(&service, start, end)).await;
+                let _ = updates.send(DataUpdate::Resources(service, data));
+            });
+        }
     }
 
-    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
-        let titles = vec![
-            Line::from(vec![
-                Span::styled("📅 ", Style::default()),
-                Span::styled("Current Month", Style::default().fg(Color::Rgb(108, 255, 108))),
-            ]),
-            Line::from(vec![
-                Span::styled("📆 ", Style::default()),
-                Span::styled("Previous Month", Style::default().fg(Color::Rgb(170, 128, 255))),
-            ]),
-            Line::from(vec![
-                Span::styled("📊 ", Style::default()),
-                Span::styled("6-Month Trend", Style::default().fg(Color::Rgb(255, 184, 77))),
-            ]),
-        ];
-        let tabs = Tabs::new(titles)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Rgb(100, 100, 100)))
-                    .title(Span::styled(" Views ", Style::default().fg(Color::White).bold())),
-            )
-            .select(self.selected_tab)
-            .style(Style::default().fg(Color::DarkGray))
-            .highlight_style(
-                Style::default()
-                    .fg(Color::White)
-                    .add_modifier(Modifier::BOLD)
-                    .add_modifier(Modifier::UNDERLINED),
-            )
-            .divider(Span::styled(" │ ", Style::default().fg(Color::DarkGray)));
-        frame.render_widget(tabs, area);
+    /// The resource under the cursor in the drill-down
+    fn selected_resource(&self) -> Option<&str> {
+        let data = self.resources.as_ref()?.data.as_ref()?;
+        Some(data.breakdown.get(self.selected_row)?.service.as_str())
     }
 
-    fn render_current_month(&self, frame: &mut Frame, area: Rect) {
-        if self.loading {
-            self.render_loading(frame, area);
+    /// Queue the selected resource ID for the clipboard
+    fn copy_selected_resource(&mut self) {
+        let Some(id) = self.selected_resource().map(str::to_string) else {
             return;
+        };
+        self.clipboard = Some(id.clone());
+        if let Some(view) = &mut self.resources {
+            view.copied = Some(id);
         }
+    }
 
-        if let Some(ref error) = self.error {
-            self.render_error(frame, area, error);
+    /// Leave the resource drill-down, back to the service it came from
+    fn close_resources(&mut self) {
+        if let Some(view) = self.resources.take() {
+            self.pending.remove(&Query::Resources);
+            self.selected_row = view.return_row;
+        }
+    }
+
+    /// Open the tag explorer for the month in view (the current month on
+    /// other tabs)
+    fn open_tag_explorer(&mut self) {
+        let Some(data) = self.month_view().or(self.current_month.as_ref()) else {
             return;
+        };
+        let (start, end) = (data.start, data.end);
+        self.tags = Some(TagExplorer {
+            period: data.period.clone(),
+            range: (start, end),
+            keys: None,
+            selected_key: 0,
+            key: None,
+            spend: None,
+            selected_value: 0,
+            error: None,
+        });
+        self.pending.insert(Query::TagKeys);
+
+        if let Some((source, updates)) = &self.loader {
+            let (source, updates) = (source.clone(), updates.clone());
+            tokio::spawn(async move {
+                let keys = timed("tag keys", source.tag_keys(start, end)).await;
+                let _ = updates.send(DataUpdate::TagKeys(keys));
+            });
         }
+    }
 
-        if let Some(ref data) = self.current_month {
-            self.render_cost_breakdown(frame, area, data, Color::Rgb(108, 255, 108));
-        } else {
-            self.render_no_data(frame, area);
+    /// Show spend by value of the highlighted tag key
+    fn pick_tag_key(&mut self) {
+        let Some(explorer) = &mut self.tags else {
+            return;
+        };
+        let Some(key) = explorer
+            .keys
+            .as_ref()
+            .and_then(|keys| keys.get(explorer.selected_key))
+            .cloned()
+        else {
+            return;
+        };
+        let (start, end) = explorer.range;
+        explorer.key = Some(key.clone());
+        explorer.spend = None;
+        explorer.selected_value = 0;
+        explorer.error = None;
+        self.pending.insert(Query::TagSpend);
+
+        if let Some((source, updates)) = &self.loader {
+            let (source, updates) = (source.clone(), updates.clone());
+            tokio::spawn(async move {
+                let group_by = GroupBy::Tag(key.clone());
+                let data = timed("tag spend", source.breakdown(start, end, &group_by)).await;
+                let _ = updates.send(DataUpdate::TagSpend(key, data));
+            });
         }
     }
 
-    fn render_previous_month(&self, frame: &mut Frame, area: Rect) {
-        if let Some(ref data) = self.previous_month {
-            self.render_cost_breakdown(frame, area, data, Color::Rgb(170, 128, 255));
+    /// Back from a key's values to the key list, or close the explorer
+    fn tag_explorer_back(&mut self) {
+        let Some(explorer) = &mut self.tags else {
+            return;
+        };
+        if explorer.key.take().is_some() {
+            explorer.spend = None;
+            explorer.error = None;
+            self.pending.remove(&Query::TagSpend);
         } else {
-            self.render_no_data(frame, area);
+            self.tags = None;
+            self.pending.remove(&Query::TagKeys);
         }
     }
 
-    fn render_trend(&self, frame: &mut Frame, area: Rect) {
-        if self.monthly_trend.is_empty() {
-            self.render_no_data(frame, area);
+    /// Open the tag coverage report for the month in view (the current
+    /// month on other tabs), trending over the loaded months up to it
+    fn open_coverage(&mut self) {
+        let Some(data) = self.month_view().or(self.current_month.as_ref()) else {
+            return;
+        };
+        let (start, end, period) = (data.start, data.end, data.period.clone());
+        let mut months: Vec<(NaiveDate, NaiveDate)> = self
+            .monthly_trend
+            .iter()
+            .filter(|m| m.start < start)
+            .map(|m| (m.start, m.end))
+            .collect();
+        months.push((start, end));
+
+        self.coverage = Some(CoverageView {
+            period: period.clone(),
+            report: None,
+            accounts: false,
+            selected: 0,
+            error: None,
+            exported: None,
+        });
+        if self.required_tags.is_empty() {
             return;
         }
+        self.pending.insert(Query::Coverage);
 
-        // Split into chart and legend
-        let chunks = Layout::default()
+        if let Some((source, updates)) = &self.loader {
+            let (source, updates) = (source.clone(), updates.clone());
+            let keys = self.required_tags.clone();
+            tokio::spawn(async move {
+                let report = timed(
+                    "tag coverage",
+                    TagCoverage::load(source.as_ref(), &keys, start, end, &months),
+                )
+                .await;
+                let _ = updates.send(DataUpdate::Coverage(period, report));
+            });
+        }
+    }
+
+    /// Split the month `[start, end)` across teams, if there are rules
+    fn spawn_allocation(&mut self, start: NaiveDate, end: NaiveDate) {
+        let (Some(rules), Some((source, updates))) = (&self.allocation_rules, &self.loader) else {
+            return;
+        };
+        let (rules, source, updates) = (rules.clone(), source.clone(), updates.clone());
+        self.pending.insert(Query::Teams);
+        tokio::spawn(async move {
+            let allocation = timed("team allocation", Allocation::load(source.as_ref(), &rules, start, end)).await;
+            let _ = updates.send(DataUpdate::Teams(allocation));
+        });
+    }
+
+    /// Look for daily spikes in `[start, end)`
+    fn spawn_anomalies(&mut self, start: NaiveDate, end: NaiveDate) {
+        let Some((source, updates)) = &self.loader else {
+            return;
+        };
+        let (source, updates, detector) = (source.clone(), updates.clone(), self.detector);
+        self.pending.insert(Query::Anomalies);
+        tokio::spawn(async move {
+            let anomalies = timed("anomaly detection", Anomalies::load(source.as_ref(), detector, start, end)).await;
+            let _ = updates.send(DataUpdate::Anomalies(anomalies));
+        });
+    }
+
+    /// Project costs to the end of the month and quarter, from `today` on
+    fn spawn_forecast(&mut self, today: NaiveDate) {
+        let Some((source, updates)) = &self.loader else {
+            return;
+        };
+        let (source, updates, forecaster) = (source.clone(), updates.clone(), self.forecaster);
+        self.pending.insert(Query::Forecast);
+        tokio::spawn(async move {
+            let forecast = timed("forecast", Forecast::load(source.as_ref(), forecaster, today)).await;
+            let _ = updates.send(DataUpdate::Forecast(forecast));
+        });
+    }
+
+    /// Load budgets with as many months of history as the trend view
+    fn spawn_budgets(&mut self) {
+        let Some((source, updates)) = &self.loader else {
+            return;
+        };
+        let (source, updates) = (source.clone(), updates.clone());
+        let months = self.settings.months;
+        self.pending.insert(Query::Budgets);
+        tokio::spawn(async move {
+            let budgets = timed("budgets", source.budgets(months)).await;
+            let _ = updates.send(DataUpdate::Budgets(budgets));
+        });
+    }
+
+    /// Load each account's costs over `[start, end)`, for sources spanning
+    /// several accounts
+    fn spawn_accounts(&mut self, start: NaiveDate, end: NaiveDate) {
+        let Some((source, updates)) = &self.loader else {
+            return;
+        };
+        if self.account_names.is_empty() {
+            return;
+        }
+        let (source, updates) = (source.clone(), updates.clone());
+        self.pending.insert(Query::Accounts);
+        tokio::spawn(async move {
+            let report = timed("accounts", AccountsReport::load(source.as_ref(), start, end)).await;
+            let _ = updates.send(DataUpdate::Accounts(report));
+        });
+    }
+
+    /// Evaluate the budgets from the config for the periods containing `today`
+    fn spawn_local_budgets(&mut self, today: NaiveDate) {
+        let (Some(rules), Some((source, updates))) = (&self.local_budget_rules, &self.loader) else {
+            return;
+        };
+        let (rules, source, updates) = (rules.clone(), source.clone(), updates.clone());
+        self.pending.insert(Query::LocalBudgets);
+        tokio::spawn(async move {
+            let budgets = timed("local budgets", rules.evaluate(source.as_ref(), today)).await;
+            let _ = updates.send(DataUpdate::LocalBudgets(budgets));
+        });
+    }
+
+    /// AWS Budgets followed by the budgets from the config
+    fn all_budgets(&self) -> Vec<&Budget> {
+        self.budgets.iter().chain(&self.local_budgets).flatten().collect()
+    }
+
+    /// Write the team totals to `teams-YYYY-MM.csv` in the working directory
+    fn export_teams(&mut self) {
+        let Some(allocation) = &self.allocation else {
+            return;
+        };
+        let path = format!("teams-{}.csv", allocation.start.format("%Y-%m"));
+        let result = std::fs::File::create(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| allocation.write_csv(file));
+        self.teams_exported = Some(match result {
+            Ok(()) => format!("💾 Saved {}", path),
+            Err(e) => format!("⚠️  Could not write {}: {:#}", path, e),
+        });
+    }
+
+    fn close_coverage(&mut self) {
+        self.coverage = None;
+        self.pending.remove(&Query::Coverage);
+    }
+
+    /// Write the open coverage report to `tag-coverage-YYYY-MM.csv` in the
+    /// working directory
+    fn export_coverage(&mut self) {
+        let Some(view) = &mut self.coverage else {
+            return;
+        };
+        let Some(report) = &view.report else {
+            return;
+        };
+        let path = format!("tag-coverage-{}.csv", report.start.format("%Y-%m"));
+        let result = std::fs::File::create(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| report.write_csv(file));
+        view.exported = Some(match result {
+            Ok(()) => format!("💾 Saved {}", path),
+            Err(e) => format!("⚠️  Could not write {}: {:#}", path, e),
+        });
+    }
+
+    /// Keys while the tag coverage popup is open
+    fn handle_coverage_key(&mut self, action: Action) {
+        let Some(view) = &mut self.coverage else {
+            return;
+        };
+        let len = view.report.as_ref().map_or(0, |r| {
+            if view.accounts { r.accounts.len() } else { r.services.len() }
+        });
+
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back | Action::Coverage => self.close_coverage(),
+            Action::NextTab | Action::PrevTab => {
+                view.accounts = !view.accounts;
+                view.selected = 0;
+            }
+            Action::Export => self.export_coverage(),
+            Action::Down if view.selected + 1 < len => view.selected += 1,
+            Action::Up => view.selected = view.selected.saturating_sub(1),
+            Action::Top => view.selected = 0,
+            Action::Bottom => view.selected = len.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Keys while the anomalies popup is open
+    fn handle_anomaly_key(&mut self, action: Action) {
+        let Some(selected) = &mut self.anomaly_panel else {
+            return;
+        };
+        let len = self.anomalies.as_ref().map_or(0, |a| a.anomalies.len());
+
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back | Action::Anomalies => self.anomaly_panel = None,
+            Action::Down if *selected + 1 < len => *selected += 1,
+            Action::Up => *selected = selected.saturating_sub(1),
+            Action::Top => *selected = 0,
+            Action::Bottom => *selected = len.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Keys while the budgets popup is open
+    fn handle_budget_key(&mut self, action: Action) {
+        let Some(top) = &mut self.budget_panel else {
+            return;
+        };
+        let len = self.budgets.as_ref().map_or(0, Vec::len) + self.local_budgets.as_ref().map_or(0, Vec::len);
+
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back | Action::Budgets => self.budget_panel = None,
+            Action::Down if *top + 1 < len => *top += 1,
+            Action::Up => *top = top.saturating_sub(1),
+            Action::Top => *top = 0,
+            Action::Bottom => *top = len.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Keys while the tag explorer is open
+    fn handle_tag_explorer_key(&mut self, action: Action) {
+        let Some(explorer) = &mut self.tags else {
+            return;
+        };
+        let (selected, len) = match &explorer.key {
+            None => (
+                &mut explorer.selected_key,
+                explorer.keys.as_ref().map_or(0, Vec::len),
+            ),
+            Some(_) => (
+                &mut explorer.selected_value,
+                explorer.spend.as_ref().map_or(0, |s| s.values.len()),
+            ),
+        };
+
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back => self.tag_explorer_back(),
+            Action::DrillDown if explorer.key.is_none() => self.pick_tag_key(),
+            Action::Down if *selected + 1 < len => *selected += 1,
+            Action::Up => *selected = selected.saturating_sub(1),
+            Action::Top => *selected = 0,
+            Action::Bottom => *selected = len.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    /// Profiles in the switcher: the one shown and others loaded before,
+    /// unless they are listed, then every listed profile
+    fn switcher_rows(&self) -> Vec<(&str, Option<&Profile>)> {
+        let listed = |name: &str| self.profiles.iter().any(|p| p.name == name);
+        let mut rows: Vec<(&str, Option<&Profile>)> = std::iter::once(&self.profile)
+            .chain(self.cached.keys())
+            .filter(|name| !name.is_empty() && !listed(name))
+            .map(|name| (name.as_str(), None))
+            .collect();
+        rows.extend(self.profiles.iter().map(|p| (p.name.as_str(), Some(p))));
+        rows
+    }
+
+    /// Open the profile switcher on the profile shown
+    fn open_switcher(&mut self) {
+        let selected = self
+            .switcher_rows()
+            .iter()
+            .position(|(name, _)| *name == self.profile)
+            .unwrap_or(0);
+        self.switcher = Some(ProfileSwitcher { selected, error: None });
+    }
+
+    /// Show `profile`: its cached data if it was loaded before, otherwise
+    /// connect to it and reload every tab
+    fn switch_profile(&mut self, profile: &str) {
+        if profile == self.profile {
+            self.switcher = None;
+            return;
+        }
+        let source = if self.cached.contains_key(profile) {
+            None
+        } else {
+            let Some(connect) = &self.connect else {
+                return;
+            };
+            match connect(profile) {
+                Ok(source) => Some(source),
+                Err(e) => {
+                    if let Some(switcher) = &mut self.switcher {
+                        switcher.error = Some(format!("{}: {:#}", profile, e));
+                    }
+                    return;
+                }
+            }
+        };
+
+        self.switcher = None;
+        let tx = self.loader.as_ref().map(|(_, updates)| updates.tx.clone());
+        let mut loaded = self.cached.remove(profile).unwrap_or_default();
+        self.swap_loaded(&mut loaded);
+        let previous = std::mem::replace(&mut self.profile, profile.to_string());
+        self.cached.insert(previous, loaded);
+        if let Some(source) = source {
+            match tx {
+                Some(tx) => self.spawn_loaders(source, Updates { profile: profile.to_string(), tx }),
+                // Not running (e.g. rendered in tests): wait for data to be supplied
+                None => {
+                    self.source_name = source.name();
+                    self.account_names = source.accounts();
+                    self.set_loading();
+                }
+            }
+        }
+        self.select_tab(self.selected_tab);
+    }
+
+    /// Keys while the profile switcher is open
+    fn handle_switcher_key(&mut self, action: Action) {
+        let len = self.switcher_rows().len();
+        let Some(switcher) = &mut self.switcher else {
+            return;
+        };
+
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::Back | Action::Profiles => self.switcher = None,
+            Action::DrillDown => {
+                let selected = switcher.selected;
+                if let Some(name) = self.switcher_rows().get(selected).map(|(name, _)| name.to_string()) {
+                    self.switch_profile(&name);
+                }
+            }
+            Action::Down if switcher.selected + 1 < len => switcher.selected += 1,
+            Action::Up => switcher.selected = switcher.selected.saturating_sub(1),
+            Action::Top => switcher.selected = 0,
+            Action::Bottom => switcher.selected = len.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    fn is_loading(&self, query: Query) -> bool {
+        self.pending.contains(&query)
+    }
+
+    /// Handle a terminal input event
+    pub fn handle_event(&mut self, event: Event) {
+        let event = match event {
+            Event::Key(event) => event,
+            Event::Mouse(event) => return self.handle_mouse(event),
+            _ => return,
+        };
+        if event.kind != KeyEventKind::Press {
+            return;
+        }
+        let Some(key) = key_of(&event) else {
+            self.chord.clear();
+            return;
+        };
+
+        // A chord's first keys wait for the rest; a key that doesn't go on
+        // with the chord counts on its own
+        self.chord.push(key);
+        let mut lookup = self.keymap.lookup(&self.chord);
+        if lookup == Lookup::Unbound && self.chord.len() > 1 {
+            self.chord = vec![key];
+            lookup = self.keymap.lookup(&self.chord);
+        }
+        match lookup {
+            Lookup::Pending => {}
+            Lookup::Unbound => self.chord.clear(),
+            Lookup::Action(action) => {
+                self.chord.clear();
+                self.handle_action(action);
+            }
+        }
+    }
+
+    /// Clicks pick a tab or a row of the main table, the wheel moves the
+    /// selection like the arrow keys, and the pointer is kept for tooltips
+    fn handle_mouse(&mut self, event: MouseEvent) {
+        let (column, row) = (event.column, event.row);
+        match event.kind {
+            MouseEventKind::ScrollDown => self.handle_action(Action::Down),
+            MouseEventKind::ScrollUp => self.handle_action(Action::Up),
+            MouseEventKind::Moved => self.pointer = Some((column, row)),
+            MouseEventKind::Down(MouseButton::Left) if !self.popup_open() => {
+                let hits = self.hits.borrow();
+                let tab = hits
+                    .tabs
+                    .as_ref()
+                    .filter(|(y, _)| *y == row)
+                    .and_then(|(_, tabs)| tabs.iter().position(|columns| columns.contains(&column)));
+                let selected = hits.rows.and_then(|(area, first)| {
+                    let inside = area.contains(Position::new(column, row));
+                    inside.then(|| first + (row - area.y) as usize)
+                });
+                drop(hits);
+                if let Some(tab) = tab {
+                    self.select_tab(tab);
+                } else if let Some(selected) = selected.filter(|&i| i < self.get_current_breakdown_len()) {
+                    self.selected_row = selected;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether a panel or the help covers the views
+    fn popup_open(&self) -> bool {
+        self.help
+            || self.tags.is_some()
+            || self.coverage.is_some()
+            || self.anomaly_panel.is_some()
+            || self.budget_panel.is_some()
+            || self.switcher.is_some()
+    }
+
+    /// Carry out `action` in whatever panel or view is open
+    fn handle_action(&mut self, action: Action) {
+        if self.help {
+            match action {
+                Action::Quit => self.should_quit = true,
+                Action::Back | Action::Help => self.help = false,
+                _ => {}
+            }
+            return;
+        }
+        if self.tags.is_some() {
+            self.handle_tag_explorer_key(action);
+            return;
+        }
+        if self.coverage.is_some() {
+            self.handle_coverage_key(action);
+            return;
+        }
+        if self.anomaly_panel.is_some() {
+            self.handle_anomaly_key(action);
+            return;
+        }
+        if self.budget_panel.is_some() {
+            self.handle_budget_key(action);
+            return;
+        }
+        if self.switcher.is_some() {
+            self.handle_switcher_key(action);
+            return;
+        }
+
+        match action {
+            Action::Back if self.resources.is_some() => {
+                self.close_resources();
+            }
+            Action::Quit | Action::Back => self.should_quit = true,
+            Action::Help => self.help = true,
+            Action::NextTab => {
+                self.select_tab(self.selected_tab + 1);
+            }
+            Action::PrevTab => {
+                self.select_tab(self.selected_tab + self.tab_count() - 1);
+            }
+            Action::Refresh => self.refresh(),
+            Action::DrillDown if self.resources.is_none() && self.by_service() => self.open_resources(),
+            Action::Copy if self.resources.is_some() => self.copy_selected_resource(),
+            Action::Tags if self.resources.is_none() => self.open_tag_explorer(),
+            Action::Coverage if self.resources.is_none() => self.open_coverage(),
+            Action::Anomalies if self.resources.is_none() => self.anomaly_panel = Some(0),
+            Action::Budgets if self.resources.is_none() => self.budget_panel = Some(0),
+            Action::Profiles if self.resources.is_none() && self.connect.is_some() => self.open_switcher(),
+            Action::Down => {
+                let max_rows = self.get_current_breakdown_len();
+                if self.selected_row < max_rows.saturating_sub(1) {
+                    self.selected_row += 1;
+                }
+            }
+            Action::Up if self.selected_row > 0 => {
+                self.selected_row -= 1;
+            }
+            Action::Top => {
+                self.selected_row = 0;
+            }
+            Action::Bottom => {
+                self.selected_row = self.get_current_breakdown_len().saturating_sub(1);
+            }
+            Action::Export if self.on_teams_tab() => self.export_teams(),
+            Action::Prorate if self.selected_tab == 3 => {
+                self.prorate = !self.prorate;
+                self.selected_row = 0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Query the source shown again, leaving other profiles' data cached
+    fn refresh(&mut self) {
+        let Some((source, updates)) = self.loader.clone() else {
+            return;
+        };
+        self.close_resources();
+        self.spawn_loaders(source, updates);
+    }
+
+    fn get_current_breakdown_len(&self) -> usize {
+        if let Some(view) = &self.resources {
+            return view.data.as_ref().map(|d| d.breakdown.len()).unwrap_or(0);
+        }
+        match self.selected_tab {
+            0 => self.current_month.as_ref().map(|d| d.breakdown.len()).unwrap_or(0),
+            1 => self.previous_month.as_ref().map(|d| d.breakdown.len()).unwrap_or(0),
+            2 => self.get_top_services_across_months().len(),
+            3 => self.comparison().map(|c| c.rows.len()).unwrap_or(0),
+            _ if self.on_teams_tab() => self.allocation.as_ref().map(|a| a.teams.len()).unwrap_or(0),
+            _ if self.on_accounts_tab() => self.accounts.as_ref().map(|a| a.services.len()).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Compare the current month against the previous month
+    fn comparison(&self) -> Option<CostComparison> {
+        let baseline = self.previous_month.as_ref()?;
+        let current = self.current_month.as_ref()?;
+        Some(CostComparison::new(baseline, current, self.prorate))
+    }
+
+    /// Color of `cost` under the configured thresholds
+    fn cost_color(&self, cost: f64) -> Color {
+        get_cost_color(cost, &self.settings.thresholds, &self.theme)
+    }
+
+    /// Whether the month views list services, which can be drilled into
+    fn by_service(&self) -> bool {
+        self.settings.group_by == GroupBy::Dimension(Dimension::Service)
+    }
+
+    /// What the month views list: "Service", "Region", a tag key...
+    fn group_name(&self) -> String {
+        match &self.settings.group_by {
+            GroupBy::Dimension(Dimension::LinkedAccount) => "Account".to_string(),
+            GroupBy::Dimension(dimension) => {
+                let name = dimension.as_str().replace('_', " ").to_lowercase();
+                let mut chars = name.chars();
+                chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+            }
+            GroupBy::Tag(key) | GroupBy::CostCategory(key) => key.clone(),
+        }
+    }
+
+    /// Metric and saved filter for the header, when not the defaults
+    fn settings_note(&self) -> String {
+        let mut note = String::new();
+        if self.settings.metric != Metric::default() {
+            note.push_str(&format!(" · {}", self.settings.metric.as_str()));
+        }
+        if let Some(name) = &self.settings.filter_name {
+            note.push_str(&format!(" · filter {}", name));
+        }
+        note
+    }
+
+    /// Get top services across all months for trend view
+    fn get_top_services_across_months(&self) -> Vec<String> {
+        let mut service_totals: HashMap<String, f64> = HashMap::new();
+        
+        for month in &self.monthly_trend {
+            for service in &month.breakdown {
+                *service_totals.entry(service.service.clone()).or_default() += service.cost;
+            }
+        }
+        
+        let mut services: Vec<_> = service_totals.into_iter().collect();
+        services.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        services.into_iter().take(self.settings.top).map(|(name, _)| name).collect()
+    }
+
+    /// Run the TUI, loading data from `source` in the background
+    pub async fn run(&mut self, source: Arc<dyn CostSource>) -> Result<()> {
+        // Setup terminal; the guard restores it however the loop ends
+        enable_raw_mode()?;
+        let _restore = RestoreTerminal;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        let (tx, updates) = mpsc::unbounded_channel();
+        self.spawn_loaders(source, Updates { profile: self.profile.clone(), tx });
+        self.event_loop(&mut terminal, updates).await
+    }
+
+    /// Redraw, then wait for whichever comes first: input or a query result
+    async fn event_loop(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        mut updates: mpsc::UnboundedReceiver<Tagged>,
+    ) -> Result<()> {
+        let mut events = EventStream::new();
+
+        while !self.should_quit {
+            terminal.draw(|f| self.render(f))?;
+
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(Ok(event)) => {
+                        self.handle_event(event);
+                        // OSC 52: the terminal sets the clipboard, even over SSH
+                        if let Some(text) = self.clipboard.take() {
+                            execute!(terminal.backend_mut(), CopyToClipboard::to_clipboard_from(text))?;
+                        }
+                    }
+                    Some(Err(e)) => return Err(e.into()),
+                    None => self.should_quit = true,
+                },
+                Some(update) = updates.recv() => self.apply_tagged(update),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Render the UI into a frame; works with any ratatui backend
+    pub fn render(&self, frame: &mut Frame) {
+        let area = frame.area();
+        *self.hits.borrow_mut() = Hits::default();
+
+        // Main layout: header, tabs, content, footer
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3), // Header
+                Constraint::Length(3), // Tabs
+                Constraint::Min(10),   // Content
+                Constraint::Length(3), // Footer
+            ])
+            .split(area);
+
+        // Header
+        self.render_header(frame, chunks[0]);
+
+        // Tabs
+        self.render_tabs(frame, chunks[1]);
+
+        // Content based on selected tab
+        match self.selected_tab {
+            0 | 1 if self.resources.is_some() => self.render_resources(frame, chunks[2]),
+            0 => self.render_current_month(frame, chunks[2]),
+            1 => self.render_previous_month(frame, chunks[2]),
+            2 => self.render_trend(frame, chunks[2]),
+            3 => self.render_compare(frame, chunks[2]),
+            _ if self.on_teams_tab() => self.render_teams(frame, chunks[2]),
+            _ if self.on_accounts_tab() => self.render_accounts(frame, chunks[2]),
+            _ => {}
+        }
+
+        // Footer
+        self.render_footer(frame, chunks[3]);
+
+        if self.tags.is_some() {
+            self.render_tag_explorer(frame, popup_area(chunks[2], 80, 90));
+        }
+        if self.coverage.is_some() {
+            self.render_coverage(frame, popup_area(chunks[2], 90, 90));
+        }
+        if self.anomaly_panel.is_some() {
+            self.render_anomalies(frame, popup_area(chunks[2], 90, 90));
+        }
+        if self.budget_panel.is_some() {
+            self.render_budgets(frame, popup_area(chunks[2], 80, 90));
+        }
+        if self.switcher.is_some() {
+            self.render_switcher(frame, popup_area(chunks[2], 70, 80));
+        }
+        if self.help {
+            self.render_help(frame, popup_area(chunks[2], 80, 100));
+        }
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect) {
+        let title = Paragraph::new(vec![Line::from(vec![
+            Span::styled("☁️  ", Style::default()),
+            Span::styled("AWS", Style::default().fg(self.theme.brand).bold()),
+            Span::styled(" Cost Explorer ", Style::default().fg(self.theme.text).bold()),
+            Span::styled("TUI", Style::default().fg(self.theme.teal).bold()),
+            Span::styled(
+                if self.connect.is_some() {
+                    format!("  · {} · {}{}", self.profile, self.source_name, self.settings_note())
+                } else {
+                    format!("  · {}{}", self.source_name, self.settings_note())
+                },
+                Style::default().fg(self.theme.dim),
+            ),
+        ])])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.brand)),
+        );
+        frame.render_widget(title, area);
+    }
+
+    fn render_tabs(&self, frame: &mut Frame, area: Rect) {
+        let mut titles = vec![
+            Line::from(vec![
+                Span::styled("📅 ", Style::default()),
+                Span::styled("Current Month", Style::default().fg(self.theme.green)),
+            ]),
+            Line::from(vec![
+                Span::styled("📆 ", Style::default()),
+                Span::styled("Previous Month", Style::default().fg(self.theme.purple)),
+            ]),
+            Line::from(vec![
+                Span::styled("📊 ", Style::default()),
+                Span::styled(
+                    format!("{}-Month Trend", self.settings.months),
+                    Style::default().fg(self.theme.orange),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("🔀 ", Style::default()),
+                Span::styled("Compare", Style::default().fg(self.theme.blue)),
+            ]),
+        ];
+        if self.allocation_rules.is_some() {
+            titles.push(Line::from(vec![
+                Span::styled("👥 ", Style::default()),
+                Span::styled("Teams", Style::default().fg(self.theme.lavender)),
+            ]));
+        }
+        if !self.account_names.is_empty() {
+            titles.push(Line::from(vec![
+                Span::styled("🏢 ", Style::default()),
+                Span::styled("Accounts", Style::default().fg(self.theme.cyan)),
+            ]));
+        }
+        // Drop the icons when the titles don't fit (padding and divider add
+        // 5 columns per tab)
+        let width: usize = titles.iter().map(|t| t.width() + 5).sum();
+        if width > area.width.saturating_sub(2) as usize + 3 {
+            for title in &mut titles {
+                title.spans.remove(0);
+            }
+        }
+        // Each title is padded by a space on either side, then the divider
+        let inner = area.inner(Margin::new(1, 1));
+        let mut x = inner.x;
+        let columns = titles
+            .iter()
+            .map(|title| {
+                let start = x;
+                x = x.saturating_add(title.width() as u16 + 2);
+                let columns = start.min(inner.right())..x.min(inner.right());
+                x = x.saturating_add(3);
+                columns
+            })
+            .collect();
+        self.hits.borrow_mut().tabs = Some((inner.y, columns));
+
+        let tabs = Tabs::new(titles)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.border))
+                    .title(Span::styled(" Views ", Style::default().fg(self.theme.text).bold())),
+            )
+            .select(self.selected_tab)
+            .style(Style::default().fg(self.theme.dim))
+            .highlight_style(
+                Style::default()
+                    .fg(self.theme.text)
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::UNDERLINED),
+            )
+            .divider(Span::styled(" │ ", Style::default().fg(self.theme.dim)));
+        frame.render_widget(tabs, area);
+    }
+
+    fn render_current_month(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::CurrentMonth) {
+            self.render_loading(frame, area);
+            return;
+        }
+
+        if let Some(ref error) = self.error {
+            self.render_error(frame, area, error);
+            return;
+        }
+
+        if let Some(ref data) = self.current_month {
+            self.render_cost_breakdown(frame, area, data, self.theme.green, &self.group_name());
+        } else {
+            self.render_no_data(frame, area);
+        }
+    }
+
+    fn render_previous_month(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::PreviousMonth) {
+            self.render_loading(frame, area);
+            return;
+        }
+
+        if let Some(ref data) = self.previous_month {
+            self.render_cost_breakdown(frame, area, data, self.theme.purple, &self.group_name());
+        } else {
+            self.render_no_data(frame, area);
+        }
+    }
+
+    fn render_trend(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::Trend) {
+            self.render_loading(frame, area);
+            return;
+        }
+
+        if self.monthly_trend.is_empty() {
+            self.render_no_data(frame, area);
+            return;
+        }
+
+        // Split into chart and legend
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(area);
+
+        // Get top services and assign colors
+        let top_services = self.get_top_services_across_months();
+        let service_colors: HashMap<String, Color> = top_services
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (s.clone(), *self.theme.service(i)))
+            .collect();
+
+        // Create grouped bar chart - each month has bars for each service
+        let bar_groups: Vec<BarGroup> = self
+            .monthly_trend
+            .iter()
+            .map(|month| {
+                let short_month = month.period.split(' ').next().unwrap_or(&month.period);
+                let short_month = if short_month.len() > 3 {
+                    &short_month[..3]
+                } else {
+                    short_month
+                };
+
+                let bars: Vec<Bar> = top_services
+                    .iter()
+                    .map(|service| {
+                        let cost = month
+                            .breakdown
+                            .iter()
+                            .find(|s| &s.service == service)
+                            .map(|s| s.cost)
+                            .unwrap_or(0.0);
+                        
+                        let color = service_colors.get(service).copied().unwrap_or(self.theme.muted);
+                        
+                        Bar::default()
+                            .value((cost * 100.0) as u64) // Scale for visibility
+                            .style(Style::default().fg(color))
+                    })
+                    .collect();
+
+                BarGroup::default()
+                    .label(Line::from(Span::styled(
+                        short_month.to_string(),
+                        Style::default().fg(self.theme.text).bold(),
+                    )))
+                    .bars(&bars)
+            })
+            .collect();
+
+        let mut bar_chart = BarChart::default()
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        format!(" 📊 Monthly Cost Trend by {} ", self.group_name()),
+                        Style::default().fg(self.theme.orange).bold(),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.orange)),
+            )
+            .value_style(Style::default().fg(self.theme.text));
+
+        for group in &bar_groups {
+            bar_chart = bar_chart.data(group.clone());
+        }
+
+        let chart_area = match self.trend_budget() {
+            Some(budget) => {
+                let top_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+                    .split(chunks[0]);
+                self.render_budget_chart(frame, top_chunks[1], budget);
+                top_chunks[0]
+            }
+            None => chunks[0],
+        };
+        // Thinner bars when a long trend or many services wouldn't fit
+        let needed = bar_groups.len() * (top_services.len() * 2 + 3);
+        let (bar_width, group_gap) = match needed <= chart_area.width.saturating_sub(2) as usize {
+            true => (2, 3),
+            false => (1, 1),
+        };
+        bar_chart = bar_chart.bar_width(bar_width).bar_gap(0).group_gap(group_gap);
+        frame.render_widget(bar_chart, chart_area);
+        if let Some(pointer) = self.pointer {
+            self.render_bar_tooltip(frame, chart_area, pointer, (bar_width, group_gap), &top_services);
+        }
+
+        // Legend and summary table side by side
+        let bottom_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(chunks[1]);
+
+        // Service legend
+        let legend_items: Vec<Line> = top_services
+            .iter()
+            .enumerate()
+            .map(|(i, service)| {
+                let color = *self.theme.service(i);
+                let display_name = truncate_service_name(service, 25);
+                Line::from(vec![
+                    Span::styled("██ ", Style::default().fg(color)),
+                    Span::styled(display_name, Style::default().fg(self.theme.text)),
+                ])
+            })
+            .collect();
+
+        let legend = Paragraph::new(legend_items)
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        match self.by_service() {
+                            true => " 🎨 Services ".to_string(),
+                            false => format!(" 🎨 {} ", self.group_name()),
+                        },
+                        Style::default().fg(self.theme.teal).bold(),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.teal))
+                    .padding(Padding::horizontal(1)),
+            );
+        frame.render_widget(legend, bottom_chunks[0]);
+
+        // Summary table with totals
+        let rows: Vec<Row> = self
+            .monthly_trend
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                let is_current = i == self.monthly_trend.len() - 1;
+                let is_incomplete = self.trend_gaps.iter().any(|g| g.start == d.start);
+                let row_style = if is_incomplete {
+                    Style::default().fg(self.theme.yellow)
+                } else if is_current {
+                    Style::default().fg(self.theme.green).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                
+                // Calculate month-over-month change
+                let change = if i > 0 {
+                    let prev = self.monthly_trend[i - 1].total_cost;
+                    if prev > 0.0 {
+                        ((d.total_cost - prev) / prev) * 100.0
+                    } else {
+                        0.0
+                    }
+                } else {
+                    0.0
+                };
+
+                let change_style = if change > 10.0 {
+                    Style::default().fg(self.theme.red) // Red for increase
+                } else if change < -10.0 {
+                    Style::default().fg(self.theme.green) // Green for decrease
+                } else {
+                    Style::default().fg(self.theme.yellow)
+                };
+
+                let change_str = if i > 0 {
+                    format!("{:+.1}%", change)
+                } else {
+                    "—".to_string()
+                };
+
+                let period = if is_incomplete {
+                    format!("{} ⚠", d.period)
+                } else {
+                    d.period.clone()
+                };
+
+                Row::new(vec![
+                    Cell::from(Span::styled(period, Style::default().fg(self.theme.text))),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", d.total_cost),
+                        Style::default().fg(self.cost_color(d.total_cost)).bold(),
+                    )),
+                    Cell::from(Span::styled(change_str, change_style)),
+                ])
+                .style(row_style)
+            })
+            .collect();
+
+        let totals_title = if self.trend_gaps.is_empty() {
+            " 📋 Monthly Totals ".to_string()
+        } else {
+            let missing: Vec<String> = self
+                .trend_gaps
+                .iter()
+                .filter(|g| !g.partial)
+                .map(|g| format!("{} ({})", g.period, g.reason))
+                .collect();
+            if missing.is_empty() {
+                format!(" 📋 Monthly Totals ⚠ {} incomplete ", self.trend_gaps.len())
+            } else {
+                format!(
+                    " 📋 Monthly Totals ⚠ {} incomplete, missing: {} ",
+                    self.trend_gaps.len(),
+                    missing.join(", ")
+                )
+            }
+        };
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(45),
+                Constraint::Percentage(30),
+                Constraint::Percentage(25),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                Cell::from(Span::styled("Period", Style::default().fg(self.theme.yellow).bold())),
+                Cell::from(Span::styled("Total", Style::default().fg(self.theme.yellow).bold())),
+                Cell::from(Span::styled("Change", Style::default().fg(self.theme.yellow).bold())),
+            ])
+            .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    totals_title,
+                    Style::default().fg(self.theme.yellow).bold(),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.yellow)),
+        );
+
+        frame.render_widget(table, bottom_chunks[1]);
+    }
+
+    fn render_compare(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::CurrentMonth) || self.is_loading(Query::PreviousMonth) {
+            self.render_loading(frame, area);
+            return;
+        }
+
+        let Some(comparison) = self.comparison() else {
+            self.render_no_data(frame, area);
+            return;
+        };
+        let accent_color = self.theme.blue;
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(5)])
+            .split(area);
+
+        let total_delta = comparison.total_delta();
+        let total_pct = comparison
+            .total_delta_pct()
+            .map(|p| format!(" ({:+.1}%)", p))
+            .unwrap_or_default();
+        let mode = match comparison.prorate_factor {
+            Some(factor) => format!("prorated baseline (×{:.2} by days covered)", factor),
+            None => "full periods".to_string(),
+        };
+
+        let summary = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("📆 Baseline: ", Style::default().fg(self.theme.muted)),
+                Span::styled(&comparison.baseline_period, Style::default().fg(self.theme.text).bold()),
+                Span::styled("  →  ", Style::default().fg(self.theme.dim)),
+                Span::styled("📅 Current: ", Style::default().fg(self.theme.muted)),
+                Span::styled(&comparison.current_period, Style::default().fg(self.theme.text).bold()),
+            ]),
+            Line::from(vec![
+                Span::styled("💰 Total: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    format!("${:.2}", comparison.baseline_total),
+                    Style::default().fg(self.cost_color(comparison.baseline_total)),
+                ),
+                Span::styled(" → ", Style::default().fg(self.theme.dim)),
+                Span::styled(
+                    format!("${:.2}", comparison.current_total),
+                    Style::default().fg(self.cost_color(comparison.current_total)).bold(),
+                ),
+                Span::styled(
+                    format!("  {}{}", format_signed_cost(total_delta), total_pct),
+                    get_delta_style(total_delta, &self.theme),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("🆕 New: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    comparison.new_services().count().to_string(),
+                    Style::default().fg(self.theme.text).bold(),
+                ),
+                Span::styled("   👻 Gone: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    comparison.removed_services().count().to_string(),
+                    Style::default().fg(self.theme.text).bold(),
+                ),
+                Span::styled("   ⚖️  Mode: ", Style::default().fg(self.theme.muted)),
+                Span::styled(mode, Style::default().fg(self.theme.label)),
+            ]),
+        ])
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    " 🔀 Month-over-Month ",
+                    Style::default().fg(accent_color).bold(),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(accent_color))
+                .padding(Padding::horizontal(1)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let rows: Vec<Row> = comparison
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                let base_style = if i == self.selected_row {
+                    selected_style(&self.theme)
+                } else {
+                    Style::default()
+                };
+
+                let (status, status_color) = match d.change {
+                    ServiceChange::New => ("NEW", self.theme.blue),
+                    ServiceChange::Removed => ("GONE", self.theme.label),
+                    ServiceChange::Increased => ("▲", self.theme.red),
+                    ServiceChange::Decreased => ("▼", self.theme.green),
+                    ServiceChange::Unchanged => ("=", self.theme.dim),
+                };
+
+                let pct = d
+                    .delta_pct
+                    .map(|p| format!("{:+.1}%", p))
+                    .unwrap_or_else(|| "—".to_string());
+
+                Row::new(vec![
+                    Cell::from(Span::styled(
+                        format!("#{}", i + 1),
+                        Style::default().fg(self.theme.dim),
+                    )),
+                    Cell::from(Line::from(vec![
+                        Span::styled(
+                            d.provider.as_ref().map(|p| format!("{} · ", p)).unwrap_or_default(),
+                            Style::default().fg(self.theme.brand),
+                        ),
+                        Span::styled(
+                            truncate_service_name(&d.service, 40),
+                            Style::default().fg(self.theme.text),
+                        ),
+                    ])),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", d.baseline),
+                        Style::default().fg(self.theme.label),
+                    )),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", d.current),
+                        Style::default().fg(self.cost_color(d.current)).bold(),
+                    )),
+                    Cell::from(Span::styled(format_signed_cost(d.delta), get_delta_style(d.delta, &self.theme))),
+                    Cell::from(Span::styled(pct, get_delta_style(d.delta, &self.theme))),
+                    Cell::from(Span::styled(status, Style::default().fg(status_color).bold())),
+                ])
+                .style(base_style)
+            })
+            .collect();
+
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let skip = self.scrolled_rows(chunks[1]);
+        let table = Table::new(
+            rows.into_iter().skip(skip),
+            [
+                Constraint::Length(4),      // Rank
+                Constraint::Percentage(35), // Service
+                Constraint::Length(12),     // Baseline
+                Constraint::Length(12),     // Current
+                Constraint::Length(12),     // Delta
+                Constraint::Length(9),      // Delta %
+                Constraint::Min(5),         // Status
+            ],
+        )
+        .header(
+            Row::new(vec![
+                Cell::from(Span::styled("#", header_style)),
+                Cell::from(Span::styled(self.group_name(), header_style)),
+                Cell::from(Span::styled("Previous", header_style)),
+                Cell::from(Span::styled("Current", header_style)),
+                Cell::from(Span::styled("Δ Cost", header_style)),
+                Cell::from(Span::styled("Δ %", header_style)),
+                Cell::from(Span::styled("", header_style)),
+            ])
+            .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" 📋 {} Changes (largest increase first) ", self.group_name()),
+                    Style::default().fg(self.theme.teal).bold(),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.teal)),
+        );
+
+        frame.render_widget(table, chunks[1]);
+    }
+
+    /// Resources of one service, with a breadcrumb back to the month view
+    /// Current month's spend per team, reconciled against the bill
+    fn render_teams(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::CurrentMonth) || self.is_loading(Query::Teams) {
+            self.render_loading(frame, area);
+            return;
+        }
+        if let Some(error) = self.allocation_error.as_ref().or(self.error.as_ref()) {
+            self.render_error(frame, area, error);
+            return;
+        }
+        let Some(allocation) = &self.allocation else {
+            self.render_no_data(frame, area);
+            return;
+        };
+        let accent_color = self.theme.lavender;
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(5)])
+            .split(area);
+
+        // The month's bill, unless the allocation covers another period
+        let bill = self
+            .current_month
+            .as_ref()
+            .filter(|m| m.start == allocation.start)
+            .map_or(allocation.total_cost, |m| m.total_cost);
+        let check = allocation.reconcile(bill);
+        let reconciliation = if check.is_balanced() {
+            Span::styled(
+                format!("✅ Reconciled: ${:.2} allocated of a ${:.2} bill", check.allocated, check.bill),
+                Style::default().fg(self.theme.green),
+            )
+        } else {
+            Span::styled(
+                format!(
+                    "⚠️  Not reconciled: ${:.2} allocated + ${:.2} unallocated of a ${:.2} bill",
+                    check.allocated, check.unallocated, check.bill
+                ),
+                Style::default().fg(self.theme.yellow),
+            )
+        };
+        let mut summary = vec![
+            Line::from(vec![
+                Span::styled("📅 Period: ", Style::default().fg(self.theme.muted)),
+                Span::styled(&allocation.period, Style::default().fg(self.theme.text).bold()),
+            ]),
+            Line::from(vec![
+                Span::styled("💰 Allocated: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    format!("${:.2}", check.allocated),
+                    Style::default().fg(self.cost_color(check.allocated)).bold(),
+                ),
+                Span::styled(format!(" {}", allocation.currency), Style::default().fg(self.theme.dim)),
+                Span::styled(
+                    format!(
+                        "  ({} team{}, {} shared bucket{})",
+                        allocation.teams.len(),
+                        if allocation.teams.len() == 1 { "" } else { "s" },
+                        allocation.buckets.len(),
+                        if allocation.buckets.len() == 1 { "" } else { "s" },
+                    ),
+                    Style::default().fg(self.theme.label),
+                ),
+            ]),
+            Line::from(reconciliation),
+        ];
+        if let Some(exported) = &self.teams_exported {
+            summary.push(Line::from(Span::styled(exported.clone(), Style::default().fg(self.theme.green))));
+        }
+        let summary = Paragraph::new(summary).wrap(Wrap { trim: true }).block(
+            Block::default()
+                .title(Span::styled(" 💵 Cost Summary ", Style::default().fg(accent_color).bold()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(accent_color))
+                .padding(Padding::horizontal(1)),
+        );
+        frame.render_widget(summary, chunks[0]);
+
+        let share = |cost: f64| {
+            if allocation.total_cost > 0.0 {
+                cost / allocation.total_cost * 100.0
+            } else {
+                0.0
+            }
+        };
+        let mut rows: Vec<Row> = allocation
+            .teams
+            .iter()
+            .enumerate()
+            .map(|(i, team)| {
+                let color = *self.theme.service(i);
+                let mut cells = vec![
+                    Cell::from(Span::styled(format!("#{}", i + 1), Style::default().fg(self.theme.dim))),
+                    Cell::from(Span::styled("██", Style::default().fg(color))),
+                    Cell::from(Span::styled(truncate_service_name(&team.name, 30), Style::default().fg(self.theme.text))),
+                    Cell::from(Span::styled(format!("${:.2}", team.direct), Style::default().fg(self.theme.label))),
+                ];
+                cells.extend(team.shared.iter().map(|cost| {
+                    Cell::from(Span::styled(format!("${:.2}", cost), Style::default().fg(self.theme.label)))
+                }));
+                cells.extend([
+                    Cell::from(Span::styled(
+                        format!("${:.2}", team.total()),
+                        Style::default().fg(self.cost_color(team.total())).bold(),
+                    )),
+                    Cell::from(Span::styled(
+                        format!("{:.1}%", share(team.total())),
+                        Style::default().fg(self.theme.label),
+                    )),
+                    Cell::from(Span::styled(create_bar(share(team.total())), Style::default().fg(color))),
+                ]);
+                let row = Row::new(cells);
+                if i == self.selected_row {
+                    row.style(selected_style(&self.theme))
+                } else {
+                    row
+                }
+            })
+            .collect();
+        if allocation.unallocated.abs() >= CHANGE_EPSILON {
+            let mut cells = vec![
+                Cell::from(""),
+                Cell::from(""),
+                Cell::from(Span::styled("(unallocated)", Style::default().fg(self.theme.yellow))),
+                Cell::from(""),
+            ];
+            cells.extend(allocation.buckets.iter().map(|_| Cell::from("")));
+            cells.extend([
+                Cell::from(Span::styled(
+                    format!("${:.2}", allocation.unallocated),
+                    Style::default().fg(self.theme.yellow).bold(),
+                )),
+                Cell::from(Span::styled(
+                    format!("{:.1}%", share(allocation.unallocated)),
+                    Style::default().fg(self.theme.label),
+                )),
+            ]);
+            rows.push(Row::new(cells));
+        }
+
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let mut widths = vec![
+            Constraint::Length(4),  // Rank
+            Constraint::Length(3),  // Color
+            Constraint::Min(16),    // Team
+            Constraint::Length(12), // Direct
+        ];
+        let mut header = vec![
+            Cell::from(Span::styled("#", header_style)),
+            Cell::from(""),
+            Cell::from(Span::styled("Team", header_style)),
+            Cell::from(Span::styled("Direct", header_style)),
+        ];
+        for bucket in &allocation.buckets {
+            widths.push(Constraint::Length(12));
+            header.push(Cell::from(Span::styled(truncate_service_name(bucket, 11), header_style)));
+        }
+        widths.extend([Constraint::Length(12), Constraint::Length(8), Constraint::Length(20)]);
+        header.extend([
+            Cell::from(Span::styled("Total", header_style)),
+            Cell::from(Span::styled("%", header_style)),
+            Cell::from(Span::styled("Distribution", header_style)),
+        ]);
+
+        let skip = self.scrolled_rows(chunks[1]);
+        let table = Table::new(rows.into_iter().skip(skip), widths)
+            .header(Row::new(header).bottom_margin(1))
+            .block(
+                Block::default()
+                    .title(Span::styled(" 👥 Teams ", Style::default().fg(self.theme.teal).bold()))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.teal)),
+            );
+        frame.render_widget(table, chunks[1]);
+    }
+
+    /// Each account's total, then the services with a column per account
+    fn render_accounts(&self, frame: &mut Frame, area: Rect) {
+        if self.is_loading(Query::CurrentMonth) || self.is_loading(Query::Accounts) {
+            self.render_loading(frame, area);
+            return;
+        }
+        if let Some(error) = self.accounts_error.as_ref().or(self.error.as_ref()) {
+            self.render_error(frame, area, error);
+            return;
+        }
+        let Some(report) = &self.accounts else {
+            self.render_no_data(frame, area);
+            return;
+        };
+        let accent_color = self.theme.cyan;
+        let total = report.total_cost();
+        let share = |cost: f64| if total > 0.0 { cost / total * 100.0 } else { 0.0 };
+
+        // A line per account, up to half the view
+        let totals_height = (report.accounts.len() as u16 + 3).min(area.height / 2);
+        let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .constraints([Constraint::Length(totals_height), Constraint::Min(5)])
             .split(area);
 
-        // Get top services and assign colors
-        let top_services = self.get_top_services_across_months();
-        let service_colors: HashMap<String, Color> = top_services
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let rows: Vec<Row> = report
+            .accounts
             .iter()
             .enumerate()
-            .map(|(i, s)| (s.clone(), SERVICE_COLORS[i % SERVICE_COLORS.len()]))
+            .map(|(i, account)| {
+                let color = *self.theme.service(i);
+                let name = Span::styled(truncate_service_name(&account.account, 30), Style::default().fg(self.theme.text));
+                match account.cost {
+                    Some(cost) => Row::new(vec![
+                        Cell::from(Span::styled("██", Style::default().fg(color))),
+                        Cell::from(name),
+                        Cell::from(Span::styled(format!("${:.2}", cost), Style::default().fg(self.cost_color(cost)).bold())),
+                        Cell::from(Span::styled(format!("{:.1}%", share(cost)), Style::default().fg(self.theme.label))),
+                        Cell::from(Span::styled(create_bar(share(cost)), Style::default().fg(color))),
+                    ]),
+                    // The error takes the rest of the row
+                    None => Row::new(vec![
+                        Cell::from(Span::styled("❌", Style::default())),
+                        Cell::from(Line::from(vec![
+                            name,
+                            Span::styled(
+                                format!("  {}", account.error.as_deref().unwrap_or("failed")),
+                                Style::default().fg(self.theme.red),
+                            ),
+                        ])),
+                    ]),
+                }
+            })
             .collect();
+        let failed = report.failed().count();
+        let title = if failed > 0 {
+            format!(" 🏢 Accounts · {} · ${:.2} · {} failed ", report.period, total, failed)
+        } else {
+            format!(" 🏢 Accounts · {} · ${:.2} ", report.period, total)
+        };
+        let totals = Table::new(
+            rows,
+            [Constraint::Length(3), Constraint::Min(16), Constraint::Length(12), Constraint::Length(8), Constraint::Length(20)],
+        )
+        .header(Row::new(vec![
+            Cell::from(""),
+            Cell::from(Span::styled("Account", header_style)),
+            Cell::from(Span::styled("Cost", header_style)),
+            Cell::from(Span::styled("%", header_style)),
+            Cell::from(Span::styled("Share", header_style)),
+        ]))
+        .block(
+            Block::default()
+                .title(Span::styled(title, Style::default().fg(accent_color).bold()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(accent_color)),
+        );
+        frame.render_widget(totals, chunks[0]);
 
-        // Create grouped bar chart - each month has bars for each service
-        let bar_groups: Vec<BarGroup> = self
-            .monthly_trend
+        // Only the loaded accounts that fit get a column; the total covers
+        // them all
+        let loaded: Vec<usize> = (0..report.accounts.len()).filter(|&i| report.accounts[i].cost.is_some()).collect();
+        let service_width = 24;
+        let fixed = 4 + 3 + service_width + 12 + 2;
+        let shown = ((chunks[1].width.saturating_sub(fixed) / 12) as usize).min(loaded.len());
+        let rows: Vec<Row> = report
+            .services
             .iter()
-            .map(|month| {
-                let short_month = month.period.split(' ').next().unwrap_or(&month.period);
-                let short_month = if short_month.len() > 3 {
-                    &short_month[..3]
+            .enumerate()
+            .map(|(i, service)| {
+                let color = *self.theme.service(i);
+                let mut cells = vec![
+                    Cell::from(Span::styled(format!("#{}", i + 1), Style::default().fg(self.theme.dim))),
+                    Cell::from(Span::styled("██", Style::default().fg(color))),
+                    Cell::from(Span::styled(truncate_service_name(&service.service, service_width as usize), Style::default().fg(self.theme.text))),
+                ];
+                cells.extend(loaded.iter().take(shown).map(|&account| {
+                    Cell::from(Span::styled(format!("${:.2}", service.costs[account]), Style::default().fg(self.theme.label)))
+                }));
+                cells.push(Cell::from(Span::styled(
+                    format!("${:.2}", service.total),
+                    Style::default().fg(self.cost_color(service.total)).bold(),
+                )));
+                let row = Row::new(cells);
+                if i == self.selected_row {
+                    row.style(selected_style(&self.theme))
                 } else {
-                    short_month
-                };
-
-                let bars: Vec<Bar> = top_services
-                    .iter()
-                    .map(|service| {
-                        let cost = month
-                            .breakdown
-                            .iter()
-                            .find(|s| &s.service == service)
-                            .map(|s| s.cost)
-                            .unwrap_or(0.0);
-                        
-                        let color = service_colors.get(service).copied().unwrap_or(Color::Gray);
-                        
-                        Bar::default()
-                            .value((cost * 100.0) as u64) // Scale for visibility
-                            .style(Style::default().fg(color))
-                    })
-                    .collect();
-
-                BarGroup::default()
-                    .label(Line::from(Span::styled(
-                        short_month.to_string(),
-                        Style::default().fg(Color::White).bold(),
-                    )))
-                    .bars(&bars)
+                    row
+                }
             })
             .collect();
 
-        let mut bar_chart = BarChart::default()
+        let mut widths = vec![Constraint::Length(4), Constraint::Length(3), Constraint::Min(16)];
+        let mut header = vec![
+            Cell::from(Span::styled("#", header_style)),
+            Cell::from(""),
+            Cell::from(Span::styled("Service", header_style)),
+        ];
+        for &account in loaded.iter().take(shown) {
+            widths.push(Constraint::Length(12));
+            header.push(Cell::from(Span::styled(truncate_service_name(&report.accounts[account].account, 11), header_style)));
+        }
+        widths.push(Constraint::Length(12));
+        header.push(Cell::from(Span::styled("Total", header_style)));
+
+        let hidden = loaded.len() - shown;
+        let title = if hidden > 0 {
+            format!(" 📋 Services by Account · {} more not shown ", hidden)
+        } else {
+            " 📋 Services by Account ".to_string()
+        };
+        let skip = self.scrolled_rows(chunks[1]);
+        let table = Table::new(rows.into_iter().skip(skip), widths)
+            .header(Row::new(header).bottom_margin(1))
             .block(
                 Block::default()
-                    .title(Span::styled(
-                        " 📊 Monthly Cost Trend by Service ",
-                        Style::default().fg(Color::Rgb(255, 184, 77)).bold(),
-                    ))
+                    .title(Span::styled(title, Style::default().fg(self.theme.teal).bold()))
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Rgb(255, 184, 77))),
-            )
-            .bar_width(2)
-            .bar_gap(0)
-            .group_gap(3)
-            .value_style(Style::default().fg(Color::White));
+                    .border_style(Style::default().fg(self.theme.teal)),
+            );
+        frame.render_widget(table, chunks[1]);
+    }
+
+    fn render_resources(&self, frame: &mut Frame, area: Rect) {
+        let Some(view) = &self.resources else {
+            return;
+        };
+        let accent_color = if self.selected_tab == 0 {
+            self.theme.green
+        } else {
+            self.theme.purple
+        };
+
+        // Cost Explorer clips the range to its resource-level window
+        let clipped = view
+            .data
+            .as_ref()
+            .filter(|d| (d.start, d.end) != view.requested);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(if clipped.is_some() { 1 } else { 0 }),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let breadcrumb = Paragraph::new(Line::from(vec![
+            Span::styled(" 🔎 ", Style::default()),
+            Span::styled(&view.service, Style::default().fg(accent_color).bold()),
+            Span::styled(" › Resources", Style::default().fg(self.theme.text)),
+            Span::styled("  (Esc to go back)", Style::default().fg(self.theme.dim)),
+        ]));
+        frame.render_widget(breadcrumb, chunks[0]);
+
+        if let Some(data) = clipped {
+            let note = Paragraph::new(Line::from(vec![
+                Span::styled(" ⏱  ", Style::default()),
+                Span::styled(
+                    format!(
+                        "Resource-level costs only go back {} days: showing {} of {}",
+                        RESOURCE_DAYS, data.period, view.period
+                    ),
+                    Style::default().fg(self.theme.yellow),
+                ),
+            ]));
+            frame.render_widget(note, chunks[1]);
+        }
+
+        if let Some(id) = self.selected_resource() {
+            let mut spans = vec![
+                Span::styled(" ▶ ", Style::default().fg(accent_color)),
+                Span::styled(id, Style::default().fg(self.theme.text)),
+            ];
+            if view.copied.as_deref() == Some(id) {
+                spans.push(Span::styled("  📋 copied", Style::default().fg(self.theme.green)));
+            }
+            frame.render_widget(Paragraph::new(Line::from(spans)), chunks[3]);
+        }
+
+        let area = chunks[2];
+        if self.is_loading(Query::Resources) {
+            self.render_loading(frame, area);
+        } else if let Some(error) = &view.error {
+            let msg = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("⚠️  {}", error),
+                    Style::default().fg(self.theme.yellow),
+                )),
+            ])
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(self.theme.yellow)),
+            );
+            frame.render_widget(msg, area);
+        } else if let Some(data) = &view.data {
+            self.render_cost_breakdown(frame, area, data, accent_color, "Resource");
+        } else {
+            self.render_no_data(frame, area);
+        }
+    }
+
+    /// Tag key list, or spend by value of the picked key, over the views
+    fn render_tag_explorer(&self, frame: &mut Frame, area: Rect) {
+        let Some(explorer) = &self.tags else {
+            return;
+        };
+        let accent_color = self.theme.pink;
+        let title = match &explorer.key {
+            Some(key) => format!(" 🏷  Tag Explorer › {} · {} ", key, explorer.period),
+            None => format!(" 🏷  Tag Explorer · {} ", explorer.period),
+        };
+        let block = Block::default()
+            .title(Span::styled(title, Style::default().fg(accent_color).bold()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let message = |text: String, color: Color| {
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(text, Style::default().fg(color))),
+            ])
+        };
+        let query = if explorer.key.is_some() { Query::TagSpend } else { Query::TagKeys };
+        if self.is_loading(query) {
+            frame.render_widget(message("⏳ Loading...".to_string(), self.theme.yellow), inner);
+            return;
+        }
+        if let Some(error) = &explorer.error {
+            frame.render_widget(message(format!("⚠️  {}", error), self.theme.yellow), inner);
+            return;
+        }
+
+        match (&explorer.key, &explorer.spend) {
+            (None, _) => {
+                let keys = explorer.keys.as_deref().unwrap_or_default();
+                if keys.is_empty() {
+                    let hint = "No tag keys with spend this month. Activate cost allocation tags in the Billing console.";
+                    frame.render_widget(message(hint.to_string(), self.theme.muted), inner);
+                    return;
+                }
+                let mut lines = vec![Line::from(Span::styled(
+                    format!("{} tag keys · ⏎ spend by value", keys.len()),
+                    Style::default().fg(self.theme.dim),
+                ))];
+                lines.extend(keys.iter().enumerate().map(|(i, key)| {
+                    let style = if i == explorer.selected_key {
+                        selected_style(&self.theme).fg(self.theme.text).bold()
+                    } else {
+                        Style::default().fg(self.theme.text)
+                    };
+                    Line::from(Span::styled(format!(" {} ", key), style))
+                }));
+                // Keep the highlighted key on screen
+                let visible = inner.height.saturating_sub(1) as usize;
+                let scroll = (explorer.selected_key + 1).saturating_sub(visible);
+                frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner);
+            }
+            (Some(_), Some(spend)) => self.render_tag_spend(frame, inner, spend, explorer.selected_value),
+            (Some(_), None) => self.render_no_data(frame, inner),
+        }
+    }
+
+    /// Untagged share and the table of values of one tag key
+    fn render_tag_spend(&self, frame: &mut Frame, area: Rect, spend: &TagSpend, selected: usize) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(3)])
+            .split(area);
+
+        let untagged = spend.untagged_percentage();
+        let untagged_color = if untagged > 20.0 {
+            self.theme.red
+        } else if untagged > 5.0 {
+            self.theme.yellow
+        } else {
+            self.theme.green
+        };
+        let summary = Paragraph::new(Line::from(vec![
+            Span::styled("💰 Total: ", Style::default().fg(self.theme.muted)),
+            Span::styled(
+                format!("${:.2} {}", spend.total_cost, spend.currency),
+                Style::default().fg(self.cost_color(spend.total_cost)).bold(),
+            ),
+            Span::styled("   🚫 Untagged: ", Style::default().fg(self.theme.muted)),
+            Span::styled(format!("{:.1}%", untagged), Style::default().fg(untagged_color).bold()),
+            Span::styled(
+                format!(" (${:.2})", spend.untagged_cost()),
+                Style::default().fg(self.theme.label),
+            ),
+        ]));
+        frame.render_widget(summary, chunks[0]);
+
+        let rows: Vec<Row> = spend
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let color = *self.theme.service(i);
+                let name = match &v.value {
+                    Some(value) => Span::styled(value.clone(), Style::default().fg(self.theme.text)),
+                    None => Span::styled("(untagged)", Style::default().fg(untagged_color).italic()),
+                };
+                let row = Row::new(vec![
+                    Cell::from(Line::from(vec![
+                        Span::styled(
+                            v.provider.as_ref().map(|p| format!("{} · ", p)).unwrap_or_default(),
+                            Style::default().fg(self.theme.brand),
+                        ),
+                        name,
+                    ])),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", v.cost),
+                        Style::default().fg(self.cost_color(v.cost)).bold(),
+                    )),
+                    Cell::from(Span::styled(
+                        format!("{:.1}%", v.percentage),
+                        Style::default().fg(self.theme.label),
+                    )),
+                    Cell::from(Span::styled(create_bar(v.percentage), Style::default().fg(color))),
+                ]);
+                if i == selected {
+                    row.style(selected_style(&self.theme))
+                } else {
+                    row
+                }
+            })
+            .collect();
+
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(40),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Min(20),
+            ],
+        )
+        .header(Row::new(vec![
+            Cell::from(Span::styled("Value", header_style)),
+            Cell::from(Span::styled("Cost", header_style)),
+            Cell::from(Span::styled("%", header_style)),
+            Cell::from(Span::styled("Distribution", header_style)),
+        ]));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    /// Tag coverage popup: each required key's coverage and trend, then the
+    /// services or accounts missing the most tagged spend
+    fn render_coverage(&self, frame: &mut Frame, area: Rect) {
+        let Some(view) = &self.coverage else {
+            return;
+        };
+        let accent_color = self.theme.cyan;
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" 📐 Tag Coverage · {} ", view.period),
+                Style::default().fg(accent_color).bold(),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let message = |text: String, color: Color| {
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(text, Style::default().fg(color))),
+            ])
+            .wrap(Wrap { trim: true })
+        };
+        if self.required_tags.is_empty() {
+            let hint = "No required tag keys. Start with --require-tag team,cost-center to check them.";
+            frame.render_widget(message(hint.to_string(), self.theme.muted), inner);
+            return;
+        }
+        if self.is_loading(Query::Coverage) {
+            frame.render_widget(message("⏳ Loading...".to_string(), self.theme.yellow), inner);
+            return;
+        }
+        if let Some(error) = &view.error {
+            frame.render_widget(message(format!("⚠️  {}", error), self.theme.yellow), inner);
+            return;
+        }
+        let Some(report) = &view.report else {
+            self.render_no_data(frame, inner);
+            return;
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(report.keys.len() as u16 + 2),
+                Constraint::Length(2),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        let mut summary = vec![Line::from(vec![
+            Span::styled("💰 Total: ", Style::default().fg(self.theme.muted)),
+            Span::styled(
+                format!("${:.2} {}", report.total_cost, report.currency),
+                Style::default().fg(self.cost_color(report.total_cost)).bold(),
+            ),
+        ])];
+        let key_width = report.keys.iter().map(|k| k.chars().count()).max().unwrap_or(0).min(20);
+        for (i, key) in report.keys.iter().enumerate() {
+            let coverage = report.coverage(i);
+            let change = match report.change(i) {
+                Some(change) if change.abs() < 0.05 => {
+                    Span::styled("  ─ 0.0 pts", Style::default().fg(self.theme.dim))
+                }
+                Some(change) if change > 0.0 => Span::styled(
+                    format!("  ▲ {:+.1} pts", change),
+                    Style::default().fg(self.theme.green),
+                ),
+                Some(change) => Span::styled(
+                    format!("  ▼ {:+.1} pts", change),
+                    Style::default().fg(self.theme.red),
+                ),
+                None => Span::raw(""),
+            };
+            let trend: Vec<f64> = report.trend.iter().filter_map(|m| m.coverage.get(i).copied()).collect();
+            summary.push(Line::from(vec![
+                Span::styled(
+                    format!("🏷  {:<key_width$} ", truncate_service_name(key, key_width)),
+                    Style::default().fg(self.theme.text),
+                ),
+                Span::styled(
+                    format!("{:>6.1}% ", coverage),
+                    Style::default().fg(coverage_color(coverage, &self.theme)).bold(),
+                ),
+                Span::styled(create_bar(coverage), Style::default().fg(coverage_color(coverage, &self.theme))),
+                change,
+                Span::styled(
+                    format!("  {}", sparkline(&trend)),
+                    Style::default().fg(accent_color),
+                ),
+            ]));
+        }
+        frame.render_widget(Paragraph::new(summary), chunks[0]);
+
+        let tab = |label: &str, active: bool| {
+            if active {
+                Span::styled(
+                    format!(" {} ", label),
+                    Style::default().fg(self.theme.on_accent).bg(accent_color).bold(),
+                )
+            } else {
+                Span::styled(format!(" {} ", label), Style::default().fg(self.theme.muted))
+            }
+        };
+        let scope = Paragraph::new(Line::from(vec![
+            tab("Services", !view.accounts),
+            tab("Accounts", view.accounts),
+            Span::styled(
+                "  most untagged spend first",
+                Style::default().fg(self.theme.dim),
+            ),
+        ]));
+        frame.render_widget(scope, chunks[1]);
+
+        let (item, rows) = if view.accounts {
+            ("Account", &report.accounts)
+        } else {
+            ("Service", &report.services)
+        };
+        let show_provider = rows.iter().any(|r| r.provider.is_some());
+        let table_rows: Vec<Row> = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                coverage_table_row(i, row, report.keys.len(), show_provider, i == view.selected, &self.settings.thresholds, &self.theme)
+            })
+            .collect();
+
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let mut widths = vec![Constraint::Length(4)];
+        let mut header = vec![Cell::from(Span::styled("#", header_style))];
+        if show_provider {
+            widths.push(Constraint::Length(10));
+            header.push(Cell::from(Span::styled("Provider", header_style)));
+        }
+        widths.extend([Constraint::Min(16), Constraint::Length(12)]);
+        header.extend([
+            Cell::from(Span::styled(item, header_style)),
+            Cell::from(Span::styled("Cost", header_style)),
+        ]);
+        for key in &report.keys {
+            widths.push(Constraint::Length(12));
+            header.push(Cell::from(Span::styled(truncate_service_name(key, 11), header_style)));
+        }
+        widths.push(Constraint::Length(12));
+        header.push(Cell::from(Span::styled("Untagged", header_style)));
+
+        // Keep the highlighted row on screen
+        let visible = chunks[2].height.saturating_sub(1) as usize;
+        let skip = (view.selected + 1).saturating_sub(visible);
+        let table = Table::new(table_rows.into_iter().skip(skip), widths).header(Row::new(header));
+        frame.render_widget(table, chunks[2]);
+
+        if let Some(exported) = &view.exported {
+            let status = Paragraph::new(Span::styled(exported.clone(), Style::default().fg(self.theme.green)));
+            frame.render_widget(status, chunks[3]);
+        }
+    }
+
+    /// Month, service and cost of the trend bar under the pointer, next to it
+    fn render_bar_tooltip(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        (x, y): (u16, u16),
+        (bar_width, group_gap): (u16, u16),
+        services: &[String],
+    ) {
+        // Bars fill the chart's inner area above the line of month labels
+        let bars = area.inner(Margin::new(1, 1));
+        let bars = Rect { height: bars.height.saturating_sub(1), ..bars };
+        if services.is_empty() || !bars.contains(Position::new(x, y)) {
+            return;
+        }
+        let group_width = services.len() as u16 * bar_width + group_gap;
+        let offset = x - bars.x;
+        let bar = ((offset % group_width) / bar_width) as usize;
+        let (Some(month), Some(service)) = (self.monthly_trend.get((offset / group_width) as usize), services.get(bar))
+        else {
+            return;
+        };
+        let cost = month.breakdown.iter().find(|s| &s.service == service).map_or(0.0, |s| s.cost);
+
+        let lines = vec![
+            Line::from(Span::styled(month.period.clone(), Style::default().fg(self.theme.text).bold())),
+            Line::from(vec![
+                Span::styled("██ ", Style::default().fg(*self.theme.service(bar))),
+                Span::styled(truncate_service_name(service, 30), Style::default().fg(self.theme.text)),
+            ]),
+            Line::from(Span::styled(format!("${:.2}", cost), Style::default().fg(self.cost_color(cost)).bold())),
+        ];
+        let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4).min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        // Right of the pointer, or left of it near the edge, inside the chart
+        let left = match x + 2 + width <= area.right() {
+            true => x + 2,
+            false => x.saturating_sub(width + 1).max(area.x),
+        };
+        let top = y.saturating_sub(height / 2).clamp(area.y, area.bottom() - height);
+        let tooltip = Rect { x: left, y: top, width, height };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(*self.theme.service(bar)))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, tooltip);
+        frame.render_widget(Paragraph::new(lines).block(block), tooltip);
+    }
+
+    /// Monthly cost budget drawn on the trend view: the one with the
+    /// biggest limit, if any has history
+    fn trend_budget(&self) -> Option<&Budget> {
+        self.budgets
+            .iter()
+            .flatten()
+            .filter(|b| b.time_unit == "MONTHLY" && b.budget_type == "COST" && !b.history.is_empty())
+            .max_by(|a, b| a.limit.total_cmp(&b.limit))
+    }
+
+    /// Spend per month against the budget line, for the months of the trend
+    fn render_budget_chart(&self, frame: &mut Frame, area: Rect, budget: &Budget) {
+        let accent_color = self.theme.red;
+        let periods = &budget.history[budget.history.len().saturating_sub(self.monthly_trend.len().max(1))..];
+
+        // Each month's bar is a few columns wide, the limit a step line
+        let (mut under, mut over, mut limit) = (Vec::new(), Vec::new(), Vec::new());
+        for (i, period) in periods.iter().enumerate() {
+            let bars = if period.actual > period.limit { &mut over } else { &mut under };
+            bars.extend((-3..=3).map(|offset| (i as f64 + offset as f64 * 0.08, period.actual)));
+            limit.extend([(i as f64 - 0.5, period.limit), (i as f64 + 0.5, period.limit)]);
+        }
+        let top = periods.iter().map(|p| p.actual.max(p.limit)).fold(0.0, f64::max) * 1.1;
+        // Labels are spread evenly, so only the first and last month line up
+        let labels: Vec<Span> = [periods.first(), periods.last()]
+            .into_iter()
+            .flatten()
+            .map(|p| Span::styled(p.start.format("%b").to_string(), Style::default().fg(self.theme.text)))
+            .collect();
+
+        let chart = Chart::new(vec![
+            Dataset::default()
+                .name("Spend")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Bar)
+                .style(Style::default().fg(self.theme.teal))
+                .data(&under),
+            Dataset::default()
+                .name("Over")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Bar)
+                .style(Style::default().fg(accent_color))
+                .data(&over),
+            Dataset::default()
+                .name("Limit")
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(self.theme.yellow))
+                .data(&limit),
+        ])
+        .block(
+            Block::default()
+                .title(Span::styled(
+                    format!(" 💰 Budget: {} ", budget.name),
+                    Style::default().fg(self.theme.orange).bold(),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(self.theme.orange)),
+        )
+        .x_axis(
+            Axis::default()
+                .bounds([-0.5, periods.len() as f64 - 0.5])
+                .labels(labels)
+                .style(Style::default().fg(self.theme.dim)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, top.max(1.0)])
+                .labels([
+                    Span::styled("0", Style::default().fg(self.theme.muted)),
+                    Span::styled(budget_amount(top.round(), &budget.unit).replace(".00", ""), Style::default().fg(self.theme.muted)),
+                ])
+                .style(Style::default().fg(self.theme.dim)),
+        );
+        frame.render_widget(chart, area);
+    }
+
+    /// Budgets popup: a gauge of each budget's spend and forecast against
+    /// its limit, colored by how close it is to breaching. AWS Budgets come
+    /// first, then the budgets from the config
+    fn render_budgets(&self, frame: &mut Frame, area: Rect) {
+        let top = self.budget_panel.unwrap_or(0);
+        let accent_color = self.theme.orange;
+        let budgets = self.all_budgets();
+        let errors: Vec<&String> = self.budgets_error.iter().chain(&self.local_budgets_error).collect();
+        // Errors get a line each above the budgets they didn't stop
+        let notes = if budgets.is_empty() { 0 } else { errors.len() as u16 };
+        // A title line, the spend gauge, the forecast line and a gap each
+        let shown = ((area.height.saturating_sub(2 + notes) / 4).max(1)) as usize;
+        let count = budgets.len();
+        let top = top.min(count.saturating_sub(shown));
+        let title = if count > shown {
+            format!(" 💰 Budgets {}–{} of {} ", top + 1, (top + shown).min(count), count)
+        } else {
+            " 💰 Budgets ".to_string()
+        };
+        let block = Block::default()
+            .title(Span::styled(title, Style::default().fg(accent_color).bold()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let message = |text: String, color: Color| {
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(text, Style::default().fg(color))),
+            ])
+            .wrap(Wrap { trim: true })
+        };
+        if budgets.is_empty() && (self.is_loading(Query::Budgets) || self.is_loading(Query::LocalBudgets)) {
+            frame.render_widget(message("⏳ Loading budgets...".to_string(), self.theme.yellow), inner);
+            return;
+        }
+        if budgets.is_empty() && !errors.is_empty() {
+            let text = errors.iter().map(|e| format!("⚠️  {}", e)).collect::<Vec<_>>().join("\n\n");
+            frame.render_widget(message(text, self.theme.yellow), inner);
+            return;
+        }
+        if budgets.is_empty() {
+            frame.render_widget(message("📭 No budgets with a fixed limit".to_string(), self.theme.muted), inner);
+            return;
+        }
+
+        let [notes_area, inner] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(notes), Constraint::Min(0)])
+            .areas(inner);
+        let notes: Vec<Line> = errors
+            .iter()
+            .map(|e| Line::from(Span::styled(format!("⚠️  {}", e), Style::default().fg(self.theme.yellow))))
+            .collect();
+        frame.render_widget(Paragraph::new(notes), notes_area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(4); shown])
+            .split(inner);
+
+        for (budget, area) in budgets.iter().skip(top).zip(rows.iter()) {
+            let status = budget.status();
+            let color = budget_color(status, &self.theme);
+            let lines = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+                .split(*area);
+
+            let mut title = vec![Span::styled(budget.name.clone(), Style::default().fg(self.theme.text).bold())];
+            if let Some(scope) = &budget.scope {
+                title.push(Span::styled(format!("  local · {}", scope), Style::default().fg(self.theme.blue)));
+            }
+            title.push(Span::styled(
+                format!("  {} · {}", budget.time_unit.to_lowercase(), budget.period_name()),
+                Style::default().fg(self.theme.dim),
+            ));
+            title.push(Span::styled(format!("  {}", status.label()), Style::default().fg(color).bold()));
+            // Budgets from the config say which of their thresholds was passed
+            if let Some(threshold) = budget.crossed_threshold().filter(|_| !budget.thresholds.is_empty()) {
+                title.push(Span::styled(format!(" · past {:.0}%", threshold), Style::default().fg(color)));
+            }
+            let title = Line::from(title);
+            frame.render_widget(Paragraph::new(title), lines[0]);
+
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(color).bg(self.theme.track))
+                .ratio((budget.percentage() / 100.0).clamp(0.0, 1.0))
+                .label(Span::styled(
+                    format!(
+                        "{} of {} ({:.0}%)",
+                        budget_amount(budget.actual, &budget.unit),
+                        budget_amount(budget.limit, &budget.unit),
+                        budget.percentage()
+                    ),
+                    Style::default().fg(self.theme.text).bold(),
+                ))
+                .use_unicode(true);
+            frame.render_widget(gauge, lines[1]);
+
+            if let (Some(forecasted), Some(percentage)) = (budget.forecasted, budget.forecast_percentage()) {
+                let forecast_color = if percentage >= 100.0 { budget_color(BudgetStatus::ForecastOver, &self.theme) } else { self.theme.label };
+                let forecast = LineGauge::default()
+                    .filled_style(Style::default().fg(forecast_color))
+                    .unfilled_style(Style::default().fg(self.theme.track))
+                    .ratio((percentage / 100.0).clamp(0.0, 1.0))
+                    .label(Span::styled(
+                        format!("forecast {} ({:.0}%)", budget_amount(forecasted, &budget.unit), percentage),
+                        Style::default().fg(forecast_color),
+                    ));
+                frame.render_widget(forecast, lines[2]);
+            }
+        }
+    }
 
-        for group in &bar_groups {
-            bar_chart = bar_chart.data(group.clone());
+    /// Anomalies popup: the current month's daily spikes with their
+    /// expected and actual cost
+    fn render_anomalies(&self, frame: &mut Frame, area: Rect) {
+        let selected = self.anomaly_panel.unwrap_or(0);
+        let accent_color = self.theme.red;
+        let range = match (&self.anomalies, &self.current_month) {
+            (Some(report), _) => crate::periods::range_name(report.start, report.end),
+            (None, Some(month)) => month.period.clone(),
+            (None, None) => String::new(),
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" 🚨 Anomalies · {} ", range),
+                Style::default().fg(accent_color).bold(),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let message = |text: String, color: Color| {
+            Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(text, Style::default().fg(color))),
+            ])
+            .wrap(Wrap { trim: true })
+        };
+        if self.is_loading(Query::Anomalies) || self.is_loading(Query::CurrentMonth) {
+            frame.render_widget(message("⏳ Loading daily costs...".to_string(), self.theme.yellow), inner);
+            return;
+        }
+        if let Some(error) = &self.anomalies_error {
+            frame.render_widget(message(format!("⚠️  {}", error), self.theme.yellow), inner);
+            return;
         }
+        let Some(report) = &self.anomalies else {
+            self.render_no_data(frame, inner);
+            return;
+        };
 
-        frame.render_widget(bar_chart, chunks[0]);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(3)])
+            .split(inner);
 
-        // Legend and summary table side by side
-        let bottom_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-            .split(chunks[1]);
+        let detector = &report.detector;
+        let found = match report.anomalies.len() {
+            0 => Span::styled("✅ No spikes found", Style::default().fg(self.theme.green)),
+            count => Span::styled(
+                format!(
+                    "💸 {} spike{}, ${:.2} {} over baseline",
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    report.total_excess(),
+                    report.currency
+                ),
+                Style::default().fg(accent_color).bold(),
+            ),
+        };
+        let summary = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("🔎 Baseline: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    format!("{} median of {} days", detector.baseline.name(), detector.window),
+                    Style::default().fg(self.theme.text),
+                ),
+                Span::styled(
+                    format!("  · flagged above {:.1} MADs", detector.sensitivity),
+                    Style::default().fg(self.theme.dim),
+                ),
+            ]),
+            Line::from(found),
+        ])
+        .wrap(Wrap { trim: true });
+        frame.render_widget(summary, chunks[0]);
 
-        // Service legend
-        let legend_items: Vec<Line> = top_services
+        let show_provider = report.anomalies.iter().any(|a| a.provider.is_some());
+        let rows: Vec<Row> = report
+            .anomalies
             .iter()
             .enumerate()
-            .map(|(i, service)| {
-                let color = SERVICE_COLORS[i % SERVICE_COLORS.len()];
-                let display_name = truncate_service_name(service, 25);
-                Line::from(vec![
-                    Span::styled("██ ", Style::default().fg(color)),
-                    Span::styled(display_name, Style::default().fg(Color::White)),
-                ])
+            .map(|(i, anomaly)| {
+                let style = if i == selected {
+                    selected_style(&self.theme)
+                } else {
+                    Style::default()
+                };
+                let change = match anomaly.change_percentage() {
+                    Some(change) => format!("+{:.0}%", change),
+                    None => "new".to_string(),
+                };
+                let mut cells = vec![
+                    Cell::from(Span::styled(format!("#{}", i + 1), Style::default().fg(self.theme.dim))),
+                    Cell::from(Span::styled(
+                        anomaly.date.format("%a %b %d").to_string(),
+                        Style::default().fg(self.theme.text),
+                    )),
+                    Cell::from(Span::styled(anomaly.days.to_string(), Style::default().fg(self.theme.label))),
+                ];
+                if show_provider {
+                    cells.push(Cell::from(Span::styled(
+                        anomaly.provider.clone().unwrap_or_default(),
+                        Style::default().fg(self.theme.brand),
+                    )));
+                }
+                cells.extend([
+                    Cell::from(Span::styled(
+                        truncate_service_name(&anomaly.service, 40),
+                        Style::default().fg(self.theme.text),
+                    )),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", anomaly.expected),
+                        Style::default().fg(self.theme.label),
+                    )),
+                    Cell::from(Span::styled(
+                        format!("${:.2}", anomaly.actual),
+                        Style::default().fg(self.cost_color(anomaly.actual)).bold(),
+                    )),
+                    Cell::from(Span::styled(change, Style::default().fg(accent_color))),
+                    Cell::from(Span::styled(
+                        format!("{:.1}", anomaly.score),
+                        Style::default().fg(self.theme.label),
+                    )),
+                ]);
+                Row::new(cells).style(style)
             })
             .collect();
 
-        let legend = Paragraph::new(legend_items)
-            .block(
-                Block::default()
-                    .title(Span::styled(
-                        " 🎨 Services ",
-                        Style::default().fg(Color::Rgb(78, 205, 196)).bold(),
-                    ))
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Rgb(78, 205, 196)))
-                    .padding(Padding::horizontal(1)),
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let mut widths = vec![Constraint::Length(4), Constraint::Length(11), Constraint::Length(4)];
+        let mut header = vec![
+            Cell::from(Span::styled("#", header_style)),
+            Cell::from(Span::styled("Date", header_style)),
+            Cell::from(Span::styled("Days", header_style)),
+        ];
+        if show_provider {
+            widths.push(Constraint::Length(10));
+            header.push(Cell::from(Span::styled("Provider", header_style)));
+        }
+        widths.extend([
+            Constraint::Min(16),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ]);
+        header.extend(
+            ["Service", "Expected", "Actual", "Change", "MADs"]
+                .map(|title| Cell::from(Span::styled(title, header_style))),
+        );
+
+        // Keep the highlighted row on screen
+        let visible = chunks[1].height.saturating_sub(1) as usize;
+        let skip = (selected + 1).saturating_sub(visible);
+        let table = Table::new(rows.into_iter().skip(skip), widths).header(Row::new(header));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    fn render_switcher(&self, frame: &mut Frame, area: Rect) {
+        let Some(switcher) = &self.switcher else {
+            return;
+        };
+        let accent_color = self.theme.blue;
+        let rows = self.switcher_rows();
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" 👤 Profiles · {} ", rows.len()),
+                Style::default().fg(accent_color).bold(),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if rows.is_empty() {
+            let text = "📭 No profiles in ~/.aws/config or ~/.aws/credentials";
+            frame.render_widget(
+                Paragraph::new(vec![Line::from(""), Line::from(Span::styled(text, Style::default().fg(self.theme.muted)))]),
+                inner,
             );
-        frame.render_widget(legend, bottom_chunks[0]);
+            return;
+        }
 
-        // Summary table with totals
-        let rows: Vec<Row> = self
-            .monthly_trend
+        let error_height = if switcher.error.is_some() { 3 } else { 0 };
+        let [table_area, error_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(error_height)])
+            .areas(inner);
+
+        let table_rows: Vec<Row> = rows
             .iter()
             .enumerate()
-            .map(|(i, d)| {
-                let is_current = i == self.monthly_trend.len() - 1;
-                let row_style = if is_current {
-                    Style::default().fg(Color::Rgb(108, 255, 108)).add_modifier(Modifier::BOLD)
+            .map(|(i, (name, profile))| {
+                let style = if i == switcher.selected {
+                    selected_style(&self.theme)
                 } else {
                     Style::default()
                 };
-                
-                // Calculate month-over-month change
-                let change = if i > 0 {
-                    let prev = self.monthly_trend[i - 1].total_cost;
-                    if prev > 0.0 {
-                        ((d.total_cost - prev) / prev) * 100.0
-                    } else {
-                        0.0
-                    }
-                } else {
-                    0.0
-                };
-
-                let change_style = if change > 10.0 {
-                    Style::default().fg(Color::Rgb(255, 107, 107)) // Red for increase
-                } else if change < -10.0 {
-                    Style::default().fg(Color::Rgb(108, 255, 108)) // Green for decrease
+                let status = if *name == self.profile {
+                    Span::styled("● shown", Style::default().fg(self.theme.green))
                 } else {
-                    Style::default().fg(Color::Yellow)
+                    match self.cached.get(*name) {
+                        Some(loaded) if !loaded.pending.is_empty() => {
+                            Span::styled("⏳ loading", Style::default().fg(self.theme.yellow))
+                        }
+                        Some(_) => Span::styled("✓ cached", Style::default().fg(self.theme.teal)),
+                        None => Span::raw(""),
+                    }
                 };
-
-                let change_str = if i > 0 {
-                    format!("{:+.1}%", change)
-                } else {
-                    "—".to_string()
+                let region = profile.and_then(|p| p.region.clone()).unwrap_or_else(|| "—".to_string());
+                let credentials = match profile {
+                    Some(p) if p.credential_type.is_supported() => {
+                        Span::styled(p.credential_type.label(), Style::default().fg(self.theme.label))
+                    }
+                    Some(p) => Span::styled(
+                        format!("{} (unsupported)", p.credential_type.label()),
+                        Style::default().fg(self.theme.dim),
+                    ),
+                    None => Span::styled("—", Style::default().fg(self.theme.dim)),
                 };
-
                 Row::new(vec![
-                    Cell::from(Span::styled(&d.period, Style::default().fg(Color::White))),
-                    Cell::from(Span::styled(
-                        format!("${:.2}", d.total_cost),
-                        Style::default().fg(get_cost_color(d.total_cost)).bold(),
-                    )),
-                    Cell::from(Span::styled(change_str, change_style)),
+                    Cell::from(Span::styled(name.to_string(), Style::default().fg(self.theme.text).bold())),
+                    Cell::from(Span::styled(region, Style::default().fg(self.theme.brand))),
+                    Cell::from(credentials),
+                    Cell::from(status),
                 ])
-                .style(row_style)
+                .style(style)
             })
             .collect();
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Percentage(45),
-                Constraint::Percentage(30),
-                Constraint::Percentage(25),
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(Span::styled("Period", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(Span::styled("Total", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(Span::styled("Change", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-            ])
-            .bottom_margin(1),
-        )
-        .block(
-            Block::default()
-                .title(Span::styled(
-                    " 📋 Monthly Totals ",
-                    Style::default().fg(Color::Rgb(255, 230, 109)).bold(),
-                ))
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(255, 230, 109))),
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let header = Row::new(
+            ["Profile", "Region", "Credentials", "Data"].map(|title| Cell::from(Span::styled(title, header_style))),
         );
+        let widths = [
+            Constraint::Min(16),
+            Constraint::Length(16),
+            Constraint::Length(28),
+            Constraint::Length(10),
+        ];
 
-        frame.render_widget(table, bottom_chunks[1]);
+        // Keep the highlighted row on screen
+        let visible = table_area.height.saturating_sub(1) as usize;
+        let skip = (switcher.selected + 1).saturating_sub(visible);
+        let table = Table::new(table_rows.into_iter().skip(skip), widths).header(header);
+        frame.render_widget(table, table_area);
+
+        if let Some(error) = &switcher.error {
+            let error = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(format!("⚠️  {}", error), Style::default().fg(self.theme.red))),
+            ])
+            .wrap(Wrap { trim: true });
+            frame.render_widget(error, error_area);
+        }
     }
 
-    fn render_cost_breakdown(&self, frame: &mut Frame, area: Rect, data: &CostData, accent_color: Color) {
-        // Split into summary and table (full width, no side chart)
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Length(5), Constraint::Min(5)])
-            .split(area);
+    /// Summary and table of `data`'s rows, each an `item` (service, resource...)
+    fn render_cost_breakdown(&self, frame: &mut Frame, area: Rect, data: &CostData, accent_color: Color, item: &str) {
+        // Daily spikes and forecasts are only worked out for the current month's services
+        let is_current = item == "Service" && self.current_month.as_ref().is_some_and(|m| m.start == data.start);
+        let spikes = self.anomalies.as_ref().filter(|a| is_current && a.start >= data.start && a.start < data.end);
+        let forecast = self.forecast.as_ref().filter(|f| is_current && f.today >= data.start && f.today < f.month_end);
 
         // Summary panel with more color
-        let summary = Paragraph::new(vec![
+        let mut summary = vec![
             Line::from(vec![
-                Span::styled("📅 Period: ", Style::default().fg(Color::Gray)),
-                Span::styled(&data.period, Style::default().fg(Color::White).bold()),
+                Span::styled("📅 Period: ", Style::default().fg(self.theme.muted)),
+                Span::styled(&data.period, Style::default().fg(self.theme.text).bold()),
             ]),
             Line::from(vec![
-                Span::styled("💰 Total Cost: ", Style::default().fg(Color::Gray)),
+                Span::styled("💰 Total Cost: ", Style::default().fg(self.theme.muted)),
                 Span::styled(
                     format!("${:.2}", data.total_cost),
-                    Style::default().fg(get_cost_color(data.total_cost)).bold(),
+                    Style::default().fg(self.cost_color(data.total_cost)).bold(),
                 ),
                 Span::styled(
                     format!(" {}", data.currency),
-                    Style::default().fg(Color::DarkGray),
+                    Style::default().fg(self.theme.dim),
                 ),
                 Span::styled(
-                    format!("  ({} services)", data.breakdown.len()),
-                    Style::default().fg(Color::Rgb(170, 170, 170)),
+                    format!("  ({} {}s)", data.breakdown.len(), item.to_lowercase()),
+                    Style::default().fg(self.theme.label),
                 ),
             ]),
-        ])
+        ];
+        if let Some(spikes) = spikes {
+            let count = spikes.anomalies.len();
+            let found = if count == 0 {
+                Span::styled("none", Style::default().fg(self.theme.green))
+            } else {
+                Span::styled(
+                    format!(
+                        "{} spike{}, ${:.2} over baseline",
+                        count,
+                        if count == 1 { "" } else { "s" },
+                        spikes.total_excess()
+                    ),
+                    Style::default().fg(self.theme.red).bold(),
+                )
+            };
+            summary.push(Line::from(vec![
+                Span::styled("🔎 Anomalies: ", Style::default().fg(self.theme.muted)),
+                found,
+                Span::styled("  (a for details)", Style::default().fg(self.theme.dim)),
+            ]));
+        } else if is_current && self.is_loading(Query::Anomalies) {
+            summary.push(Line::from(vec![
+                Span::styled("🔎 Anomalies: ", Style::default().fg(self.theme.muted)),
+                Span::styled("checking daily costs...", Style::default().fg(self.theme.dim)),
+            ]));
+        }
+        if let Some(forecast) = forecast {
+            let month_end = forecast.month_end - chrono::Duration::days(1);
+            summary.push(Line::from(vec![
+                Span::styled("📈 Forecast: ", Style::default().fg(self.theme.muted)),
+                Span::styled(
+                    format!("${:.2}", forecast.month.total()),
+                    Style::default().fg(self.cost_color(forecast.month.total())).bold(),
+                ),
+                Span::styled(format!(" by {} via ", month_end.format("%b %d")), Style::default().fg(self.theme.muted)),
+                Span::styled(forecast.method.name(), Style::default().fg(self.theme.blue)),
+                Span::styled(
+                    format!(
+                        "  (${:.2} – ${:.2}, {}: ${:.2})",
+                        forecast.month.low(),
+                        forecast.month.high(),
+                        crate::periods::quarter_name(month_end),
+                        forecast.quarter.total(),
+                    ),
+                    Style::default().fg(self.theme.label),
+                ),
+            ]));
+        } else if is_current && self.is_loading(Query::Forecast) {
+            summary.push(Line::from(vec![
+                Span::styled("📈 Forecast: ", Style::default().fg(self.theme.muted)),
+                Span::styled("projecting daily costs...", Style::default().fg(self.theme.dim)),
+            ]));
+        }
+
+        // Split into summary and table (full width, no side chart)
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(summary.len().max(3) as u16 + 2), Constraint::Min(5)])
+            .split(area);
+
+        let summary = Paragraph::new(summary)
         .block(
             Block::default()
                 .title(Span::styled(
@@ -540,6 +3469,9 @@ impl App {
         );
         frame.render_widget(summary, chunks[0]);
 
+        // Merged multi-cloud data gets a provider column
+        let show_provider = data.breakdown.iter().any(|s| s.provider.is_some());
+
         // Service breakdown table with colored bars - full width
         let rows: Vec<Row> = data
             .breakdown
@@ -547,99 +3479,143 @@ impl App {
             .enumerate()
             .map(|(i, s)| {
                 let is_selected = i == self.selected_row;
-                let service_color = SERVICE_COLORS[i % SERVICE_COLORS.len()];
+                let service_color = *self.theme.service(i);
                 
                 let base_style = if is_selected {
-                    Style::default().bg(Color::Rgb(60, 60, 80))
+                    selected_style(&self.theme)
                 } else {
                     Style::default()
                 };
 
-                Row::new(vec![
+                let mut cells = vec![
                     // Rank with color
                     Cell::from(Span::styled(
                         format!("#{}", i + 1),
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(self.theme.dim),
                     )),
                     // Color indicator
                     Cell::from(Span::styled("██", Style::default().fg(service_color))),
-                    // Service name
-                    Cell::from(Span::styled(
-                        truncate_service_name(&s.service, 40),
-                        Style::default().fg(Color::White),
-                    )),
+                ];
+                if show_provider {
+                    cells.push(Cell::from(Span::styled(
+                        s.provider.clone().unwrap_or_default(),
+                        Style::default().fg(self.theme.brand),
+                    )));
+                }
+                cells.extend([
+                    // Service name, flagged when it spiked
+                    match spikes.filter(|a| a.of_service(&s.service, s.provider.as_deref()).next().is_some()) {
+                        Some(_) => Cell::from(Line::from(vec![
+                            Span::styled(truncate_service_name(&s.service, 38), Style::default().fg(self.theme.text)),
+                            Span::styled(" ⚠", Style::default().fg(self.theme.red).bold()),
+                        ])),
+                        None => Cell::from(Span::styled(
+                            truncate_service_name(&s.service, 40),
+                            Style::default().fg(self.theme.text),
+                        )),
+                    },
                     // Cost with color based on amount
                     Cell::from(Span::styled(
                         format!("${:.2}", s.cost),
-                        Style::default().fg(get_cost_color(s.cost)).bold(),
+                        Style::default().fg(self.cost_color(s.cost)).bold(),
                     )),
                     // Percentage
                     Cell::from(Span::styled(
                         format!("{:.1}%", s.percentage),
-                        Style::default().fg(Color::Rgb(170, 170, 170)),
+                        Style::default().fg(self.theme.label),
                     )),
-                    // Colored progress bar
-                    Cell::from(Span::styled(
-                        create_bar(s.percentage),
-                        Style::default().fg(service_color),
-                    )),
-                ])
-                .style(base_style)
+                ]);
+                // Projected month-end cost
+                if let Some(forecast) = forecast {
+                    let projected = forecast.of_service(&s.service, s.provider.as_deref()).map(|f| f.month.total());
+                    cells.push(Cell::from(Span::styled(
+                        projected.map_or_else(|| "-".to_string(), |cost| format!("${:.2}", cost)),
+                        Style::default().fg(self.theme.blue),
+                    )));
+                }
+                // Colored progress bar
+                cells.push(Cell::from(Span::styled(
+                    create_bar(s.percentage),
+                    Style::default().fg(service_color),
+                )));
+                Row::new(cells).style(base_style)
             })
             .collect();
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(4),     // Rank
-                Constraint::Length(3),     // Color
-                Constraint::Percentage(40), // Service
-                Constraint::Length(12),    // Cost
-                Constraint::Length(8),     // Percentage
-                Constraint::Min(20),       // Bar
-            ],
-        )
-        .header(
-            Row::new(vec![
-                Cell::from(Span::styled("#", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(""),
-                Cell::from(Span::styled("Service", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(Span::styled("Cost", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(Span::styled("%", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-                Cell::from(Span::styled("Distribution", Style::default().fg(Color::Rgb(255, 230, 109)).bold())),
-            ])
-            .bottom_margin(1),
-        )
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let mut widths = vec![
+            Constraint::Length(4), // Rank
+            Constraint::Length(3), // Color
+        ];
+        let mut header = vec![Cell::from(Span::styled("#", header_style)), Cell::from("")];
+        if show_provider {
+            widths.push(Constraint::Length(10)); // Provider
+            header.push(Cell::from(Span::styled("Provider", header_style)));
+        }
+        widths.extend([
+            Constraint::Percentage(40), // Service
+            Constraint::Length(12),    // Cost
+            Constraint::Length(8),     // Percentage
+        ]);
+        header.extend([
+            Cell::from(Span::styled(item, header_style)),
+            Cell::from(Span::styled("Cost", header_style)),
+            Cell::from(Span::styled("%", header_style)),
+        ]);
+        if forecast.is_some() {
+            widths.push(Constraint::Length(12)); // Month-end
+            header.push(Cell::from(Span::styled("Month-end", header_style)));
+        }
+        widths.push(Constraint::Min(20)); // Bar
+        header.push(Cell::from(Span::styled("Distribution", header_style)));
+
+        let skip = self.scrolled_rows(chunks[1]);
+        let table = Table::new(rows.into_iter().skip(skip), widths)
+        .header(Row::new(header).bottom_margin(1))
         .block(
             Block::default()
                 .title(Span::styled(
-                    " 📋 Service Breakdown ",
-                    Style::default().fg(Color::Rgb(78, 205, 196)).bold(),
+                    format!(" 📋 {} Breakdown ", item),
+                    Style::default().fg(self.theme.teal).bold(),
                 ))
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(78, 205, 196))),
+                .border_style(Style::default().fg(self.theme.teal)),
         );
 
         frame.render_widget(table, chunks[1]);
     }
 
+    /// Rows to skip so the selected one stays in a bordered table with a
+    /// two-line header; recorded so a click can pick the row under it
+    fn scrolled_rows(&self, table_area: Rect) -> usize {
+        let body = Rect {
+            x: table_area.x + 1,
+            y: table_area.y + 3,
+            width: table_area.width.saturating_sub(2),
+            height: table_area.height.saturating_sub(4),
+        };
+        let skip = (self.selected_row + 1).saturating_sub((body.height as usize).max(1));
+        self.hits.borrow_mut().rows = Some((body, skip));
+        skip
+    }
+
     fn render_loading(&self, frame: &mut Frame, area: Rect) {
         let loading = Paragraph::new(vec![
             Line::from(""),
             Line::from(Span::styled(
                 "⏳ Loading cost data from AWS...",
-                Style::default().fg(Color::Rgb(255, 230, 109)).bold(),
+                Style::default().fg(self.theme.yellow).bold(),
             )),
             Line::from(""),
             Line::from(Span::styled(
                 "This may take a few seconds",
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(self.theme.dim),
             )),
         ])
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(255, 230, 109))),
+                .border_style(Style::default().fg(self.theme.yellow)),
         );
         frame.render_widget(loading, area);
     }
@@ -648,31 +3624,31 @@ impl App {
         let error_msg = Paragraph::new(vec![
             Line::from(Span::styled(
                 "❌ Error",
-                Style::default().fg(Color::Rgb(255, 107, 107)).bold(),
+                Style::default().fg(self.theme.red).bold(),
             )),
             Line::from(""),
-            Line::from(Span::styled(error, Style::default().fg(Color::White))),
+            Line::from(Span::styled(error, Style::default().fg(self.theme.text))),
             Line::from(""),
             Line::from(Span::styled(
                 "💡 Make sure you have:",
-                Style::default().fg(Color::Rgb(255, 230, 109)),
+                Style::default().fg(self.theme.yellow),
             )),
             Line::from(Span::styled(
                 "   • Valid AWS credentials configured",
-                Style::default().fg(Color::Gray),
+                Style::default().fg(self.theme.muted),
             )),
             Line::from(Span::styled(
                 "   • Cost Explorer API access (ce:GetCostAndUsage)",
-                Style::default().fg(Color::Gray),
+                Style::default().fg(self.theme.muted),
             )),
         ])
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(255, 107, 107)))
+                .border_style(Style::default().fg(self.theme.red))
                 .title(Span::styled(
                     " Error ",
-                    Style::default().fg(Color::Rgb(255, 107, 107)).bold(),
+                    Style::default().fg(self.theme.red).bold(),
                 )),
         );
         frame.render_widget(error_msg, area);
@@ -683,48 +3659,346 @@ impl App {
             Line::from(""),
             Line::from(Span::styled(
                 "📭 No data available",
-                Style::default().fg(Color::Gray),
+                Style::default().fg(self.theme.muted),
             )),
         ])
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::DarkGray)),
+                .border_style(Style::default().fg(self.theme.dim)),
         );
         frame.render_widget(msg, area);
     }
 
+    /// Footer hint: the first key of each of `actions`, then `label`; left
+    /// out when none of them is bound
+    fn key_hint(&self, keys: &mut Vec<Span<'static>>, actions: &[Action], label: &str, color: Color) {
+        let labels: Vec<String> = actions
+            .iter()
+            .filter_map(|&action| self.keymap.bindings(action).first().map(|b| b.to_string()))
+            .collect();
+        if labels.is_empty() {
+            return;
+        }
+        // Arrows run together ("←→"), anything else is split ("gg/G")
+        let arrows = labels.iter().all(|l| matches!(l.as_str(), "←" | "→" | "↑" | "↓"));
+        if !keys.is_empty() {
+            keys.push(Span::styled("  ", Style::default()));
+        }
+        keys.push(Span::styled(
+            format!(" {} ", labels.join(if arrows { "" } else { "/" })),
+            Style::default().fg(self.theme.on_accent).bg(color),
+        ));
+        keys.push(Span::styled(format!(" {}", label), Style::default().fg(self.theme.muted)));
+    }
+
+    /// Every action with its keys, straight from the keymap in use
+    fn render_help(&self, frame: &mut Frame, area: Rect) {
+        let accent_color = self.theme.muted;
+        let block = Block::default()
+            .title(Span::styled(" ⌨️  Keys ", Style::default().fg(accent_color).bold()))
+            .title_bottom(Span::styled(
+                " Remap them in the config's [keys] ",
+                Style::default().fg(self.theme.dim),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent_color))
+            .padding(Padding::horizontal(1));
+        frame.render_widget(Clear, area);
+
+        let rows: Vec<Row> = Action::ALL
+            .iter()
+            .map(|&action| {
+                let bindings: Vec<String> = self.keymap.bindings(action).iter().map(|b| b.to_string()).collect();
+                let keys = match bindings.is_empty() {
+                    true => Span::styled("—", Style::default().fg(self.theme.dim)),
+                    false => Span::styled(bindings.join(", "), Style::default().fg(self.theme.yellow).bold()),
+                };
+                Row::new(vec![
+                    Cell::from(keys),
+                    Cell::from(Span::styled(action.description(), Style::default().fg(self.theme.text))),
+                    Cell::from(Span::styled(action.name(), Style::default().fg(self.theme.dim))),
+                ])
+            })
+            .collect();
+
+        let header_style = Style::default().fg(self.theme.yellow).bold();
+        let table = Table::new(
+            rows,
+            [Constraint::Length(16), Constraint::Min(20), Constraint::Length(10)],
+        )
+        .header(
+            Row::new(vec![
+                Cell::from(Span::styled("Keys", header_style)),
+                Cell::from(Span::styled("Action", header_style)),
+                Cell::from(Span::styled("Config", header_style)),
+            ])
+            .bottom_margin(1),
+        )
+        .block(block);
+        frame.render_widget(table, area);
+    }
+
     fn render_footer(&self, frame: &mut Frame, area: Rect) {
-        let help = Paragraph::new(Line::from(vec![
-            Span::styled(" q ", Style::default().fg(Color::Black).bg(Color::Rgb(255, 107, 107))),
-            Span::styled(" Quit  ", Style::default().fg(Color::Gray)),
-            Span::styled(" ←→ ", Style::default().fg(Color::Black).bg(Color::Rgb(78, 205, 196))),
-            Span::styled(" Tab  ", Style::default().fg(Color::Gray)),
-            Span::styled(" ↑↓ ", Style::default().fg(Color::Black).bg(Color::Rgb(255, 230, 109))),
-            Span::styled(" Navigate  ", Style::default().fg(Color::Gray)),
-            Span::styled(" g/G ", Style::default().fg(Color::Black).bg(Color::Rgb(170, 128, 255))),
-            Span::styled(" Top/Bottom", Style::default().fg(Color::Gray)),
-        ]))
+        let mut keys = Vec::new();
+        self.key_hint(&mut keys, &[Action::Quit], "Quit", self.theme.red);
+        if !self.popup_open() {
+            self.key_hint(&mut keys, &[Action::Help], "Help", self.theme.muted);
+        }
+        self.key_hint(&mut keys, &[Action::PrevTab, Action::NextTab], "Tab", self.theme.teal);
+        self.key_hint(&mut keys, &[Action::Up, Action::Down], "Navigate", self.theme.yellow);
+        self.key_hint(&mut keys, &[Action::Top, Action::Bottom], "Top/Bottom", self.theme.purple);
+        if self.help {
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+        } else if self.switcher.is_some() {
+            self.key_hint(&mut keys, &[Action::DrillDown], "Switch", self.theme.blue);
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+        } else if self.anomaly_panel.is_some() || self.budget_panel.is_some() {
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+        } else if self.coverage.is_some() {
+            self.key_hint(&mut keys, &[Action::PrevTab, Action::NextTab], "Services/Accounts", self.theme.cyan);
+            self.key_hint(&mut keys, &[Action::Export], "Export CSV", self.theme.orange);
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+        } else if let Some(explorer) = &self.tags {
+            if explorer.key.is_none() {
+                self.key_hint(&mut keys, &[Action::DrillDown], "Pick Key", self.theme.pink);
+            }
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+        } else if self.resources.is_some() {
+            self.key_hint(&mut keys, &[Action::Back], "Back", self.theme.green);
+            self.key_hint(&mut keys, &[Action::Copy], "Copy ID", self.theme.orange);
+        } else if self.selected_tab < 2 && self.by_service() {
+            self.key_hint(&mut keys, &[Action::DrillDown], "Resources", self.theme.green);
+        }
+        if !self.popup_open() && self.resources.is_none() {
+            self.key_hint(&mut keys, &[Action::Tags], "Tags", self.theme.pink);
+            self.key_hint(&mut keys, &[Action::Anomalies], "Anomalies", self.theme.red);
+            if !self.required_tags.is_empty() {
+                self.key_hint(&mut keys, &[Action::Coverage], "Coverage", self.theme.cyan);
+            }
+            if !self.all_budgets().is_empty() {
+                self.key_hint(&mut keys, &[Action::Budgets], "Budgets", self.theme.orange);
+            }
+            if self.connect.is_some() {
+                self.key_hint(&mut keys, &[Action::Profiles], "Profile", self.theme.blue);
+            }
+        }
+        if self.on_teams_tab() && !self.popup_open() {
+            self.key_hint(&mut keys, &[Action::Export], "Export CSV", self.theme.orange);
+        }
+        if self.selected_tab == 3 {
+            let label = if self.prorate { "Full Periods" } else { "Prorate" };
+            self.key_hint(&mut keys, &[Action::Prorate], label, self.theme.blue);
+        }
+
+        let help = Paragraph::new(Line::from(keys))
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Rgb(80, 80, 80)))
-                .title(Span::styled(" Shortcuts ", Style::default().fg(Color::DarkGray))),
+                .border_style(Style::default().fg(self.theme.border))
+                .title(Span::styled(" Shortcuts ", Style::default().fg(self.theme.dim))),
         );
         frame.render_widget(help, area);
     }
 }
 
-/// Get color based on cost value
-fn get_cost_color(cost: f64) -> Color {
-    if cost > 1000.0 {
-        Color::Rgb(255, 107, 107) // Red
-    } else if cost > 100.0 {
-        Color::Rgb(255, 184, 77)  // Orange
-    } else if cost > 10.0 {
-        Color::Rgb(255, 230, 109) // Yellow
+/// Leaves raw mode, mouse capture and the alternate screen when dropped, so
+/// the terminal is restored even if the TUI fails or panics
+struct RestoreTerminal;
+
+impl Drop for RestoreTerminal {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), DisableMouseCapture, LeaveAlternateScreen, Show);
+    }
+}
+
+/// The keymap's name for a key press, if it has one
+fn key_of(event: &KeyEvent) -> Option<Key> {
+    let key = match event.code {
+        KeyCode::Char(c) if event.modifiers.contains(KeyModifiers::CONTROL) => Key::Ctrl(c.to_ascii_lowercase()),
+        KeyCode::Char(c) => Key::Char(c),
+        KeyCode::Enter => Key::Enter,
+        KeyCode::Esc => Key::Esc,
+        KeyCode::Backspace => Key::Backspace,
+        KeyCode::Tab => Key::Tab,
+        KeyCode::BackTab => Key::BackTab,
+        KeyCode::Up => Key::Up,
+        KeyCode::Down => Key::Down,
+        KeyCode::Left => Key::Left,
+        KeyCode::Right => Key::Right,
+        KeyCode::Home => Key::Home,
+        KeyCode::End => Key::End,
+        KeyCode::PageUp => Key::PageUp,
+        KeyCode::PageDown => Key::PageDown,
+        KeyCode::Delete => Key::Delete,
+        KeyCode::F(n) => Key::F(n),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// The terminal color of a theme color; ANSI colors keep their 16-color codes
+fn color_of(color: theme::Color) -> Color {
+    const ANSI: [Color; 16] = [
+        Color::Black, Color::Red, Color::Green, Color::Yellow,
+        Color::Blue, Color::Magenta, Color::Cyan, Color::Gray,
+        Color::DarkGray, Color::LightRed, Color::LightGreen, Color::LightYellow,
+        Color::LightBlue, Color::LightMagenta, Color::LightCyan, Color::White,
+    ];
+    match color {
+        theme::Color::Reset => Color::Reset,
+        theme::Color::Ansi(n) => ANSI[n as usize % 16],
+        theme::Color::Indexed(i) => Color::Indexed(i),
+        theme::Color::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    }
+}
+
+/// Background of the selected row, or reversed colors without one
+fn selected_style(theme: &Theme<Color>) -> Style {
+    match theme.selection {
+        Color::Reset => Style::default().add_modifier(Modifier::REVERSED),
+        selection => Style::default().bg(selection),
+    }
+}
+
+/// Gauge color of a budget status
+fn budget_color(status: BudgetStatus, theme: &Theme<Color>) -> Color {
+    match status {
+        BudgetStatus::OnTrack => theme.green,
+        BudgetStatus::Warning => theme.yellow,
+        BudgetStatus::ForecastOver => theme.orange,
+        BudgetStatus::Exceeded => theme.red,
+    }
+}
+
+/// A budget amount in dollars, or in its usage unit
+fn budget_amount(amount: f64, unit: &str) -> String {
+    if unit == "USD" {
+        format!("${:.2}", amount)
+    } else {
+        format!("{:.2} {}", amount, unit)
+    }
+}
+
+/// A `percent_x` by `percent_y` rectangle centered in `area`
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// One service or account of the coverage table
+fn coverage_table_row(
+    index: usize,
+    row: &CoverageRow,
+    keys: usize,
+    show_provider: bool,
+    selected: bool,
+    thresholds: &Thresholds,
+    theme: &Theme<Color>,
+) -> Row<'static> {
+    let mut cells = vec![Cell::from(Span::styled(
+        format!("#{}", index + 1),
+        Style::default().fg(theme.dim),
+    ))];
+    if show_provider {
+        cells.push(Cell::from(Span::styled(
+            row.provider.clone().unwrap_or_default(),
+            Style::default().fg(theme.brand),
+        )));
+    }
+    cells.push(Cell::from(Span::styled(
+        truncate_service_name(&row.name, 40),
+        Style::default().fg(theme.text),
+    )));
+    cells.push(Cell::from(Span::styled(
+        format!("${:.2}", row.cost),
+        Style::default().fg(get_cost_color(row.cost, thresholds, theme)).bold(),
+    )));
+    for i in 0..keys {
+        let coverage = row.coverage(i);
+        cells.push(Cell::from(Span::styled(
+            format!("{:.1}%", coverage),
+            Style::default().fg(coverage_color(coverage, theme)),
+        )));
+    }
+    cells.push(Cell::from(Span::styled(
+        format!("${:.2}", row.untagged_cost()),
+        Style::default().fg(theme.label),
+    )));
+
+    let row = Row::new(cells);
+    if selected {
+        row.style(selected_style(theme))
+    } else {
+        row
+    }
+}
+
+/// Green from 95% tagged, yellow from 80%, red below
+fn coverage_color(coverage: f64, theme: &Theme<Color>) -> Color {
+    if coverage >= 95.0 {
+        theme.green
+    } else if coverage >= 80.0 {
+        theme.yellow
     } else {
-        Color::Rgb(108, 255, 108) // Green
+        theme.red
+    }
+}
+
+/// One block character per 0-100 value, low to high
+fn sparkline(values: &[f64]) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    values
+        .iter()
+        .map(|v| LEVELS[((v.clamp(0.0, 100.0) / 100.0) * 7.0).round() as usize])
+        .collect()
+}
+
+/// Get color based on cost value and the configured thresholds
+fn get_cost_color(cost: f64, thresholds: &Thresholds, theme: &Theme<Color>) -> Color {
+    if cost > thresholds.high {
+        theme.red
+    } else if cost > thresholds.medium {
+        theme.orange
+    } else if cost > thresholds.low {
+        theme.yellow
+    } else {
+        theme.green
+    }
+}
+
+/// Get style for a cost delta (red = increase, green = savings)
+fn get_delta_style(delta: f64, theme: &Theme<Color>) -> Style {
+    if delta > CHANGE_EPSILON {
+        Style::default().fg(theme.red)
+    } else if delta < -CHANGE_EPSILON {
+        Style::default().fg(theme.green)
+    } else {
+        Style::default().fg(theme.yellow)
+    }
+}
+
+/// Format a cost change with an explicit sign
+fn format_signed_cost(delta: f64) -> String {
+    if delta < 0.0 {
+        format!("-${:.2}", delta.abs())
+    } else {
+        format!("+${:.2}", delta)
     }
 }
 
@@ -739,15 +4013,5 @@ fn create_bar(percentage: f64) -> String {
 
 /// Truncate long service names
 fn truncate_service_name(name: &str, max_len: usize) -> String {
-    // Remove common prefixes
-    let name = name
-        .trim_start_matches("Amazon ")
-        .trim_start_matches("AWS ")
-        .trim_start_matches("Amazon");
-    
-    if name.len() > max_len {
-        format!("{}…", &name[..max_len - 1])
-    } else {
-        name.to_string()
-    }
+    text::truncate(name, max_len, "…")
 }
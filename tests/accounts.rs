@@ -0,0 +1,126 @@
+mod common;
+
+use aws_costs_tui::analysis::AccountsReport;
+use aws_costs_tui::aws::{Dimension, GroupBy};
+use aws_costs_tui::source::{AccountsSource, CostSource, Fixture, FixtureSource, SyntheticSource};
+use common::date;
+use std::sync::Arc;
+
+fn demo(seed: u64) -> SyntheticSource {
+    SyntheticSource::new(seed).with_today(date(2026, 3, 15))
+}
+
+/// Two demo accounts, one whose queries fail and one without credentials
+fn accounts() -> AccountsSource {
+    AccountsSource::default()
+        .with_account("prod", Arc::new(demo(42)))
+        .with_account("broken", Arc::new(FixtureSource::new(Fixture::default())))
+        .with_account("staging", Arc::new(demo(43)))
+        .with_failed_account("sandbox", "No credentials found")
+}
+
+#[tokio::test]
+async fn sums_accounts_that_answer() {
+    let source = accounts();
+    assert_eq!(source.accounts(), ["prod", "broken", "staging", "sandbox"]);
+    assert_eq!(source.name(), "4 accounts");
+
+    let month = source.current_month().await.unwrap();
+    let prod = demo(42).current_month().await.unwrap();
+    let staging = demo(43).current_month().await.unwrap();
+    assert!((month.total_cost - prod.total_cost - staging.total_cost).abs() < 0.01);
+    // Accounts are not providers, so no provider column
+    assert!(month.breakdown.iter().all(|s| s.provider.is_none()));
+    let ec2 = |data: &aws_costs_tui::aws::CostData| {
+        data.breakdown
+            .iter()
+            .find(|s| s.service == "Amazon Elastic Compute Cloud - Compute")
+            .unwrap()
+            .cost
+    };
+    assert!((ec2(&month) - ec2(&prod) - ec2(&staging)).abs() < 0.01);
+
+    let trend = source.monthly_trend(6).await.unwrap();
+    assert_eq!(trend.months.len(), 6);
+    let prod_trend = demo(42).monthly_trend(6).await.unwrap();
+    assert_eq!(trend.months[0].start, prod_trend.months[0].start);
+    assert!(trend.months[0].total_cost > prod_trend.months[0].total_cost);
+
+    // Linked accounts of every account are kept apart
+    let by_account = source
+        .breakdown(
+            month.start,
+            month.end,
+            &GroupBy::Dimension(Dimension::LinkedAccount),
+        )
+        .await
+        .unwrap();
+    assert!((by_account.total_cost - month.total_cost).abs() < 0.01);
+
+    // Budgets say whose they are
+    let budgets = source.budgets(6).await.unwrap();
+    assert!(budgets.iter().any(|b| b.name == "prod: Monthly total"));
+    assert!(budgets.iter().any(|b| b.name == "staging: Monthly total"));
+}
+
+#[tokio::test]
+async fn fails_only_when_every_account_does() {
+    let source = AccountsSource::default()
+        .with_account("broken", Arc::new(FixtureSource::new(Fixture::default())))
+        .with_failed_account("sandbox", "No credentials found");
+    let error = format!("{:#}", source.current_month().await.unwrap_err());
+    assert!(error.contains("Every account failed"), "{}", error);
+    assert!(
+        error.contains("broken: Fixture has no current month data"),
+        "{}",
+        error
+    );
+    assert!(error.contains("sandbox: No credentials found"), "{}", error);
+}
+
+#[tokio::test]
+async fn report_has_a_column_per_account() {
+    let source = accounts();
+    let (start, end) = (date(2026, 3, 1), date(2026, 3, 16));
+    let report = AccountsReport::load(&source, start, end).await.unwrap();
+    assert_eq!(report.period, "March 2026");
+
+    let names: Vec<&str> = report.accounts.iter().map(|a| a.account.as_str()).collect();
+    assert_eq!(names, ["prod", "broken", "staging", "sandbox"]);
+    let failed: Vec<&str> = report.failed().map(|a| a.account.as_str()).collect();
+    assert_eq!(failed, ["broken", "sandbox"]);
+    assert_eq!(
+        report.accounts[3].error.as_deref(),
+        Some("No credentials found")
+    );
+    assert_eq!(report.accounts[1].cost, None);
+
+    let prod = demo(42)
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::Service))
+        .await
+        .unwrap();
+    assert!((report.accounts[0].cost.unwrap() - prod.total_cost).abs() < 0.01);
+
+    // Biggest service first, one cost per account, zero where it failed
+    let top = &report.services[0];
+    assert_eq!(top.service, "Amazon Elastic Compute Cloud - Compute");
+    assert_eq!(top.costs.len(), 4);
+    assert_eq!((top.costs[1], top.costs[3]), (0.0, 0.0));
+    assert!((top.total - top.costs.iter().sum::<f64>()).abs() < 1e-9);
+    assert!(report
+        .services
+        .windows(2)
+        .all(|pair| pair[0].total >= pair[1].total));
+    let services_total: f64 = report.services.iter().map(|s| s.total).sum();
+    assert!((services_total - report.total_cost()).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn single_account_sources_have_no_accounts() {
+    let source = demo(42);
+    assert!(source.accounts().is_empty());
+    assert!(source
+        .account_breakdown(date(2026, 3, 1), date(2026, 3, 16))
+        .await
+        .is_err());
+}
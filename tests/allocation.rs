@@ -0,0 +1,192 @@
+mod common;
+
+use aws_costs_tui::analysis::{Allocation, AllocationRules, CostLine, Split};
+use aws_costs_tui::source::CurSource;
+use common::{date, fixture};
+
+fn line(service: &str, account: &str, tag: Option<&str>, cost: f64) -> CostLine {
+    CostLine {
+        service: Some(service.to_string()),
+        account: Some(account.to_string()),
+        tag: tag.map(str::to_string),
+        cost,
+    }
+}
+
+fn team_total(allocation: &Allocation, name: &str) -> f64 {
+    allocation
+        .teams
+        .iter()
+        .find(|t| t.name == name)
+        .map(|t| t.total())
+        .unwrap_or_else(|| panic!("{} missing from {:?}", name, allocation.teams))
+}
+
+const RULES: &str = r#"
+tag = "team"
+
+[tags]
+platform = "Platform"
+
+[accounts]
+"222222222222" = "Data"
+
+[[shared]]
+name = "Support"
+services = ["AWS Support"]
+
+[[shared]]
+name = "Untagged"
+untagged = true
+split = { fixed = { Platform = 75, Web = 25 } }
+"#;
+
+#[test]
+fn rules_split_shared_buckets() {
+    let rules = AllocationRules::parse(RULES).unwrap();
+    assert_eq!(rules.shared[0].split, Split::Proportional);
+
+    let lines = [
+        line("Amazon EC2", "111111111111", Some("platform"), 60.0),
+        line("Amazon EC2", "111111111111", Some("web"), 20.0),
+        // Untagged, but the account belongs to Data
+        line("Amazon S3", "222222222222", None, 20.0),
+        // Support is shared even when tagged
+        line("AWS Support", "111111111111", Some("web"), 10.0),
+        line("AWS Support", "111111111111", None, 10.0),
+        line("AWS Lambda", "111111111111", None, 40.0),
+    ];
+    let allocation = Allocation::new(
+        &rules,
+        &lines,
+        "March 2026",
+        date(2026, 3, 1),
+        date(2026, 4, 1),
+    );
+
+    assert_eq!(allocation.buckets, ["Support", "Untagged"]);
+    assert_eq!(allocation.bucket_costs, [20.0, 40.0]);
+    // Support by direct spend (60/20/20), untagged 75/25
+    assert!((team_total(&allocation, "Platform") - (60.0 + 12.0 + 30.0)).abs() < 1e-9);
+    assert!((team_total(&allocation, "web") - (20.0 + 4.0)).abs() < 1e-9);
+    assert!((team_total(&allocation, "Data") - (20.0 + 4.0)).abs() < 1e-9);
+    // Fixed splits may name teams without direct spend
+    assert!((team_total(&allocation, "Web") - 10.0).abs() < 1e-9);
+    assert_eq!(allocation.teams[0].name, "Platform");
+    assert_eq!(allocation.unallocated, 0.0);
+
+    let check = allocation.reconcile(160.0);
+    assert!(check.is_balanced());
+    let check = allocation.reconcile(170.0);
+    assert!(!check.is_balanced());
+    assert!((check.difference() - 10.0).abs() < 1e-9);
+
+    let mut csv = Vec::new();
+    allocation.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "team,direct_cost,Support_cost,Untagged_cost,total_cost,share_pct"
+    );
+    assert_eq!(lines[1], "Platform,60.00,12.00,30.00,102.00,63.75");
+    assert_eq!(
+        lines.last(),
+        Some(&"total,100.00,20.00,40.00,160.00,100.00")
+    );
+}
+
+#[test]
+fn spend_without_a_rule_is_unallocated() {
+    let rules = AllocationRules::parse(
+        r#"
+        tag = "team"
+        [[shared]]
+        name = "Shared"
+        tag_values = ["shared"]
+        "#,
+    )
+    .unwrap();
+    let lines = [
+        line("Amazon EC2", "111111111111", Some("shared"), 30.0),
+        line("Amazon EC2", "111111111111", None, 25.0),
+    ];
+    let allocation = Allocation::new(
+        &rules,
+        &lines,
+        "March 2026",
+        date(2026, 3, 1),
+        date(2026, 4, 1),
+    );
+
+    // No team has direct spend to split the bucket by
+    assert!(allocation.teams.is_empty());
+    assert_eq!(allocation.unallocated, 55.0);
+    let check = allocation.reconcile(55.0);
+    assert_eq!(check.difference(), 0.0);
+    assert!(!check.is_balanced());
+
+    let mut csv = Vec::new();
+    allocation.write_csv(&mut csv).unwrap();
+    assert!(String::from_utf8(csv)
+        .unwrap()
+        .contains("(unallocated),,,55.00,100.00"));
+}
+
+#[test]
+fn invalid_rules_are_rejected() {
+    let err = |rules: &str| format!("{:#}", AllocationRules::parse(rules).unwrap_err());
+
+    let message = err("[[shared]]\nname = \"Support\"\nsplit = { fixed = { a = 60, b = 30 } }\nservices = [\"x\"]");
+    assert!(message.contains("adds up to 90%"), "{}", message);
+    let message = err("[[shared]]\nname = \"Support\"");
+    assert!(message.contains("matches nothing"), "{}", message);
+    let message = err("[tags]\nplatform = \"Platform\"");
+    assert!(message.contains("no `tag` key"), "{}", message);
+    // Typos point at the offending line
+    let message = err("tag = \"team\"\n\n[acounts]\n");
+    assert!(message.contains("line 3"), "{}", message);
+}
+
+#[tokio::test]
+async fn load_from_cur_export() {
+    let cur = CurSource::load(&fixture("cur/20260301-20260401/costs-Manifest.json")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+
+    // Untagged Lambda spend is all in account 111111111111
+    let rules = AllocationRules::parse(
+        r#"
+        tag = "team"
+        [accounts]
+        "111111111111" = "platform"
+        "#,
+    )
+    .unwrap();
+    let allocation = Allocation::load(&cur, &rules, start, end).await.unwrap();
+    assert_eq!(allocation.period, "March 2026");
+    assert_eq!(team_total(&allocation, "platform"), 90.0);
+    assert_eq!(team_total(&allocation, "data"), 40.0);
+    assert_eq!(team_total(&allocation, "web"), 20.0);
+    assert!(allocation.reconcile(150.0).is_balanced());
+
+    // Lambda shared by direct spend: platform 60, data 40
+    let rules = AllocationRules::parse(
+        r#"
+        tag = "team"
+        [[shared]]
+        name = "Lambda"
+        services = ["AWS Lambda"]
+        "#,
+    )
+    .unwrap();
+    let allocation = Allocation::load(&cur, &rules, start, end).await.unwrap();
+    assert_eq!(allocation.bucket_costs, [50.0]);
+    assert_eq!(team_total(&allocation, "platform"), 90.0);
+    assert_eq!(team_total(&allocation, "data"), 60.0);
+
+    // Without a tag key, accounts own all of their spend
+    let rules = AllocationRules::parse("[accounts]\n\"111111111111\" = \"Platform\"").unwrap();
+    let allocation = Allocation::load(&cur, &rules, start, end).await.unwrap();
+    assert_eq!(team_total(&allocation, "Platform"), 110.0);
+    assert_eq!(allocation.unallocated, 40.0);
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use aws_costs_tui::analysis::{Anomalies, Baseline, Detector};
+use aws_costs_tui::aws::{DailyCosts, DailySeries, Dimension, GroupBy};
+use aws_costs_tui::periods;
+use aws_costs_tui::source::{CostSource, CurSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::{date, fixture};
+
+/// Five weeks of a service costing 100 on weekdays and 40 at weekends,
+/// starting on a Monday
+fn weekly(service: &str) -> DailySeries {
+    let costs = (0..35)
+        .map(|day| {
+            if day % 7 >= 5 {
+                40.0
+            } else {
+                100.0 + (day % 3) as f64
+            }
+        })
+        .collect();
+    DailySeries {
+        service: service.to_string(),
+        costs,
+        provider: None,
+    }
+}
+
+fn daily(series: Vec<DailySeries>) -> DailyCosts {
+    let start = date(2026, 2, 2);
+    DailyCosts {
+        start,
+        end: start + chrono::Duration::days(35),
+        currency: "USD".to_string(),
+        series,
+    }
+}
+
+#[test]
+fn weekday_baseline_ignores_weekends_but_flags_spikes() {
+    let mut spiky = weekly("AWS Lambda");
+    // A Saturday at weekday levels and a Wednesday at triple
+    spiky.costs[33] = 100.0;
+    spiky.costs[30] = 300.0;
+    let data = daily(vec![weekly("Amazon EC2"), spiky]);
+    let from = date(2026, 3, 2);
+
+    let anomalies = Detector::default().detect(&data, from);
+    assert_eq!(anomalies.len(), 2, "{:?}", anomalies);
+    let wednesday = &anomalies[0];
+    assert_eq!(wednesday.service, "AWS Lambda");
+    assert_eq!(wednesday.date, date(2026, 3, 4));
+    assert!((wednesday.expected - 100.0).abs() < 2.0);
+    assert_eq!(wednesday.actual, 300.0);
+    assert!(wednesday.change_percentage().unwrap() > 190.0);
+    // The Saturday is a spike against other Saturdays
+    assert_eq!(anomalies[1].date, date(2026, 3, 7));
+    assert!((anomalies[1].expected - 40.0).abs() < 1e-9);
+
+    // A rolling baseline mixes in weekends and only sees the big one
+    let rolling = Detector {
+        baseline: Baseline::Rolling,
+        ..Detector::default()
+    };
+    let anomalies = rolling.detect(&data, from);
+    assert_eq!(anomalies.len(), 1, "{:?}", anomalies);
+    assert_eq!(anomalies[0].date, date(2026, 3, 4));
+}
+
+#[test]
+fn sensitivity_and_minimum_excess_filter_spikes() {
+    let mut series = weekly("Amazon S3");
+    series.costs[30] = 125.0;
+    let data = daily(vec![series]);
+    let from = date(2026, 3, 2);
+
+    assert!(Detector::default().detect(&data, from).is_empty());
+    let sensitive = Detector {
+        sensitivity: 2.0,
+        ..Detector::default()
+    };
+    assert_eq!(sensitive.detect(&data, from).len(), 1);
+    let picky = Detector {
+        sensitivity: 2.0,
+        min_excess: 50.0,
+        ..Detector::default()
+    };
+    assert!(picky.detect(&data, from).is_empty());
+}
+
+#[test]
+fn consecutive_spike_days_are_merged() {
+    let mut series = weekly("Amazon EC2");
+    // Tuesday to Thursday of the last week at double
+    for day in 29..32 {
+        series.costs[day] *= 2.0;
+    }
+    let data = daily(vec![series.clone()]);
+
+    let anomalies = Detector::default().detect(&data, date(2026, 3, 2));
+    assert_eq!(anomalies.len(), 1, "{:?}", anomalies);
+    let spike = &anomalies[0];
+    assert_eq!(spike.date, date(2026, 3, 3));
+    assert_eq!(spike.days, 3);
+    assert_eq!(spike.end(), date(2026, 3, 6));
+    let actual: f64 = series.costs[29..32].iter().sum();
+    assert!((spike.actual - actual).abs() < 1e-9);
+    assert!((spike.excess() - actual / 2.0).abs() < 5.0);
+}
+
+#[test]
+fn days_without_history_are_skipped() {
+    let mut series = weekly("Amazon S3");
+    series.costs[3] = 1000.0;
+    let data = daily(vec![series]);
+
+    // Only the first week is checked, and it has nothing before it
+    let anomalies = Anomalies::new(Detector::default(), &data, data.start);
+    assert!(anomalies.anomalies.is_empty());
+    assert_eq!(anomalies.start, data.start);
+    assert_eq!(anomalies.total_excess(), 0.0);
+}
+
+#[tokio::test]
+async fn synthetic_spikes_are_found() {
+    let today = date(2026, 3, 18);
+    let source = SyntheticSource::new(42).with_today(today);
+    let (start, end) = periods::month_to_date(today).unwrap();
+
+    let daily = source.daily(start, end).await.unwrap();
+    assert_eq!(daily.days(), 18);
+    let month = source.current_month().await.unwrap();
+    let total: f64 = daily.series.iter().map(|s| s.total()).sum();
+    // Weekday swings and spikes only shift the month total a little
+    assert!((total - month.total_cost).abs() / month.total_cost < 0.05);
+
+    let report = Anomalies::load(&source, Detector::default(), start, end)
+        .await
+        .unwrap();
+    let found: Vec<(&str, NaiveDate)> = report
+        .anomalies
+        .iter()
+        .map(|a| (a.service.as_str(), a.date))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("AWS Lambda", date(2026, 3, 17)),
+            ("Amazon CloudWatch", date(2026, 3, 12)),
+        ]
+    );
+    assert_eq!(report.of_service("AWS Lambda", None).count(), 1);
+    assert_eq!(
+        report
+            .of_service("Amazon Simple Storage Service", None)
+            .count(),
+        0
+    );
+    assert!(report.total_excess() > 0.0);
+}
+
+#[tokio::test]
+async fn cur_daily_costs_by_usage_day() {
+    let source = CurSource::load(&fixture("cur")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+
+    let daily = source.daily(start, end).await.unwrap();
+    assert_eq!(daily.days(), 31);
+    let month = source
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::Service))
+        .await
+        .unwrap();
+    for service in &month.breakdown {
+        let series = daily
+            .series
+            .iter()
+            .find(|s| s.service == service.service)
+            .unwrap_or_else(|| panic!("{} has no daily series", service.service));
+        assert!((series.total() - service.cost).abs() < 0.01);
+    }
+}
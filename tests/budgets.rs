@@ -0,0 +1,239 @@
+mod common;
+
+use aws_costs_tui::aws::budgets::budget_period;
+use aws_costs_tui::aws::{
+    Budget, BudgetStatus, CostExplorerClient, Credentials, Recorder, Replayer,
+};
+use aws_costs_tui::source::{CostSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::{date, scratch_dir};
+use std::fs;
+use std::path::Path;
+
+fn budget(limit: f64, actual: f64, forecasted: Option<f64>) -> Budget {
+    Budget {
+        name: "Monthly".to_string(),
+        budget_type: "COST".to_string(),
+        time_unit: "MONTHLY".to_string(),
+        start: date(2026, 3, 1),
+        end: date(2026, 4, 1),
+        limit,
+        unit: "USD".to_string(),
+        actual,
+        forecasted,
+        history: Vec::new(),
+        thresholds: Vec::new(),
+        scope: None,
+    }
+}
+
+/// Record the exchanges `BudgetsClient` makes on 2026-03-15 for an account
+/// with a paginated budget list
+fn record_budgets(dir: &Path) {
+    let recorder =
+        Recorder::create(dir, &Credentials::anonymous("eu-west-1"), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCallerIdentity",
+            r#"{"Action": "GetCallerIdentity", "Version": "2011-06-15"}"#,
+            200,
+            r#"{"GetCallerIdentityResponse": {"GetCallerIdentityResult": {
+                "Account": "123456789012", "Arn": "arn:aws:iam::123456789012:user/ci"
+            }}}"#,
+        )
+        .unwrap();
+    recorder
+        .record(
+            "DescribeBudgets",
+            r#"{"AccountId": "123456789012", "MaxResults": 100}"#,
+            200,
+            r#"{"Budgets": [
+                {"BudgetName": "Monthly total", "BudgetType": "COST", "TimeUnit": "MONTHLY",
+                 "BudgetLimit": {"Amount": "1000.0", "Unit": "USD"},
+                 "CalculatedSpend": {"ActualSpend": {"Amount": "850.5", "Unit": "USD"},
+                                     "ForecastedSpend": {"Amount": "1400.0", "Unit": "USD"}}},
+                {"BudgetName": "RI utilization", "BudgetType": "RI_UTILIZATION", "TimeUnit": "MONTHLY"}
+            ], "NextToken": "page-2"}"#,
+        )
+        .unwrap();
+    recorder
+        .record(
+            "DescribeBudgets",
+            r#"{"AccountId": "123456789012", "MaxResults": 100, "NextToken": "page-2"}"#,
+            200,
+            r#"{"Budgets": [
+                {"BudgetName": "Data transfer", "BudgetType": "USAGE", "TimeUnit": "QUARTERLY",
+                 "BudgetLimit": {"Amount": "500", "Unit": "GB"},
+                 "CalculatedSpend": {"ActualSpend": {"Amount": "120", "Unit": "GB"}}}
+            ]}"#,
+        )
+        .unwrap();
+    // Six months of history up to the end of March 2026
+    let history = |name: &str| {
+        format!(
+            r#"{{"AccountId": "123456789012", "BudgetName": "{}", "MaxResults": 100,
+                "TimePeriod": {{"Start": 1759276800, "End": 1775001600}}}}"#,
+            name
+        )
+    };
+    recorder
+        .record(
+            "DescribeBudgetPerformanceHistory",
+            &history("Monthly total"),
+            200,
+            r#"{"BudgetPerformanceHistory": {"BudgetedAndActualAmountsList": [
+                {"BudgetedAmount": {"Amount": "1000", "Unit": "USD"},
+                 "ActualAmount": {"Amount": "850.5", "Unit": "USD"},
+                 "TimePeriod": {"Start": 1772323200, "End": 1775001600}},
+                {"BudgetedAmount": {"Amount": "900", "Unit": "USD"},
+                 "ActualAmount": {"Amount": "950", "Unit": "USD"},
+                 "TimePeriod": {"Start": 1769904000, "End": 1772323200}}
+            ]}, "NextToken": "older"}"#,
+        )
+        .unwrap();
+    recorder
+        .record(
+            "DescribeBudgetPerformanceHistory",
+            &history("Monthly total").replace("100,", r#"100, "NextToken": "older","#),
+            200,
+            r#"{"BudgetPerformanceHistory": {"BudgetedAndActualAmountsList": [
+                {"BudgetedAmount": {"Amount": "900", "Unit": "USD"},
+                 "ActualAmount": {"Amount": "700", "Unit": "USD"},
+                 "TimePeriod": {"Start": 1767225600, "End": 1769904000}}
+            ]}}"#,
+        )
+        .unwrap();
+    // A history that fails to load leaves the budget without one
+    recorder
+        .record(
+            "DescribeBudgetPerformanceHistory",
+            &history("Data transfer"),
+            400,
+            r#"{"__type": "AccessDeniedException", "Message": "not allowed"}"#,
+        )
+        .unwrap();
+}
+
+#[tokio::test]
+async fn replays_budgets_with_history() {
+    let dir = scratch_dir("budgets-replay");
+    record_budgets(&dir);
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1"))
+        .replay_from(Replayer::load(&dir).unwrap());
+    let budgets = client.budgets(6).await.unwrap();
+
+    // The budget without a limit is skipped, the second page followed
+    let names: Vec<&str> = budgets.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["Monthly total", "Data transfer"]);
+
+    let monthly = &budgets[0];
+    assert_eq!(
+        (monthly.start, monthly.end),
+        (date(2026, 3, 1), date(2026, 4, 1))
+    );
+    assert_eq!(monthly.limit, 1000.0);
+    assert_eq!(monthly.actual, 850.5);
+    assert_eq!(monthly.forecasted, Some(1400.0));
+    assert_eq!(monthly.status(), BudgetStatus::ForecastOver);
+    // Both pages of history, oldest first
+    let starts: Vec<NaiveDate> = monthly.history.iter().map(|p| p.start).collect();
+    assert_eq!(
+        starts,
+        [date(2026, 1, 1), date(2026, 2, 1), date(2026, 3, 1)]
+    );
+    assert_eq!(monthly.history[1].limit, 900.0);
+    assert_eq!(monthly.history[1].actual, 950.0);
+    assert_eq!(monthly.history[1].end, date(2026, 3, 1));
+
+    let transfer = &budgets[1];
+    assert_eq!(transfer.unit, "GB");
+    assert_eq!(transfer.period_name(), "Q1 2026");
+    assert_eq!(transfer.forecasted, None);
+    assert!(transfer.history.is_empty());
+    assert_eq!(transfer.status(), BudgetStatus::OnTrack);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn status_by_threshold() {
+    assert_eq!(
+        budget(100.0, 50.0, Some(90.0)).status(),
+        BudgetStatus::OnTrack
+    );
+    assert_eq!(
+        budget(100.0, 80.0, Some(95.0)).status(),
+        BudgetStatus::Warning
+    );
+    assert_eq!(
+        budget(100.0, 50.0, Some(120.0)).status(),
+        BudgetStatus::ForecastOver
+    );
+    assert_eq!(
+        budget(100.0, 100.0, Some(180.0)).status(),
+        BudgetStatus::Exceeded
+    );
+    assert_eq!(budget(100.0, 85.0, None).status(), BudgetStatus::Warning);
+    assert!(BudgetStatus::Exceeded > BudgetStatus::ForecastOver);
+
+    let over = budget(200.0, 50.0, Some(250.0));
+    assert_eq!(over.percentage(), 25.0);
+    assert_eq!(over.forecast_percentage(), Some(125.0));
+    // A zero limit is never breached by share
+    assert_eq!(budget(0.0, 10.0, None).percentage(), 0.0);
+}
+
+#[test]
+fn budget_periods() {
+    let today = date(2026, 5, 17);
+    assert_eq!(
+        budget_period("DAILY", today).unwrap(),
+        (today, date(2026, 5, 18))
+    );
+    assert_eq!(
+        budget_period("MONTHLY", today).unwrap(),
+        (date(2026, 5, 1), date(2026, 6, 1))
+    );
+    assert_eq!(
+        budget_period("QUARTERLY", today).unwrap(),
+        (date(2026, 4, 1), date(2026, 7, 1))
+    );
+    assert_eq!(
+        budget_period("ANNUALLY", today).unwrap(),
+        (date(2026, 1, 1), date(2027, 1, 1))
+    );
+    assert!(budget_period("FORTNIGHTLY", today).is_err());
+}
+
+#[tokio::test]
+async fn synthetic_budgets() {
+    let today = date(2026, 3, 15);
+    let source = SyntheticSource::new(42).with_today(today);
+
+    let budgets = source.budgets(6).await.unwrap();
+    // The demo shows every color of gauge
+    let mut statuses: Vec<BudgetStatus> = budgets.iter().map(Budget::status).collect();
+    statuses.sort();
+    statuses.dedup();
+    assert_eq!(
+        statuses,
+        [
+            BudgetStatus::OnTrack,
+            BudgetStatus::Warning,
+            BudgetStatus::ForecastOver,
+            BudgetStatus::Exceeded
+        ]
+    );
+
+    let total = budgets.iter().find(|b| b.name == "Monthly total").unwrap();
+    let month = source.current_month().await.unwrap();
+    assert!((total.actual - month.total_cost).abs() < 0.01);
+    assert_eq!(total.history.len(), 6);
+    assert_eq!(total.history.last().unwrap().actual, total.actual);
+    let trend = source.monthly_trend(6).await.unwrap();
+    for (period, month) in total.history.iter().zip(&trend.months) {
+        assert_eq!(period.start, month.start);
+        assert!((period.actual - month.total_cost).abs() < 0.01);
+    }
+}
@@ -0,0 +1,189 @@
+mod common;
+
+use aws_costs_tui::periods;
+use chrono::{Duration, NaiveDate};
+use common::{date, fixture, scratch_dir};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const FOCUS_HEADER: &str = "BillingAccountId,BillingPeriodStart,BillingPeriodEnd,ChargePeriodStart,\
+ChargePeriodEnd,BilledCost,EffectiveCost,BillingCurrency,ChargeCategory,PricingCategory,ProviderName,\
+ServiceName,SubAccountId,RegionId,Tags";
+
+/// A FOCUS export row charging `cost` for `service` on `day`
+fn focus_row(day: NaiveDate, cost: f64, service: &str, tags: &str) -> String {
+    let period = periods::month_start(day).unwrap();
+    format!(
+        "ea-1,{}T00:00:00Z,{}T00:00:00Z,{}T00:00:00Z,{}T23:59:59Z,{},{},USD,Usage,Standard,\
+         Microsoft,{},sub-a,eastus,\"{}\"",
+        period,
+        periods::next_month_start(day).unwrap(),
+        day,
+        day,
+        cost,
+        cost,
+        service,
+        tags.replace('"', "\"\"")
+    )
+}
+
+/// A FOCUS export of `rows` in `dir`
+fn focus_export(dir: &Path, rows: &[String]) -> String {
+    let path = dir.join("focus.csv");
+    fs::write(&path, format!("{}\n{}\n", FOCUS_HEADER, rows.join("\n"))).unwrap();
+    path.display().to_string()
+}
+
+/// Run `aws-costs` with an empty config, returning its output
+fn aws_costs(dir: &Path, args: &[&str]) -> String {
+    aws_costs_with_config(dir, "", args)
+}
+
+/// Run `aws-costs` with `config` as its config, returning its output
+fn aws_costs_with_config(dir: &Path, config: &str, args: &[&str]) -> String {
+    let path = dir.join("config.toml");
+    fs::write(&path, config).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aws-costs"))
+        .args(args)
+        .arg("--config")
+        .arg(&path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    stdout
+}
+
+#[test]
+fn fixtures_saved_from_an_export_replay_its_periods() {
+    let dir = scratch_dir("cli-save-fixture");
+    fs::create_dir_all(&dir).unwrap();
+    let cur = fixture("cur").display().to_string();
+    let saved = dir.join("cur.json").display().to_string();
+
+    aws_costs(
+        &dir,
+        &[
+            "--cur",
+            &cur,
+            "--require-tag",
+            "team",
+            "--save-fixture",
+            &saved,
+        ],
+    );
+    let out = aws_costs(
+        &dir,
+        &[
+            "--fixture",
+            &saved,
+            "--no-tui",
+            "--tag",
+            "team",
+            "--require-tag",
+            "team",
+        ],
+    );
+    fs::remove_dir_all(&dir).ok();
+    assert!(out.contains("📅 March 2026"), "{}", out);
+    assert!(
+        out.contains("🚫 Untagged: 20.0% ($30.00 of $150.00)"),
+        "{}",
+        out
+    );
+    assert!(out.contains("📐 Tag Coverage (March 2026)"), "{}", out);
+}
+
+#[test]
+fn text_reports_follow_the_configured_months() {
+    let dir = scratch_dir("cli-months");
+    fs::create_dir_all(&dir).unwrap();
+
+    let out = aws_costs_with_config(
+        &dir,
+        "months = 3",
+        &["--demo", "--no-tui", "--require-tag", "team"],
+    );
+    fs::remove_dir_all(&dir).ok();
+    let trend = &out[out.find("📈 Coverage Trend:").unwrap()..];
+    // A header line, then one line per month
+    assert_eq!(trend.lines().skip(2).count(), 3, "{}", trend);
+}
+
+/// Every report's names are cut between characters, whatever script they
+/// are in
+#[test]
+fn long_non_ascii_names_are_cut_between_characters() {
+    let dir = scratch_dir("cli-non-ascii");
+    fs::create_dir_all(&dir).unwrap();
+    let service = "Machines virtuelles mémoire optimisée";
+    let value = format!("a{}", "é".repeat(40));
+    let tags = format!(r#"{{"team": "{}"}}"#, value);
+    // A steady five weeks, then a spike; the first row sets "today"
+    let mut rows = vec![focus_row(date(2026, 3, 11), 300.0, service, &tags)];
+    rows.extend(
+        (0..38).map(|day| focus_row(date(2026, 2, 1) + Duration::days(day), 10.0, service, &tags)),
+    );
+    let focus = focus_export(&dir, &rows);
+    let rules = dir.join("teams.toml");
+    fs::write(
+        &rules,
+        format!(
+            "tag = \"team\"\n[tags]\n\"{}\" = \"Infrastructure données · équipe\"\n",
+            value
+        ),
+    )
+    .unwrap();
+    let rules = rules.display().to_string();
+    let budgets = dir.join("budgets.toml");
+    fs::write(
+        &budgets,
+        format!(
+            "[[budget]]\nname = \"Budget mensuel · équipe données\"\nperiod = \"monthly\"\n\
+             amount = 500\ntag = {{ key = \"team\", value = \"{}\" }}\n",
+            value
+        ),
+    )
+    .unwrap();
+    let budgets = budgets.display().to_string();
+
+    let cut_value = format!("Microsoft · a{}...", "é".repeat(22));
+    let cut_scope = format!("tag team=a{}...", "é".repeat(14));
+    let cases: [(&[&str], &[&str]); 5] = [
+        (&["--tag", "team"], &[&cut_value]),
+        (
+            &["--allocation-rules", &rules],
+            &["Infrastructure données · ..."],
+        ),
+        (
+            &["--anomalies"],
+            &["Microsoft · Machines virtuelles mém..."],
+        ),
+        (&["--forecast"], &["Microsoft · Machines virtuelles mém..."]),
+        (
+            &["--local-budgets", &budgets],
+            &["Budget mensuel ·... ", &cut_scope],
+        ),
+    ];
+    for (flags, expected) in cases {
+        let mut args = vec!["--focus", focus.as_str(), "--no-tui"];
+        args.extend(flags);
+        let out = aws_costs(&dir, &args);
+        for name in expected {
+            assert!(
+                out.contains(name),
+                "{:?}: {:?} missing from\n{}",
+                flags,
+                name,
+                out
+            );
+        }
+    }
+    fs::remove_dir_all(&dir).ok();
+}
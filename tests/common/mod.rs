@@ -0,0 +1,46 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test crate uses its own subset of these.
+#![allow(dead_code)]
+
+use aws_costs_tui::aws::CostData;
+use chrono::NaiveDate;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+/// A file or directory under `tests/fixtures`
+pub fn fixture(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
+
+/// Cost of the breakdown row named `name`
+pub fn cost_of(data: &CostData, name: &str) -> f64 {
+    data.breakdown
+        .iter()
+        .find(|s| s.service == name)
+        .map(|s| s.cost)
+        .unwrap_or_else(|| panic!("{} missing from {:?}", name, data.breakdown))
+}
+
+/// Cost of the breakdown row named `name` from `provider`
+pub fn provider_cost_of(data: &CostData, provider: &str, name: &str) -> f64 {
+    data.breakdown
+        .iter()
+        .find(|s| s.provider.as_deref() == Some(provider) && s.service == name)
+        .map(|s| s.cost)
+        .unwrap_or_else(|| panic!("{}/{} missing from {:?}", provider, name, data.breakdown))
+}
+
+/// A directory path under the system temp dir for this test run, cleared of
+/// anything a previous run left there
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aws-costs-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
@@ -0,0 +1,56 @@
+use aws_costs_tui::analysis::{CostComparison, ServiceChange};
+use aws_costs_tui::source::{CostSource, FixtureSource};
+use std::path::Path;
+
+async fn sample() -> (aws_costs_tui::aws::CostData, aws_costs_tui::aws::CostData) {
+    let source = FixtureSource::load(Path::new("tests/fixtures/sample.json")).unwrap();
+    (
+        source.previous_month().await.unwrap(),
+        source.current_month().await.unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn compare_lines_up_services() {
+    let (previous, current) = sample().await;
+    let comparison = CostComparison::new(&previous, &current, false);
+
+    let rows: Vec<(&str, ServiceChange)> = comparison
+        .rows
+        .iter()
+        .map(|r| (r.service.as_str(), r.change))
+        .collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("AWS Lambda", ServiceChange::New),
+            ("Amazon Simple Storage Service", ServiceChange::Removed),
+            (
+                "Amazon Elastic Compute Cloud - Compute",
+                ServiceChange::Decreased
+            ),
+        ]
+    );
+    assert_eq!(comparison.rows[2].delta, -100.0);
+    assert_eq!(comparison.rows[2].delta_pct, Some(-50.0));
+    assert_eq!(comparison.rows[0].delta_pct, None);
+    assert_eq!(comparison.total_delta(), -130.0);
+}
+
+#[tokio::test]
+async fn compare_prorates_baseline_by_days() {
+    let (previous, current) = sample().await;
+    let comparison = CostComparison::new(&previous, &current, true);
+
+    // 15 days of March against 28 days of February
+    let factor = comparison.prorate_factor.unwrap();
+    assert!((factor - 15.0 / 28.0).abs() < 1e-9);
+
+    let ec2 = comparison
+        .rows
+        .iter()
+        .find(|r| r.service.contains("Compute"))
+        .unwrap();
+    assert!((ec2.baseline - 200.0 * factor).abs() < 1e-9);
+    assert_eq!(ec2.change, ServiceChange::Decreased);
+}
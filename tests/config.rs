@@ -0,0 +1,255 @@
+mod common;
+
+use aws_costs_tui::aws::{Dimension, Filter, GroupBy, Metric};
+use aws_costs_tui::config::{Config, Settings, Thresholds};
+use aws_costs_tui::source::{CostSource, GroupedSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::scratch_dir;
+use std::fs;
+use std::sync::Arc;
+
+const CONFIG: &str = r#"
+profiles = ["prod", "staging"]
+metric = "AmortizedCost"
+months = 12
+top = 5
+filter = "production"
+
+[thresholds]
+high = 5000
+medium = 500
+
+[filters.production]
+LINKED_ACCOUNT = ["111111111111", "222222222222"]
+"TAG:environment" = ["prod"]
+
+[filters.ireland]
+REGION = ["eu-west-1"]
+
+[views.regions]
+group_by = "REGION"
+months = 3
+filter = "ireland"
+
+[views.teams]
+group_by = "TAG:team"
+thresholds = { high = 300, medium = 30, low = 3 }
+"#;
+
+/// The error's full chain on one line
+fn error(content: &str) -> String {
+    format!("{:#}", Config::parse(content).unwrap_err())
+}
+
+#[test]
+fn parses_defaults_filters_and_views() {
+    let config = Config::parse(CONFIG).unwrap();
+    assert_eq!(config.profiles, ["prod", "staging"]);
+
+    let settings = config.settings(None).unwrap();
+    assert_eq!(settings.metric, Metric::AmortizedCost);
+    assert_eq!(settings.months, 12);
+    assert_eq!(settings.top, 5);
+    assert_eq!(settings.group_by, GroupBy::Dimension(Dimension::Service));
+    // Unset thresholds keep their defaults
+    assert_eq!(
+        settings.thresholds,
+        Thresholds {
+            high: 5000.0,
+            medium: 500.0,
+            low: 10.0
+        }
+    );
+    assert_eq!(settings.filter_name.as_deref(), Some("production"));
+    assert_eq!(
+        settings.filter,
+        Some(Filter::And(vec![
+            Filter::Dimension {
+                key: Dimension::LinkedAccount,
+                values: vec!["111111111111".to_string(), "222222222222".to_string()],
+            },
+            Filter::Tag {
+                key: "environment".to_string(),
+                values: vec!["prod".to_string()],
+            },
+        ]))
+    );
+
+    // An empty config is the built-in defaults
+    assert_eq!(
+        Config::parse("").unwrap().settings(None).unwrap(),
+        Settings::default()
+    );
+}
+
+#[test]
+fn views_override_the_top_level_settings() {
+    let config = Config::parse(CONFIG).unwrap();
+
+    let regions = config.settings(Some("regions")).unwrap();
+    assert_eq!(regions.group_by, GroupBy::Dimension(Dimension::Region));
+    assert_eq!(regions.months, 3);
+    // Everything the view leaves out comes from the top level
+    assert_eq!(regions.metric, Metric::AmortizedCost);
+    assert_eq!(regions.top, 5);
+    assert_eq!(regions.filter_name.as_deref(), Some("ireland"));
+
+    let teams = config.settings(Some("teams")).unwrap();
+    assert_eq!(teams.group_by, GroupBy::Tag("team".to_string()));
+    assert_eq!(teams.thresholds.low, 3.0);
+    assert_eq!(teams.filter_name.as_deref(), Some("production"));
+
+    // The config's own view is the starting point
+    let config = Config::parse(&format!("view = \"teams\"\n{}", CONFIG)).unwrap();
+    assert_eq!(config.settings(None).unwrap(), teams);
+
+    let unknown = config.settings(Some("nope")).unwrap_err().to_string();
+    assert!(unknown.contains("No view named \"nope\""), "{}", unknown);
+    assert!(unknown.contains("regions, teams"), "{}", unknown);
+}
+
+#[test]
+fn errors_name_the_line() {
+    let unknown_metric = error("months = 6\nmetric = \"Cheapest\"\n");
+    assert!(unknown_metric.contains("line 2"), "{}", unknown_metric);
+    assert!(
+        unknown_metric.contains("UnblendedCost"),
+        "{}",
+        unknown_metric
+    );
+
+    let bad_group = error("\n\n[views.x]\ngroup_by = \"COLOUR\"\n");
+    assert!(bad_group.contains("line 4"), "{}", bad_group);
+    assert!(bad_group.contains("COLOUR"), "{}", bad_group);
+
+    let months = error("months = 0\n");
+    assert!(months.contains("line 1"), "{}", months);
+    assert!(
+        months.contains("months must be between 1 and 38"),
+        "{}",
+        months
+    );
+
+    let top = error("top = 0\n");
+    assert!(top.contains("top must be at least 1"), "{}", top);
+
+    let thresholds = error("[thresholds]\nhigh = 50\n");
+    assert!(thresholds.contains("line 1"), "{}", thresholds);
+    assert!(
+        thresholds.contains("thresholds must rise from low to medium to high"),
+        "{}",
+        thresholds
+    );
+
+    let typo = error("mnoths = 3\n");
+    assert!(typo.contains("line 1"), "{}", typo);
+    assert!(typo.contains("mnoths"), "{}", typo);
+
+    let empty_filter = error("[filters.empty]\nREGION = []\n");
+    assert!(
+        empty_filter.contains("REGION needs at least one value"),
+        "{}",
+        empty_filter
+    );
+
+    let missing_filter = error("[filters.a]\nREGION = [\"x\"]\n\n[views.v]\nfilter = \"b\"\n");
+    assert!(
+        missing_filter.contains("line 5: no saved filter named \"b\""),
+        "{}",
+        missing_filter
+    );
+
+    let missing_view = error("\nview = \"v\"\n");
+    assert!(
+        missing_view.contains("line 2: no view named \"v\""),
+        "{}",
+        missing_view
+    );
+}
+
+#[test]
+fn loads_files_and_reports_the_path() {
+    let dir = std::env::temp_dir().join(format!("aws-costs-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+
+    std::fs::write(&path, CONFIG).unwrap();
+    assert_eq!(Config::load(&path).unwrap().profiles.len(), 2);
+
+    std::fs::write(&path, "top = \"many\"\n").unwrap();
+    let err = format!("{:#}", Config::load(&path).unwrap_err());
+    assert!(err.contains("Invalid config"), "{}", err);
+    assert!(err.contains("config.toml"), "{}", err);
+    assert!(err.contains("line 1"), "{}", err);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn grouped_source_regroups_the_month_views() {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let demo = Arc::new(SyntheticSource::new(42).with_today(today));
+    let grouped = GroupedSource::new(demo.clone(), GroupBy::Dimension(Dimension::Region));
+    assert!(grouped.name().ends_with("by REGION"), "{}", grouped.name());
+
+    let services = demo.current_month().await.unwrap();
+    let regions = grouped.current_month().await.unwrap();
+    let by_region = demo
+        .breakdown(
+            services.start,
+            services.end,
+            &GroupBy::Dimension(Dimension::Region),
+        )
+        .await
+        .unwrap();
+    assert_eq!(regions.period, services.period);
+    let names = |data: &aws_costs_tui::aws::CostData| -> Vec<String> {
+        data.breakdown
+            .iter()
+            .map(|row| row.service.clone())
+            .collect()
+    };
+    assert_eq!(names(&regions), names(&by_region));
+    assert!((regions.total_cost - services.total_cost).abs() < 0.01);
+
+    let trend = grouped.monthly_trend(3).await.unwrap();
+    assert_eq!(trend.months.len(), 3);
+    for month in &trend.months {
+        assert!(month
+            .breakdown
+            .iter()
+            .all(|row| !row.service.starts_with("Amazon")));
+    }
+}
+
+/// The first TOML block after `heading` in a Markdown document
+fn toml_after(markdown: &str, heading: &str) -> String {
+    let rest = &markdown[markdown.find(heading).unwrap()..];
+    let block = &rest[rest.find("```toml\n").unwrap() + "```toml\n".len()..];
+    block[..block.find("```").unwrap()].to_string()
+}
+
+/// The documented examples load as written, so they can't drift from what
+/// the parser takes
+#[test]
+fn documented_examples_load() {
+    let readme = toml_after(include_str!("../README.md"), "## ⚙️ Configuration");
+    let module_doc: String = include_str!("../src/config.rs")
+        .lines()
+        .filter_map(|line| line.strip_prefix("//!"))
+        .map(|line| format!("{}\n", line.strip_prefix(' ').unwrap_or(line)))
+        .collect();
+    let module_doc = toml_after(&module_doc, "");
+
+    let dir = scratch_dir("config-examples");
+    fs::create_dir_all(&dir).unwrap();
+    for (name, example) in [("readme", readme), ("module", module_doc)] {
+        let path = dir.join(format!("{}.toml", name));
+        fs::write(&path, example).unwrap();
+        let config = Config::load(&path).unwrap_or_else(|e| panic!("{}: {:#}", name, e));
+        config.settings(None).unwrap();
+        config.keymap().unwrap();
+        config.theme(None).unwrap();
+    }
+    fs::remove_dir_all(&dir).ok();
+}
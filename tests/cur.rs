@@ -0,0 +1,121 @@
+mod common;
+
+use aws_costs_tui::aws::{Dimension, GroupBy};
+use aws_costs_tui::source::{CostSource, CurSource};
+use common::{cost_of, date, fixture};
+
+#[tokio::test]
+async fn directory_uses_latest_report_version_per_period() {
+    let cur = CurSource::load(&fixture("cur")).unwrap();
+    // The superseded 0ld0 assembly is ignored in favour of the shallower manifest
+    assert_eq!(cur.files().len(), 3);
+    assert_eq!(cur.today(), date(2026, 3, 31));
+
+    let current = cur.current_month().await.unwrap();
+    assert_eq!(current.period, "March 2026");
+    assert_eq!(current.currency, "USD");
+    assert_eq!(current.total_cost, 150.0);
+    assert_eq!(current.breakdown[0].service, "Amazon Elastic Compute Cloud");
+    assert_eq!(cost_of(&current, "Amazon Elastic Compute Cloud"), 100.0);
+    assert_eq!(cost_of(&current, "AWS Lambda"), 50.0);
+
+    let previous = cur.previous_month().await.unwrap();
+    assert_eq!(previous.total_cost, 280.0);
+    assert_eq!(cost_of(&previous, "Amazon Simple Storage Service"), 80.0);
+}
+
+#[tokio::test]
+async fn trend_reports_months_without_line_items() {
+    let cur = CurSource::load(&fixture("cur")).unwrap();
+    let trend = cur.monthly_trend(3).await.unwrap();
+
+    let periods: Vec<_> = trend.months.iter().map(|m| m.period.as_str()).collect();
+    assert_eq!(periods, ["February 2026", "March 2026"]);
+    assert_eq!(trend.gaps.len(), 1);
+    assert_eq!(trend.gaps[0].start, date(2026, 1, 1));
+    assert!(!trend.gaps[0].partial);
+}
+
+#[tokio::test]
+async fn breakdown_by_account_and_tag() {
+    let cur = CurSource::load(&fixture("cur/20260301-20260401/costs-Manifest.json")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+
+    let accounts = cur
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::LinkedAccount))
+        .await
+        .unwrap();
+    assert_eq!(cost_of(&accounts, "111111111111"), 110.0);
+    assert_eq!(cost_of(&accounts, "222222222222"), 40.0);
+
+    let teams = cur
+        .breakdown(start, end, &GroupBy::Tag("team".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(cost_of(&teams, "team$platform"), 60.0);
+    assert_eq!(cost_of(&teams, "team$data"), 40.0);
+    assert_eq!(cost_of(&teams, "team$web"), 20.0);
+    // Untagged spend keeps an empty value, like Cost Explorer
+    assert_eq!(cost_of(&teams, "team$"), 30.0);
+
+    // `resourceTags/user:team` without the `user:` prefix
+    assert_eq!(cur.tag_keys(start, end).await.unwrap(), ["team"]);
+}
+
+#[tokio::test]
+async fn single_gzip_file_and_pinned_today() {
+    let feb = CurSource::load(&fixture("cur/20260201-20260301/a1b2/costs-00001.csv.gz")).unwrap();
+    assert_eq!(feb.today(), date(2026, 2, 28));
+    assert_eq!(feb.current_month().await.unwrap().total_cost, 280.0);
+
+    let march = CurSource::load(&fixture("cur/20260301-20260401/costs-Manifest.json"))
+        .unwrap()
+        .with_today(date(2026, 3, 9));
+    let month_to_date = march.current_month().await.unwrap();
+    assert_eq!(month_to_date.end, date(2026, 3, 10));
+    assert_eq!(month_to_date.total_cost, 90.0);
+}
+
+#[test]
+fn missing_manifest_file_is_an_error() {
+    let dir = std::env::temp_dir().join(format!("aws-costs-cur-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let manifest = dir.join("costs-Manifest.json");
+    std::fs::write(&manifest, r#"{"reportKeys": ["costs/gone.csv.gz"]}"#).unwrap();
+
+    let err = CurSource::load(&manifest).unwrap_err();
+    assert!(err.to_string().contains("gone.csv.gz"), "{:#}", err);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn matrix_splits_each_account_by_tag() {
+    let cur = CurSource::load(&fixture("cur/20260301-20260401/costs-Manifest.json")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+    let matrix = cur
+        .matrix(
+            start,
+            end,
+            &GroupBy::Dimension(Dimension::LinkedAccount),
+            &GroupBy::Tag("team".to_string()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(matrix.period, "March 2026");
+    assert_eq!(matrix.total_cost, 150.0);
+    let cell = |row: &str, column: &str| {
+        matrix
+            .cells
+            .iter()
+            .find(|c| c.row == row && c.column == column)
+            .map(|c| c.cost)
+    };
+    assert_eq!(cell("111111111111", "team$platform"), Some(60.0));
+    assert_eq!(cell("111111111111", "team$"), Some(30.0));
+    assert_eq!(cell("111111111111", "team$web"), Some(20.0));
+    assert_eq!(cell("222222222222", "team$data"), Some(40.0));
+    assert_eq!(cell("222222222222", "team$"), None);
+    assert_eq!(matrix.cells[0].cost, 60.0);
+}
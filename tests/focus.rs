@@ -0,0 +1,153 @@
+mod common;
+
+use aws_costs_tui::analysis::{CostComparison, ServiceChange};
+use aws_costs_tui::aws::{Dimension, GroupBy};
+use aws_costs_tui::source::{
+    CostSource, Fixture, FixtureSource, FocusCost, FocusSource, MergedSource, SyntheticSource,
+};
+use common::{date, fixture, provider_cost_of, scratch_dir};
+use std::fs;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn reads_every_provider_in_a_directory() {
+    let focus = FocusSource::load(&fixture("focus")).unwrap();
+    assert_eq!(focus.files().len(), 2);
+    assert_eq!(focus.today(), date(2026, 3, 31));
+
+    let current = focus.current_month().await.unwrap();
+    assert_eq!(current.period, "March 2026");
+    assert_eq!(current.total_cost, 145.0);
+    // Tax is charged against the service it applies to
+    assert_eq!(
+        provider_cost_of(&current, "Microsoft", "Virtual Machines"),
+        75.0
+    );
+    assert_eq!(provider_cost_of(&current, "Microsoft", "Storage"), 30.0);
+    assert_eq!(
+        provider_cost_of(&current, "Google Cloud", "Compute Engine"),
+        40.0
+    );
+
+    let trend = focus.monthly_trend(3).await.unwrap();
+    assert_eq!(trend.months.len(), 2);
+    assert_eq!(trend.months[0].total_cost, 100.0);
+    assert_eq!(trend.gaps[0].start, date(2026, 1, 1));
+}
+
+#[tokio::test]
+async fn effective_cost_column() {
+    let focus = FocusSource::load(&fixture("focus"))
+        .unwrap()
+        .with_cost(FocusCost::Effective);
+    let current = focus.current_month().await.unwrap();
+    assert_eq!(
+        provider_cost_of(&current, "Microsoft", "Virtual Machines"),
+        65.0
+    );
+    assert_eq!(
+        provider_cost_of(&current, "Google Cloud", "Compute Engine"),
+        32.0
+    );
+}
+
+#[tokio::test]
+async fn breakdown_by_charge_category_and_tag() {
+    let focus = FocusSource::load(&fixture("focus")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+
+    let categories = focus
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::RecordType))
+        .await
+        .unwrap();
+    assert_eq!(provider_cost_of(&categories, "Microsoft", "Usage"), 100.0);
+    assert_eq!(provider_cost_of(&categories, "Microsoft", "Tax"), 5.0);
+    assert_eq!(provider_cost_of(&categories, "Google Cloud", "Usage"), 40.0);
+
+    let accounts = focus
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::LinkedAccount))
+        .await
+        .unwrap();
+    assert_eq!(provider_cost_of(&accounts, "Microsoft", "sub-a"), 75.0);
+    assert_eq!(provider_cost_of(&accounts, "Google Cloud", "proj-1"), 40.0);
+
+    let teams = focus
+        .breakdown(start, end, &GroupBy::Tag("team".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(provider_cost_of(&teams, "Microsoft", "team$platform"), 70.0);
+    assert_eq!(provider_cost_of(&teams, "Microsoft", "team$"), 35.0);
+    assert_eq!(provider_cost_of(&teams, "Google Cloud", "team$data"), 40.0);
+
+    assert_eq!(focus.tag_keys(start, end).await.unwrap(), ["team"]);
+}
+
+#[tokio::test]
+async fn merged_with_aws_source() {
+    let today = date(2026, 3, 31);
+    let aws = SyntheticSource::new(42).with_today(today);
+    let azure = FocusSource::load(&fixture("focus/azure.csv"))
+        .unwrap()
+        .with_today(today);
+    let aws_total = aws.current_month().await.unwrap().total_cost;
+
+    let merged = MergedSource::new(vec![Arc::new(aws), Arc::new(azure)]);
+    assert_eq!(
+        merged.name(),
+        format!(
+            "demo data + FOCUS: {}",
+            fixture("focus/azure.csv").display()
+        )
+    );
+
+    let current = merged.current_month().await.unwrap();
+    assert!((current.total_cost - (aws_total + 105.0)).abs() < 1e-6);
+    assert!(provider_cost_of(&current, "AWS", "AWS Lambda") > 0.0);
+    assert_eq!(provider_cost_of(&current, "Microsoft", "Storage"), 30.0);
+    let percent: f64 = current.breakdown.iter().map(|s| s.percentage).sum();
+    assert!((percent - 100.0).abs() < 1e-6);
+
+    // Storage only exists in March, so it is new for Microsoft alone
+    let previous = merged.previous_month().await.unwrap();
+    let comparison = CostComparison::new(&previous, &current, false);
+    let storage = comparison
+        .rows
+        .iter()
+        .find(|r| r.service == "Storage")
+        .unwrap();
+    assert_eq!(storage.provider.as_deref(), Some("Microsoft"));
+    assert_eq!(storage.change, ServiceChange::New);
+
+    let trend = merged.monthly_trend(3).await.unwrap();
+    assert_eq!(trend.months.len(), 3);
+    // AWS has January, so the FOCUS gap only makes it partial
+    assert!(trend.gaps.iter().all(|g| g.partial));
+}
+
+#[tokio::test]
+async fn fixtures_keep_the_captured_provider() {
+    let today = date(2026, 3, 31);
+    let azure = FocusSource::load(&fixture("focus/azure.csv"))
+        .unwrap()
+        .with_today(today);
+    let dir = scratch_dir("focus-fixture");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("azure.json");
+    Fixture::capture(&azure, 1, &[])
+        .await
+        .unwrap()
+        .save(&path)
+        .unwrap();
+    let replay = FixtureSource::load(&path).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    assert_eq!(replay.provider(), azure.provider());
+
+    let aws = SyntheticSource::new(42).with_today(today);
+    let merged = MergedSource::new(vec![Arc::new(aws), Arc::new(replay)]);
+    let current = merged.current_month().await.unwrap();
+    assert_eq!(provider_cost_of(&current, "Microsoft", "Storage"), 30.0);
+
+    // Fixtures saved before the provider was recorded came from AWS
+    let sample = FixtureSource::load(&fixture("sample.json")).unwrap();
+    assert_eq!(sample.provider().as_deref(), Some("AWS"));
+}
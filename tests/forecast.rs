@@ -0,0 +1,172 @@
+mod common;
+
+use aws_costs_tui::analysis::{Forecast, Forecaster, Method};
+use aws_costs_tui::aws::{DailyCosts, DailySeries};
+use aws_costs_tui::periods;
+use aws_costs_tui::source::{CostSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::date;
+
+fn series(service: &str, costs: Vec<f64>) -> DailySeries {
+    DailySeries {
+        service: service.to_string(),
+        costs,
+        provider: None,
+    }
+}
+
+/// Five weeks of `series` starting on Monday 2026-02-02, up to (not
+/// including) Monday 2026-03-09
+fn daily(series: Vec<DailySeries>) -> DailyCosts {
+    let start = date(2026, 2, 2);
+    DailyCosts {
+        start,
+        end: start + chrono::Duration::days(35),
+        currency: "USD".to_string(),
+        series,
+    }
+}
+
+fn today() -> NaiveDate {
+    date(2026, 3, 9)
+}
+
+fn using(method: Method) -> Forecaster {
+    Forecaster {
+        method: Some(method),
+        ..Forecaster::default()
+    }
+}
+
+#[test]
+fn weekday_method_repeats_the_week() {
+    // 100 on weekdays, 40 at weekends
+    let costs: Vec<f64> = (0..35)
+        .map(|day| if day % 7 >= 5 { 40.0 } else { 100.0 })
+        .collect();
+    let february: f64 = costs[..27].iter().sum();
+    let data = daily(vec![series("Amazon EC2", costs)]);
+
+    let forecast = using(Method::Weekday).forecast(&data, today()).unwrap();
+    assert_eq!(forecast.method, Method::Weekday);
+    assert_eq!(forecast.today, today());
+    assert_eq!(forecast.month_end, date(2026, 4, 1));
+    assert_eq!(forecast.quarter_end, date(2026, 4, 1));
+    // March 2026 has 22 weekdays and 9 weekend days
+    assert!((forecast.month.total() - 2560.0).abs() < 1e-6);
+    assert_eq!(forecast.month.actual, 100.0 * 5.0 + 40.0 * 3.0);
+    assert!(forecast.month.margin < 1e-9);
+    // The quarter adds February; January has no data
+    assert!((forecast.quarter.total() - forecast.month.total() - february).abs() < 1e-6);
+}
+
+#[test]
+fn auto_picks_run_rate_for_a_trend() {
+    let costs = (0..35).map(|day| 10.0 + 2.0 * day as f64).collect();
+    let data = daily(vec![series("Amazon S3", costs)]);
+
+    let forecast = Forecaster::default().forecast(&data, today()).unwrap();
+    assert_eq!(forecast.method, Method::RunRate);
+    // March 1st is day 27: the sum of 10 + 2d over days 27 to 57
+    assert!((forecast.month.total() - 2914.0).abs() < 1e-6);
+    assert!(forecast.month.margin < 1e-6);
+}
+
+#[test]
+fn smoothing_follows_a_step() {
+    let costs = (0..35)
+        .map(|day| if day < 25 { 50.0 } else { 80.0 })
+        .collect();
+    let data = daily(vec![series("AWS Lambda", costs)]);
+
+    let smoothed = using(Method::Smoothing).forecast(&data, today()).unwrap();
+    let run_rate = using(Method::RunRate).forecast(&data, today()).unwrap();
+    // Ten days after the step the level is most of the way there
+    let level = 80.0 - 30.0 * 0.7f64.powi(10);
+    let days_left = 23.0;
+    assert!((smoothed.month.projected - level * days_left).abs() < 1e-6);
+    assert!(smoothed.month.projected < 80.0 * days_left);
+    assert!(smoothed.month.margin > 0.0);
+    assert_ne!(run_rate.month.projected, smoothed.month.projected);
+}
+
+#[test]
+fn bands_widen_with_noise_and_add_up() {
+    let noisy = |seed: usize| {
+        (0..35)
+            .map(|day| 100.0 + ((day * 37 + seed * 11) % 17) as f64 - 8.0)
+            .collect()
+    };
+    let data = daily(vec![
+        series("Amazon EC2", noisy(1)),
+        series("Amazon RDS", noisy(2)),
+    ]);
+
+    let forecast = using(Method::RunRate).forecast(&data, today()).unwrap();
+    for service in &forecast.services {
+        let month = service.month;
+        assert!(month.margin > 0.0);
+        assert!(month.low() < month.total() && month.total() < month.high());
+        assert!(month.low() >= month.actual);
+        // A longer way to go is less certain
+        assert!(service.quarter.margin >= month.margin);
+    }
+    let margins: f64 = forecast
+        .services
+        .iter()
+        .map(|s| s.month.margin.powi(2))
+        .sum();
+    assert!((forecast.month.margin - margins.sqrt()).abs() < 1e-9);
+    let total: f64 = forecast.services.iter().map(|s| s.month.total()).sum();
+    assert!((forecast.month.total() - total).abs() < 1e-9);
+}
+
+#[test]
+fn stale_data_is_projected_from_its_last_day() {
+    let data = daily(vec![series("Amazon EC2", vec![10.0; 35])]);
+
+    let forecast = Forecaster::default()
+        .forecast(&data, date(2026, 3, 20))
+        .unwrap();
+    assert_eq!(forecast.today, data.end);
+    assert!((forecast.month.total() - 310.0).abs() < 1e-6);
+    assert!(forecast.of_service("Amazon EC2", None).is_some());
+    assert!(forecast.of_service("Amazon EC2", Some("AWS")).is_none());
+}
+
+#[test]
+fn quarters() {
+    let day = date(2026, 5, 17);
+    assert_eq!(periods::quarter_start(day).unwrap(), date(2026, 4, 1));
+    assert_eq!(periods::next_quarter_start(day).unwrap(), date(2026, 7, 1));
+    assert_eq!(periods::quarter_name(day), "Q2 2026");
+    let december = date(2026, 12, 31);
+    assert_eq!(
+        periods::next_quarter_start(december).unwrap(),
+        date(2027, 1, 1)
+    );
+    assert_eq!(periods::quarter_name(december), "Q4 2026");
+}
+
+#[tokio::test]
+async fn synthetic_forecast() {
+    let today = date(2026, 3, 15);
+    let source = SyntheticSource::new(42).with_today(today);
+
+    let forecast = Forecast::load(&source, Forecaster::default(), today)
+        .await
+        .unwrap();
+    let month = source.current_month().await.unwrap();
+    assert_eq!(forecast.today, today);
+    assert_eq!(forecast.services.len(), month.breakdown.len());
+    assert!(forecast.month.total() > month.total_cost);
+    assert!(forecast.quarter.total() > forecast.month.total());
+    assert!(forecast
+        .services
+        .windows(2)
+        .all(|pair| pair[0].month.total() >= pair[1].month.total()));
+
+    // Month to date at the start of today, so a little under the bill
+    assert!(forecast.month.actual < month.total_cost);
+    assert!(forecast.month.actual > month.total_cost * 0.9);
+}
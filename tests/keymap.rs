@@ -0,0 +1,135 @@
+use aws_costs_tui::config::Config;
+use aws_costs_tui::keymap::{Action, Binding, Key, Keymap, Lookup};
+use std::collections::BTreeMap;
+
+fn binding(s: &str) -> Binding {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parses_keys_and_chords() {
+    assert_eq!(binding("j"), Binding(vec![Key::Char('j')]));
+    assert_eq!(binding("gg"), Binding(vec![Key::Char('g'), Key::Char('g')]));
+    assert_eq!(binding("Shift-Tab"), Binding(vec![Key::BackTab]));
+    assert_eq!(binding("ctrl-R"), Binding(vec![Key::Ctrl('r')]));
+    assert_eq!(binding("Space"), Binding(vec![Key::Char(' ')]));
+    assert_eq!(binding("F5"), Binding(vec![Key::F(5)]));
+    assert_eq!(
+        binding("g Enter"),
+        Binding(vec![Key::Char('g'), Key::Enter])
+    );
+
+    // Shown the way they are written, with characters run together
+    assert_eq!(binding("gg").to_string(), "gg");
+    assert_eq!(binding("g Enter").to_string(), "g ⏎");
+    assert_eq!(binding("Ctrl-w j").to_string(), "Ctrl-w j");
+
+    assert!("".parse::<Binding>().is_err());
+    assert!("Ctrl-".parse::<Binding>().is_err());
+    assert!("Hyper-x".parse::<Binding>().is_err());
+}
+
+#[test]
+fn chords_wait_for_their_last_key() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.lookup(&[Key::Char('g')]), Lookup::Pending);
+    assert_eq!(
+        keymap.lookup(&[Key::Char('g'), Key::Char('g')]),
+        Lookup::Action(Action::Top)
+    );
+    assert_eq!(
+        keymap.lookup(&[Key::Char('G')]),
+        Lookup::Action(Action::Bottom)
+    );
+    assert_eq!(
+        keymap.lookup(&[Key::Char('g'), Key::Char('x')]),
+        Lookup::Unbound
+    );
+    assert_eq!(
+        keymap.lookup(&[Key::Char('?')]),
+        Lookup::Action(Action::Help)
+    );
+
+    // Every action has a default key, the preferred one first
+    for action in Action::ALL {
+        assert!(!keymap.bindings(action).is_empty(), "{} is unbound", action);
+    }
+    assert_eq!(keymap.bindings(Action::NextTab)[0].to_string(), "→");
+
+    // A stray Backspace mustn't quit from the main views
+    assert_eq!(keymap.lookup(&[Key::Backspace]), Lookup::Unbound);
+}
+
+#[test]
+fn overrides_replace_and_take_over_defaults() {
+    let overrides = BTreeMap::from([
+        (Action::NextTab, vec![binding("l")]),
+        // `t` belonged to the tag explorer, `g` started `gg`
+        (Action::Refresh, vec![binding("t"), binding("g")]),
+    ]);
+    let keymap = Keymap::with_overrides(&overrides).unwrap();
+    assert_eq!(
+        keymap.lookup(&[Key::Char('l')]),
+        Lookup::Action(Action::NextTab)
+    );
+    assert_eq!(keymap.lookup(&[Key::Right]), Lookup::Unbound);
+    assert_eq!(
+        keymap.lookup(&[Key::Char('t')]),
+        Lookup::Action(Action::Refresh)
+    );
+    assert_eq!(
+        keymap.lookup(&[Key::Char('g')]),
+        Lookup::Action(Action::Refresh)
+    );
+    assert!(keymap.bindings(Action::Tags).is_empty());
+    assert_eq!(keymap.bindings(Action::Top), vec![&binding("Home")]);
+
+    let clash = BTreeMap::from([
+        (Action::Up, vec![binding("k")]),
+        (Action::Down, vec![binding("k")]),
+    ]);
+    let (action, message) = Keymap::with_overrides(&clash).unwrap_err();
+    assert_eq!(action, Action::Up);
+    assert!(
+        message.contains("k is already bound to down"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn config_keys_report_their_line() {
+    let config =
+        Config::parse("[keys]\nnext_tab = [\"l\", \"Tab\"]\nrefresh = \"Ctrl-r\"\n").unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(
+        keymap.lookup(&[Key::Ctrl('r')]),
+        Lookup::Action(Action::Refresh)
+    );
+    assert_eq!(keymap.lookup(&[Key::Tab]), Lookup::Action(Action::NextTab));
+
+    let config = Config::parse("[keys]\nback = [\"Esc\", \"Backspace\"]\n").unwrap();
+    let keymap = config.keymap().unwrap();
+    assert_eq!(
+        keymap.lookup(&[Key::Backspace]),
+        Lookup::Action(Action::Back)
+    );
+
+    let error = |content: &str| format!("{:#}", Config::parse(content).unwrap_err());
+
+    let unknown = error("[keys]\n\nlaunch = \"x\"\n");
+    assert!(unknown.contains("line 3"), "{}", unknown);
+    assert!(unknown.contains("Unknown action \"launch\""), "{}", unknown);
+
+    let bad_key = error("[keys]\ntop = [\"Home\", \"Hyper-x\"]\n");
+    assert!(bad_key.contains("line 2"), "{}", bad_key);
+    assert!(bad_key.contains("Unknown key \"Hyper-x\""), "{}", bad_key);
+
+    let overlap = error("[keys]\nhelp = \"h\"\nprev_tab = \"hh\"\n");
+    assert!(overlap.contains("line 3"), "{}", overlap);
+    assert!(
+        overlap.contains("one is the start of the other"),
+        "{}",
+        overlap
+    );
+}
@@ -0,0 +1,242 @@
+mod common;
+
+use aws_costs_tui::analysis::{LocalBudgets, Scope};
+use aws_costs_tui::aws::{BudgetStatus, CostData, Dimension, GroupBy, ServiceCost};
+use aws_costs_tui::source::{group_by_label, parse_group_by_label, CostSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::date;
+
+fn data(start: NaiveDate, end: NaiveDate, costs: &[(&str, f64)]) -> CostData {
+    CostData {
+        period: String::new(),
+        start,
+        end,
+        total_cost: costs.iter().map(|(_, c)| c).sum(),
+        currency: "USD".to_string(),
+        breakdown: costs
+            .iter()
+            .map(|(name, cost)| ServiceCost {
+                service: name.to_string(),
+                cost: *cost,
+                percentage: 0.0,
+                provider: None,
+            })
+            .collect(),
+    }
+}
+
+const BUDGETS: &str = r#"
+[[budget]]
+name = "Total"
+period = "monthly"
+amount = 3000
+thresholds = [100, 50, 90]
+
+[[budget]]
+name = "EC2"
+period = "quarterly"
+amount = 6000
+service = "Amazon Elastic Compute Cloud - Compute"
+
+[[budget]]
+name = "Web"
+period = "annual"
+amount = 20000
+tag = { key = "team", value = "web" }
+
+[[budget]]
+name = "Ireland"
+period = "monthly"
+amount = 500
+filter = { key = "REGION", value = "eu-west-1" }
+
+[[budget]]
+name = "Prod"
+period = "monthly"
+amount = 1500
+account = "111111111111"
+"#;
+
+#[test]
+fn parses_scopes_and_thresholds() {
+    let budgets = LocalBudgets::parse(BUDGETS).unwrap();
+    let scopes: Vec<Scope> = budgets.budgets.iter().map(|b| b.scope().unwrap()).collect();
+    assert_eq!(scopes[0], Scope::All);
+    assert_eq!(
+        scopes[2],
+        Scope::Value {
+            group_by: GroupBy::Tag("team".to_string()),
+            value: "web".to_string()
+        }
+    );
+    assert_eq!(scopes[3].group_by(), GroupBy::Dimension(Dimension::Region));
+    assert_eq!(scopes[4].describe(), "account 111111111111");
+    // Thresholds are kept lowest first
+    assert_eq!(budgets.budgets[0].thresholds, [50.0, 90.0, 100.0]);
+
+    // Tag scopes match on the value, not the untagged spend
+    assert!(scopes[2].matches("team$web"));
+    assert!(!scopes[2].matches("team$"));
+    assert!(!scopes[2].matches("web"));
+}
+
+#[test]
+fn rejects_invalid_budgets() {
+    let error = |toml: &str| format!("{:#}", LocalBudgets::parse(toml).unwrap_err());
+
+    let twice = r#"
+        [[budget]]
+        name = "A"
+        period = "monthly"
+        amount = 10
+        [[budget]]
+        name = "A"
+        period = "annual"
+        amount = 10
+    "#;
+    assert!(error(twice).contains("defined twice"));
+
+    let scopes = r#"
+        [[budget]]
+        name = "A"
+        period = "monthly"
+        amount = 10
+        service = "AWS Lambda"
+        account = "111111111111"
+    "#;
+    assert!(error(scopes).contains("at most one"));
+
+    let filter = r#"
+        [[budget]]
+        name = "A"
+        period = "monthly"
+        amount = 10
+        filter = { key = "COLOR", value = "red" }
+    "#;
+    assert!(error(filter).contains("Unknown grouping \"COLOR\""));
+
+    let amount = r#"
+        [[budget]]
+        name = "A"
+        period = "monthly"
+        amount = 0
+    "#;
+    assert!(error(amount).contains("positive amount"));
+
+    let threshold = r#"
+        [[budget]]
+        name = "A"
+        period = "monthly"
+        amount = 10
+        thresholds = [80, -5]
+    "#;
+    assert!(error(threshold).contains("threshold"));
+
+    let period = r#"
+        [[budget]]
+        name = "A"
+        period = "weekly"
+        amount = 10
+    "#;
+    assert!(error(period).contains("unknown variant"));
+}
+
+#[test]
+fn group_by_labels_round_trip() {
+    for group_by in [
+        GroupBy::Dimension(Dimension::UsageType),
+        GroupBy::Tag("team".to_string()),
+        GroupBy::CostCategory("Business unit".to_string()),
+    ] {
+        assert_eq!(
+            parse_group_by_label(&group_by_label(&group_by)).unwrap(),
+            group_by
+        );
+    }
+    assert!(parse_group_by_label("TAG:").is_err());
+}
+
+#[test]
+fn evaluates_against_cost_data() {
+    let budgets = LocalBudgets::parse(BUDGETS).unwrap();
+    let today = date(2026, 3, 10);
+
+    // Ten days of a 31-day month
+    let month = data(
+        date(2026, 3, 1),
+        date(2026, 3, 11),
+        &[
+            ("Amazon Elastic Compute Cloud - Compute", 1000.0),
+            ("AWS Lambda", 500.0),
+        ],
+    );
+    let total = budgets.budgets[0].evaluate(&month, today).unwrap();
+    assert_eq!(
+        (total.start, total.end),
+        (date(2026, 3, 1), date(2026, 4, 1))
+    );
+    assert_eq!(total.actual, 1500.0);
+    assert_eq!(total.forecasted, Some(4650.0));
+    assert_eq!(total.scope.as_deref(), Some("all spend"));
+    assert!(total.is_local());
+    // Past the 50% threshold and forecast over
+    assert_eq!(total.crossed_threshold(), Some(50.0));
+    assert_eq!(total.status(), BudgetStatus::ForecastOver);
+
+    // The quarter so far, by service
+    let quarter = data(
+        date(2026, 1, 1),
+        date(2026, 3, 11),
+        &[
+            ("Amazon Elastic Compute Cloud - Compute", 5700.0),
+            ("AWS Lambda", 900.0),
+        ],
+    );
+    let ec2 = budgets.budgets[1].evaluate(&quarter, today).unwrap();
+    assert_eq!(ec2.actual, 5700.0);
+    assert_eq!(ec2.period_name(), "Q1 2026");
+    // Default thresholds flag 80% of the limit
+    assert_eq!(ec2.crossed_threshold(), Some(80.0));
+
+    // A scope without spend is at zero, not missing
+    let ireland = budgets.budgets[3].evaluate(&month, today).unwrap();
+    assert_eq!(ireland.actual, 0.0);
+    assert_eq!(ireland.crossed_threshold(), None);
+    assert_eq!(ireland.status(), BudgetStatus::OnTrack);
+}
+
+#[tokio::test]
+async fn evaluates_synthetic_source() {
+    let today = date(2026, 3, 15);
+    let source = SyntheticSource::new(42).with_today(today);
+    let budgets = LocalBudgets::parse(BUDGETS)
+        .unwrap()
+        .evaluate(&source, today)
+        .await
+        .unwrap();
+    let names: Vec<&str> = budgets.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["Total", "EC2", "Web", "Ireland", "Prod"]);
+
+    let month = source.current_month().await.unwrap();
+    assert!((budgets[0].actual - month.total_cost).abs() < 0.01);
+
+    let web = source
+        .breakdown(
+            date(2026, 1, 1),
+            date(2026, 3, 16),
+            &GroupBy::Tag("team".to_string()),
+        )
+        .await
+        .unwrap();
+    let spend = web
+        .breakdown
+        .iter()
+        .find(|s| s.service == "team$web")
+        .unwrap();
+    assert!((budgets[2].actual - spend.cost).abs() < 0.01);
+
+    for budget in &budgets {
+        assert!(budget.actual > 0.0, "{} has no spend", budget.name);
+        assert!(budget.history.is_empty());
+    }
+}
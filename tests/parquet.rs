@@ -0,0 +1,140 @@
+mod common;
+
+use aws_costs_tui::aws::{Dimension, GroupBy};
+use aws_costs_tui::source::{CostSource, FixtureSource, MergedSource, ParquetSource};
+use common::{cost_of, date, fixture};
+use std::sync::Arc;
+
+#[tokio::test]
+async fn reads_partitioned_data_export() {
+    let export = ParquetSource::load(&fixture("parquet/costs")).unwrap();
+    assert_eq!(export.files().len(), 2);
+    assert_eq!(export.today(), date(2026, 3, 31));
+
+    let current = export.current_month().await.unwrap();
+    assert_eq!(current.period, "March 2026");
+    assert_eq!(current.currency, "USD");
+    assert_eq!(current.total_cost, 145.0);
+    // Names come from the `product` map, not the product code
+    assert_eq!(cost_of(&current, "Amazon Elastic Compute Cloud"), 95.0);
+    assert_eq!(cost_of(&current, "AWS Lambda"), 25.0);
+
+    let previous = export.previous_month().await.unwrap();
+    assert_eq!(previous.total_cost, 100.0);
+
+    let trend = export.monthly_trend(3).await.unwrap();
+    assert_eq!(trend.months.len(), 2);
+    assert_eq!(trend.gaps[0].start, date(2026, 1, 1));
+}
+
+#[tokio::test]
+async fn breakdown_by_resource_and_tag() {
+    let export = ParquetSource::load(&fixture("parquet/costs")).unwrap();
+    let (start, end) = (date(2026, 3, 1), date(2026, 4, 1));
+
+    let resources = export
+        .breakdown(start, end, &GroupBy::Dimension(Dimension::ResourceId))
+        .await
+        .unwrap();
+    assert_eq!(cost_of(&resources, "i-0aaa"), 60.0);
+    assert_eq!(
+        cost_of(
+            &resources,
+            "arn:aws:lambda:us-east-1:111111111111:function:api"
+        ),
+        25.0
+    );
+    assert_eq!(cost_of(&resources, "(none)"), 5.0);
+
+    let teams = export
+        .breakdown(start, end, &GroupBy::Tag("team".to_string()))
+        .await
+        .unwrap();
+    // `user_team` in the resource_tags map
+    assert_eq!(cost_of(&teams, "team$platform"), 60.0);
+    assert_eq!(cost_of(&teams, "team$"), 35.0);
+
+    assert_eq!(export.tag_keys(start, end).await.unwrap(), ["team"]);
+}
+
+#[tokio::test]
+async fn resources_of_one_service() {
+    let export = ParquetSource::load(&fixture("parquet/costs")).unwrap();
+    let ec2 = export
+        .resources(
+            "Amazon Elastic Compute Cloud",
+            date(2026, 3, 1),
+            date(2026, 4, 1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(ec2.total_cost, 95.0);
+    let ids: Vec<_> = ec2.breakdown.iter().map(|r| r.service.as_str()).collect();
+    assert_eq!(ids, ["i-0aaa", "i-0bbb", "(none)"]);
+}
+
+#[tokio::test]
+async fn month_to_date_filters_rows_by_usage_date() {
+    let export = ParquetSource::load(&fixture("parquet/costs"))
+        .unwrap()
+        .with_today(date(2026, 3, 3));
+    let current = export.current_month().await.unwrap();
+    assert_eq!(current.end, date(2026, 3, 4));
+    assert_eq!(current.total_cost, 75.0);
+}
+
+#[tokio::test]
+async fn athena_layout_with_string_timestamps() {
+    let export = ParquetSource::load(&fixture("parquet/athena-2026-01.parquet")).unwrap();
+    assert_eq!(export.today(), date(2026, 1, 31));
+
+    let current = export.current_month().await.unwrap();
+    assert_eq!(current.currency, "EUR");
+    assert_eq!(cost_of(&current, "Amazon Elastic Compute Cloud"), 50.0);
+
+    let teams = export
+        .breakdown(
+            date(2026, 1, 1),
+            date(2026, 2, 1),
+            &GroupBy::Tag("team".to_string()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(cost_of(&teams, "team$data"), 12.5);
+    assert_eq!(cost_of(&teams, "team$"), 20.0);
+
+    // From the flattened `resource_tags_user_team` column
+    let keys = export
+        .tag_keys(date(2026, 1, 1), date(2026, 2, 1))
+        .await
+        .unwrap();
+    assert_eq!(keys, ["team"]);
+}
+
+#[tokio::test]
+async fn merged_resources_skip_sources_without_them() {
+    let sample = fixture("sample.json");
+    let export = ParquetSource::load(&fixture("parquet/costs")).unwrap();
+    let merged = MergedSource::new(vec![
+        Arc::new(FixtureSource::load(&sample).unwrap()),
+        Arc::new(export),
+    ]);
+
+    let lambda = merged
+        .resources("AWS Lambda", date(2026, 3, 1), date(2026, 4, 1))
+        .await
+        .unwrap();
+    assert_eq!(lambda.breakdown.len(), 1);
+    assert_eq!(lambda.breakdown[0].provider.as_deref(), Some("AWS"));
+
+    let err = FixtureSource::load(&sample)
+        .unwrap()
+        .resources("AWS Lambda", date(2026, 3, 1), date(2026, 4, 1))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("no resource-level data"),
+        "{}",
+        err
+    );
+}
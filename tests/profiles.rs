@@ -0,0 +1,61 @@
+use aws_costs_tui::aws::{describe_profiles, list_profiles, CredentialType, Credentials, Profile};
+use std::env;
+use std::path::Path;
+
+/// One test, since it points the whole process at the fixture config
+#[test]
+fn describes_profiles_from_config_and_credentials() {
+    let config = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/aws/config");
+    env::set_var("AWS_CONFIG_FILE", &config);
+    for var in [
+        "AWS_ACCESS_KEY_ID",
+        "AWS_SECRET_ACCESS_KEY",
+        "AWS_REGION",
+        "AWS_DEFAULT_REGION",
+    ] {
+        env::remove_var(var);
+    }
+
+    assert_eq!(
+        list_profiles(),
+        ["audit", "default", "dev", "prod", "sandbox", "staging", "tool"]
+    );
+
+    let profile = |name: &str, region: Option<&str>, credential_type| Profile {
+        name: name.to_string(),
+        region: region.map(str::to_string),
+        credential_type,
+    };
+    assert_eq!(
+        describe_profiles(),
+        [
+            profile("audit", None, CredentialType::AssumeRole),
+            profile("default", Some("us-east-1"), CredentialType::Missing),
+            profile("dev", Some("us-west-2"), CredentialType::AccessKeys),
+            profile("prod", Some("eu-west-1"), CredentialType::AccessKeys),
+            profile("sandbox", None, CredentialType::Sso),
+            profile("staging", None, CredentialType::SessionToken),
+            profile("tool", None, CredentialType::Process),
+        ]
+    );
+
+    // The supported types are the ones credentials load for
+    for profile in describe_profiles() {
+        let loaded = Credentials::load(&profile.name, None);
+        assert_eq!(
+            loaded.is_ok(),
+            profile.credential_type.is_supported(),
+            "{}",
+            profile.name
+        );
+    }
+    let prod = Credentials::load("prod", None).unwrap();
+    assert_eq!(prod.region, "eu-west-1");
+
+    // Credentials in the environment stand in for the default profile
+    env::set_var("AWS_ACCESS_KEY_ID", "AKIAENVEXAMPLE");
+    env::set_var("AWS_SECRET_ACCESS_KEY", "env-secret");
+    let default = describe_profiles().remove(1);
+    assert_eq!(default.credential_type, CredentialType::Environment);
+    assert!(default.credential_type.is_supported());
+}
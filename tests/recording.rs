@@ -0,0 +1,372 @@
+mod common;
+
+use aws_costs_tui::aws::{
+    CostExplorerClient, CostQuery, Credentials, Dimension, GroupBy, Recorder, Replayer,
+};
+use common::{date, scratch_dir};
+use std::fs;
+
+fn credentials() -> Credentials {
+    Credentials {
+        access_key_id: "AKIDEXAMPLE".to_string(),
+        secret_access_key: "wJalrXUtnFEMI/K7MDENG".to_string(),
+        session_token: Some("FwoGZXIvYXdzEXAMPLETOKEN".to_string()),
+        region: "eu-west-1".to_string(),
+    }
+}
+
+/// Request body the client sends for the month-to-date view on 2026-03-15,
+/// with keys deliberately out of order
+const CURRENT_MONTH_REQUEST: &str = r#"{
+    "Metrics": ["UnblendedCost"],
+    "GroupBy": [{"Key": "SERVICE", "Type": "DIMENSION"}],
+    "Granularity": "MONTHLY",
+    "TimePeriod": {"End": "2026-03-16", "Start": "2026-03-01"}
+}"#;
+
+const CURRENT_MONTH_RESPONSE: &str = r#"{
+    "ResultsByTime": [{
+        "TimePeriod": {"Start": "2026-03-01", "End": "2026-03-16"},
+        "Groups": [
+            {"Keys": ["Amazon EC2"], "Metrics": {"UnblendedCost": {"Amount": "120.5", "Unit": "USD"}}},
+            {"Keys": ["AWS Lambda"], "Metrics": {"UnblendedCost": {"Amount": "9.5", "Unit": "USD"}}}
+        ]
+    }]
+}"#;
+
+#[test]
+fn recorder_scrubs_credentials() {
+    let dir = scratch_dir("record-scrub");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCostAndUsage",
+            r#"{"Echo": "AKIDEXAMPLE wJalrXUtnFEMI/K7MDENG"}"#,
+            400,
+            "token FwoGZXIvYXdzEXAMPLETOKEN rejected",
+        )
+        .unwrap();
+
+    let written: String = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(written.contains("eu-west-1"));
+    assert!(written.contains("<redacted>"));
+    for secret in [
+        "AKIDEXAMPLE",
+        "wJalrXUtnFEMI/K7MDENG",
+        "FwoGZXIvYXdzEXAMPLETOKEN",
+    ] {
+        assert!(
+            !written.contains(secret),
+            "{} leaked into recording",
+            secret
+        );
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn replay_serves_recorded_responses_offline() {
+    let dir = scratch_dir("replay");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCostAndUsage",
+            CURRENT_MONTH_REQUEST,
+            200,
+            CURRENT_MONTH_RESPONSE,
+        )
+        .unwrap();
+
+    let replayer = Replayer::load(&dir).unwrap();
+    assert_eq!(replayer.manifest().recorded_on, date(2026, 3, 15));
+    assert_eq!(replayer.manifest().region, "eu-west-1");
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1")).replay_from(replayer);
+    assert_eq!(client.today(), date(2026, 3, 15));
+
+    let data = client.get_current_month_costs().await.unwrap();
+    assert_eq!(data.period, "March 2026");
+    assert_eq!(data.total_cost, 130.0);
+    assert_eq!(data.breakdown[0].service, "Amazon EC2");
+
+    // Nothing was recorded for the previous month
+    let err = client.get_previous_month_costs().await.unwrap_err();
+    assert!(
+        err.to_string().contains("No recorded exchange"),
+        "{:#}",
+        err
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// `GetCostAndUsageWithResources` body for EC2 on 2026-03-15: the month
+/// to date, clipped to the last 14 days
+const RESOURCES_REQUEST: &str = r#"{
+    "TimePeriod": {"Start": "2026-03-02", "End": "2026-03-16"},
+    "Granularity": "DAILY",
+    "Metrics": ["UnblendedCost"],
+    "GroupBy": [{"Type": "DIMENSION", "Key": "RESOURCE_ID"}],
+    "Filter": {"Dimensions": {"Key": "SERVICE", "Values": ["Amazon EC2"]}}
+}"#;
+
+const RESOURCES_RESPONSE: &str = r#"{
+    "ResultsByTime": [
+        {
+            "TimePeriod": {"Start": "2026-03-02", "End": "2026-03-03"},
+            "Groups": [
+                {"Keys": ["i-0aaa"], "Metrics": {"UnblendedCost": {"Amount": "6", "Unit": "USD"}}},
+                {"Keys": ["i-0bbb"], "Metrics": {"UnblendedCost": {"Amount": "2", "Unit": "USD"}}}
+            ]
+        },
+        {
+            "TimePeriod": {"Start": "2026-03-03", "End": "2026-03-04"},
+            "Groups": [
+                {"Keys": ["i-0aaa"], "Metrics": {"UnblendedCost": {"Amount": "4", "Unit": "USD"}}}
+            ]
+        }
+    ]
+}"#;
+
+#[tokio::test]
+async fn resource_costs_use_the_resource_window() {
+    let dir = scratch_dir("resources");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCostAndUsageWithResources",
+            RESOURCES_REQUEST,
+            200,
+            RESOURCES_RESPONSE,
+        )
+        .unwrap();
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1"))
+        .replay_from(Replayer::load(&dir).unwrap());
+    assert_eq!(
+        client.resource_window(),
+        (date(2026, 3, 2), date(2026, 3, 16))
+    );
+
+    let data = client
+        .get_resource_costs("Amazon EC2", date(2026, 3, 1), date(2026, 3, 16))
+        .await
+        .unwrap();
+    assert_eq!(
+        (data.start, data.end),
+        (date(2026, 3, 2), date(2026, 3, 16))
+    );
+    assert_eq!(data.total_cost, 12.0);
+    assert_eq!(data.breakdown[0].service, "i-0aaa");
+    assert_eq!(data.breakdown[0].cost, 10.0);
+
+    // February is entirely outside the window, so nothing is sent
+    let err = client
+        .get_resource_costs("Amazon EC2", date(2026, 2, 1), date(2026, 3, 1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("last 14 days"), "{:#}", err);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn listing_calls_follow_next_page_token() {
+    let dir = scratch_dir("listing");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    let period = r#""TimePeriod": {"Start": "2026-03-01", "End": "2026-03-16"}"#;
+    let exchanges = [
+        (
+            "GetTags",
+            format!("{{{}}}", period),
+            r#"{"Tags": ["cost-center", "team"], "NextPageToken": "page2"}"#,
+        ),
+        (
+            "GetTags",
+            format!(r#"{{{}, "NextPageToken": "page2"}}"#, period),
+            r#"{"Tags": ["environment"]}"#,
+        ),
+        (
+            "GetTags",
+            format!(r#"{{{}, "TagKey": "team"}}"#, period),
+            r#"{"Tags": ["", "data", "platform"]}"#,
+        ),
+        (
+            "GetDimensionValues",
+            format!(
+                r#"{{{}, "Dimension": "LINKED_ACCOUNT", "Context": "COST_AND_USAGE"}}"#,
+                period
+            ),
+            r#"{"DimensionValues": [{"Value": "111111111111", "Attributes": {"description": "prod"}}]}"#,
+        ),
+        (
+            "GetCostCategories",
+            format!("{{{}}}", period),
+            r#"{"CostCategoryNames": ["Business Unit"]}"#,
+        ),
+    ];
+    for (action, request, response) in &exchanges {
+        recorder.record(action, request, 200, response).unwrap();
+    }
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1"))
+        .replay_from(Replayer::load(&dir).unwrap());
+    let (start, end) = (date(2026, 3, 1), date(2026, 3, 16));
+
+    let keys = client.get_tags(start, end, None).await.unwrap();
+    assert_eq!(keys, ["cost-center", "team", "environment"]);
+    let values = client.get_tags(start, end, Some("team")).await.unwrap();
+    assert_eq!(values, ["", "data", "platform"]);
+
+    let accounts = client
+        .get_dimension_values(start, end, Dimension::LinkedAccount)
+        .await
+        .unwrap();
+    assert_eq!(accounts, ["111111111111"]);
+    let categories = client.get_cost_categories(start, end, None).await.unwrap();
+    assert_eq!(categories, ["Business Unit"]);
+
+    // Nothing recorded for this category's values
+    let err = client
+        .get_cost_categories(start, end, Some("Business Unit"))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("GetCostCategories page 1"),
+        "{:#}",
+        err
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn cost_matrix_groups_by_two_keys() {
+    let dir = scratch_dir("matrix");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCostAndUsage",
+            r#"{
+                "TimePeriod": {"Start": "2026-03-01", "End": "2026-03-16"},
+                "Granularity": "MONTHLY",
+                "Metrics": ["UnblendedCost"],
+                "GroupBy": [
+                    {"Type": "DIMENSION", "Key": "SERVICE"},
+                    {"Type": "TAG", "Key": "team"}
+                ]
+            }"#,
+            200,
+            r#"{
+                "ResultsByTime": [{
+                    "TimePeriod": {"Start": "2026-03-01", "End": "2026-03-16"},
+                    "Groups": [
+                        {"Keys": ["Amazon EC2", "team$platform"], "Metrics": {"UnblendedCost": {"Amount": "90", "Unit": "USD"}}},
+                        {"Keys": ["Amazon EC2", "team$"], "Metrics": {"UnblendedCost": {"Amount": "30", "Unit": "USD"}}},
+                        {"Keys": ["AWS Lambda", "team$web"], "Metrics": {"UnblendedCost": {"Amount": "10", "Unit": "USD"}}}
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1"))
+        .replay_from(Replayer::load(&dir).unwrap());
+    let query = CostQuery::new(date(2026, 3, 1), date(2026, 3, 16))
+        .group_by(GroupBy::Dimension(Dimension::Service))
+        .group_by(GroupBy::Tag("team".to_string()));
+    let matrix = client.get_cost_matrix(&query, "March 2026").await.unwrap();
+    assert_eq!(matrix.total_cost, 130.0);
+    let cells: Vec<_> = matrix
+        .cells
+        .iter()
+        .map(|c| (c.row.as_str(), c.column.as_str(), c.cost))
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            ("Amazon EC2", "team$platform", 90.0),
+            ("Amazon EC2", "team$", 30.0),
+            ("AWS Lambda", "team$web", 10.0),
+        ]
+    );
+
+    let single = CostQuery::new(date(2026, 3, 1), date(2026, 3, 16))
+        .group_by(GroupBy::Dimension(Dimension::Service));
+    let err = client
+        .get_cost_matrix(&single, "March 2026")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("two groupings"), "{:#}", err);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn daily_costs_spread_by_service_and_day() {
+    let dir = scratch_dir("daily");
+    let recorder = Recorder::create(&dir, &credentials(), date(2026, 3, 15)).unwrap();
+    recorder
+        .record(
+            "GetCostAndUsage",
+            r#"{
+                "TimePeriod": {"Start": "2026-03-01", "End": "2026-03-04"},
+                "Granularity": "DAILY",
+                "Metrics": ["UnblendedCost"],
+                "GroupBy": [{"Type": "DIMENSION", "Key": "SERVICE"}]
+            }"#,
+            200,
+            r#"{
+                "ResultsByTime": [
+                    {
+                        "TimePeriod": {"Start": "2026-03-01", "End": "2026-03-02"},
+                        "Groups": [
+                            {"Keys": ["Amazon EC2"], "Metrics": {"UnblendedCost": {"Amount": "40", "Unit": "USD"}}},
+                            {"Keys": ["AWS Lambda"], "Metrics": {"UnblendedCost": {"Amount": "2", "Unit": "USD"}}}
+                        ]
+                    },
+                    {
+                        "TimePeriod": {"Start": "2026-03-02", "End": "2026-03-03"},
+                        "Groups": [
+                            {"Keys": ["Amazon EC2"], "Metrics": {"UnblendedCost": {"Amount": "42", "Unit": "USD"}}}
+                        ]
+                    },
+                    {
+                        "TimePeriod": {"Start": "2026-03-03", "End": "2026-03-04"},
+                        "Groups": [
+                            {"Keys": ["AWS Lambda"], "Metrics": {"UnblendedCost": {"Amount": "9", "Unit": "USD"}}},
+                            {"Keys": ["Amazon EC2"], "Metrics": {"UnblendedCost": {"Amount": "41", "Unit": "USD"}}}
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+    let client = CostExplorerClient::new(Credentials::anonymous("eu-west-1"))
+        .replay_from(Replayer::load(&dir).unwrap());
+    let daily = client
+        .get_daily_costs(date(2026, 3, 1), date(2026, 3, 4))
+        .await
+        .unwrap();
+    assert_eq!(daily.days(), 3);
+    assert_eq!(daily.currency, "USD");
+    let series: Vec<_> = daily
+        .series
+        .iter()
+        .map(|s| (s.service.as_str(), s.costs.clone()))
+        .collect();
+    assert_eq!(
+        series,
+        [
+            ("Amazon EC2", vec![40.0, 42.0, 41.0]),
+            ("AWS Lambda", vec![2.0, 0.0, 9.0]),
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,365 @@
+mod common;
+
+use aws_costs_tui::analysis::tags::{tag_value, TagSpend};
+use aws_costs_tui::analysis::TagCoverage;
+use aws_costs_tui::aws::{Dimension, GroupBy};
+use aws_costs_tui::source::{CostSource, Fixture, FixtureSource, SyntheticSource};
+use chrono::NaiveDate;
+use common::date;
+use std::path::Path;
+
+fn demo(today: NaiveDate) -> SyntheticSource {
+    SyntheticSource::new(42).with_today(today)
+}
+
+#[tokio::test]
+async fn synthetic_source_is_deterministic() {
+    let a = demo(date(2026, 5, 14)).current_month().await.unwrap();
+    let b = demo(date(2026, 5, 14)).current_month().await.unwrap();
+    assert_eq!(a.total_cost, b.total_cost);
+    assert_eq!(
+        a.breakdown.iter().map(|s| &s.service).collect::<Vec<_>>(),
+        b.breakdown.iter().map(|s| &s.service).collect::<Vec<_>>()
+    );
+
+    let other_seed = SyntheticSource::new(7)
+        .with_today(date(2026, 5, 14))
+        .current_month()
+        .await
+        .unwrap();
+    assert_ne!(a.total_cost, other_seed.total_cost);
+}
+
+#[tokio::test]
+async fn synthetic_current_month_covers_month_to_date() {
+    let source = demo(date(2026, 5, 14));
+    let current = source.current_month().await.unwrap();
+    let previous = source.previous_month().await.unwrap();
+
+    assert_eq!(
+        (current.start, current.end),
+        (date(2026, 5, 1), date(2026, 5, 15))
+    );
+    assert_eq!(
+        (previous.start, previous.end),
+        (date(2026, 4, 1), date(2026, 5, 1))
+    );
+    assert_eq!(current.period, "May 2026");
+    assert!(current.total_cost < previous.total_cost);
+
+    let sum: f64 = current.breakdown.iter().map(|s| s.cost).sum();
+    assert!((sum - current.total_cost).abs() < 0.01);
+    assert!(current.breakdown.windows(2).all(|w| w[0].cost >= w[1].cost));
+}
+
+#[tokio::test]
+async fn synthetic_trend_uses_calendar_months() {
+    // Stepping back 30 days at a time from March 31 would skip February
+    let trend = demo(date(2026, 3, 31)).monthly_trend(6).await.unwrap();
+    let starts: Vec<NaiveDate> = trend.months.iter().map(|m| m.start).collect();
+    assert_eq!(
+        starts,
+        vec![
+            date(2025, 10, 1),
+            date(2025, 11, 1),
+            date(2025, 12, 1),
+            date(2026, 1, 1),
+            date(2026, 2, 1),
+            date(2026, 3, 1),
+        ]
+    );
+    assert!(trend.gaps.is_empty());
+}
+
+#[tokio::test]
+async fn synthetic_breakdown_splits_range_total() {
+    let source = demo(date(2026, 5, 14));
+    let services = source
+        .breakdown(
+            date(2026, 4, 1),
+            date(2026, 5, 1),
+            &GroupBy::Dimension(Dimension::Service),
+        )
+        .await
+        .unwrap();
+    let accounts = source
+        .breakdown(
+            date(2026, 4, 1),
+            date(2026, 5, 1),
+            &GroupBy::Dimension(Dimension::LinkedAccount),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(accounts.breakdown.len(), 3);
+    assert!((services.total_cost - accounts.total_cost).abs() < 0.05);
+
+    let tags = source
+        .breakdown(
+            date(2026, 4, 1),
+            date(2026, 5, 1),
+            &GroupBy::Tag("team".into()),
+        )
+        .await
+        .unwrap();
+    assert!(tags.breakdown.iter().any(|s| s.service == "team$"));
+}
+
+#[tokio::test]
+async fn synthetic_resources_cover_the_last_14_days() {
+    let source = demo(date(2026, 5, 20));
+    let ec2 = source
+        .resources(
+            "Amazon Elastic Compute Cloud - Compute",
+            date(2026, 5, 1),
+            date(2026, 5, 21),
+        )
+        .await
+        .unwrap();
+    assert_eq!((ec2.start, ec2.end), (date(2026, 5, 7), date(2026, 5, 21)));
+    assert!(ec2.breakdown[0].service.starts_with("i-"));
+    assert!(ec2.breakdown[0].cost > ec2.breakdown[1].cost);
+
+    let err = source
+        .resources(
+            "Amazon Elastic Compute Cloud - Compute",
+            date(2026, 4, 1),
+            date(2026, 5, 1),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("last 14 days"), "{}", err);
+}
+
+#[tokio::test]
+async fn synthetic_tag_keys_and_untagged_share() {
+    let source = demo(date(2026, 5, 14));
+    let (start, end) = (date(2026, 5, 1), date(2026, 5, 15));
+    let keys = source.tag_keys(start, end).await.unwrap();
+    assert_eq!(keys, ["cost-center", "environment", "team"]);
+
+    let data = source
+        .breakdown(start, end, &GroupBy::Tag("environment".into()))
+        .await
+        .unwrap();
+    let spend = TagSpend::new("environment", &data);
+    assert_eq!(spend.values[0].value.as_deref(), Some("prod"));
+    let untagged: Vec<_> = spend.values.iter().filter(|v| v.value.is_none()).collect();
+    assert_eq!(untagged.len(), 1);
+    assert!((spend.untagged_percentage() - 6.0).abs() < 0.01);
+    assert_eq!(spend.untagged_cost(), untagged[0].cost);
+
+    assert_eq!(tag_value("team", "team$web"), Some("web"));
+    assert_eq!(tag_value("team", "team$"), None);
+    assert_eq!(tag_value("team", "teams$web"), None);
+}
+
+#[tokio::test]
+async fn fixture_source_replays_sample() {
+    let source = FixtureSource::load(Path::new("tests/fixtures/sample.json")).unwrap();
+    assert_eq!(source.name(), "fixture: sample account");
+
+    let current = source.current_month().await.unwrap();
+    assert_eq!(current.total_cost, 150.0);
+    assert_eq!(current.days(), 15);
+
+    let trend = source.monthly_trend(1).await.unwrap();
+    assert_eq!(trend.months.len(), 1);
+    assert_eq!(trend.months[0].period, "February 2026");
+    assert!(trend.gaps.is_empty());
+
+    let accounts = source
+        .breakdown(
+            date(2026, 3, 1),
+            date(2026, 3, 16),
+            &GroupBy::Dimension(Dimension::LinkedAccount),
+        )
+        .await
+        .unwrap();
+    assert_eq!(accounts.breakdown[0].service, "111111111111");
+
+    assert!(source
+        .breakdown(
+            date(2026, 3, 1),
+            date(2026, 3, 16),
+            &GroupBy::Dimension(Dimension::Region)
+        )
+        .await
+        .is_err());
+
+    // Another period is an error rather than the captured one relabelled
+    let err = source
+        .breakdown(
+            date(2026, 2, 1),
+            date(2026, 3, 1),
+            &GroupBy::Dimension(Dimension::LinkedAccount),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Fixture has no LINKED_ACCOUNT breakdown for 2026-02-01 – 2026-02-28 \
+         (captured: 2026-03-01 – 2026-03-15)"
+    );
+}
+
+#[tokio::test]
+async fn fixture_capture_round_trips() {
+    let synthetic = demo(date(2026, 2, 10));
+    let breakdowns = [(
+        date(2026, 2, 1),
+        date(2026, 2, 11),
+        GroupBy::Tag("team".into()),
+    )];
+    let fixture = Fixture::capture(&synthetic, 3, &breakdowns).await.unwrap();
+
+    let path = std::env::temp_dir().join(format!("aws-costs-fixture-{}.json", std::process::id()));
+    fixture.save(&path).unwrap();
+    let replay = FixtureSource::load(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+
+    let expected = synthetic.previous_month().await.unwrap();
+    let replayed = replay.previous_month().await.unwrap();
+    assert_eq!(replayed.total_cost, expected.total_cost);
+    assert_eq!(replayed.breakdown.len(), expected.breakdown.len());
+    assert_eq!(replay.monthly_trend(3).await.unwrap().months.len(), 3);
+    assert!(replay
+        .breakdown(
+            date(2026, 2, 1),
+            date(2026, 2, 11),
+            &GroupBy::Tag("team".into())
+        )
+        .await
+        .is_ok());
+    // Tag keys come from the captured tag breakdowns
+    let keys = replay
+        .tag_keys(date(2026, 2, 1), date(2026, 2, 11))
+        .await
+        .unwrap();
+    assert_eq!(keys, ["team"]);
+}
+
+#[tokio::test]
+async fn synthetic_tag_coverage_ranks_worst_offenders() {
+    let source = demo(date(2026, 5, 14));
+    let (start, end) = (date(2026, 5, 1), date(2026, 5, 15));
+    let keys = ["team".to_string(), "cost-center".to_string()];
+    let months = [
+        (date(2026, 3, 1), date(2026, 4, 1)),
+        (date(2026, 4, 1), date(2026, 5, 1)),
+        (start, end),
+    ];
+    let report = TagCoverage::load(&source, &keys, start, end, &months)
+        .await
+        .unwrap();
+
+    let total = source
+        .breakdown(start, end, &GroupBy::Tag("team".into()))
+        .await
+        .unwrap();
+    // Matrix cells are rounded to the cent one by one
+    assert!((report.total_cost - total.total_cost).abs() < 0.5);
+    let team = TagSpend::new("team", &total);
+    assert!((report.coverage(0) - (100.0 - team.untagged_percentage())).abs() < 0.1);
+    assert!(report.coverage(1) < report.coverage(0));
+
+    // Every service and account is listed, worst offenders first
+    assert!(!report.services.is_empty());
+    assert!(!report.accounts.is_empty());
+    for rows in [&report.services, &report.accounts] {
+        let cost: f64 = rows.iter().map(|r| r.cost).sum();
+        assert!((cost - report.total_cost).abs() < 0.5);
+        assert!(rows
+            .windows(2)
+            .all(|w| w[0].untagged_cost() >= w[1].untagged_cost()));
+    }
+
+    assert_eq!(report.trend.len(), 3);
+    assert_eq!(report.trend[2].period, "May 2026");
+    assert!(report.change(0).unwrap().abs() < 0.01);
+
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("scope,name,provider,cost,team_coverage_pct,team_untagged_cost,cost-center_coverage_pct,cost-center_untagged_cost")
+    );
+    assert!(lines.next().unwrap().starts_with("total,May 2026,,"));
+    assert_eq!(csv.lines().filter(|l| l.starts_with("month,")).count(), 3);
+}
+
+#[tokio::test]
+async fn fixture_replays_captured_matrices() {
+    let synthetic = demo(date(2026, 2, 10));
+    let (start, end) = (date(2026, 2, 1), date(2026, 2, 11));
+    let (services, team) = (
+        GroupBy::Dimension(Dimension::Service),
+        GroupBy::Tag("team".into()),
+    );
+    let mut fixture = Fixture::capture(&synthetic, 1, &[]).await.unwrap();
+    fixture
+        .capture_matrices(&synthetic, &[(start, end, services.clone(), team.clone())])
+        .await
+        .unwrap();
+    let replay = FixtureSource::new(fixture);
+
+    let expected = synthetic
+        .matrix(start, end, &services, &team)
+        .await
+        .unwrap();
+    let replayed = replay.matrix(start, end, &services, &team).await.unwrap();
+    assert_eq!(replayed.cells.len(), expected.cells.len());
+    assert_eq!(replayed.cells[0].row, expected.cells[0].row);
+    assert_eq!(replayed.cells[0].column, expected.cells[0].column);
+    assert_eq!(replayed.total_cost, expected.total_cost);
+
+    let err = replay
+        .matrix(start, end, &team, &services)
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Fixture has no TAG:team by SERVICE matrix");
+
+    assert!(replay
+        .matrix(start, date(2026, 2, 10), &services, &team)
+        .await
+        .unwrap_err()
+        .to_string()
+        .contains("for 2026-02-01 – 2026-02-09 (captured: 2026-02-01 – 2026-02-10)"));
+}
+
+#[tokio::test]
+async fn fixture_daily_costs_keep_to_the_range() {
+    let synthetic = demo(date(2026, 2, 10));
+    let mut fixture = Fixture::capture(&synthetic, 1, &[]).await.unwrap();
+    fixture
+        .capture_daily(&synthetic, date(2026, 1, 1), date(2026, 2, 11))
+        .await
+        .unwrap();
+    let replay = FixtureSource::new(fixture);
+
+    let (start, end) = (date(2026, 2, 1), date(2026, 2, 11));
+    let expected = synthetic.daily(start, end).await.unwrap();
+    let replayed = replay.daily(start, end).await.unwrap();
+    assert_eq!((replayed.start, replayed.end), (start, end));
+    assert_eq!(replayed.days(), 10);
+    assert_eq!(replayed.series.len(), expected.series.len());
+    for (replayed, expected) in replayed.series.iter().zip(&expected.series) {
+        assert_eq!(replayed.service, expected.service);
+        assert_eq!(replayed.costs, expected.costs);
+    }
+
+    // Days past the capture are left out, not made up
+    let clipped = replay.daily(start, date(2026, 3, 1)).await.unwrap();
+    assert_eq!(clipped.end, end);
+    let err = replay
+        .daily(date(2026, 3, 1), date(2026, 4, 1))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Fixture has no daily costs for 2026-03-01 – 2026-03-31 \
+         (captured: 2026-01-01 – 2026-02-10)"
+    );
+}
@@ -0,0 +1,23 @@
+use aws_costs_tui::text::truncate;
+
+#[test]
+fn cuts_names_to_the_width() {
+    assert_eq!(
+        truncate("Amazon Elastic Compute Cloud", 40, "…"),
+        "Elastic Compute Cloud"
+    );
+    assert_eq!(truncate("AWS Lambda", 6, "…"), "Lambda");
+    assert_eq!(truncate("Elastic Compute Cloud", 10, "..."), "Elastic...");
+    assert_eq!(truncate("Elastic Compute Cloud", 10, "…"), "Elastic C…");
+    assert_eq!(truncate("Elastic", 2, "..."), "..");
+}
+
+#[test]
+fn counts_characters_not_bytes() {
+    // The `·` and `é` are two bytes each; cutting by bytes would split them
+    assert_eq!(truncate("Microsoft · équipe", 12, "…"), "Microsoft ·…");
+    assert_eq!(truncate("aéééé", 4, "..."), "a...");
+    assert_eq!(truncate("équipe-propriétaire", 10, "…"), "équipe-pr…");
+    assert_eq!(truncate("チーム・プラットフォーム", 5, "…"), "チーム・…");
+    assert_eq!(truncate("équipe", 6, "…"), "équipe");
+}
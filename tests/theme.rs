@@ -0,0 +1,165 @@
+use aws_costs_tui::config::Config;
+use aws_costs_tui::theme::{Color, ColorDepth, Palette, Theme};
+
+/// The error's full chain on one line
+fn error(content: &str) -> String {
+    format!("{:#}", Config::parse(content).unwrap_err())
+}
+
+#[test]
+fn parses_colors() {
+    assert_eq!("red".parse(), Ok(Color::Ansi(1)));
+    assert_eq!("Dark-Gray".parse(), Ok(Color::Ansi(8)));
+    assert_eq!("white".parse(), Ok(Color::Ansi(15)));
+    assert_eq!("#FF9900".parse(), Ok(Color::Rgb(255, 153, 0)));
+    assert_eq!("208".parse(), Ok(Color::Indexed(208)));
+    assert_eq!("default".parse(), Ok(Color::Reset));
+    assert!("#ff99".parse::<Color>().is_err());
+    assert!("256".parse::<Color>().is_err());
+    assert!("mauve".parse::<Color>().is_err());
+
+    assert_eq!(Color::Rgb(255, 153, 0).to_string(), "#ff9900");
+    assert_eq!(Color::Ansi(8).to_string(), "darkgray");
+}
+
+#[test]
+fn detects_the_color_depth() {
+    let depth = ColorDepth::from_env;
+    assert_eq!(
+        depth(None, Some("truecolor"), Some("xterm-256color")),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(
+        depth(None, None, Some("xterm-256color")),
+        ColorDepth::Ansi256
+    );
+    assert_eq!(depth(None, None, Some("linux")), ColorDepth::Ansi16);
+    assert_eq!(depth(None, None, Some("dumb")), ColorDepth::None);
+    // NO_COLOR wins over everything, unless it is empty
+    assert_eq!(
+        depth(Some("1"), Some("truecolor"), Some("xterm-256color")),
+        ColorDepth::None
+    );
+    assert_eq!(depth(Some(""), Some("24bit"), None), ColorDepth::TrueColor);
+}
+
+#[test]
+fn themes_come_down_to_the_terminal() {
+    let dark = Theme::dark();
+    assert_eq!(dark.clone().for_depth(ColorDepth::TrueColor), dark);
+
+    let indexed = dark.clone().for_depth(ColorDepth::Ansi256);
+    assert_eq!(indexed.brand, Color::Indexed(208));
+    assert_eq!(indexed.label, Color::Indexed(248));
+    assert_eq!(indexed.text, Color::Ansi(15));
+
+    // Below 256 colors the selected row is reversed instead
+    let ansi = dark.clone().for_depth(ColorDepth::Ansi16);
+    assert_eq!(ansi.red, Color::Ansi(9));
+    assert_eq!(ansi.selection, Color::Reset);
+    assert!(ansi
+        .services
+        .iter()
+        .all(|color| matches!(color, Color::Ansi(_))));
+
+    assert_eq!(dark.for_depth(ColorDepth::None), Theme::monochrome());
+}
+
+#[test]
+fn colorblind_palette_replaces_the_service_colors() {
+    let theme = Theme::light().with_palette(Palette::Colorblind);
+    assert_eq!(theme.services.len(), 8);
+    assert_eq!(theme.services[0], Color::Rgb(230, 159, 0));
+    // Okabe-Ito's black is the text color, readable on either background
+    assert_eq!(theme.services[7], Theme::light().text);
+    assert_eq!(theme.service(9), theme.service(1));
+    assert_eq!(Theme::dark().with_palette(Palette::Theme), Theme::dark());
+}
+
+#[test]
+fn config_themes_build_on_the_built_in_ones() {
+    let config = Config::parse(
+        r##"
+theme = "paper"
+palette = "colorblind"
+colors = "256"
+
+[themes.paper]
+base = "light"
+text = "black"
+selection = "#ffe8b0"
+
+[themes.neon]
+services = ["lightmagenta", "lightcyan"]
+"##,
+    )
+    .unwrap();
+    assert_eq!(config.colors, Some(ColorDepth::Ansi256));
+    assert_eq!(config.color_depth(), ColorDepth::Ansi256);
+
+    let paper = config.theme(None).unwrap();
+    assert_eq!(paper.text, Color::Ansi(0));
+    assert_eq!(paper.selection, Color::Rgb(255, 232, 176));
+    assert_eq!(paper.red, Theme::light().red);
+    assert_eq!(paper.services[0], Color::Rgb(230, 159, 0));
+
+    // --theme picks another; the palette still applies
+    let neon = config.theme(Some("neon")).unwrap();
+    assert_eq!(neon.text, Theme::dark().text);
+    assert_eq!(neon.services.len(), 8);
+    let high_contrast = config.theme(Some("high-contrast")).unwrap();
+    assert_eq!(high_contrast.yellow, Theme::high_contrast().yellow);
+
+    let unknown = config.theme(Some("solarized")).unwrap_err().to_string();
+    assert!(
+        unknown.contains("No theme named \"solarized\""),
+        "{}",
+        unknown
+    );
+    assert!(unknown.contains("monochrome, neon, paper"), "{}", unknown);
+
+    // Without a config, the dark theme
+    assert_eq!(
+        Config::parse("").unwrap().theme(None).unwrap(),
+        Theme::dark()
+    );
+}
+
+#[test]
+fn config_theme_errors_name_the_line() {
+    let unknown = error("\ntheme = \"solarized\"\n");
+    assert!(unknown.contains("line 2"), "{}", unknown);
+    assert!(
+        unknown.contains("no theme named \"solarized\""),
+        "{}",
+        unknown
+    );
+
+    let base = error("[themes.x]\n\nbase = \"sepia\"\n");
+    assert!(base.contains("line 3"), "{}", base);
+    assert!(
+        base.contains("no built-in theme named \"sepia\""),
+        "{}",
+        base
+    );
+
+    let color = error("[themes.x]\nred = \"crimson\"\n");
+    assert!(color.contains("line 2"), "{}", color);
+    assert!(color.contains("Unknown color \"crimson\""), "{}", color);
+
+    let slot = error("[themes.x]\nbackground = \"black\"\n");
+    assert!(slot.contains("background"), "{}", slot);
+
+    let services = error("[themes.x]\nservices = []\n");
+    assert!(
+        services.contains("services needs at least one color"),
+        "{}",
+        services
+    );
+
+    let depth = error("colors = \"8\"\n");
+    assert!(depth.contains("Unknown color depth"), "{}", depth);
+
+    let palette = error("palette = \"pastel\"\n");
+    assert!(palette.contains("line 1"), "{}", palette);
+}
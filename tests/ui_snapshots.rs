@@ -0,0 +1,715 @@
+//! Golden snapshots of every TUI view rendered into ratatui's `TestBackend`.
+//!
+//! Review changes with `cargo insta review`, or accept them all with
+//! `INSTA_UPDATE=always cargo test --test ui_snapshots`.
+
+use anyhow::anyhow;
+use aws_costs_tui::analysis::{
+    AccountsReport, Allocation, AllocationRules, Anomalies, Detector, Forecast, Forecaster, TagCoverage,
+};
+use aws_costs_tui::aws::{DailyCosts, DailySeries};
+use aws_costs_tui::config::Config;
+use aws_costs_tui::source::{
+    AccountsSource, CostSource, FixtureSource, FocusSource, GroupedSource, MergedSource, SyntheticSource,
+};
+use aws_costs_tui::theme::{ColorDepth, Palette, Theme};
+use aws_costs_tui::ui::App;
+use chrono::NaiveDate;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use std::path::Path;
+use std::sync::Arc;
+
+const TABS: [&str; 4] = ["current", "previous", "trend", "compare"];
+
+const SIZES: [(u16, u16); 3] = [(80, 24), (120, 36), (200, 50)];
+
+/// Build an app loaded from `source`, the way the event loop would
+async fn loaded_app(source: &dyn CostSource) -> App {
+    let mut app = App::new();
+    app.set_source_name(source.name());
+    app.set_current_month(source.current_month().await);
+    app.set_previous_month(source.previous_month().await);
+    app.set_trend(source.monthly_trend(6).await);
+    app
+}
+
+async fn demo_app() -> App {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    loaded_app(&SyntheticSource::new(42).with_today(today)).await
+}
+
+fn render(app: &App, width: u16, height: u16) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    terminal.backend().clone()
+}
+
+#[tokio::test]
+async fn every_tab_at_every_size() {
+    let mut app = demo_app().await;
+    for (tab, name) in TABS.iter().enumerate() {
+        app.select_tab(tab);
+        for (width, height) in SIZES {
+            insta::assert_snapshot!(
+                format!("{}_{}x{}", name, width, height),
+                render(&app, width, height)
+            );
+        }
+    }
+}
+
+#[test]
+fn every_tab_while_loading() {
+    let mut app = App::new();
+    app.set_source_name("demo data");
+    app.set_loading();
+    for (tab, name) in TABS.iter().enumerate() {
+        app.select_tab(tab);
+        insta::assert_snapshot!(format!("{}_loading", name), render(&app, 100, 30));
+    }
+}
+
+#[test]
+fn every_tab_without_data() {
+    let mut app = App::new();
+    for (tab, name) in TABS.iter().enumerate() {
+        app.select_tab(tab);
+        insta::assert_snapshot!(format!("{}_no_data", name), render(&app, 100, 30));
+    }
+}
+
+#[test]
+fn current_month_error() {
+    let mut app = App::new();
+    app.set_current_month(Err(anyhow!(
+        "API request failed with status 403: AccessDenied"
+    )));
+    insta::assert_snapshot!(render(&app, 100, 30));
+}
+
+#[tokio::test]
+async fn trend_with_missing_month() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample.json");
+    let mut app = loaded_app(&FixtureSource::load(&path).unwrap()).await;
+    app.select_tab(2);
+    insta::assert_snapshot!(render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn compare_prorated_with_selection() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = demo_app().await;
+    app.select_tab(3);
+    for code in [KeyCode::Char('P'), KeyCode::Down, KeyCode::Down] {
+        app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+    insta::assert_snapshot!(render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn merged_sources_show_provider_column() {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/focus/azure.csv");
+    let merged = MergedSource::new(vec![
+        Arc::new(SyntheticSource::new(42).with_today(today)),
+        Arc::new(FocusSource::load(&path).unwrap().with_today(today)),
+    ]);
+    let mut app = loaded_app(&merged).await;
+    app.set_source_name("demo data + FOCUS: azure.csv");
+    insta::assert_snapshot!("current_with_providers", render(&app, 120, 36));
+    app.select_tab(3);
+    insta::assert_snapshot!("compare_with_providers", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn resource_drill_down() {
+    use aws_costs_tui::aws::{CostData, ServiceCost};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let mut app = demo_app().await;
+    for code in [KeyCode::Down, KeyCode::Enter] {
+        app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+    insta::assert_snapshot!("resources_loading", render(&app, 120, 36));
+
+    let row = |service: &str, cost: f64| ServiceCost {
+        service: service.to_string(),
+        cost,
+        percentage: cost / 95.0 * 100.0,
+        provider: None,
+    };
+    app.set_resources(Ok(CostData {
+        period: "March 2026".to_string(),
+        start: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        end: NaiveDate::from_ymd_opt(2026, 3, 16).unwrap(),
+        total_cost: 95.0,
+        currency: "USD".to_string(),
+        breakdown: vec![
+            row("i-0123456789abcdef0", 60.0),
+            row("arn:aws:elasticloadbalancing:us-east-1:111111111111:loadbalancer/app/web/50dc6c495c0c9188", 30.0),
+            row("(none)", 5.0),
+        ],
+    }));
+    insta::assert_snapshot!("resources", render(&app, 120, 36));
+
+    // Cost Explorer only returned the last 14 days; copy the second ID
+    app.set_resources(Ok(CostData {
+        period: "2026-03-02 – 2026-03-15".to_string(),
+        start: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+        end: NaiveDate::from_ymd_opt(2026, 3, 16).unwrap(),
+        total_cost: 95.0,
+        currency: "USD".to_string(),
+        breakdown: vec![
+            row("i-0123456789abcdef0", 60.0),
+            row("arn:aws:elasticloadbalancing:us-east-1:111111111111:loadbalancer/app/web/50dc6c495c0c9188", 30.0),
+            row("(none)", 5.0),
+        ],
+    }));
+    for code in [KeyCode::Down, KeyCode::Char('y')] {
+        app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    }
+    insta::assert_snapshot!("resources_clipped_copied", render(&app, 120, 36));
+
+    // Back to the service list, then into a source without resource data
+    app.handle_event(Event::Key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)));
+    app.handle_event(Event::Key(KeyEvent::new(
+        KeyCode::Enter,
+        KeyModifiers::NONE,
+    )));
+    app.set_resources(Err(anyhow!("demo data has no resource-level data")));
+    insta::assert_snapshot!("resources_unavailable", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn tag_explorer() {
+    use aws_costs_tui::aws::GroupBy;
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let mut app = loaded_app(&source).await;
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+    press(&mut app, KeyCode::Char('t'));
+    let (start, end) = (
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+        today.succ_opt().unwrap(),
+    );
+    app.set_tag_keys(source.tag_keys(start, end).await);
+    press(&mut app, KeyCode::Down);
+    insta::assert_snapshot!("tag_keys", render(&app, 120, 36));
+
+    press(&mut app, KeyCode::Enter);
+    let group_by = GroupBy::Tag("environment".to_string());
+    app.set_tag_spend(source.breakdown(start, end, &group_by).await);
+    insta::assert_snapshot!("tag_spend", render(&app, 120, 36));
+
+    // Back to the keys, then a source without tags
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('t'));
+    app.set_tag_keys(Err(anyhow!("demo data can't list tag keys")));
+    insta::assert_snapshot!("tag_keys_unavailable", render(&app, 80, 24));
+}
+
+#[tokio::test]
+async fn tag_coverage() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let keys = vec!["team".to_string(), "cost-center".to_string()];
+    let mut app = loaded_app(&source).await.with_required_tags(keys.clone());
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+    press(&mut app, KeyCode::Char('c'));
+    insta::assert_snapshot!("tag_coverage_loading", render(&app, 80, 24));
+
+    let trend = source.monthly_trend(6).await.unwrap();
+    let months: Vec<_> = trend.months.iter().map(|m| (m.start, m.end)).collect();
+    let (start, end) = *months.last().unwrap();
+    app.set_tag_coverage(TagCoverage::load(&source, &keys, start, end, &months).await);
+    press(&mut app, KeyCode::Down);
+    insta::assert_snapshot!("tag_coverage_services", render(&app, 120, 36));
+
+    press(&mut app, KeyCode::Tab);
+    insta::assert_snapshot!("tag_coverage_accounts", render(&app, 120, 36));
+
+    // Without required keys the popup explains how to set them
+    let mut app = demo_app().await;
+    press(&mut app, KeyCode::Char('c'));
+    insta::assert_snapshot!("tag_coverage_no_keys", render(&app, 80, 24));
+
+    // Keys in any script are cut between characters
+    let keys = vec!["centre-coût-équipe".to_string(), "propriétaire".to_string()];
+    let mut app = loaded_app(&source).await.with_required_tags(keys.clone());
+    press(&mut app, KeyCode::Char('c'));
+    app.set_tag_coverage(TagCoverage::load(&source, &keys, start, end, &months).await);
+    insta::assert_snapshot!("tag_coverage_non_ascii", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn teams_tab() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let rules = AllocationRules::parse(
+        r#"
+        tag = "team"
+        [[shared]]
+        name = "Networking"
+        services = ["Amazon Virtual Private Cloud", "Amazon CloudFront"]
+        "#,
+    )
+    .unwrap();
+    let mut app = loaded_app(&source)
+        .await
+        .with_allocation_rules(rules.clone());
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+    // Teams comes after Compare
+    press(&mut app, KeyCode::BackTab);
+    let current = source.current_month().await.unwrap();
+    app.set_allocation(Allocation::load(&source, &rules, current.start, current.end).await);
+    insta::assert_snapshot!("teams", render(&app, 120, 36));
+
+    // Untagged spend without a rule is left unallocated
+    let rules = AllocationRules::parse(r#"tag = "team""#).unwrap();
+    app.set_allocation(Allocation::load(&source, &rules, current.start, current.end).await);
+    insta::assert_snapshot!("teams_unallocated", render(&app, 80, 24));
+
+    // Team names from the rules are cut between characters
+    let rules = AllocationRules::parse(
+        r#"
+        tag = "team"
+        [tags]
+        platform = "Plateforme de la donnée · équipe"
+        data = "Infrastructure données · équipe"
+        "#,
+    )
+    .unwrap();
+    app.set_allocation(Allocation::load(&source, &rules, current.start, current.end).await);
+    insta::assert_snapshot!("teams_non_ascii", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn anomalies() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let mut app = loaded_app(&source).await;
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+    // Spiking services are flagged in the breakdown
+    let current = source.current_month().await.unwrap();
+    app.set_anomalies(
+        Anomalies::load(&source, Detector::default(), current.start, current.end).await,
+    );
+    insta::assert_snapshot!("anomalies_flagged", render(&app, 120, 36));
+
+    // The panel lists them with expected and actual cost
+    press(&mut app, KeyCode::Char('a'));
+    press(&mut app, KeyCode::Char('j'));
+    insta::assert_snapshot!("anomalies_panel", render(&app, 120, 36));
+
+    // Sources without daily data say so
+    app.set_anomalies(Err(anyhow!("fixture has no daily costs")));
+    insta::assert_snapshot!("anomalies_error", render(&app, 80, 24));
+    press(&mut app, KeyCode::Esc);
+    insta::assert_snapshot!("anomalies_closed", render(&app, 80, 24));
+
+    // Service names in any script are cut between characters
+    let mut costs = vec![10.0; 42];
+    costs.push(300.0);
+    let daily = DailyCosts {
+        start: NaiveDate::from_ymd_opt(2026, 2, 1).unwrap(),
+        end: today.succ_opt().unwrap(),
+        currency: "USD".to_string(),
+        series: vec![DailySeries {
+            service: "Machine virtuelle haute mémoire dédiée – série générale".to_string(),
+            costs,
+            provider: None,
+        }],
+    };
+    app.set_anomalies(Ok(Anomalies::new(
+        Detector::default(),
+        &daily,
+        current.start,
+    )));
+    press(&mut app, KeyCode::Char('a'));
+    insta::assert_snapshot!("anomalies_non_ascii", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn forecast() {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let mut app = loaded_app(&source).await;
+
+    // The summary shows the projection and its method, the breakdown each
+    // service's month-end
+    let current = source.current_month().await.unwrap();
+    app.set_anomalies(
+        Anomalies::load(&source, Detector::default(), current.start, current.end).await,
+    );
+    app.set_forecast(Forecast::load(&source, Forecaster::default(), today).await);
+    insta::assert_snapshot!("forecast_120x36", render(&app, 120, 36));
+    insta::assert_snapshot!("forecast_80x24", render(&app, 80, 24));
+
+    // Other months don't get one
+    app.select_tab(1);
+    insta::assert_snapshot!("forecast_previous", render(&app, 80, 24));
+}
+
+#[tokio::test]
+async fn budgets() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let mut app = loaded_app(&source).await;
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    app.set_budgets(source.budgets(6).await);
+
+    // One gauge per budget, colored by how close it is to its limit
+    press(&mut app, KeyCode::Char('b'));
+    insta::assert_snapshot!("budgets_panel", render(&app, 120, 36));
+    insta::assert_snapshot!("budgets_panel_80x24", render(&app, 80, 24));
+    press(&mut app, KeyCode::Char('b'));
+
+    // The biggest monthly budget is drawn next to the trend
+    app.select_tab(2);
+    insta::assert_snapshot!("budgets_trend", render(&app, 120, 36));
+
+    // Sources without budgets say so
+    app.set_budgets(Err(anyhow!("demo data has no budgets")));
+    press(&mut app, KeyCode::Char('b'));
+    insta::assert_snapshot!("budgets_error", render(&app, 80, 24));
+
+    // Budgets from the config are listed below the error
+    let local = aws_costs_tui::analysis::LocalBudgets::parse(
+        r#"
+        [[budget]]
+        name = "Web team"
+        period = "monthly"
+        amount = 400
+        thresholds = [50, 90]
+        tag = { key = "team", value = "web" }
+
+        [[budget]]
+        name = "Ireland"
+        period = "quarterly"
+        amount = 2000
+        filter = { key = "REGION", value = "eu-west-1" }
+        "#,
+    )
+    .unwrap();
+    app.set_local_budgets(local.evaluate(&source, today).await);
+    insta::assert_snapshot!("budgets_local", render(&app, 100, 24));
+}
+
+#[tokio::test]
+async fn configured_view() {
+    let config = Config::parse(
+        r#"
+metric = "AmortizedCost"
+filter = "production"
+
+[filters.production]
+"TAG:environment" = ["prod"]
+
+[views.regions]
+group_by = "REGION"
+months = 12
+top = 4
+thresholds = { high = 20000, medium = 5000, low = 1000 }
+"#,
+    )
+    .unwrap();
+    let settings = config.settings(Some("regions")).unwrap();
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let demo = Arc::new(SyntheticSource::new(42).with_today(today));
+    let source = GroupedSource::new(demo, settings.group_by.clone());
+
+    let mut app = App::new().with_settings(settings);
+    app.set_source_name(source.name());
+    app.set_current_month(source.current_month().await);
+    app.set_previous_month(source.previous_month().await);
+    app.set_trend(source.monthly_trend(12).await);
+
+    // Regions instead of services, colored by the view's thresholds
+    insta::assert_snapshot!("configured_current", render(&app, 120, 36));
+    // A year of the top four regions
+    app.select_tab(2);
+    insta::assert_snapshot!("configured_trend", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn long_trend_shows_every_month() {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let source = SyntheticSource::new(42).with_today(today);
+    let trend = source.monthly_trend(12).await.unwrap();
+    let labels: Vec<&str> = trend.months.iter().map(|m| &m.period[..3]).collect();
+
+    // Bars thin out rather than months falling off the right edge
+    for (config, width, height) in [("months = 12", 120, 36), ("months = 12\ntop = 5", 80, 24)] {
+        let settings = Config::parse(config).unwrap().settings(None).unwrap();
+        let mut app = App::new().with_settings(settings);
+        app.set_trend(Ok(trend.clone()));
+        app.select_tab(2);
+        let backend = render(&app, width, height);
+        let buffer = backend.buffer();
+        let axis = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .find(|row| row.contains(labels[0]))
+            .unwrap();
+        assert!(
+            labels.iter().all(|label| axis.contains(label)),
+            "{}x{}: {}",
+            width,
+            height,
+            axis
+        );
+        insta::assert_snapshot!(format!("trend_12_months_{}x{}", width, height), backend);
+    }
+}
+
+#[tokio::test]
+async fn keymap_chords_and_help() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let config = Config::parse("[keys]\nnext_tab = [\"l\", \"Tab\"]\nrefresh = \"Ctrl-r\"\n").unwrap();
+    let mut app = demo_app().await.with_keymap(config.keymap().unwrap());
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    let top = render(&app, 120, 36);
+
+    // `G` goes to the last row and the `gg` chord back to the first
+    press(&mut app, KeyCode::Char('G'));
+    assert_ne!(render(&app, 120, 36), top);
+    press(&mut app, KeyCode::Char('g'));
+    press(&mut app, KeyCode::Char('g'));
+    assert_eq!(render(&app, 120, 36), top);
+
+    // A key that doesn't finish the chord counts on its own
+    let mut moved = demo_app().await.with_keymap(config.keymap().unwrap());
+    press(&mut moved, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Char('g'));
+    press(&mut app, KeyCode::Char('j'));
+    assert_eq!(render(&app, 120, 36), render(&moved, 120, 36));
+
+    // Remapped keys switch tabs; the arrow they replaced does nothing
+    press(&mut app, KeyCode::Right);
+    assert_eq!(render(&app, 120, 36), render(&moved, 120, 36));
+    press(&mut app, KeyCode::Char('l'));
+    moved.select_tab(1);
+    assert_eq!(render(&app, 120, 36), render(&moved, 120, 36));
+
+    // The help lists the keys in use, remapped ones included
+    press(&mut app, KeyCode::Char('?'));
+    insta::assert_snapshot!("help", render(&app, 120, 36));
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(render(&app, 120, 36), render(&moved, 120, 36));
+}
+
+#[tokio::test]
+async fn themes() {
+    use ratatui::style::{Color, Modifier};
+
+    let dark = render(&demo_app().await, 120, 36);
+    let symbols = |backend: &TestBackend| -> Vec<String> {
+        backend.buffer().content.iter().map(|cell| cell.symbol().to_string()).collect()
+    };
+    let colors = |backend: &TestBackend| -> Vec<Color> {
+        backend.buffer().content.iter().flat_map(|cell| [cell.fg, cell.bg]).collect()
+    };
+
+    // Monochrome keeps the terminal's colors and reverses the selected row
+    let mono = render(&demo_app().await.with_theme(Theme::monochrome()), 120, 36);
+    assert_eq!(symbols(&mono), symbols(&dark));
+    assert!(colors(&mono).iter().all(|&color| color == Color::Reset));
+    let buffer = mono.buffer();
+    let first_row = (0..buffer.area.height)
+        .find(|&y| buffer[(2, y)].symbol() == "#" && buffer[(3, y)].symbol() == "1")
+        .unwrap();
+    assert!(buffer[(2, first_row)].modifier.contains(Modifier::REVERSED));
+
+    // Dark text instead of white, which is only left on accent colors
+    let white_text = |backend: &TestBackend| {
+        backend.buffer().content.iter().any(|cell| cell.fg == Color::White && cell.bg == Color::Reset)
+    };
+    let light = render(&demo_app().await.with_theme(Theme::light()), 120, 36);
+    assert_eq!(symbols(&light), symbols(&dark));
+    assert!(white_text(&dark));
+    assert!(!white_text(&light));
+    assert!(colors(&light).contains(&Color::Rgb(30, 30, 30)));
+
+    // 16 colors: nothing outside the ANSI palette
+    let ansi = Theme::high_contrast().with_palette(Palette::Colorblind).for_depth(ColorDepth::Ansi16);
+    let ansi = render(&demo_app().await.with_theme(ansi), 120, 36);
+    assert!(colors(&ansi).iter().all(|color| !matches!(color, Color::Rgb(..) | Color::Indexed(_))));
+}
+
+#[tokio::test]
+async fn mouse() {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    let mouse = |app: &mut App, kind, column, row| {
+        app.handle_event(Event::Mouse(MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE }))
+    };
+    let click = |app: &mut App, column, row| mouse(app, MouseEventKind::Down(MouseButton::Left), column, row);
+    let press = |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+    // Column and line where `text` starts in the rendered frame
+    let find = |backend: &TestBackend, text: &str| -> (u16, u16) {
+        let buffer = backend.buffer();
+        for y in 0..buffer.area.height {
+            let line: Vec<&str> = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+            for x in 0..line.len() {
+                if line[x..].concat().starts_with(text) {
+                    return (x as u16, y);
+                }
+            }
+        }
+        panic!("{:?} isn't on screen", text);
+    };
+
+    // Clicking a tab switches to it
+    let mut app = demo_app().await;
+    let mut expected = demo_app().await;
+    let (x, y) = find(&render(&app, 120, 36), "Compare");
+    click(&mut app, x + 2, y);
+    expected.select_tab(3);
+    assert_eq!(render(&app, 120, 36), render(&expected, 120, 36));
+
+    // Clicking a row selects it, and the wheel moves the selection
+    app.select_tab(0);
+    expected.select_tab(0);
+    let (x, y) = find(&render(&app, 120, 36), "#3 ");
+    click(&mut app, x + 10, y);
+    press(&mut expected, KeyCode::Char('j'));
+    press(&mut expected, KeyCode::Char('j'));
+    assert_eq!(render(&app, 120, 36), render(&expected, 120, 36));
+    mouse(&mut app, MouseEventKind::ScrollDown, 60, 20);
+    press(&mut expected, KeyCode::Char('j'));
+    assert_eq!(render(&app, 120, 36), render(&expected, 120, 36));
+
+    // Scrolling past the bottom of the table keeps the selection in view
+    for _ in 0..30 {
+        mouse(&mut app, MouseEventKind::ScrollDown, 60, 20);
+    }
+    insta::assert_snapshot!("current_scrolled", render(&app, 120, 30));
+
+    // Panels and the help take the clicks
+    press(&mut app, KeyCode::Char('?'));
+    let (x, y) = find(&render(&app, 120, 36), "Previous Month");
+    click(&mut app, x, y);
+    press(&mut app, KeyCode::Esc);
+    press(&mut expected, KeyCode::Char('G'));
+    assert_eq!(render(&app, 120, 36), render(&expected, 120, 36));
+
+    // Hovering a trend bar shows its month and service's cost
+    app.select_tab(2);
+    let (_, y) = find(&render(&app, 120, 36), "Monthly Cost Trend");
+    mouse(&mut app, MouseEventKind::Moved, 2, y + 4);
+    insta::assert_snapshot!("trend_tooltip", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn accounts() {
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let accounts = ["prod", "staging", "dev"]
+        .into_iter()
+        .zip(42..)
+        .fold(AccountsSource::default(), |accounts, (name, seed)| {
+            accounts.with_account(name, Arc::new(SyntheticSource::new(seed).with_today(today)))
+        })
+        .with_failed_account("sandbox", "No credentials found for profile sandbox");
+    let mut app = loaded_app(&accounts).await;
+    let month = accounts.current_month().await.unwrap();
+    app.set_accounts(AccountsReport::load(&accounts, month.start, month.end).await);
+
+    // The other views show every account summed, the last tab each one
+    app.select_tab(4);
+    insta::assert_snapshot!("accounts", render(&app, 120, 36));
+    insta::assert_snapshot!("accounts_80x24", render(&app, 80, 24));
+
+    // Profile names in any script are cut between characters
+    let accounts = ["réseau-équipe-intégration-européen", "équipe-développement-européen"]
+        .into_iter()
+        .zip(42..)
+        .fold(AccountsSource::default(), |accounts, (name, seed)| {
+            accounts.with_account(name, Arc::new(SyntheticSource::new(seed).with_today(today)))
+        });
+    app.set_accounts(AccountsReport::load(&accounts, month.start, month.end).await);
+    insta::assert_snapshot!("accounts_non_ascii", render(&app, 120, 36));
+}
+
+#[tokio::test]
+async fn profile_switcher() {
+    use aws_costs_tui::aws::{CredentialType, Profile};
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+    let profile = |name: &str, region: Option<&str>, credential_type| Profile {
+        name: name.to_string(),
+        region: region.map(str::to_string),
+        credential_type,
+    };
+    let profiles = vec![
+        profile("prod", Some("us-east-1"), CredentialType::AccessKeys),
+        profile("staging", Some("eu-west-1"), CredentialType::SessionToken),
+        profile("sandbox", None, CredentialType::Sso),
+    ];
+    let mut app = App::new().with_profiles("prod", profiles, move |name| match name {
+        "staging" => {
+            let source: Arc<dyn CostSource> = Arc::new(SyntheticSource::new(43).with_today(today));
+            Ok(source)
+        }
+        _ => Err(anyhow!("No credentials found for profile '{}'", name)),
+    });
+    let prod = SyntheticSource::new(42).with_today(today);
+    app.set_source_name(prod.name());
+    app.set_current_month(prod.current_month().await);
+    app.set_previous_month(prod.previous_month().await);
+    app.set_trend(prod.monthly_trend(6).await);
+    let before = render(&app, 120, 36).to_string();
+    let press =
+        |app: &mut App, code| app.handle_event(Event::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+
+    press(&mut app, KeyCode::Char('p'));
+    insta::assert_snapshot!("profiles", render(&app, 120, 36));
+
+    // Profiles that can't be connected to say why and stay put
+    press(&mut app, KeyCode::End);
+    press(&mut app, KeyCode::Enter);
+    insta::assert_snapshot!("profiles_error", render(&app, 120, 36));
+
+    // Another profile loads from scratch
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Enter);
+    insta::assert_snapshot!("profiles_switched_loading", render(&app, 120, 36));
+    let staging = SyntheticSource::new(43).with_today(today);
+    app.set_current_month(staging.current_month().await);
+    app.set_previous_month(staging.previous_month().await);
+    app.set_trend(staging.monthly_trend(6).await);
+
+    // The first one is kept, so switching back is instant
+    press(&mut app, KeyCode::Char('p'));
+    insta::assert_snapshot!("profiles_cached", render(&app, 120, 36));
+    press(&mut app, KeyCode::Home);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(render(&app, 120, 36).to_string(), before);
+}